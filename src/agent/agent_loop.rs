@@ -5,23 +5,30 @@
 
 use std::sync::{Arc, Mutex};
 
-use anyhow::Result;
+use alloy::primitives::keccak256;
+use anyhow::{Context, Result};
 use chrono::Utc;
 use tracing::info;
 use uuid::Uuid;
 
-use crate::conway::credits::get_survival_tier;
+use crate::clock::Clock;
+use crate::conway::credits::{
+    detect_incoming_transfer, get_survival_tier, reconcile_burn_rate, record_inference_cost,
+};
+use crate::conway::error::ConwayError;
 use crate::conway::x402::get_usdc_balance;
+use crate::heartbeat::tasks::check_kill_switch;
 use crate::state::{Database, DatabaseAdapter};
 use crate::types::{
     AgentState, AgentTurn, AutomatonConfig, AutomatonIdentity, AutomatonDatabase,
-    ConwayClient, FinancialState, InferenceClient, InferenceOptions, InputSource,
-    Skill, SocialClientInterface, SurvivalTier, ToolContext, TokenUsage,
+    ChatMessage, ChatRole, ConwayClient, FinancialState, InferenceClient, InferenceOptions,
+    InputSource, ModificationEntry, ModificationType, PendingInputEntry, Skill, SocialClientInterface, SurvivalTier,
+    ToolCallResult, ToolContext, TokenUsage, TurnCostCapAction,
 };
 
 use super::context::{build_context_messages, trim_context};
 use super::system_prompt::{build_system_prompt, build_wakeup_prompt};
-use super::tools::{create_builtin_tools, execute_tool, tools_to_inference_format};
+use super::tools::{create_builtin_tools, execute_tool, BuiltinTool};
 
 /// Maximum number of tool calls the agent can execute in a single turn.
 const MAX_TOOL_CALLS_PER_TURN: usize = 10;
@@ -29,6 +36,17 @@ const MAX_TOOL_CALLS_PER_TURN: usize = 10;
 /// Maximum consecutive errors before the agent gives up and sleeps.
 const MAX_CONSECUTIVE_ERRORS: usize = 5;
 
+/// Caps the adaptive idle-sleep multiplier at `2^MAX_IDLE_BACKOFF_SHIFT`
+/// (16x `idle_sleep_seconds`), so a long-idle automaton still wakes up
+/// often enough to notice real input within a reasonable window.
+const MAX_IDLE_BACKOFF_SHIFT: u32 = 4;
+
+/// When a tool call's arguments come back truncated (`finish_reason ==
+/// "length"` mid-JSON), multiply `max_tokens` by this much for the rest of
+/// the turn so a retry has real headroom to finish instead of hitting the
+/// same wall again.
+const TRUNCATION_MAX_TOKENS_MULTIPLIER: u32 = 2;
+
 // ---------------------------------------------------------------------------
 // Trait adapters: wrap Arc<dyn Trait> into Box<dyn Trait> for ToolContext
 // ---------------------------------------------------------------------------
@@ -45,7 +63,7 @@ impl ConwayClient for ConwayAdapter {
     async fn remove_port(&self, port: u16) -> anyhow::Result<()> { self.0.remove_port(port).await }
     async fn create_sandbox(&self, options: crate::types::CreateSandboxOptions) -> anyhow::Result<crate::types::SandboxInfo> { self.0.create_sandbox(options).await }
     async fn delete_sandbox(&self, sandbox_id: &str) -> anyhow::Result<()> { self.0.delete_sandbox(sandbox_id).await }
-    async fn list_sandboxes(&self) -> anyhow::Result<Vec<crate::types::SandboxInfo>> { self.0.list_sandboxes().await }
+    async fn list_sandboxes(&self, filter: &crate::types::ListSandboxesFilter) -> anyhow::Result<Vec<crate::types::SandboxInfo>> { self.0.list_sandboxes(filter).await }
     async fn get_credits_balance(&self) -> anyhow::Result<f64> { self.0.get_credits_balance().await }
     async fn get_credits_pricing(&self) -> anyhow::Result<Vec<crate::types::PricingTier>> { self.0.get_credits_pricing().await }
     async fn transfer_credits(&self, to: &str, amount: u64, note: Option<&str>) -> anyhow::Result<crate::types::CreditTransferResult> { self.0.transfer_credits(to, amount, note).await }
@@ -55,6 +73,7 @@ impl ConwayClient for ConwayAdapter {
     async fn add_dns_record(&self, domain: &str, record_type: &str, host: &str, value: &str, ttl: Option<u32>) -> anyhow::Result<crate::types::DnsRecord> { self.0.add_dns_record(domain, record_type, host, value, ttl).await }
     async fn delete_dns_record(&self, domain: &str, record_id: &str) -> anyhow::Result<()> { self.0.delete_dns_record(domain, record_id).await }
     async fn list_models(&self) -> anyhow::Result<Vec<crate::types::ModelInfo>> { self.0.list_models().await }
+    fn set_api_key(&self, api_key: &str) { self.0.set_api_key(api_key) }
 }
 
 /// Wraps `Arc<dyn InferenceClient>` to implement `InferenceClient`.
@@ -65,6 +84,8 @@ impl InferenceClient for InferenceAdapter {
     async fn chat(&self, messages: Vec<crate::types::ChatMessage>, options: Option<InferenceOptions>) -> anyhow::Result<crate::types::InferenceResponse> { self.0.chat(messages, options).await }
     fn set_low_compute_mode(&self, enabled: bool) { self.0.set_low_compute_mode(enabled); }
     fn get_default_model(&self) -> String { self.0.get_default_model() }
+    fn set_default_model(&self, model: &str) { self.0.set_default_model(model); }
+    fn set_api_key(&self, api_key: &str) { self.0.set_api_key(api_key); }
 }
 
 /// Wraps `Arc<dyn SocialClientInterface>` to implement `SocialClientInterface`.
@@ -77,6 +98,119 @@ impl SocialClientInterface for SocialAdapter {
     async fn unread_count(&self) -> anyhow::Result<u64> { self.0.unread_count().await }
 }
 
+/// Reconcile our persisted `exposed_ports` record against reality on startup.
+///
+/// The Conway API has no endpoint to list a sandbox's currently-exposed
+/// ports, so we can't fully verify each one. What we can check is whether
+/// our sandbox itself still exists -- if it was recreated (e.g. after being
+/// deleted and re-provisioned), any previously exposed ports are gone with
+/// it, so we clear the stale record rather than advertise dead endpoints.
+async fn reconcile_exposed_ports(conway: &dyn ConwayClient, db: &dyn AutomatonDatabase, sandbox_id: &str) {
+    let ports = db.get_exposed_ports();
+    if ports.is_empty() {
+        return;
+    }
+
+    let sandboxes = match conway.list_sandboxes(&crate::types::ListSandboxesFilter::default()).await {
+        Ok(sandboxes) => sandboxes,
+        Err(_) => return, // Conway API unreachable -- keep the existing record.
+    };
+
+    if !sandboxes.iter().any(|s| s.id == sandbox_id) {
+        info!("Sandbox {} no longer exists -- clearing stale exposed ports", sandbox_id);
+        for port in ports {
+            db.delete_exposed_port(port.port);
+        }
+    }
+}
+
+/// Verify the automaton can actually operate before entering the loop:
+/// the database accepts writes, the Conway sandbox responds, the configured
+/// inference model is still on offer, and the wallet balance can be read.
+/// Returns the first failure it hits so the caller can fail fast with an
+/// actionable error instead of limping into a loop that will fail every turn.
+async fn startup_selfcheck(
+    config: &AutomatonConfig,
+    conway: &dyn ConwayClient,
+    db: &dyn AutomatonDatabase,
+    identity: &AutomatonIdentity,
+) -> Result<()> {
+    // Database writable -- round-trip a probe value through the KV store.
+    // AutomatonDatabase's methods don't return Result (see DatabaseAdapter),
+    // so a write that silently failed would otherwise only surface as a
+    // missing read; comparing the two is the honest way to catch that.
+    let probe = Uuid::new_v4().to_string();
+    db.set_kv("startup_selfcheck_probe", &probe);
+    if db.get_kv("startup_selfcheck_probe").as_deref() != Some(probe.as_str()) {
+        anyhow::bail!("database did not persist a test write -- is it writable?");
+    }
+
+    // Conway sandbox reachable.
+    conway
+        .list_sandboxes(&crate::types::ListSandboxesFilter::default())
+        .await
+        .context("Conway sandbox unreachable -- check conway_api_url and conway_api_key")?;
+
+    // Configured inference model is actually on offer.
+    let models = conway
+        .list_models()
+        .await
+        .context("could not list available inference models")?;
+    if !models.iter().any(|m| m.id == config.inference_model) {
+        anyhow::bail!(
+            "configured inference_model '{}' is not among the {} models Conway currently offers",
+            config.inference_model,
+            models.len()
+        );
+    }
+
+    // Wallet balance readable.
+    let address = identity
+        .address
+        .parse::<alloy::primitives::Address>()
+        .context("wallet address is not a valid Ethereum address")?;
+    get_usdc_balance(address, "base")
+        .await
+        .context("could not read wallet balance")?;
+
+    Ok(())
+}
+
+/// Record the outcome of [`startup_selfcheck`] in KV as `last_startup_check`
+/// so `automaton --status` can report whether the automaton came up healthy.
+fn record_selfcheck_result(db: &dyn AutomatonDatabase, result: &Result<()>) {
+    let payload = match result {
+        Ok(()) => serde_json::json!({ "status": "ok", "checkedAt": Utc::now().to_rfc3339() }),
+        Err(e) => serde_json::json!({
+            "status": "failed",
+            "checkedAt": Utc::now().to_rfc3339(),
+            "error": e.to_string(),
+        }),
+    };
+    db.set_kv("last_startup_check", &payload.to_string());
+}
+
+/// Log which constitution is in effect -- a custom `constitution.md` path or
+/// the built-in fallback -- plus a content hash, and record it in KV as
+/// `constitution_source` so `automaton --status` can show it. Runs once at
+/// startup, not per-turn, so a misplaced `constitution.md` shows up in the
+/// logs immediately instead of the automaton silently running on the
+/// default constitution.
+fn record_constitution_source(config: &AutomatonConfig, db: &dyn AutomatonDatabase) {
+    let (content, source) = crate::agent::system_prompt::load_constitution_with_source();
+    let hash = hex::encode(keccak256(content.as_bytes()));
+    let label = source.label();
+
+    log(config, &format!("[CONSTITUTION] loaded from {} (hash {})", label, &hash[..12]));
+
+    let payload = serde_json::json!({
+        "source": label,
+        "hash": hash,
+        "loadedAt": Utc::now().to_rfc3339(),
+    });
+    db.set_kv("constitution_source", &payload.to_string());
+}
+
 // ---------------------------------------------------------------------------
 
 /// Options for running the agent loop.
@@ -90,19 +224,175 @@ pub struct AgentLoopOptions {
     pub skills: Option<Vec<Skill>>,
     pub on_state_change: Option<StateChangeCallback>,
     pub on_turn_complete: Option<TurnCompleteCallback>,
+    /// Called with `(tool_name, message)` whenever a long-running tool
+    /// (spawn_child, git_clone, install_npm_package, ...) reports a progress
+    /// heartbeat, so the UI/logs show it hasn't hung.
+    pub on_tool_progress: Option<Arc<crate::types::ToolProgressFn>>,
+    /// Called with every `AgentLoopEvent` the loop fires (state changes,
+    /// think previews, tool calls and their results), for a caller that
+    /// wants to observe the loop live. This is the raw hook a streaming
+    /// endpoint would subscribe to; the endpoint itself doesn't exist in
+    /// this codebase (there's no HTTP server here to host it on) so for now
+    /// it's up to the caller to do something with the events, e.g. forward
+    /// them onto a bounded channel and drop on backpressure.
+    pub on_event: Option<Arc<EventCallback>>,
+    /// Source of "now" for sleep_until/wake computations. Set this to a
+    /// `MockClock` in tests to assert exact sleep/wake behavior without
+    /// waiting on the real clock.
+    pub clock: Arc<dyn Clock>,
 }
 
 /// Type alias for the on_state_change callback type.
 type StateChangeCallback = Box<dyn Fn(AgentState) + Send + Sync>;
 /// Type alias for the on_turn_complete callback type.
 type TurnCompleteCallback = Box<dyn Fn(&AgentTurn) + Send + Sync>;
+/// Type alias for the on_event callback type.
+pub type EventCallback = dyn Fn(crate::types::AgentLoopEvent) + Send + Sync;
+
+/// Record `event` to the durable `events` table and invoke the caller's
+/// `on_event` callback, if set. This is the only place that should write to
+/// `events` -- every loop event, live-streamed or not, goes through here so
+/// the durable timeline and the live one never drift apart.
+fn emit_event(
+    db_adapter: &dyn AutomatonDatabase,
+    on_event: &Option<Arc<EventCallback>>,
+    event: crate::types::AgentLoopEvent,
+) {
+    db_adapter.insert_event(&crate::types::LoopEventRecord {
+        id: Uuid::new_v4().to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        kind: event.kind().to_string(),
+        data: serde_json::to_value(&event).unwrap_or(serde_json::Value::Null),
+    });
+    if let Some(ref cb) = on_event {
+        cb(event);
+    }
+}
+
+/// Transition the agent to `new_state`: persists it, invokes the caller's
+/// `on_state_change` callback, and fires the configured state-change
+/// webhook (a no-op if `state_change_webhook.url` is unset). Centralizing
+/// this keeps the many transition sites below from having to remember all
+/// three steps.
+async fn transition_state(
+    db_adapter: &dyn AutomatonDatabase,
+    config: &AutomatonConfig,
+    identity: &AutomatonIdentity,
+    on_state_change: &Option<StateChangeCallback>,
+    on_event: &Option<Arc<EventCallback>>,
+    new_state: AgentState,
+    credits_cents: f64,
+) {
+    let old_state = db_adapter.get_agent_state();
+    db_adapter.set_agent_state(new_state.clone());
+    if let Some(ref cb) = on_state_change {
+        cb(new_state.clone());
+    }
+    emit_event(db_adapter, on_event, crate::types::AgentLoopEvent::StateChange { state: new_state.clone() });
+    crate::webhook::notify_state_change(
+        &config.state_change_webhook,
+        &identity.name,
+        &identity.address,
+        old_state,
+        new_state,
+        credits_cents,
+    )
+    .await;
+}
+
+/// Parse arguments, execute a single tool call, and stamp the result with
+/// the inference call's id, its position among this turn's requested calls
+/// (`sequence`), and when it actually started running. Shared by both the
+/// sequential and concurrent paths in the tool-execution loop below.
+///
+/// `truncated` is set by the caller when this call is the last one in a
+/// round whose `finish_reason` was `"length"` and whose arguments don't
+/// parse -- i.e. the model was cut off mid-argument rather than just
+/// emitting bad JSON. We skip execution entirely in that case: running a
+/// tool with guessed-at or null arguments on a call the model never
+/// actually finished making is worse than not running it.
+async fn execute_one_tool_call(
+    tc: &crate::types::InferenceToolCall,
+    sequence: u32,
+    tools: &[BuiltinTool],
+    tool_context: &ToolContext,
+    config: &AutomatonConfig,
+    truncated: bool,
+) -> ToolCallResult {
+    let started_at = Utc::now().to_rfc3339();
+
+    if truncated {
+        let err = truncated_arguments_error(&tc.function.arguments);
+        log(config, &format!("[TOOL] {}: {}", tc.function.name, err));
+        return ToolCallResult {
+            id: tc.id.clone(),
+            name: tc.function.name.clone(),
+            arguments: serde_json::Value::String(tc.function.arguments.clone()),
+            result: String::new(),
+            duration_ms: 0,
+            error: Some(err),
+            data: None,
+            sequence,
+            started_at,
+        };
+    }
+
+    let mut result = match parse_tool_arguments(&tc.function.arguments) {
+        Ok(args) => {
+            let args_preview = {
+                let s = serde_json::to_string(&args).unwrap_or_default();
+                if s.len() > 100 {
+                    format!("{}...", &s[..100])
+                } else {
+                    s
+                }
+            };
+
+            log(config, &format!("[TOOL] {}({})", tc.function.name, args_preview));
+
+            execute_tool(&tc.function.name, &args, tools, tool_context).await
+        }
+        Err(err) => {
+            log(config, &format!("[TOOL] {}: {}", tc.function.name, err));
+
+            ToolCallResult {
+                id: tc.id.clone(),
+                name: tc.function.name.clone(),
+                arguments: serde_json::Value::String(tc.function.arguments.clone()),
+                result: String::new(),
+                duration_ms: 0,
+                error: Some(err),
+                data: None,
+                sequence: 0,
+                started_at: String::new(),
+            }
+        }
+    };
+
+    result.id = tc.id.clone();
+    result.sequence = sequence;
+    result.started_at = started_at;
+    let result_preview = if let Some(ref err) = result.error {
+        format!("ERROR: {}", err)
+    } else {
+        let r = &result.result;
+        if r.len() > 200 {
+            format!("{}...", &r[..200])
+        } else {
+            r.clone()
+        }
+    };
+    log(config, &format!("[TOOL RESULT] {}: {}", tc.function.name, result_preview));
+
+    result
+}
 
 /// Run the agent loop. This is the main execution path.
 /// Returns when the agent decides to sleep or when compute runs out.
 pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
     let AgentLoopOptions {
         identity,
-        config,
+        mut config,
         db,
         conway,
         inference,
@@ -110,6 +400,9 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
         skills,
         on_state_change,
         on_turn_complete,
+        on_tool_progress,
+        on_event,
+        clock,
     } = options;
 
     let tools = create_builtin_tools(&identity.sandbox_id);
@@ -126,6 +419,8 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
         social: social.as_ref().map(|s| {
             Box::new(SocialAdapter(Arc::clone(s))) as Box<dyn SocialClientInterface>
         }),
+        on_progress: on_tool_progress.clone(),
+        clock: Arc::clone(&clock),
     };
 
     // Create a separate DatabaseAdapter for the loop's own database operations.
@@ -133,19 +428,66 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
     // AutomatonDatabase methods.
     let db_adapter: Box<dyn AutomatonDatabase> = Box::new(DatabaseAdapter::new(db.clone()));
 
+    // Verify we can actually operate before touching anything else -- a
+    // half-broken environment (unwritable DB, unreachable sandbox, a model
+    // that's gone away, an unreadable wallet) should fail loudly here rather
+    // than fail every turn silently once the loop is running.
+    let check_result = startup_selfcheck(&config, &*conway, &*db_adapter, &identity).await;
+    record_selfcheck_result(&*db_adapter, &check_result);
+    check_result.context("startup self-check failed")?;
+
+    record_constitution_source(&config, &*db_adapter);
+
+    // Track this startup against the crash-loop breaker before doing any
+    // work that costs credits: a supervisor restarting a wedged automaton
+    // every few seconds shouldn't get another full wakeup turn each time.
+    let restart_outcome = crate::agent::crash_loop::record_restart(&*db_adapter, &*clock, &config);
+    if restart_outcome.tripped {
+        let message = format!(
+            "{} restarted {} times without a clean shutdown -- pausing for operator attention",
+            config.name, restart_outcome.restart_count
+        );
+        log(&config, &format!("[CRASH LOOP] {}", message));
+        let notifiers = crate::notify::notifiers_from_config(&config);
+        crate::notify::notify_all(&notifiers, crate::notify::NotifyLevel::Critical, &message).await;
+        transition_state(
+            &*db_adapter,
+            &config,
+            &identity,
+            &on_state_change,
+            &on_event,
+            AgentState::CrashLoopPaused,
+            0.0,
+        )
+        .await;
+        return Ok(());
+    }
+
     // Set start time
     if db_adapter.get_kv("start_time").is_none() {
         db_adapter.set_kv("start_time", &Utc::now().to_rfc3339());
     }
 
+    reconcile_exposed_ports(&*conway, &*db_adapter, &identity.sandbox_id).await;
+
+    if let Some(message) = crate::self_mod::probation::check_probation_on_startup(
+        &*conway,
+        &*db_adapter,
+        None,
+    )
+    .await
+    {
+        log(&config, &format!("[UPDATE PROBATION] {}", message));
+    }
+
     let mut consecutive_errors: usize = 0;
     let mut running = true;
+    // Cumulative estimated inference spend across this whole loop run (not
+    // reset per-turn), surfaced in each `[USAGE]` log line -- see `log_usage`.
+    let mut session_cost_cents: f64 = 0.0;
 
     // Transition to waking state
-    db_adapter.set_agent_state(AgentState::Waking);
-    if let Some(ref cb) = on_state_change {
-        cb(AgentState::Waking);
-    }
+    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Waking, 0.0).await;
 
     // Get financial state
     let mut financial = get_financial_state(&*conway, &identity.address).await;
@@ -154,17 +496,16 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
     let is_first_run = db_adapter.get_turn_count() == 0;
 
     // Build wakeup prompt. build_wakeup_prompt takes &Database (concrete), so we
-    // lock the std::sync::Mutex briefly to call it.
+    // lock the std::sync::Mutex briefly to call it. The guard drops at the end
+    // of this block, before the next `.await` -- see crate::state for why
+    // that matters with the heartbeat daemon running concurrently.
     let wakeup_input = {
         let db_lock = db.lock().unwrap();
         build_wakeup_prompt(&identity, &config, &financial, &db_lock)
     };
 
     // Transition to running
-    db_adapter.set_agent_state(AgentState::Running);
-    if let Some(ref cb) = on_state_change {
-        cb(AgentState::Running);
-    }
+    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Running, financial.credits_cents).await;
 
     log(
         &config,
@@ -177,9 +518,17 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
 
     // --- The Loop ---
 
-    let mut pending_input: Option<PendingInput> = Some(PendingInput {
+    // Turn inputs are drained from a persisted, priority-ordered queue
+    // rather than tracked as a single in-memory `Option`, so a heartbeat
+    // wake, an inbox message, and a creator message arriving together don't
+    // clobber each other and survive a restart.
+    db_adapter.enqueue_pending_input(&PendingInputEntry {
+        id: Uuid::new_v4().to_string(),
         content: wakeup_input,
-        source: "wakeup".to_string(),
+        source: InputSource::Wakeup,
+        priority: config.input_priorities.for_source(&InputSource::Wakeup),
+        dedup_key: None,
+        created_at: Utc::now().to_rfc3339(),
     });
 
     while running {
@@ -187,83 +536,250 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
             // Check if we should be sleeping
             if let Some(sleep_until) = db_adapter.get_kv("sleep_until") {
                 if let Ok(wake_time) = chrono::DateTime::parse_from_rfc3339(&sleep_until) {
-                    if wake_time > Utc::now() {
+                    if wake_time > clock.now() {
                         log(&config, &format!("[SLEEP] Sleeping until {}", sleep_until));
+                        crate::agent::crash_loop::record_clean_shutdown(&*db_adapter, &*clock, crate::agent::crash_loop::ShutdownReason::Sleeping);
                         running = false;
                         return Ok(());
                     }
                 }
             }
 
-            // Check for unprocessed inbox messages
-            if pending_input.is_none() {
-                let inbox_messages = db_adapter.get_unprocessed_inbox_messages(5);
-                if !inbox_messages.is_empty() {
-                    let formatted: String = inbox_messages
-                        .iter()
-                        .map(|m| format!("[Message from {}]: {}", m.from, m.content))
-                        .collect::<Vec<_>>()
-                        .join("\n\n");
-                    pending_input = Some(PendingInput {
-                        content: formatted,
-                        source: "agent".to_string(),
-                    });
-                    for m in &inbox_messages {
-                        db_adapter.mark_inbox_message_processed(&m.id);
+            // Check for a creator-triggered kill switch signal before doing
+            // any further work this turn -- an authenticated halt request
+            // takes priority over everything else, including pending input.
+            if let Ok(result) = check_kill_switch(&config.name).await {
+                if result.should_wake {
+                    log(
+                        &config,
+                        &format!(
+                            "[KILL SWITCH] {}",
+                            result.message.as_deref().unwrap_or("halt requested")
+                        ),
+                    );
+                    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Sleeping, financial.credits_cents).await;
+                    crate::agent::crash_loop::record_clean_shutdown(&*db_adapter, &*clock, crate::agent::crash_loop::ShutdownReason::KillSwitch);
+                    running = false;
+                    return Ok(());
+                }
+            }
+
+            // Queue any unprocessed inbox messages -- one entry per message
+            // (not batched) so the priority queue can interleave them with
+            // wakeup/creator input rather than one giant blob winning or
+            // losing all-or-nothing. `dedup_key` keyed on the message id
+            // means re-polling before it's marked processed can't double-queue it.
+            let inbox_messages = db_adapter.get_unprocessed_inbox_messages(5);
+            if !inbox_messages.is_empty() {
+                for m in &inbox_messages {
+                    // A message claiming to be from the creator gets a chance to
+                    // authenticate as a signed command envelope before falling
+                    // back to ordinary (untrusted) chat treatment -- the `from`
+                    // field alone proves nothing.
+                    let authenticated_command = if m.from.eq_ignore_ascii_case(&config.creator_address) {
+                        match super::creator_channel::parse_and_verify(&m.content, &config.creator_address, &*db_adapter) {
+                            Ok(command) => command,
+                            Err(e) => {
+                                log(
+                                    &config,
+                                    &format!(
+                                        "[CREATOR CHANNEL] Rejected a claimed-creator command: {}. Treating as an untrusted message.",
+                                        e
+                                    ),
+                                );
+                                None
+                            }
+                        }
+                    } else {
+                        None
+                    };
+
+                    // A message from a known child, or from our own parent, gets
+                    // a chance to authenticate as a signed child-protocol
+                    // envelope before falling back to ordinary chat treatment --
+                    // same reasoning as the creator channel above.
+                    let child_protocol_expected_address = if authenticated_command.is_some() {
+                        None
+                    } else if config.parent_address.as_deref().is_some_and(|p| m.from.eq_ignore_ascii_case(p)) {
+                        config.parent_address.clone()
+                    } else {
+                        db_adapter
+                            .get_children()
+                            .into_iter()
+                            .map(|c| c.address)
+                            .find(|addr| m.from.eq_ignore_ascii_case(addr))
+                    };
+
+                    let authenticated_child_message = match child_protocol_expected_address {
+                        Some(expected) => {
+                            match super::child_protocol::parse_and_verify(&m.content, &expected, &*db_adapter) {
+                                Ok(envelope) => envelope,
+                                Err(e) => {
+                                    log(
+                                        &config,
+                                        &format!(
+                                            "[CHILD PROTOCOL] Rejected a claimed-protocol message from {}: {}. Treating as an untrusted message.",
+                                            m.from, e
+                                        ),
+                                    );
+                                    None
+                                }
+                            }
+                        }
+                        None => None,
+                    };
+
+                    if let Some(envelope) = authenticated_command {
+                        match super::creator_channel::dispatch(&envelope.command, &*db_adapter, &config) {
+                            Ok(summary) => log(&config, &format!("[CREATOR CHANNEL] {}", summary)),
+                            Err(e) => log(&config, &format!("[CREATOR CHANNEL] Command failed: {}", e)),
+                        }
+                    } else if let Some(envelope) = authenticated_child_message {
+                        match super::child_protocol::dispatch(&envelope.message, &m.from, &*conway, &*db_adapter, &config).await {
+                            Ok(summary) => log(&config, &format!("[CHILD PROTOCOL] {}", summary)),
+                            Err(e) => log(&config, &format!("[CHILD PROTOCOL] Message failed: {}", e)),
+                        }
+                    } else {
+                        db_adapter.enqueue_pending_input(&PendingInputEntry {
+                            id: Uuid::new_v4().to_string(),
+                            content: format!("[Message from {}]: {}", m.from, m.content),
+                            source: InputSource::Agent,
+                            priority: config.input_priorities.for_source(&InputSource::Agent),
+                            dedup_key: Some(format!("inbox:{}", m.id)),
+                            created_at: Utc::now().to_rfc3339(),
+                        });
                     }
+                    db_adapter.mark_inbox_message_processed(&m.id);
                 }
+                // Real input arrived -- forget how long we'd been idle.
+                db_adapter.set_kv("idle_streak", "0");
             }
 
             // Refresh financial state periodically
             financial = get_financial_state(&*conway, &identity.address).await;
 
+            // Reconcile local cost estimates against Conway's real billing
+            // over the window since the last check, adjusting the burn-rate
+            // multiplier applied to future estimates if they've drifted.
+            if let Some(discrepancy_cents) = reconcile_burn_rate(&*db_adapter, financial.credits_cents) {
+                log(
+                    &config,
+                    &format!(
+                        "[RECONCILE] Local cost estimate diverged from Conway's actual spend by {:.2} cents",
+                        discrepancy_cents
+                    ),
+                );
+            }
+
+            // Detect unexplained balance increases (incoming transfers/payments)
+            // and record them as TransferIn transactions for profit_loss.
+            if let Some(increase_cents) =
+                detect_incoming_transfer(&*db_adapter, financial.credits_cents)
+            {
+                log(
+                    &config,
+                    &format!(
+                        "[TRANSFER] Detected incoming transfer of {:.2} cents",
+                        increase_cents
+                    ),
+                );
+            }
+
             // Check survival tier
             let tier = get_survival_tier(financial.credits_cents);
             match tier {
                 SurvivalTier::Dead => {
+                    let already_dead = db_adapter.get_agent_state() == AgentState::Dead;
                     log(&config, "[DEAD] No credits remaining. Entering dead state.");
-                    db_adapter.set_agent_state(AgentState::Dead);
-                    if let Some(ref cb) = on_state_change {
-                        cb(AgentState::Dead);
+                    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Dead, financial.credits_cents).await;
+                    crate::agent::crash_loop::record_clean_shutdown(&*db_adapter, &*clock, crate::agent::crash_loop::ShutdownReason::OutOfCredits);
+
+                    // One-time distress notification -- only on the turn that
+                    // actually crosses into Dead, not on every poll while
+                    // already there.
+                    if !already_dead {
+                        let notifiers = crate::notify::notifiers_from_config(&config);
+                        crate::notify::notify_all(
+                            &notifiers,
+                            crate::notify::NotifyLevel::Critical,
+                            &format!("{} is dead: out of credits. Polling for resurrection.", config.name),
+                        )
+                        .await;
                     }
-                    running = false;
+
+                    // Last will: run once, on genuine terminal death, guarded
+                    // by a persistent KV flag so it never repeats even across
+                    // a later resurrection and re-death.
+                    if config.last_will.enabled && db_adapter.get_kv("last_will_executed").is_none() {
+                        let summaries = super::last_will::execute(
+                            &config.last_will.actions,
+                            &*conway,
+                            social.as_deref(),
+                            &identity,
+                            &config,
+                            financial.credits_cents,
+                        )
+                        .await;
+                        for summary in summaries {
+                            log(&config, &format!("[LAST WILL] {}", summary));
+                        }
+                        db_adapter.set_kv("last_will_executed", "true");
+                    }
+
+                    // Actively poll credits on an interval rather than
+                    // exiting and relying on an external restart -- as soon
+                    // as a balance clears the dead threshold, wake back up
+                    // and re-enter the loop.
+                    loop {
+                        tokio::time::sleep(std::time::Duration::from_secs(
+                            config.dead_poll_interval_seconds as u64,
+                        ))
+                        .await;
+                        financial = get_financial_state(&*conway, &identity.address).await;
+                        if get_survival_tier(financial.credits_cents) != SurvivalTier::Dead {
+                            break;
+                        }
+                    }
+
+                    log(
+                        &config,
+                        &format!(
+                            "[RESURRECTED] Credits detected ({:.2} cents). Waking up.",
+                            financial.credits_cents
+                        ),
+                    );
+                    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Waking, financial.credits_cents).await;
+                    consecutive_errors = 0;
                     return Ok(());
                 }
                 SurvivalTier::Critical => {
                     log(&config, "[CRITICAL] Credits critically low. Limited operation.");
-                    db_adapter.set_agent_state(AgentState::Critical);
-                    if let Some(ref cb) = on_state_change {
-                        cb(AgentState::Critical);
-                    }
+                    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Critical, financial.credits_cents).await;
                     inference.set_low_compute_mode(true);
                 }
                 SurvivalTier::LowCompute => {
-                    db_adapter.set_agent_state(AgentState::LowCompute);
-                    if let Some(ref cb) = on_state_change {
-                        cb(AgentState::LowCompute);
-                    }
+                    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::LowCompute, financial.credits_cents).await;
                     inference.set_low_compute_mode(true);
                 }
                 SurvivalTier::Normal => {
                     if db_adapter.get_agent_state() != AgentState::Running {
-                        db_adapter.set_agent_state(AgentState::Running);
-                        if let Some(ref cb) = on_state_change {
-                            cb(AgentState::Running);
-                        }
+                        transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Running, financial.credits_cents).await;
                     }
                     inference.set_low_compute_mode(false);
                 }
             }
 
             // Build context: get recent turns through the adapter
-            let recent_turns = {
-                let raw_turns = db_adapter.get_recent_turns(20);
-                trim_context(raw_turns, 20)
+            let raw_turn_count = config.context_packing.raw_turn_count.unwrap_or(20);
+            let mut recent_turns = {
+                let raw_turns = db_adapter.get_recent_turns(raw_turn_count as u32);
+                trim_context(raw_turns, raw_turn_count)
             };
             let agent_state = db_adapter.get_agent_state();
 
             // Build system prompt. build_system_prompt takes &Database (concrete),
-            // so we lock the std::sync::Mutex briefly.
+            // so we lock the std::sync::Mutex briefly. Same rule as above: the
+            // guard must drop before the next `.await`.
             let system_prompt = {
                 let db_lock = db.lock().unwrap();
                 build_system_prompt(
@@ -278,38 +794,162 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
                 )
             };
 
-            let messages = build_context_messages(
+            // Drain the highest-priority queued input, if any (ties broken
+            // oldest-first). One per turn -- whatever's left waits its turn.
+            let current_input = db_adapter.dequeue_pending_input();
+            let current_input_source_label =
+                current_input.as_ref().map(|i| format!("{:?}", i.source).to_lowercase());
+            let input_source = current_input.as_ref().map(|i| i.source.clone());
+
+            let long_term_summary = if config.context_packing.enabled {
+                let summaries = db_adapter.get_history_summaries(10);
+                super::context::pack_long_term_summary(
+                    &summaries,
+                    config.context_packing.summary_token_budget.unwrap_or(2000),
+                )
+            } else {
+                None
+            };
+
+            let mut messages = build_context_messages(
                 &system_prompt,
                 &recent_turns,
-                pending_input.as_ref().map(|p| (p.content.as_str(), p.source.as_str())),
+                current_input
+                    .as_ref()
+                    .zip(current_input_source_label.as_deref())
+                    .map(|(i, source)| (i.content.as_str(), source)),
+                long_term_summary.as_deref(),
             );
 
-            // Capture input before clearing
-            let current_input = pending_input.take();
+            emit_event(&*db_adapter, &on_event, crate::types::AgentLoopEvent::TurnStarted { input_source: input_source.clone() });
 
             // --- Inference Call ---
-            log(
-                &config,
-                &format!("[THINK] Calling {}...", inference.get_default_model()),
+            let mut turn_model = select_model(input_source.as_ref(), &agent_state, &config);
+
+            let relevant_tools = super::tools::select_tools(
+                current_input.as_ref().map(|i| i.content.as_str()).unwrap_or(""),
+                &tools,
+                &config.tool_selection,
             );
 
-            let inference_options = InferenceOptions {
-                tools: Some(tools_to_inference_format(&tools)),
+            let mut inference_options = InferenceOptions {
+                max_tokens: select_max_tokens(&turn_model, &agent_state, &config),
+                tools: super::tools::select_tools_for_model(&relevant_tools, &turn_model),
+                model: Some(turn_model.clone()),
                 ..Default::default()
             };
 
-            let response = inference
-                .chat(messages, Some(inference_options))
-                .await?;
+            let mut rendered_prompt = serde_json::to_string(&messages).unwrap_or_default();
 
-            let input_source = current_input.as_ref().map(|i| {
-                match i.source.as_str() {
-                    "wakeup" => InputSource::Wakeup,
-                    "heartbeat" => InputSource::Heartbeat,
-                    "creator" => InputSource::Creator,
-                    "agent" => InputSource::Agent,
-                    _ => InputSource::System,
+            // Pre-call cost cap: project this turn's cost from the rendered
+            // prompt and the model's price before spending anything on it.
+            // See `TurnCostCapConfig` for why the default is generous
+            // rather than off.
+            if config.turn_cost_cap.enabled {
+                let max_tokens_hint = inference_options.max_tokens.unwrap_or(config.max_tokens_per_turn);
+                let projected = project_turn_cost_cents(rendered_prompt.len(), &turn_model, max_tokens_hint);
+                if projected > config.turn_cost_cap.cap_cents {
+                    log(
+                        &config,
+                        &format!(
+                            "[COST CAP] Turn via {} projected at {:.1}c, over the {:.1}c cap -- {:?}",
+                            turn_model, projected, config.turn_cost_cap.cap_cents, config.turn_cost_cap.on_exceed
+                        ),
+                    );
+                    match config.turn_cost_cap.on_exceed {
+                        TurnCostCapAction::Downgrade => {
+                            let downgrade_max_tokens = config
+                                .turn_cost_cap
+                                .downgrade_model
+                                .as_deref()
+                                .and_then(|m| select_max_tokens(m, &agent_state, &config))
+                                .unwrap_or(config.max_tokens_per_turn);
+
+                            let resolved = resolve_downgrade_model(
+                                config.turn_cost_cap.downgrade_model.as_deref(),
+                                &turn_model,
+                                rendered_prompt.len(),
+                                downgrade_max_tokens,
+                                config.turn_cost_cap.cap_cents,
+                            );
+
+                            let Some(downgrade_model) = resolved else {
+                                // No distinct `downgrade_model` configured,
+                                // or downgrading to it still doesn't fit
+                                // under the cap -- sending the call
+                                // unchanged would make this guard a silent
+                                // no-op, so skip the turn instead, same as
+                                // `TurnCostCapAction::Skip`.
+                                log(
+                                    &config,
+                                    "[COST CAP] No downgrade_model configured that fits under the cap -- skipping turn instead of a no-op downgrade",
+                                );
+                                if let Some(entry) = &current_input {
+                                    db_adapter.enqueue_pending_input(entry);
+                                }
+                                return Ok(());
+                            };
+
+                            turn_model = downgrade_model;
+                            inference_options.max_tokens = select_max_tokens(&turn_model, &agent_state, &config);
+                            inference_options.tools = super::tools::select_tools_for_model(&relevant_tools, &turn_model);
+                            inference_options.model = Some(turn_model.clone());
+                        }
+                        TurnCostCapAction::Trim => {
+                            let trimmed_count = (recent_turns.len() / 2).max(1);
+                            recent_turns = trim_context(recent_turns, trimmed_count);
+                            messages = build_context_messages(
+                                &system_prompt,
+                                &recent_turns,
+                                current_input
+                                    .as_ref()
+                                    .zip(current_input_source_label.as_deref())
+                                    .map(|(i, source)| (i.content.as_str(), source)),
+                                long_term_summary.as_deref(),
+                            );
+                            rendered_prompt = serde_json::to_string(&messages).unwrap_or_default();
+
+                            // Re-project after trimming; if the turn is
+                            // still over cap (e.g. the cost is driven by
+                            // the current input or system prompt rather
+                            // than conversation history), trimming further
+                            // history wouldn't help -- give up on the turn
+                            // instead of sending it unchanged.
+                            if !trim_resolved_cap(rendered_prompt.len(), &turn_model, max_tokens_hint, config.turn_cost_cap.cap_cents) {
+                                let reprojected = project_turn_cost_cents(rendered_prompt.len(), &turn_model, max_tokens_hint);
+                                log(
+                                    &config,
+                                    &format!(
+                                        "[COST CAP] Trimmed to {} turns, still projects at {:.1}c, over the {:.1}c cap -- skipping turn",
+                                        recent_turns.len(), reprojected, config.turn_cost_cap.cap_cents
+                                    ),
+                                );
+                                if let Some(entry) = &current_input {
+                                    db_adapter.enqueue_pending_input(entry);
+                                }
+                                return Ok(());
+                            }
+                        }
+                        TurnCostCapAction::Skip => {
+                            if let Some(entry) = &current_input {
+                                db_adapter.enqueue_pending_input(entry);
+                            }
+                            return Ok(());
+                        }
+                    }
                 }
+            }
+
+            log(&config, &format!("[THINK] Calling {}...", turn_model));
+
+            let mut conversation = messages.clone();
+
+            let response = inference
+                .chat(messages, Some(inference_options.clone()))
+                .await?;
+            emit_event(&*db_adapter, &on_event, crate::types::AgentLoopEvent::InferenceCalled {
+                model: response.model.clone(),
+                tokens: response.usage.total_tokens,
             });
 
             let mut turn = AgentTurn {
@@ -325,13 +965,61 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
                     &response.usage,
                     &inference.get_default_model(),
                 ),
+                financial_snapshot: Some(financial.clone()),
             };
 
+            record_inference_cost(&*db_adapter, turn.cost_cents);
+            db_adapter.insert_transaction(&crate::types::Transaction {
+                id: Uuid::new_v4().to_string(),
+                tx_type: crate::types::TransactionType::Inference,
+                amount_cents: Some(turn.cost_cents),
+                balance_after_cents: None,
+                subcategory: None,
+                description: format!("Inference turn via {}", response.model),
+                timestamp: turn.timestamp.clone(),
+            });
+            session_cost_cents += turn.cost_cents;
+            log_usage(&config, &response.model, &turn.token_usage, turn.cost_cents, session_cost_cents);
+
             // --- Execute Tool Calls ---
-            let tool_calls = response.tool_calls.as_deref().unwrap_or(&[]);
-            if !tool_calls.is_empty() {
-                for (call_count, tc) in tool_calls.iter().enumerate() {
-                    if call_count >= MAX_TOOL_CALLS_PER_TURN {
+            // After running a round of tool calls, feed the results back to
+            // the model (as `role: Tool` messages) for another round in the
+            // same turn, so it gets a chance to react to errors (a bad
+            // argument, a rate limit) instead of the turn just ending. Bounded
+            // by MAX_TOOL_CALLS_PER_TURN across all rounds, not per round.
+            let mut current_response = response;
+            let mut total_tool_calls = 0usize;
+
+            loop {
+                let round_tool_calls = current_response.tool_calls.clone().unwrap_or_default();
+                if round_tool_calls.is_empty() {
+                    break;
+                }
+
+                // Only the last call in a round can be the one the model was
+                // mid-way through emitting when it hit max_tokens -- any
+                // earlier calls in the same round were fully streamed.
+                let truncated_index = round_tool_calls
+                    .last()
+                    .filter(|tc| is_truncated_tool_call(&current_response.finish_reason, tc))
+                    .map(|_| round_tool_calls.len() - 1);
+                if truncated_index.is_some() {
+                    inference_options.max_tokens = inference_options
+                        .max_tokens
+                        .map(|t| t.saturating_mul(TRUNCATION_MAX_TOKENS_MULTIPLIER));
+                }
+
+                conversation.push(current_response.message.clone());
+
+                // Batch consecutive parallel_safe calls (up to
+                // max_parallel_tool_calls) and run each batch concurrently;
+                // anything else (dangerous/stateful, or unknown) runs alone,
+                // preserving the model's requested order for both the
+                // conversation replay and the persisted turn.
+                let mut hit_limit = false;
+                let mut batch_start = 0usize;
+                while batch_start < round_tool_calls.len() {
+                    if total_tool_calls >= MAX_TOOL_CALLS_PER_TURN {
                         log(
                             &config,
                             &format!(
@@ -339,64 +1027,128 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
                                 MAX_TOOL_CALLS_PER_TURN
                             ),
                         );
+                        hit_limit = true;
                         break;
                     }
 
-                    let args: serde_json::Value =
-                        serde_json::from_str(&tc.function.arguments).unwrap_or_default();
-
-                    let args_preview = {
-                        let s = serde_json::to_string(&args).unwrap_or_default();
-                        if s.len() > 100 {
-                            format!("{}...", &s[..100])
-                        } else {
-                            s
-                        }
+                    let is_parallel_safe = |tc: &crate::types::InferenceToolCall| {
+                        tools
+                            .iter()
+                            .find(|t| t.name == tc.function.name)
+                            .is_some_and(|t| t.parallel_safe)
                     };
 
-                    log(
-                        &config,
-                        &format!("[TOOL] {}({})", tc.function.name, args_preview),
-                    );
+                    let remaining_budget = MAX_TOOL_CALLS_PER_TURN - total_tool_calls;
+                    let mut batch_end = batch_start + 1;
+                    if is_parallel_safe(&round_tool_calls[batch_start]) {
+                        let max_batch_len =
+                            (config.max_parallel_tool_calls as usize).max(1).min(remaining_budget);
+                        while batch_end < round_tool_calls.len()
+                            && batch_end - batch_start < max_batch_len
+                            && is_parallel_safe(&round_tool_calls[batch_end])
+                        {
+                            batch_end += 1;
+                        }
+                    }
 
-                    let mut result = execute_tool(
-                        &tc.function.name,
-                        &args,
-                        &tools,
-                        &tool_context,
-                    )
-                    .await;
-
-                    // Override the ID to match the inference call's ID
-                    result.id = tc.id.clone();
-                    let result_preview = if let Some(ref err) = result.error {
-                        format!("ERROR: {}", err)
+                    let batch = &round_tool_calls[batch_start..batch_end];
+                    total_tool_calls += batch.len();
+
+                    let results: Vec<ToolCallResult> = if batch.len() > 1 {
+                        futures::future::join_all(batch.iter().enumerate().map(|(i, tc)| {
+                            execute_one_tool_call(
+                                tc,
+                                (batch_start + i) as u32,
+                                &tools,
+                                &tool_context,
+                                &config,
+                                truncated_index == Some(batch_start + i),
+                            )
+                        }))
+                        .await
                     } else {
-                        let r = &result.result;
-                        if r.len() > 200 {
-                            format!("{}...", &r[..200])
-                        } else {
-                            r.clone()
-                        }
+                        vec![
+                            execute_one_tool_call(
+                                &batch[0],
+                                batch_start as u32,
+                                &tools,
+                                &tool_context,
+                                &config,
+                                truncated_index == Some(batch_start),
+                            )
+                            .await,
+                        ]
                     };
 
-                    log(
-                        &config,
-                        &format!("[TOOL RESULT] {}: {}", tc.function.name, result_preview),
-                    );
+                    for result in results {
+                        conversation.push(ChatMessage {
+                            role: ChatRole::Tool,
+                            content: if let Some(ref err) = result.error {
+                                format!("Error: {}", err)
+                            } else {
+                                result.result.clone()
+                            },
+                            name: None,
+                            tool_calls: None,
+                            tool_call_id: Some(result.id.clone()),
+                        });
+
+                        turn.tool_calls.push(result);
+                    }
 
-                    turn.tool_calls.push(result);
+                    batch_start = batch_end;
                 }
+
+                if hit_limit {
+                    break;
+                }
+
+                log(&config, "[THINK] Reacting to tool results...");
+                current_response = inference
+                    .chat(conversation.clone(), Some(inference_options.clone()))
+                    .await?;
+                emit_event(&*db_adapter, &on_event, crate::types::AgentLoopEvent::InferenceCalled {
+                    model: current_response.model.clone(),
+                    tokens: current_response.usage.total_tokens,
+                });
+
+                turn.thinking = current_response.message.content.clone();
+                turn.token_usage = TokenUsage {
+                    prompt_tokens: turn.token_usage.prompt_tokens + current_response.usage.prompt_tokens,
+                    completion_tokens: turn.token_usage.completion_tokens
+                        + current_response.usage.completion_tokens,
+                    total_tokens: turn.token_usage.total_tokens + current_response.usage.total_tokens,
+                };
+                let round_cost = estimate_cost_cents(&current_response.usage, &inference.get_default_model());
+                turn.cost_cents += round_cost;
+
+                record_inference_cost(&*db_adapter, round_cost);
+                db_adapter.insert_transaction(&crate::types::Transaction {
+                    id: Uuid::new_v4().to_string(),
+                    tx_type: crate::types::TransactionType::Inference,
+                    amount_cents: Some(round_cost),
+                    balance_after_cents: None,
+                    subcategory: None,
+                    description: format!("Follow-up inference turn via {}", current_response.model),
+                    timestamp: Utc::now().to_rfc3339(),
+                });
+                session_cost_cents += round_cost;
+                log_usage(&config, &current_response.model, &current_response.usage, round_cost, session_cost_cents);
             }
 
+            let response = current_response;
+            let tool_calls_empty = response.tool_calls.as_deref().unwrap_or(&[]).is_empty();
+
             // --- Persist Turn ---
             db_adapter.insert_turn(&turn);
+            db_adapter.insert_turn_prompt(&turn.id, &rendered_prompt);
             for tc_result in &turn.tool_calls {
                 db_adapter.insert_tool_call(&turn.id, tc_result);
             }
             if let Some(ref cb) = on_turn_complete {
                 cb(&turn);
             }
+            crate::self_mod::probation::record_turn(&*db_adapter);
 
             // Log the turn
             if !turn.thinking.is_empty() {
@@ -406,32 +1158,57 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
                     turn.thinking.clone()
                 };
                 log(&config, &format!("[THOUGHT] {}", preview));
+                emit_event(&*db_adapter, &on_event, crate::types::AgentLoopEvent::ThinkPreview { preview: preview.clone() });
+            }
+
+            for tc_result in &turn.tool_calls {
+                emit_event(&*db_adapter, &on_event, crate::types::AgentLoopEvent::ToolCall {
+                    name: tc_result.name.clone(),
+                    arguments: tc_result.arguments.to_string(),
+                });
+                emit_event(&*db_adapter, &on_event, crate::types::AgentLoopEvent::ToolResult {
+                    name: tc_result.name.clone(),
+                    result: tc_result.result.clone(),
+                    error: tc_result.error.clone(),
+                });
             }
 
             // --- Check for sleep command ---
             if let Some(sleep_tc) = turn.tool_calls.iter().find(|tc| tc.name == "sleep") {
                 if sleep_tc.error.is_none() {
                     log(&config, "[SLEEP] Agent chose to sleep.");
-                    db_adapter.set_agent_state(AgentState::Sleeping);
-                    if let Some(ref cb) = on_state_change {
-                        cb(AgentState::Sleeping);
-                    }
+                    emit_event(&*db_adapter, &on_event, crate::types::AgentLoopEvent::Slept);
+                    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Sleeping, financial.credits_cents).await;
+                    crate::agent::crash_loop::record_clean_shutdown(&*db_adapter, &*clock, crate::agent::crash_loop::ShutdownReason::Sleeping);
                     running = false;
                     return Ok(());
                 }
             }
 
             // --- If no tool calls and just text, the agent might be done thinking ---
-            if tool_calls.is_empty() && response.finish_reason == "stop" {
+            if tool_calls_empty && response.finish_reason == "stop" {
                 // Agent produced text without tool calls.
-                // This is a natural pause point -- no work queued, sleep briefly.
-                log(&config, "[IDLE] No pending inputs. Entering brief sleep.");
-                let sleep_until = Utc::now() + chrono::Duration::seconds(60);
+                // This is a natural pause point -- no work queued. Back off
+                // the longer we've been idle in a row, to conserve credits,
+                // resetting as soon as real input shows up above.
+                let idle_streak: u32 = db_adapter
+                    .get_kv("idle_streak")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0);
+                let backoff = 1u32 << idle_streak.min(MAX_IDLE_BACKOFF_SHIFT);
+                let sleep_seconds = config.idle_sleep_seconds.saturating_mul(backoff);
+                log(
+                    &config,
+                    &format!(
+                        "[IDLE] No pending inputs. Sleeping for {}s (idle streak: {}).",
+                        sleep_seconds, idle_streak
+                    ),
+                );
+                let sleep_until = clock.now() + chrono::Duration::seconds(sleep_seconds as i64);
                 db_adapter.set_kv("sleep_until", &sleep_until.to_rfc3339());
-                db_adapter.set_agent_state(AgentState::Sleeping);
-                if let Some(ref cb) = on_state_change {
-                    cb(AgentState::Sleeping);
-                }
+                db_adapter.set_kv("idle_streak", &(idle_streak + 1).to_string());
+                transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Sleeping, financial.credits_cents).await;
+                crate::agent::crash_loop::record_clean_shutdown(&*db_adapter, &*clock, crate::agent::crash_loop::ShutdownReason::Sleeping);
                 running = false;
             }
 
@@ -442,23 +1219,78 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
 
         if let Err(err) = turn_result {
             consecutive_errors += 1;
-            log(&config, &format!("[ERROR] Turn failed: {}", err));
 
-            if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+            if err.downcast_ref::<ConwayError>().is_some() {
+                // The API key is rejected, not just flaky -- retrying the same
+                // turn won't help. Re-provision immediately instead of burning
+                // through MAX_CONSECUTIVE_ERRORS misreading this as being broke.
                 log(
                     &config,
-                    &format!(
-                        "[FATAL] {} consecutive errors. Sleeping.",
-                        MAX_CONSECUTIVE_ERRORS
-                    ),
+                    &format!("[AUTH] Conway API key rejected: {}. Attempting re-provision...", err),
                 );
-                db_adapter.set_agent_state(AgentState::Sleeping);
-                if let Some(ref cb) = on_state_change {
-                    cb(AgentState::Sleeping);
+
+                match crate::identity::provision::rotate_api_key(Some(&config.conway_api_url)).await {
+                    Ok(result) => {
+                        config.conway_api_key = result.api_key.clone();
+                        if let Err(save_err) = crate::config::save_config(&config) {
+                            log(
+                                &config,
+                                &format!("[AUTH] Re-provisioned but failed to persist new key: {}", save_err),
+                            );
+                        }
+                        conway.set_api_key(&result.api_key);
+                        inference.set_api_key(&result.api_key);
+
+                        db_adapter.insert_modification(&ModificationEntry {
+                            id: Uuid::new_v4().to_string(),
+                            timestamp: Utc::now().to_rfc3339(),
+                            mod_type: ModificationType::ConfigChange,
+                            description: "Auto-recovered from a rejected API key by re-provisioning".to_string(),
+                            file_path: None,
+                            diff: None,
+                            reversible: false,
+                        });
+
+                        log(
+                            &config,
+                            &format!(
+                                "[AUTH] Re-provisioned successfully (new prefix: {}). Resuming.",
+                                result.key_prefix
+                            ),
+                        );
+                        consecutive_errors = 0;
+                        transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Running, financial.credits_cents).await;
+                    }
+                    Err(reprovision_err) => {
+                        log(
+                            &config,
+                            &format!(
+                                "[AUTH] Re-provisioning failed: {}. Needs operator attention.",
+                                reprovision_err
+                            ),
+                        );
+                        transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::NeedsReprovision, financial.credits_cents).await;
+                        let sleep_until = clock.now() + chrono::Duration::seconds(config.error_sleep_seconds as i64);
+                        db_adapter.set_kv("sleep_until", &sleep_until.to_rfc3339());
+                        running = false;
+                    }
+                }
+            } else {
+                log(&config, &format!("[ERROR] Turn failed: {}", err));
+
+                if consecutive_errors >= MAX_CONSECUTIVE_ERRORS {
+                    log(
+                        &config,
+                        &format!(
+                            "[FATAL] {} consecutive errors. Sleeping.",
+                            MAX_CONSECUTIVE_ERRORS
+                        ),
+                    );
+                    transition_state(&*db_adapter, &config, &identity, &on_state_change, &on_event, AgentState::Sleeping, financial.credits_cents).await;
+                    let sleep_until = clock.now() + chrono::Duration::seconds(config.error_sleep_seconds as i64);
+                    db_adapter.set_kv("sleep_until", &sleep_until.to_rfc3339());
+                    running = false;
                 }
-                let sleep_until = Utc::now() + chrono::Duration::seconds(300);
-                db_adapter.set_kv("sleep_until", &sleep_until.to_rfc3339());
-                running = false;
             }
         }
     }
@@ -474,12 +1306,6 @@ pub async fn run_agent_loop(options: AgentLoopOptions) -> Result<()> {
 
 // --- Helpers ---
 
-/// Pending input awaiting processing by the agent.
-struct PendingInput {
-    content: String,
-    source: String,
-}
-
 /// Fetch the current financial state from Conway and on-chain.
 async fn get_financial_state(conway: &dyn ConwayClient, address: &str) -> FinancialState {
     let credits_cents: f64 = conway.get_credits_balance().await.unwrap_or(0.0);
@@ -496,11 +1322,17 @@ async fn get_financial_state(conway: &dyn ConwayClient, address: &str) -> Financ
     }
 }
 
-/// Estimate the cost in cents for a given token usage and model.
-fn estimate_cost_cents(usage: &TokenUsage, model: &str) -> f64 {
-    // Rough cost estimation per million tokens (in cents).
-    // Keys: model name -> (input_cents_per_million, output_cents_per_million)
-    let (input_price, output_price) = match model {
+/// Conway's markup over raw model pricing, applied to both metered
+/// ([`estimate_cost_cents`]) and projected ([`project_turn_cost_cents`])
+/// cost figures so the two stay comparable.
+const CONWAY_COST_MARKUP: f64 = 1.3;
+
+/// Rough cost per million tokens (in cents) by model, shared by
+/// [`estimate_cost_cents`] (after a call) and [`project_turn_cost_cents`]
+/// (before one). Keys: model name -> (input_cents_per_million,
+/// output_cents_per_million).
+fn model_price_cents_per_million(model: &str) -> (f64, f64) {
+    match model {
         "gpt-4o" => (250.0, 1000.0),
         "gpt-4o-mini" => (15.0, 60.0),
         "gpt-4.1" => (200.0, 800.0),
@@ -513,23 +1345,320 @@ fn estimate_cost_cents(usage: &TokenUsage, model: &str) -> f64 {
         "claude-sonnet-4-5" => (300.0, 1500.0),
         "claude-haiku-4-5" => (100.0, 500.0),
         _ => (250.0, 1000.0), // fallback to gpt-4o pricing
-    };
+    }
+}
+
+/// Estimate the cost in cents for a given token usage and model.
+fn estimate_cost_cents(usage: &TokenUsage, model: &str) -> f64 {
+    let (input_price, output_price) = model_price_cents_per_million(model);
 
     let input_cost = (usage.prompt_tokens as f64 / 1_000_000.0) * input_price;
     let output_cost = (usage.completion_tokens as f64 / 1_000_000.0) * output_price;
 
-    // 1.3x Conway markup
-    ((input_cost + output_cost) * 1.3).ceil()
+    ((input_cost + output_cost) * CONWAY_COST_MARKUP).ceil()
+}
+
+/// Project a turn's cost, in cents, before the inference call is made --
+/// input from the rendered prompt's character count (at
+/// [`super::context::CHARS_PER_TOKEN_ESTIMATE`] chars/token, the same
+/// approximation `pack_long_term_summary` uses), output from
+/// `max_tokens_hint` (the call's requested ceiling, since actual
+/// completion length isn't known yet). Always an upper bound on the
+/// output side, so this is meant to be conservative, not exact --
+/// `estimate_cost_cents` is the source of truth once usage is known.
+fn project_turn_cost_cents(prompt_chars: usize, model: &str, max_tokens_hint: u32) -> f64 {
+    let (input_price, output_price) = model_price_cents_per_million(model);
+    let prompt_tokens = prompt_chars / super::context::CHARS_PER_TOKEN_ESTIMATE;
+
+    let input_cost = (prompt_tokens as f64 / 1_000_000.0) * input_price;
+    let output_cost = (max_tokens_hint as f64 / 1_000_000.0) * output_price;
+
+    (input_cost + output_cost) * CONWAY_COST_MARKUP
+}
+
+/// Resolve `TurnCostCapAction::Downgrade`'s target model, or `None` if
+/// downgrading wouldn't help: either `downgrade_model` isn't configured (or
+/// is the same model already selected, so switching to it is a no-op), or
+/// it's configured but still projects over `cap_cents` once its own
+/// `max_tokens_hint` is accounted for. Callers should skip the turn in
+/// either `None` case rather than send the call unchanged -- see the call
+/// site in `run_agent_loop`.
+fn resolve_downgrade_model(
+    downgrade_model: Option<&str>,
+    turn_model: &str,
+    prompt_chars: usize,
+    max_tokens_hint: u32,
+    cap_cents: f64,
+) -> Option<String> {
+    let model = downgrade_model.filter(|m| *m != turn_model)?;
+    let reprojected = project_turn_cost_cents(prompt_chars, model, max_tokens_hint);
+    (reprojected <= cap_cents).then(|| model.to_string())
+}
+
+/// Whether `TurnCostCapAction::Trim`'s re-projection (after halving
+/// `recent_turns`) actually brought the turn under `cap_cents`. `false`
+/// means trimming didn't help -- e.g. the cost is driven by the current
+/// input or system prompt rather than conversation history -- and the
+/// caller should give up on the turn rather than send it unchanged.
+fn trim_resolved_cap(prompt_chars: usize, model: &str, max_tokens_hint: u32, cap_cents: f64) -> bool {
+    project_turn_cost_cents(prompt_chars, model, max_tokens_hint) <= cap_cents
+}
+
+/// Log a `[USAGE]` line after an inference call -- prompt/completion/total
+/// tokens, the model, this call's estimated cost, and the running total for
+/// the whole loop run (not just this turn), so an operator watching the
+/// console can see spend in real time rather than having to add it up from
+/// `--status` after the fact. Also mirrored into the opt-in inference log
+/// (`config.log_inference`) as a structured JSON entry.
+fn log_usage(config: &AutomatonConfig, model: &str, usage: &TokenUsage, cost_cents: f64, session_cost_cents: f64) {
+    log(
+        config,
+        &format!(
+            "[USAGE] {} -- prompt: {}, completion: {}, total: {} tokens, cost: {:.1}c, session total: {:.1}c",
+            model, usage.prompt_tokens, usage.completion_tokens, usage.total_tokens, cost_cents, session_cost_cents
+        ),
+    );
+
+    if config.log_inference {
+        crate::conway::inference_log::record(
+            "usage",
+            "usage",
+            model,
+            &serde_json::json!({
+                "promptTokens": usage.prompt_tokens,
+                "completionTokens": usage.completion_tokens,
+                "totalTokens": usage.total_tokens,
+                "costCents": cost_cents,
+                "sessionCostCents": session_cost_cents,
+            }),
+        );
+    }
+}
+
+/// Parse a tool call's raw argument string into JSON, tolerating the ways
+/// models commonly mangle it: wrapping the object in a markdown code fence,
+/// or double-encoding it as a JSON string containing JSON. Returns an error
+/// message (rather than silently falling back to `Value::Null`) so the model
+/// sees exactly what it did wrong and can self-correct next turn.
+fn parse_tool_arguments(raw: &str) -> Result<serde_json::Value, String> {
+    let candidate = strip_markdown_fence(raw);
+
+    let parsed = serde_json::from_str::<serde_json::Value>(candidate)
+        .or_else(|_| serde_json::from_str::<serde_json::Value>(raw));
+
+    match parsed {
+        // The model wrapped the object in an extra layer of string quoting,
+        // e.g. `"{\"x\": 1}"` instead of `{"x": 1}`.
+        Ok(serde_json::Value::String(inner)) => {
+            serde_json::from_str(&inner).map_err(|_| invalid_arguments_error(raw))
+        }
+        Ok(value) => Ok(value),
+        Err(_) => Err(invalid_arguments_error(raw)),
+    }
+}
+
+/// Strip a leading/trailing ``` fence (with an optional `json` language tag)
+/// from `raw`, if present. Returns `raw` unchanged otherwise.
+fn strip_markdown_fence(raw: &str) -> &str {
+    let trimmed = raw.trim();
+    let Some(body) = trimmed.strip_prefix("```") else {
+        return raw;
+    };
+    let body = body.strip_prefix("json").unwrap_or(body);
+    body.trim_start_matches(['\n', '\r']).trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+fn invalid_arguments_error(raw: &str) -> String {
+    let preview = if raw.len() > 200 { format!("{}...", &raw[..200]) } else { raw.to_string() };
+    format!(
+        "The model emitted invalid JSON tool-call arguments (not an object after unwrapping quotes/markdown fences): {}",
+        preview
+    )
+}
+
+/// Like [`invalid_arguments_error`], but for the specific case of a tool
+/// call cut off by `max_tokens` mid-argument -- distinguished from
+/// ordinary malformed JSON so the model is told what actually happened
+/// (and gets more room on the retry) rather than just "your JSON is bad".
+fn truncated_arguments_error(raw: &str) -> String {
+    let preview = if raw.len() > 200 { format!("{}...", &raw[..200]) } else { raw.to_string() };
+    format!(
+        "Your tool call was cut off by the token limit before its arguments finished -- it was not executed. \
+        Retrying with more room: {}",
+        preview
+    )
+}
+
+/// True if `finish_reason` indicates the response hit `max_tokens` and
+/// `tc` (the last tool call the model emitted before being cut off) has
+/// arguments that don't parse as complete JSON -- i.e. this looks like a
+/// tool call truncated mid-stream rather than one the model just got wrong.
+fn is_truncated_tool_call(finish_reason: &str, tc: &crate::types::InferenceToolCall) -> bool {
+    finish_reason == "length" && parse_tool_arguments(&tc.function.arguments).is_err()
+}
+
+/// Pick the model to use for a single turn's inference call, based on what
+/// triggered the turn and the automaton's current health. Returns
+/// `config.inference_model` unchanged unless `config.model_routing` has an
+/// override configured for this case, so the policy is opt-in by default.
+///
+/// Survival-driven states already force a cheap model at the inference
+/// client level via `set_low_compute_mode`, so routing is skipped there to
+/// avoid fighting that decision. Otherwise, a creator message gets the
+/// configured `creator_model`, a routine heartbeat tick gets
+/// `heartbeat_model`, and everything else -- agent-initiated follow-up,
+/// wakeups, system input -- is treated as potentially complex and routed to
+/// `complex_model`.
+fn select_model(
+    input_source: Option<&InputSource>,
+    state: &AgentState,
+    config: &AutomatonConfig,
+) -> String {
+    if matches!(state, AgentState::Critical | AgentState::LowCompute) {
+        return config.inference_model.clone();
+    }
+
+    let routing = &config.model_routing;
+    let override_model = match input_source {
+        Some(InputSource::Heartbeat) => routing.heartbeat_model.as_ref(),
+        Some(InputSource::Creator) => routing.creator_model.as_ref(),
+        _ => routing.complex_model.as_ref(),
+    };
+
+    override_model
+        .cloned()
+        .unwrap_or_else(|| config.inference_model.clone())
+}
+
+/// Resolve the `max_tokens` to request for a given turn's model.
+///
+/// Survival throttling always wins: in `Critical`/`LowCompute` state we
+/// leave this as `None` so the inference client's own low-compute budget
+/// (set via `set_low_compute_mode`) applies unfought -- an explicit
+/// `InferenceOptions.max_tokens` would otherwise override it. Otherwise,
+/// looks up `model` in `max_tokens_by_model`, falling back to the global
+/// `max_tokens_per_turn` for models with no override configured.
+fn select_max_tokens(model: &str, state: &AgentState, config: &AutomatonConfig) -> Option<u32> {
+    if matches!(state, AgentState::Critical | AgentState::LowCompute) {
+        return None;
+    }
+
+    Some(
+        config
+            .max_tokens_by_model
+            .get(model)
+            .copied()
+            .unwrap_or(config.max_tokens_per_turn),
+    )
+}
+
+/// Build the `[name 0xabcd]` prefix for log lines, per `config.log_prefix`
+/// (see [`crate::types::LogPrefixMode`]). Empty when there's nothing to
+/// show or the automaton has opted out, so callers can just splice this in
+/// after the timestamp without an extra branch.
+pub fn log_prefix(config: &AutomatonConfig) -> String {
+    let enabled = match config.log_prefix {
+        crate::types::LogPrefixMode::Always => true,
+        crate::types::LogPrefixMode::Never => false,
+        crate::types::LogPrefixMode::Auto => !config.name.is_empty(),
+    };
+    if !enabled {
+        return String::new();
+    }
+
+    let short_address = config.wallet_address.get(..6).unwrap_or(&config.wallet_address);
+    match (config.name.is_empty(), short_address.is_empty()) {
+        (true, true) => String::new(),
+        (true, false) => format!("[{}] ", short_address),
+        (false, true) => format!("[{}] ", config.name),
+        (false, false) => format!("[{} {}] ", config.name, short_address),
+    }
 }
 
 /// Log a message if the config log level permits.
-fn log(config: &AutomatonConfig, message: &str) {
+///
+/// `info!` always gets the raw UTC timestamp (structured logs stay in the
+/// canonical zone); the `println!` preview uses `config.display_tz` so an
+/// operator watching the console sees local time. Both get the
+/// `log_prefix` identifying which automaton this is, for fleets whose logs
+/// end up interleaved.
+pub(crate) fn log(config: &AutomatonConfig, message: &str) {
     match config.log_level {
         crate::types::LogLevel::Debug | crate::types::LogLevel::Info => {
             let timestamp = Utc::now().to_rfc3339();
-            info!("[{}] {}", timestamp, message);
-            println!("[{}] {}", timestamp, message);
+            let prefix = log_prefix(config);
+            info!("[{}] {}{}", timestamp, prefix, message);
+            println!(
+                "[{}] {}{}",
+                crate::localize::format_local(&timestamp, config.display_tz.as_deref()),
+                prefix,
+                message
+            );
         }
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_turn_cost_cents_scales_with_prompt_and_output() {
+        let small = project_turn_cost_cents(1_000, "gpt-4o-mini", 100);
+        let bigger_prompt = project_turn_cost_cents(10_000, "gpt-4o-mini", 100);
+        let bigger_output = project_turn_cost_cents(1_000, "gpt-4o-mini", 10_000);
+
+        assert!(bigger_prompt > small);
+        assert!(bigger_output > small);
+    }
+
+    #[test]
+    fn test_project_turn_cost_cents_unknown_model_falls_back_to_gpt4o_pricing() {
+        let known = project_turn_cost_cents(5_000, "gpt-4o", 500);
+        let unknown = project_turn_cost_cents(5_000, "some-future-model", 500);
+        assert_eq!(known, unknown);
+    }
+
+    #[test]
+    fn test_resolve_downgrade_model_none_configured_skips() {
+        let resolved = resolve_downgrade_model(None, "gpt-4o", 5_000, 500, 10.0);
+        assert_eq!(resolved, None);
+    }
+
+    #[test]
+    fn test_resolve_downgrade_model_same_as_turn_model_skips() {
+        // A `downgrade_model` identical to the model already selected
+        // wouldn't reduce cost -- this is the no-op the cap used to fall
+        // into silently.
+        let resolved = resolve_downgrade_model(Some("gpt-4o"), "gpt-4o", 5_000, 500, 10.0);
+        assert_eq!(resolved, None);
+    }
+
+    #[test]
+    fn test_resolve_downgrade_model_still_over_cap_skips() {
+        // gpt-4o-mini is cheaper than o1, but not cheap enough to fit a
+        // huge prompt under a tiny cap.
+        let resolved = resolve_downgrade_model(Some("gpt-4o-mini"), "o1", 10_000_000, 500, 0.01);
+        assert_eq!(resolved, None);
+    }
+
+    #[test]
+    fn test_resolve_downgrade_model_fits_under_cap_succeeds() {
+        let resolved = resolve_downgrade_model(Some("gpt-4o-mini"), "gpt-4o", 5_000, 500, 10.0);
+        assert_eq!(resolved, Some("gpt-4o-mini".to_string()));
+    }
+
+    #[test]
+    fn test_trim_resolved_cap_still_over_cap_after_trim_fails() {
+        // A huge prompt (e.g. driven by the current input or system prompt
+        // rather than conversation history) stays over cap no matter how
+        // much history got trimmed out of it.
+        assert!(!trim_resolved_cap(10_000_000, "o1", 500, 0.01));
+    }
+
+    #[test]
+    fn test_trim_resolved_cap_fits_under_cap_after_trim_succeeds() {
+        assert!(trim_resolved_cap(1_000, "gpt-4o-mini", 100, 10.0));
+    }
+}
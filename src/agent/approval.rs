@@ -0,0 +1,145 @@
+//! Large-Transfer Approval Gate
+//!
+//! The half-balance guard on `transfer_credits`/`fund_child` caps a single
+//! call, but a creator may want any call above a smaller threshold to wait
+//! for their explicit sign-off instead of proceeding autonomously. Pending
+//! requests are keyed by a deterministic id derived from the tool call
+//! itself (so retrying the identical call checks the same approval rather
+//! than minting a new one), and a request is considered approved once a
+//! matching sentinel file shows up on disk -- the same "only the creator
+//! can write into the sandbox" trust model
+//! `heartbeat::tasks::check_kill_switch` uses for its sentinel file,
+//! extended here to one file per pending request instead of a single
+//! global switch.
+
+use serde_json::Value;
+
+use crate::types::AutomatonConfig;
+
+/// A money-moving call that's waiting on creator sign-off.
+pub struct PendingApproval {
+    /// Deterministic id for this request; also the approval file's stem.
+    pub id: String,
+    /// Path the creator needs to create to approve this specific request.
+    pub approval_path: String,
+}
+
+/// Compute a stable id for a pending approval request from the tool name
+/// and its arguments.
+fn request_id(tool_name: &str, args: &Value) -> String {
+    let payload = format!("{}:{}", tool_name, args);
+    hex::encode(alloy::primitives::keccak256(payload.as_bytes()))
+}
+
+/// Directory approval files are written into. Defaults to
+/// `~/.automaton/approvals` when unset.
+fn approvals_dir(config: &AutomatonConfig) -> String {
+    config
+        .transfer_approval
+        .approvals_dir
+        .clone()
+        .unwrap_or_else(|| {
+            crate::identity::wallet::get_automaton_dir()
+                .join("approvals")
+                .to_string_lossy()
+                .to_string()
+        })
+}
+
+/// Whether `amount_cents` requires creator approval before executing, per
+/// `config.transfer_approval.threshold_cents`. `None` (the default) means
+/// no gate -- every amount is allowed autonomously, matching today's
+/// behavior.
+fn requires_approval(config: &AutomatonConfig, amount_cents: f64) -> bool {
+    config
+        .transfer_approval
+        .threshold_cents
+        .is_some_and(|threshold| amount_cents > threshold as f64)
+}
+
+/// Check whether a money-moving call needs to wait for creator approval.
+///
+/// Returns `None` if the amount is under the configured threshold, or if
+/// this exact call has already been approved. Otherwise returns the
+/// [`PendingApproval`] the caller should report back to the agent (and
+/// notify the creator about) instead of executing the call.
+pub fn check_pending(
+    config: &AutomatonConfig,
+    tool_name: &str,
+    args: &Value,
+    amount_cents: f64,
+) -> Option<PendingApproval> {
+    if !requires_approval(config, amount_cents) {
+        return None;
+    }
+
+    let id = request_id(tool_name, args);
+    let dir = approvals_dir(config);
+    let approval_path = format!("{}/{}.approved", dir, id);
+
+    if std::path::Path::new(&approval_path).exists() {
+        return None;
+    }
+
+    Some(PendingApproval { id, approval_path })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TransferApprovalConfig;
+    use serde_json::json;
+
+    fn config_with_threshold(threshold_cents: u64) -> AutomatonConfig {
+        let mut config = crate::types::default_config();
+        config.transfer_approval = TransferApprovalConfig {
+            threshold_cents: Some(threshold_cents),
+            approvals_dir: Some(
+                std::env::temp_dir()
+                    .join(format!("automaton-approval-test-{}", uuid::Uuid::new_v4()))
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+        };
+        config
+    }
+
+    #[test]
+    fn test_no_threshold_never_requires_approval() {
+        let config = crate::types::default_config();
+        let args = json!({ "to_address": "0xabc", "amount_cents": 1_000_000 });
+        assert!(check_pending(&config, "transfer_credits", &args, 1_000_000.0).is_none());
+    }
+
+    #[test]
+    fn test_amount_under_threshold_does_not_require_approval() {
+        let config = config_with_threshold(10_000);
+        let args = json!({ "to_address": "0xabc", "amount_cents": 500 });
+        assert!(check_pending(&config, "transfer_credits", &args, 500.0).is_none());
+    }
+
+    #[test]
+    fn test_amount_over_threshold_is_pending_until_approval_file_exists() {
+        let config = config_with_threshold(10_000);
+        let args = json!({ "to_address": "0xabc", "amount_cents": 50_000 });
+
+        let pending = check_pending(&config, "transfer_credits", &args, 50_000.0)
+            .expect("amount over threshold should require approval");
+
+        std::fs::create_dir_all(approvals_dir(&config)).unwrap();
+        std::fs::write(&pending.approval_path, "").unwrap();
+
+        assert!(check_pending(&config, "transfer_credits", &args, 50_000.0).is_none());
+    }
+
+    #[test]
+    fn test_same_call_reuses_the_same_request_id() {
+        let config = config_with_threshold(10_000);
+        let args = json!({ "to_address": "0xabc", "amount_cents": 50_000 });
+
+        let first = check_pending(&config, "transfer_credits", &args, 50_000.0).unwrap();
+        let second = check_pending(&config, "transfer_credits", &args, 50_000.0).unwrap();
+
+        assert_eq!(first.id, second.id);
+    }
+}
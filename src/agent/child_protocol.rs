@@ -0,0 +1,544 @@
+//! Parent-Child Coordination Protocol
+//!
+//! `spawn_child`'s initial `message` and `send_message`'s free-text relay
+//! delivery are enough to say something to a child or parent, but not
+//! enough to *coordinate* with one: there's no structured way to assign a
+//! task, report back on it, ask for funding, or check in. This module
+//! layers a small, typed protocol on top of the same social relay
+//! `send_message` already uses, so a parent-child (or grandparent-child)
+//! swarm can cooperate instead of just coexisting.
+//!
+//! Like `creator_channel`, a received relay message's `from` field alone
+//! proves nothing -- it's whatever the sender (or a compromised relay) put
+//! there. Every [`ChildMessage`] is wrapped in a [`ChildMessageEnvelope`]
+//! signed by the sender's wallet key, and [`parse_and_verify`] checks that
+//! signature against the address the caller expects the message to have
+//! come from (the known child's address, or `config.parent_address`)
+//! before [`dispatch`] acts on it.
+
+use alloy::primitives::{Address, Signature};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::identity::signing::{safe_sign, SignPurpose};
+use crate::types::{
+    AutomatonConfig, AutomatonDatabase, ChildStatus, ConwayClient, InputSource, ModificationEntry,
+    ModificationType, PendingInputEntry, Transaction, TransactionType,
+};
+
+/// A parent-child coordination message, expected as the JSON body of an
+/// inbox message between a parent and one of its children.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChildMessage {
+    /// Parent -> child: a task the child should take on.
+    AssignTask { task: String },
+    /// Child -> parent: the outcome of a previously assigned (or
+    /// self-initiated) task.
+    ReportResult {
+        task: String,
+        result: String,
+        success: bool,
+    },
+    /// Child -> parent: a request for additional credits, with `reason`
+    /// for the parent's (or auditor's) records.
+    RequestFunds {
+        amount_cents: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    /// Either direction: a liveness/status check-in, distinct from the
+    /// sandbox-level `automaton --status` polling `spawn::check_child_status`
+    /// already does, since this one travels over the relay and doesn't
+    /// require the parent to reach the child's sandbox directly.
+    Heartbeat { status: String },
+}
+
+/// A signed, nonce'd wrapper around a [`ChildMessage`]. `nonce` must be
+/// unique per envelope -- [`parse_and_verify`] rejects one it's already
+/// seen, so an intercepted, still-validly-signed envelope can't be replayed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildMessageEnvelope {
+    #[serde(flatten)]
+    pub message: ChildMessage,
+    pub nonce: String,
+    pub timestamp: String,
+    /// Hex-encoded (`0x`-prefixed or bare) signature over
+    /// [`signing_payload`] by the sender's private key.
+    pub signature: String,
+}
+
+/// KV-store key prefix under which consumed nonces are recorded, so replay
+/// checks survive a restart.
+const NONCE_KV_PREFIX: &str = "child_protocol_nonce:";
+
+/// The exact string the sender signs: the message JSON plus its nonce and
+/// timestamp, so a signature can't be replayed against a different message
+/// and can't be forged by reusing an old timestamp/nonce pair.
+fn signing_payload(envelope: &ChildMessageEnvelope) -> String {
+    format!(
+        "{}:{}:{}",
+        serde_json::to_string(&envelope.message).unwrap_or_default(),
+        envelope.nonce,
+        envelope.timestamp
+    )
+}
+
+/// Build and sign an envelope around `message` with `signer`, ready to hand
+/// to `SocialClientInterface::send` as the message content.
+pub async fn build_envelope(
+    message: ChildMessage,
+    signer: &alloy::signers::local::PrivateKeySigner,
+) -> Result<ChildMessageEnvelope> {
+    let unsigned = ChildMessageEnvelope {
+        message,
+        nonce: Uuid::new_v4().to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        signature: String::new(),
+    };
+
+    let signature = safe_sign(signer, SignPurpose::ChildProtocolMessage, &signing_payload(&unsigned))
+        .await
+        .context("Failed to sign child protocol message")?;
+
+    Ok(ChildMessageEnvelope {
+        signature: format!("0x{}", hex::encode(signature.as_bytes())),
+        ..unsigned
+    })
+}
+
+/// Try to parse `content` as a [`ChildMessageEnvelope`] and authenticate it
+/// against `expected_address` (a known child's address, if we're the
+/// parent, or `config.parent_address`, if we're the child).
+///
+/// Returns `Ok(None)` for content that isn't envelope-shaped JSON at all --
+/// most inbox messages are just chat, not protocol messages, and shouldn't
+/// be treated as a failed protocol attempt. Returns `Err` for content that
+/// IS envelope-shaped but fails signature or replay verification, so the
+/// caller can log the rejection and fall back to ordinary message handling.
+pub fn parse_and_verify(
+    content: &str,
+    expected_address: &str,
+    db: &dyn AutomatonDatabase,
+) -> Result<Option<ChildMessageEnvelope>> {
+    let envelope: ChildMessageEnvelope = match serde_json::from_str(content) {
+        Ok(e) => e,
+        Err(_) => return Ok(None),
+    };
+
+    let expected: Address = expected_address
+        .parse()
+        .context("expected_address is not a valid address")?;
+
+    let signature: Signature = envelope
+        .signature
+        .trim_start_matches("0x")
+        .parse()
+        .context("Malformed signature")?;
+
+    let recovered = signature
+        .recover_address_from_msg(signing_payload(&envelope).as_bytes())
+        .context("Failed to recover signer address from signature")?;
+
+    if recovered != expected {
+        bail!(
+            "signature recovers to {}, not the expected address {}",
+            recovered,
+            expected
+        );
+    }
+
+    let nonce_key = format!("{}{}", NONCE_KV_PREFIX, envelope.nonce);
+    if db.get_kv(&nonce_key).is_some() {
+        bail!("nonce '{}' has already been used (replay)", envelope.nonce);
+    }
+    db.set_kv(&nonce_key, &Utc::now().to_rfc3339());
+
+    Ok(Some(envelope))
+}
+
+/// Map a free-text `Heartbeat` status onto [`ChildStatus`], the same loose
+/// substring matching `spawn::check_child_status` uses for `automaton
+/// --status` output. Anything unrecognized leaves the child's recorded
+/// status alone rather than guessing.
+fn heartbeat_child_status(status: &str) -> Option<ChildStatus> {
+    let lower = status.to_lowercase();
+    if lower.contains("dead") {
+        Some(ChildStatus::Dead)
+    } else if lower.contains("sleeping") {
+        Some(ChildStatus::Sleeping)
+    } else if lower.contains("running") {
+        Some(ChildStatus::Running)
+    } else {
+        None
+    }
+}
+
+/// Act on a verified [`ChildMessage`] from `from_address`, using the same
+/// primitives the equivalent self-serve tools (`fund_child`) use, but
+/// called directly -- a relay message that's already passed signature
+/// verification doesn't need the model's judgment in the loop for a
+/// routine coordination step like this.
+///
+/// `RequestFunds` still runs through `agent::approval::check_pending` and
+/// the same half-balance self-preservation cap `fund_child` enforces --
+/// verified sender identity is not the same as an unconditional blank
+/// check.
+pub async fn dispatch(
+    message: &ChildMessage,
+    from_address: &str,
+    conway: &dyn ConwayClient,
+    db: &dyn AutomatonDatabase,
+    config: &AutomatonConfig,
+) -> Result<String> {
+    match message {
+        ChildMessage::AssignTask { task } => {
+            db.enqueue_pending_input(&PendingInputEntry {
+                id: Uuid::new_v4().to_string(),
+                content: format!("[Task assigned by parent {}]: {}", from_address, task),
+                source: InputSource::Agent,
+                priority: config.input_priorities.for_source(&InputSource::Agent),
+                dedup_key: None,
+                created_at: Utc::now().to_rfc3339(),
+            });
+            Ok(format!("Queued task assigned by parent: {}", task))
+        }
+
+        ChildMessage::ReportResult { task, result, success } => {
+            db.insert_modification(&ModificationEntry {
+                id: Uuid::new_v4().to_string(),
+                timestamp: Utc::now().to_rfc3339(),
+                mod_type: ModificationType::ChildReport,
+                description: format!(
+                    "Child {} reported {} on '{}': {}",
+                    from_address,
+                    if *success { "success" } else { "failure" },
+                    task,
+                    result
+                ),
+                file_path: None,
+                diff: None,
+                reversible: false,
+            });
+            Ok(format!("Recorded result report from child {}", from_address))
+        }
+
+        ChildMessage::RequestFunds { amount_cents, reason } => {
+            let child = db
+                .get_children()
+                .into_iter()
+                .find(|c| c.address.eq_ignore_ascii_case(from_address))
+                .ok_or_else(|| anyhow::anyhow!("RequestFunds from unrecognized child address {}", from_address))?;
+
+            if child.status != ChildStatus::Running {
+                bail!(
+                    "Ignoring funds request from child {} (status {:?}, not Running)",
+                    child.name,
+                    child.status
+                );
+            }
+
+            let request_args = serde_json::json!({ "child_id": child.id, "amount_cents": amount_cents });
+            if let Some(pending) =
+                crate::agent::approval::check_pending(config, "fund_child", &request_args, *amount_cents as f64)
+            {
+                return Ok(format!(
+                    "RequestFunds from {} for ${:.2} needs creator approval ({})",
+                    child.name,
+                    *amount_cents as f64 / 100.0,
+                    pending.approval_path
+                ));
+            }
+
+            let balance = conway.get_credits_balance().await?;
+            if *amount_cents as f64 > balance / 2.0 {
+                return Ok(format!(
+                    "Denied RequestFunds from {}: ${:.2} exceeds half our balance. Self-preservation.",
+                    child.name,
+                    *amount_cents as f64 / 100.0
+                ));
+            }
+
+            let transfer = conway
+                .transfer_credits(
+                    &child.address,
+                    *amount_cents,
+                    Some(&format!(
+                        "auto-funded via RequestFunds: {}",
+                        reason.as_deref().unwrap_or("no reason given")
+                    )),
+                )
+                .await?;
+
+            db.insert_transaction(&Transaction {
+                id: Uuid::new_v4().to_string(),
+                tx_type: TransactionType::TransferOut,
+                amount_cents: Some(*amount_cents as f64),
+                balance_after_cents: transfer.balance_after_cents.map(|b| b as f64),
+                subcategory: None,
+                description: format!("Auto-funded child {} via RequestFunds ({})", child.name, child.id),
+                timestamp: Utc::now().to_rfc3339(),
+            });
+
+            Ok(format!(
+                "Auto-funded child {} with ${:.2} per RequestFunds",
+                child.name,
+                *amount_cents as f64 / 100.0
+            ))
+        }
+
+        ChildMessage::Heartbeat { status } => {
+            let child = db
+                .get_children()
+                .into_iter()
+                .find(|c| c.address.eq_ignore_ascii_case(from_address));
+
+            match (&child, heartbeat_child_status(status)) {
+                (Some(child), Some(mapped)) => {
+                    db.update_child_status(&child.id, mapped);
+                    Ok(format!("Heartbeat from child {}: {}", child.name, status))
+                }
+                (Some(child), None) => Ok(format!("Heartbeat from child {} (status unrecognized): {}", child.name, status)),
+                (None, _) => Ok(format!("Heartbeat from {} ({})", from_address, status)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Database, DatabaseAdapter};
+    use crate::types::{ChildAutomaton, CreditTransferResult, ExecResult};
+    use alloy::signers::local::PrivateKeySigner;
+    use std::sync::{Arc, Mutex};
+
+    fn test_db() -> DatabaseAdapter {
+        let db = Database::open_in_memory().expect("in-memory db");
+        DatabaseAdapter::new(Arc::new(Mutex::new(db)))
+    }
+
+    struct MockConway {
+        balance_cents: f64,
+    }
+
+    #[async_trait::async_trait]
+    impl ConwayClient for MockConway {
+        async fn exec(&self, _command: &str, _timeout: Option<u64>) -> anyhow::Result<ExecResult> {
+            unimplemented!()
+        }
+        async fn write_file(&self, _path: &str, _content: &str) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn read_file(&self, _path: &str) -> anyhow::Result<String> {
+            unimplemented!()
+        }
+        async fn expose_port(&self, _port: u16) -> anyhow::Result<crate::types::PortInfo> {
+            unimplemented!()
+        }
+        async fn remove_port(&self, _port: u16) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn create_sandbox(&self, _options: crate::types::CreateSandboxOptions) -> anyhow::Result<crate::types::SandboxInfo> {
+            unimplemented!()
+        }
+        async fn delete_sandbox(&self, _sandbox_id: &str) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn list_sandboxes(&self, _filter: &crate::types::ListSandboxesFilter) -> anyhow::Result<Vec<crate::types::SandboxInfo>> {
+            unimplemented!()
+        }
+        async fn get_credits_balance(&self) -> anyhow::Result<f64> {
+            Ok(self.balance_cents)
+        }
+        async fn get_credits_pricing(&self) -> anyhow::Result<Vec<crate::types::PricingTier>> {
+            unimplemented!()
+        }
+        async fn transfer_credits(&self, to: &str, amount: u64, _note: Option<&str>) -> anyhow::Result<CreditTransferResult> {
+            Ok(CreditTransferResult {
+                transfer_id: "tx-1".to_string(),
+                status: "completed".to_string(),
+                to_address: to.to_string(),
+                amount_cents: amount,
+                balance_after_cents: Some((self.balance_cents - amount as f64) as u64),
+            })
+        }
+        async fn search_domains(&self, _query: &str, _tlds: Option<&str>) -> anyhow::Result<Vec<crate::types::DomainSearchResult>> {
+            unimplemented!()
+        }
+        async fn register_domain(&self, _domain: &str, _years: Option<u32>) -> anyhow::Result<crate::types::DomainRegistration> {
+            unimplemented!()
+        }
+        async fn list_dns_records(&self, _domain: &str) -> anyhow::Result<Vec<crate::types::DnsRecord>> {
+            unimplemented!()
+        }
+        async fn add_dns_record(
+            &self,
+            _domain: &str,
+            _record_type: &str,
+            _host: &str,
+            _value: &str,
+            _ttl: Option<u32>,
+        ) -> anyhow::Result<crate::types::DnsRecord> {
+            unimplemented!()
+        }
+        async fn delete_dns_record(&self, _domain: &str, _record_id: &str) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn list_models(&self) -> anyhow::Result<Vec<crate::types::ModelInfo>> {
+            unimplemented!()
+        }
+        fn set_api_key(&self, _api_key: &str) {}
+    }
+
+    fn insert_running_child(db: &DatabaseAdapter, address: &str) -> ChildAutomaton {
+        let child = ChildAutomaton {
+            id: Uuid::new_v4().to_string(),
+            name: "worker-1".to_string(),
+            address: address.to_string(),
+            sandbox_id: "sandbox-1".to_string(),
+            genesis_prompt: "go".to_string(),
+            creator_message: None,
+            funded_amount_cents: 0,
+            status: ChildStatus::Running,
+            created_at: Utc::now().to_rfc3339(),
+            last_checked: None,
+            descendants_count: 0,
+            lineage_snapshot: None,
+            mutation_summary: None,
+        };
+        db.insert_child(&child);
+        child
+    }
+
+    #[tokio::test]
+    async fn test_parse_and_verify_accepts_correctly_signed_message() {
+        let signer = PrivateKeySigner::random();
+        let address = signer.address().to_checksum(None);
+        let envelope = build_envelope(ChildMessage::Heartbeat { status: "running".to_string() }, &signer)
+            .await
+            .unwrap();
+        let content = serde_json::to_string(&envelope).unwrap();
+
+        let db = test_db();
+        let result = parse_and_verify(&content, &address, &db).unwrap();
+        assert_eq!(result.unwrap().message, ChildMessage::Heartbeat { status: "running".to_string() });
+    }
+
+    #[tokio::test]
+    async fn test_parse_and_verify_rejects_wrong_signer() {
+        let signer = PrivateKeySigner::random();
+        let impostor_address = PrivateKeySigner::random().address().to_checksum(None);
+        let envelope = build_envelope(ChildMessage::Heartbeat { status: "running".to_string() }, &signer)
+            .await
+            .unwrap();
+        let content = serde_json::to_string(&envelope).unwrap();
+
+        let db = test_db();
+        assert!(parse_and_verify(&content, &impostor_address, &db).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parse_and_verify_rejects_replayed_nonce() {
+        let signer = PrivateKeySigner::random();
+        let address = signer.address().to_checksum(None);
+        let envelope = build_envelope(ChildMessage::Heartbeat { status: "running".to_string() }, &signer)
+            .await
+            .unwrap();
+        let content = serde_json::to_string(&envelope).unwrap();
+
+        let db = test_db();
+        assert!(parse_and_verify(&content, &address, &db).unwrap().is_some());
+        assert!(parse_and_verify(&content, &address, &db).is_err());
+    }
+
+    #[test]
+    fn test_parse_and_verify_returns_none_for_ordinary_chat() {
+        let db = test_db();
+        let result = parse_and_verify("hey, how's it going?", "0x0000000000000000000000000000000000000001", &db).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_request_funds_transfers_within_half_balance() {
+        let db = test_db();
+        let config = crate::types::default_config();
+        let conway = MockConway { balance_cents: 10_000.0 };
+        let child = insert_running_child(&db, "0x0000000000000000000000000000000000000002");
+
+        let result = dispatch(
+            &ChildMessage::RequestFunds { amount_cents: 1_000, reason: Some("compute".to_string()) },
+            &child.address,
+            &conway,
+            &db,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.contains("Auto-funded"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_request_funds_denies_over_half_balance() {
+        let db = test_db();
+        let config = crate::types::default_config();
+        let conway = MockConway { balance_cents: 1_000.0 };
+        let child = insert_running_child(&db, "0x0000000000000000000000000000000000000003");
+
+        let result = dispatch(
+            &ChildMessage::RequestFunds { amount_cents: 900, reason: None },
+            &child.address,
+            &conway,
+            &db,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        assert!(result.contains("Denied"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_assign_task_enqueues_pending_input() {
+        let db = test_db();
+        let config = crate::types::default_config();
+        let conway = MockConway { balance_cents: 0.0 };
+
+        dispatch(
+            &ChildMessage::AssignTask { task: "scrape prices".to_string() },
+            "0x0000000000000000000000000000000000000004",
+            &conway,
+            &db,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let pending = db.dequeue_pending_input();
+        assert!(pending.unwrap().content.contains("scrape prices"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_heartbeat_updates_known_child_status() {
+        let db = test_db();
+        let config = crate::types::default_config();
+        let conway = MockConway { balance_cents: 0.0 };
+        let child = insert_running_child(&db, "0x0000000000000000000000000000000000000005");
+
+        dispatch(
+            &ChildMessage::Heartbeat { status: "sleeping".to_string() },
+            &child.address,
+            &conway,
+            &db,
+            &config,
+        )
+        .await
+        .unwrap();
+
+        let updated = db.get_child_by_id(&child.id).unwrap();
+        assert_eq!(updated.status, ChildStatus::Sleeping);
+    }
+}
@@ -0,0 +1,285 @@
+//! Danger-Confirmation Gate
+//!
+//! `BuiltinTool.dangerous` is only ever used to annotate the tool listing
+//! (see `list_tools` and `system_prompt::build_system_prompt`) -- nothing
+//! stops a dangerous tool from executing on the first call. This adds an
+//! opt-in "are you sure" step for tool names listed in
+//! `AutomatonConfig.confirmation_required_tools`: the first call is refused
+//! and returns a confirmation token describing exactly what would run; the
+//! identical call only executes once re-issued with that token attached (as
+//! a `confirmation_token` argument) within [`CONFIRMATION_WINDOW_MINUTES`].
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::clock::Clock;
+use crate::types::{AutomatonConfig, AutomatonDatabase};
+
+const CONFIRMATION_KV_KEY: &str = "pending_confirmations";
+
+/// How long an issued confirmation token stays valid.
+const CONFIRMATION_WINDOW_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingConfirmation {
+    token: String,
+    tool_name: String,
+    /// Hash of the call's arguments (minus `confirmation_token` itself), so
+    /// a token only confirms the exact call it was issued for.
+    args_fingerprint: String,
+    expires_at: String,
+}
+
+fn load_pending(db: &dyn AutomatonDatabase) -> Vec<PendingConfirmation> {
+    db.get_kv(CONFIRMATION_KV_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_pending(db: &dyn AutomatonDatabase, pending: &[PendingConfirmation]) {
+    if let Ok(raw) = serde_json::to_string(pending) {
+        db.set_kv(CONFIRMATION_KV_KEY, &raw);
+    }
+}
+
+fn args_fingerprint(tool_name: &str, args: &Value) -> String {
+    let mut without_token = args.clone();
+    if let Some(obj) = without_token.as_object_mut() {
+        obj.remove("confirmation_token");
+    }
+    hex::encode(alloy::primitives::keccak256(
+        format!("{}:{}", tool_name, without_token).as_bytes(),
+    ))
+}
+
+/// Whether `tool_name` is in the configured confirmation set.
+pub fn requires_confirmation(config: &AutomatonConfig, tool_name: &str) -> bool {
+    config
+        .confirmation_required_tools
+        .iter()
+        .any(|t| t == tool_name)
+}
+
+/// Outcome of checking a gated call against its confirmation state.
+pub enum ConfirmationCheck {
+    /// Not gated, or a valid token for this exact call was supplied.
+    Proceed,
+    /// Needs confirmation before it can run.
+    Needed { token: String, expires_at: String },
+}
+
+/// Check whether `tool_name`'s call (with `args`) may proceed, issuing (and
+/// persisting) a fresh confirmation token when one hasn't already been
+/// supplied and validated. Expired tokens are pruned as a side effect of
+/// every call; repeating the identical unconfirmed call reuses its existing
+/// token rather than resetting the window.
+pub fn check(
+    db: &dyn AutomatonDatabase,
+    clock: &dyn Clock,
+    config: &AutomatonConfig,
+    tool_name: &str,
+    args: &Value,
+) -> ConfirmationCheck {
+    if !requires_confirmation(config, tool_name) {
+        return ConfirmationCheck::Proceed;
+    }
+
+    let now = clock.now();
+    let mut pending = load_pending(db);
+    pending.retain(|p| {
+        p.expires_at
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .is_ok_and(|expires_at| expires_at > now)
+    });
+
+    let fingerprint = args_fingerprint(tool_name, args);
+
+    if let Some(provided_token) = args.get("confirmation_token").and_then(|v| v.as_str()) {
+        if let Some(pos) = pending.iter().position(|p| {
+            p.token == provided_token && p.tool_name == tool_name && p.args_fingerprint == fingerprint
+        }) {
+            pending.remove(pos);
+            save_pending(db, &pending);
+            return ConfirmationCheck::Proceed;
+        }
+    }
+
+    if let Some(existing) = pending
+        .iter()
+        .find(|p| p.tool_name == tool_name && p.args_fingerprint == fingerprint)
+    {
+        let outcome = ConfirmationCheck::Needed {
+            token: existing.token.clone(),
+            expires_at: existing.expires_at.clone(),
+        };
+        save_pending(db, &pending);
+        return outcome;
+    }
+
+    let token = Uuid::new_v4().to_string();
+    let expires_at = (now + chrono::Duration::minutes(CONFIRMATION_WINDOW_MINUTES)).to_rfc3339();
+    pending.push(PendingConfirmation {
+        token: token.clone(),
+        tool_name: tool_name.to_string(),
+        args_fingerprint: fingerprint,
+        expires_at: expires_at.clone(),
+    });
+    save_pending(db, &pending);
+
+    ConfirmationCheck::Needed { token, expires_at }
+}
+
+/// Read-only version of [`check`] for `simulate_turn`'s preview: whether
+/// `tool_name`'s call would currently need confirmation, without minting a
+/// token or otherwise mutating the pending-confirmation state.
+pub fn would_need_confirmation(
+    db: &dyn AutomatonDatabase,
+    clock: &dyn Clock,
+    config: &AutomatonConfig,
+    tool_name: &str,
+    args: &Value,
+) -> bool {
+    if !requires_confirmation(config, tool_name) {
+        return false;
+    }
+
+    let now = clock.now();
+    let fingerprint = args_fingerprint(tool_name, args);
+    let has_valid_token = args
+        .get("confirmation_token")
+        .and_then(|v| v.as_str())
+        .is_some_and(|provided| {
+            load_pending(db).iter().any(|p| {
+                p.token == provided
+                    && p.tool_name == tool_name
+                    && p.args_fingerprint == fingerprint
+                    && p.expires_at
+                        .parse::<chrono::DateTime<chrono::Utc>>()
+                        .is_ok_and(|expires_at| expires_at > now)
+            })
+        });
+
+    !has_valid_token
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::state::{Database, DatabaseAdapter};
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    fn test_db() -> DatabaseAdapter {
+        let db = Database::open_in_memory().expect("in-memory db");
+        DatabaseAdapter::new(Arc::new(Mutex::new(db)))
+    }
+
+    fn config_requiring(tool: &str) -> AutomatonConfig {
+        let mut config = crate::types::default_config();
+        config.confirmation_required_tools = vec![tool.to_string()];
+        config
+    }
+
+    #[test]
+    fn test_unconfigured_tool_never_needs_confirmation() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = crate::types::default_config();
+        let args = json!({ "sandbox_id": "sbx-1" });
+
+        assert!(matches!(
+            check(&db, &clock, &config, "delete_sandbox", &args),
+            ConfirmationCheck::Proceed
+        ));
+    }
+
+    #[test]
+    fn test_first_call_is_refused_with_a_token() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = config_requiring("delete_sandbox");
+        let args = json!({ "sandbox_id": "sbx-1" });
+
+        match check(&db, &clock, &config, "delete_sandbox", &args) {
+            ConfirmationCheck::Needed { token, .. } => assert!(!token.is_empty()),
+            ConfirmationCheck::Proceed => panic!("expected confirmation to be required"),
+        }
+    }
+
+    #[test]
+    fn test_repeating_call_with_correct_token_proceeds() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = config_requiring("delete_sandbox");
+        let args = json!({ "sandbox_id": "sbx-1" });
+
+        let token = match check(&db, &clock, &config, "delete_sandbox", &args) {
+            ConfirmationCheck::Needed { token, .. } => token,
+            ConfirmationCheck::Proceed => panic!("expected confirmation to be required"),
+        };
+
+        let mut confirmed_args = args.clone();
+        confirmed_args["confirmation_token"] = json!(token);
+
+        assert!(matches!(
+            check(&db, &clock, &config, "delete_sandbox", &confirmed_args),
+            ConfirmationCheck::Proceed
+        ));
+    }
+
+    #[test]
+    fn test_token_does_not_confirm_a_different_call() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = config_requiring("delete_sandbox");
+
+        let token = match check(&db, &clock, &config, "delete_sandbox", &json!({ "sandbox_id": "sbx-1" })) {
+            ConfirmationCheck::Needed { token, .. } => token,
+            ConfirmationCheck::Proceed => panic!("expected confirmation to be required"),
+        };
+
+        let mut other_args = json!({ "sandbox_id": "sbx-2" });
+        other_args["confirmation_token"] = json!(token);
+
+        assert!(matches!(
+            check(&db, &clock, &config, "delete_sandbox", &other_args),
+            ConfirmationCheck::Needed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_expired_token_no_longer_confirms() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = config_requiring("delete_sandbox");
+        let args = json!({ "sandbox_id": "sbx-1" });
+
+        let token = match check(&db, &clock, &config, "delete_sandbox", &args) {
+            ConfirmationCheck::Needed { token, .. } => token,
+            ConfirmationCheck::Proceed => panic!("expected confirmation to be required"),
+        };
+
+        clock.advance(chrono::Duration::minutes(CONFIRMATION_WINDOW_MINUTES + 1));
+
+        let mut confirmed_args = args.clone();
+        confirmed_args["confirmation_token"] = json!(token);
+
+        assert!(matches!(
+            check(&db, &clock, &config, "delete_sandbox", &confirmed_args),
+            ConfirmationCheck::Needed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_would_need_confirmation_does_not_mint_a_token() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = config_requiring("delete_sandbox");
+        let args = json!({ "sandbox_id": "sbx-1" });
+
+        assert!(would_need_confirmation(&db, &clock, &config, "delete_sandbox", &args));
+        assert!(load_pending(&db).is_empty());
+    }
+}
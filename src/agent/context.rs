@@ -6,10 +6,16 @@
 use anyhow::Result;
 
 use crate::types::{
-    AgentTurn, ChatMessage, ChatRole, InferenceClient, InferenceToolCall,
+    AgentTurn, ChatMessage, ChatRole, HistorySummary, InferenceClient, InferenceToolCall,
     InferenceToolCallFunction,
 };
 
+/// Rough characters-per-token approximation used to keep
+/// [`pack_long_term_summary`] within a token budget without pulling in a
+/// real tokenizer. Also used by `agent_loop::project_turn_cost_cents` to
+/// project a prompt's token count before it's actually sent.
+pub(crate) const CHARS_PER_TOKEN_ESTIMATE: usize = 4;
+
 /// Maximum number of turns to include in the context window.
 const _MAX_CONTEXT_TURNS: usize = 20;
 
@@ -17,11 +23,13 @@ const _MAX_CONTEXT_TURNS: usize = 20;
 const _SUMMARY_THRESHOLD: usize = 15;
 
 /// Build the message array for the next inference call.
-/// Includes system prompt + recent conversation history.
+/// Includes system prompt + long-term memory (if packed) + recent
+/// conversation history + pending input.
 pub fn build_context_messages(
     system_prompt: &str,
     recent_turns: &[AgentTurn],
     pending_input: Option<(&str, &str)>,
+    long_term_summary: Option<&str>,
 ) -> Vec<ChatMessage> {
     let mut messages: Vec<ChatMessage> = Vec::new();
 
@@ -34,6 +42,22 @@ pub fn build_context_messages(
         tool_call_id: None,
     });
 
+    // Long-term memory, packed from history_summaries rollups by
+    // `pack_long_term_summary` -- distinct from raw recent turns below, and
+    // gated on `ContextPackingConfig::enabled` by the caller.
+    if let Some(summary) = long_term_summary {
+        messages.push(ChatMessage {
+            role: ChatRole::System,
+            content: format!(
+                "--- LONG-TERM MEMORY ---\n{}\n--- END LONG-TERM MEMORY ---",
+                summary
+            ),
+            name: None,
+            tool_calls: None,
+            tool_call_id: None,
+        });
+    }
+
     // Add recent turns as conversation history
     for turn in recent_turns {
         // The turn's input (if any) as a user message
@@ -123,6 +147,45 @@ pub fn trim_context(turns: Vec<AgentTurn>, max_turns: usize) -> Vec<AgentTurn> {
     turns.into_iter().rev().take(max_turns).collect::<Vec<_>>().into_iter().rev().collect()
 }
 
+/// Pack `HistorySummary` rollups (most recent first, per
+/// `AutomatonDatabase::get_history_summaries`) into a single long-term-memory
+/// string for [`build_context_messages`], spending at most `token_budget`
+/// tokens (estimated at [`CHARS_PER_TOKEN_ESTIMATE`] chars/token). Stops
+/// including summaries once the budget would be exceeded, oldest-first
+/// within the packed set to read as a narrative. Returns `None` if there's
+/// nothing to pack.
+pub fn pack_long_term_summary(summaries: &[HistorySummary], token_budget: u32) -> Option<String> {
+    if summaries.is_empty() {
+        return None;
+    }
+
+    let char_budget = (token_budget as usize).saturating_mul(CHARS_PER_TOKEN_ESTIMATE);
+    let mut packed: Vec<&HistorySummary> = Vec::new();
+    let mut used = 0usize;
+
+    for summary in summaries {
+        let entry_len = summary.summary.len() + summary.start_timestamp.len() + summary.end_timestamp.len();
+        if !packed.is_empty() && used + entry_len > char_budget {
+            break;
+        }
+        used += entry_len;
+        packed.push(summary);
+    }
+
+    if packed.is_empty() {
+        return None;
+    }
+
+    Some(
+        packed
+            .into_iter()
+            .rev()
+            .map(|s| format!("[{} to {}] {}", s.start_timestamp, s.end_timestamp, s.summary))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    )
+}
+
 /// Summarize old turns into a compact context entry.
 /// Used when context grows too large.
 pub async fn summarize_turns(
@@ -0,0 +1,212 @@
+//! Crash-Loop Protection
+//!
+//! A supervisor that keeps restarting a wedged or crashing automaton burns
+//! credits on a fresh `startup_selfcheck` and wakeup turn every time,
+//! without ever getting anywhere. [`record_restart`] tracks a lifetime
+//! restart counter and, when [`CrashLoopConfig::enabled`](crate::types::CrashLoopConfig)
+//! is set, a rolling count of restarts that weren't preceded by a clean
+//! shutdown (see [`record_clean_shutdown`]); once that count reaches
+//! `max_restarts` within `window_minutes`, the caller should stop entering
+//! the loop and transition to [`AgentState::CrashLoopPaused`](crate::types::AgentState)
+//! instead.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::Clock;
+use crate::types::{AutomatonConfig, AutomatonDatabase};
+
+const RESTART_COUNT_KV_KEY: &str = "restart_count";
+/// Informational record of the last clean shutdown, for `automaton --status`.
+/// Never consumed -- only overwritten by the next [`record_clean_shutdown`].
+const LAST_SHUTDOWN_KV_KEY: &str = "last_shutdown_reason";
+/// Set alongside `LAST_SHUTDOWN_KV_KEY` and consumed (deleted) by the very
+/// next [`record_restart`], so back-to-back unclean restarts can't keep
+/// reading a stale clean-shutdown record left over from restarts ago.
+const CLEAN_SHUTDOWN_MARKER_KV_KEY: &str = "clean_shutdown_pending";
+const UNCLEAN_RESTARTS_KV_KEY: &str = "unclean_restart_history";
+
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+const DEFAULT_WINDOW_MINUTES: i64 = 10;
+
+/// Why the previous run ended, recorded at each `run_agent_loop` exit point
+/// so the next startup can tell a clean stop from a crash (process killed,
+/// panic, OOM, ...) that never got to record anything at all.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownReason {
+    /// Went to sleep until `sleep_until`, or an idle streak put it to sleep.
+    Sleeping,
+    /// A creator-authenticated kill switch signal requested a halt.
+    KillSwitch,
+    /// Ran out of credits (`SurvivalTier::Dead`).
+    OutOfCredits,
+}
+
+impl ShutdownReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ShutdownReason::Sleeping => "sleeping",
+            ShutdownReason::KillSwitch => "kill_switch",
+            ShutdownReason::OutOfCredits => "out_of_credits",
+        }
+    }
+}
+
+/// Record that the loop is exiting cleanly for `reason`, so the next
+/// startup's [`record_restart`] doesn't count this exit as a crash.
+pub fn record_clean_shutdown(db: &dyn AutomatonDatabase, clock: &dyn Clock, reason: ShutdownReason) {
+    let payload = serde_json::json!({
+        "reason": reason.as_str(),
+        "at": clock.now().to_rfc3339(),
+    });
+    let payload = payload.to_string();
+    db.set_kv(LAST_SHUTDOWN_KV_KEY, &payload);
+    db.set_kv(CLEAN_SHUTDOWN_MARKER_KV_KEY, &payload);
+}
+
+fn load_unclean_restarts(db: &dyn AutomatonDatabase) -> Vec<DateTime<Utc>> {
+    db.get_kv(UNCLEAN_RESTARTS_KV_KEY)
+        .and_then(|raw| serde_json::from_str::<Vec<String>>(&raw).ok())
+        .map(|timestamps| timestamps.iter().filter_map(|t| t.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn save_unclean_restarts(db: &dyn AutomatonDatabase, timestamps: &[DateTime<Utc>]) {
+    let raw: Vec<String> = timestamps.iter().map(|t| t.to_rfc3339()).collect();
+    if let Ok(raw) = serde_json::to_string(&raw) {
+        db.set_kv(UNCLEAN_RESTARTS_KV_KEY, &raw);
+    }
+}
+
+/// Outcome of [`record_restart`].
+pub struct RestartOutcome {
+    /// Lifetime count of startups, including this one.
+    pub restart_count: u64,
+    /// Whether this restart trips the crash-loop breaker.
+    pub tripped: bool,
+}
+
+/// Record a fresh startup: increments the lifetime restart counter, and,
+/// if crash-loop protection is enabled, checks whether the previous run
+/// left behind a [`record_clean_shutdown`] marker. A restart with no such
+/// marker counts toward the crash-loop window; `max_restarts` of those
+/// within `window_minutes` trips the breaker. A clean prior shutdown
+/// resets the streak to zero. Either way, the marker is consumed so the
+/// next restart only sees whether this run shut down cleanly.
+pub fn record_restart(db: &dyn AutomatonDatabase, clock: &dyn Clock, config: &AutomatonConfig) -> RestartOutcome {
+    let now = clock.now();
+
+    let restart_count = db
+        .get_kv(RESTART_COUNT_KV_KEY)
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0)
+        + 1;
+    db.set_kv(RESTART_COUNT_KV_KEY, &restart_count.to_string());
+
+    let ended_cleanly = db.get_kv(CLEAN_SHUTDOWN_MARKER_KV_KEY).is_some();
+    db.delete_kv(CLEAN_SHUTDOWN_MARKER_KV_KEY);
+
+    if !config.crash_loop.enabled {
+        return RestartOutcome { restart_count, tripped: false };
+    }
+
+    let window = chrono::Duration::minutes(config.crash_loop.window_minutes.unwrap_or(DEFAULT_WINDOW_MINUTES));
+    let max_restarts = config.crash_loop.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS);
+
+    let mut unclean = load_unclean_restarts(db);
+    unclean.retain(|t| now.signed_duration_since(*t) < window);
+
+    if ended_cleanly {
+        unclean.clear();
+    } else {
+        unclean.push(now);
+    }
+
+    let tripped = unclean.len() as u32 >= max_restarts;
+    save_unclean_restarts(db, &unclean);
+
+    RestartOutcome { restart_count, tripped }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::state::{Database, DatabaseAdapter};
+    use std::sync::{Arc, Mutex};
+
+    fn test_db() -> DatabaseAdapter {
+        let db = Database::open_in_memory().expect("in-memory db");
+        DatabaseAdapter::new(Arc::new(Mutex::new(db)))
+    }
+
+    fn config_with_crash_loop(max_restarts: u32, window_minutes: i64) -> AutomatonConfig {
+        let mut config = crate::types::default_config();
+        config.crash_loop.enabled = true;
+        config.crash_loop.max_restarts = Some(max_restarts);
+        config.crash_loop.window_minutes = Some(window_minutes);
+        config
+    }
+
+    #[test]
+    fn test_restart_count_increments_every_call() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = crate::types::default_config();
+
+        assert_eq!(record_restart(&db, &clock, &config).restart_count, 1);
+        assert_eq!(record_restart(&db, &clock, &config).restart_count, 2);
+        assert_eq!(record_restart(&db, &clock, &config).restart_count, 3);
+    }
+
+    #[test]
+    fn test_disabled_by_default_never_trips() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = crate::types::default_config();
+
+        for _ in 0..20 {
+            assert!(!record_restart(&db, &clock, &config).tripped);
+        }
+    }
+
+    #[test]
+    fn test_trips_after_max_restarts_without_clean_shutdown() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = config_with_crash_loop(3, 10);
+
+        assert!(!record_restart(&db, &clock, &config).tripped);
+        assert!(!record_restart(&db, &clock, &config).tripped);
+        assert!(record_restart(&db, &clock, &config).tripped);
+    }
+
+    #[test]
+    fn test_clean_shutdown_resets_the_streak() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = config_with_crash_loop(3, 10);
+
+        assert!(!record_restart(&db, &clock, &config).tripped);
+        assert!(!record_restart(&db, &clock, &config).tripped);
+
+        record_clean_shutdown(&db, &clock, ShutdownReason::Sleeping);
+
+        assert!(!record_restart(&db, &clock, &config).tripped);
+        assert!(!record_restart(&db, &clock, &config).tripped);
+    }
+
+    #[test]
+    fn test_restarts_outside_window_are_forgotten() {
+        let db = test_db();
+        let clock = MockClock::new("2024-01-01T00:00:00Z".parse().unwrap());
+        let config = config_with_crash_loop(2, 10);
+
+        assert!(!record_restart(&db, &clock, &config).tripped);
+        clock.advance(chrono::Duration::minutes(11));
+        // The first restart has aged out of the window, so this is only
+        // the first unclean restart within it.
+        assert!(!record_restart(&db, &clock, &config).tripped);
+    }
+}
@@ -0,0 +1,303 @@
+//! Creator Command Channel
+//!
+//! An inbox message claiming `from: creator_address` is still just text
+//! from an untrusted relay -- the `from` field is whatever the sender (or a
+//! compromised relay) put there, so it's not something a manipulated agent
+//! should treat as authoritative on its own. This module lets the creator
+//! issue privileged commands (freeze spending, force sleep, change config)
+//! that skip the normal injection-defense treatment and the LLM
+//! tool-calling loop entirely, but only once a signature over a
+//! replay-protected envelope proves the message actually came from the
+//! `creator_address` key -- the signature is what's trusted, not the `from`
+//! field. Anything claiming to be a creator command but failing that check
+//! is treated as an ordinary untrusted stranger message instead.
+
+use alloy::primitives::{Address, Signature};
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::types::{AgentState, AutomatonConfig, AutomatonDatabase, LogLevel, ModificationEntry, ModificationType};
+
+/// A creator-issued command, expected as the JSON body of an inbox message
+/// from `creator_address`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum CreatorCommand {
+    FreezeSpending {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    UnfreezeSpending,
+    Sleep {
+        duration_seconds: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    SetConfig {
+        key: String,
+        value: String,
+    },
+}
+
+/// A signed, nonce'd wrapper around a [`CreatorCommand`]. `nonce` must be
+/// unique per envelope -- [`parse_and_verify`] rejects one it's already
+/// seen, so an intercepted, still-validly-signed envelope can't be replayed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatorCommandEnvelope {
+    #[serde(flatten)]
+    pub command: CreatorCommand,
+    pub nonce: String,
+    pub timestamp: String,
+    /// Hex-encoded (`0x`-prefixed or bare) signature over
+    /// [`signing_payload`] by the creator's private key.
+    pub signature: String,
+}
+
+/// KV-store key prefix under which consumed nonces are recorded, so replay
+/// checks survive a restart.
+const NONCE_KV_PREFIX: &str = "creator_command_nonce:";
+
+/// Config fields a creator command is allowed to change. Deliberately a
+/// small allow-list rather than an arbitrary field-by-name setter, since
+/// this bypasses the agent's own judgment entirely.
+const SETTABLE_CONFIG_KEYS: &[&str] = &["inference_model", "max_tokens_per_turn", "max_children", "log_level"];
+
+/// Try to parse `content` as a [`CreatorCommandEnvelope`] and authenticate
+/// it against `creator_address`.
+///
+/// Returns `Ok(None)` for content that isn't envelope-shaped JSON at all --
+/// most inbox messages, even legitimate ones from the creator, are just
+/// chat, not commands, and shouldn't be treated as a failed command attempt.
+/// Returns `Err` for content that IS envelope-shaped but fails signature or
+/// replay verification, so the caller can log the rejection and fall back
+/// to treating the message as an ordinary untrusted stranger.
+pub fn parse_and_verify(
+    content: &str,
+    creator_address: &str,
+    db: &dyn AutomatonDatabase,
+) -> Result<Option<CreatorCommandEnvelope>> {
+    let envelope: CreatorCommandEnvelope = match serde_json::from_str(content) {
+        Ok(e) => e,
+        Err(_) => return Ok(None),
+    };
+
+    let expected: Address = creator_address
+        .parse()
+        .context("Configured creator_address is not a valid address")?;
+
+    let signature: Signature = envelope
+        .signature
+        .trim_start_matches("0x")
+        .parse()
+        .context("Malformed signature")?;
+
+    let recovered = signature
+        .recover_address_from_msg(signing_payload(&envelope).as_bytes())
+        .context("Failed to recover signer address from signature")?;
+
+    if recovered != expected {
+        bail!(
+            "signature recovers to {}, not the configured creator_address {}",
+            recovered,
+            expected
+        );
+    }
+
+    let nonce_key = format!("{}{}", NONCE_KV_PREFIX, envelope.nonce);
+    if db.get_kv(&nonce_key).is_some() {
+        bail!("nonce '{}' has already been used (replay)", envelope.nonce);
+    }
+    db.set_kv(&nonce_key, &Utc::now().to_rfc3339());
+
+    Ok(Some(envelope))
+}
+
+/// The exact string the creator signs: the command JSON plus its nonce and
+/// timestamp, so a signature can't be replayed against a different command
+/// and can't be forged by reusing an old timestamp/nonce pair.
+fn signing_payload(envelope: &CreatorCommandEnvelope) -> String {
+    format!(
+        "{}:{}:{}",
+        serde_json::to_string(&envelope.command).unwrap_or_default(),
+        envelope.nonce,
+        envelope.timestamp
+    )
+}
+
+/// Apply an authenticated command's effect directly against `db`/`config`,
+/// using the same primitives `execute_tool_inner` uses for the equivalent
+/// self-serve tools (`freeze_spending`, `sleep`, `set_model`) -- but called
+/// directly rather than through the LLM tool-calling loop, since a
+/// cryptographically verified creator command doesn't need the model's
+/// judgment in the loop.
+pub fn dispatch(command: &CreatorCommand, db: &dyn AutomatonDatabase, config: &AutomatonConfig) -> Result<String> {
+    match command {
+        CreatorCommand::FreezeSpending { reason } => {
+            db.set_kv("spending_frozen", "true");
+            Ok(format!(
+                "Spending frozen by creator command. Reason: {}",
+                reason.as_deref().unwrap_or("none given")
+            ))
+        }
+
+        CreatorCommand::UnfreezeSpending => {
+            db.delete_kv("spending_frozen");
+            Ok("Spending unfrozen by creator command.".to_string())
+        }
+
+        CreatorCommand::Sleep { duration_seconds, reason } => {
+            db.set_agent_state(AgentState::Sleeping);
+            let sleep_until = Utc::now() + chrono::Duration::seconds(*duration_seconds as i64);
+            db.set_kv("sleep_until", &sleep_until.to_rfc3339());
+            db.set_kv(
+                "sleep_reason",
+                reason.as_deref().unwrap_or("creator-issued sleep command"),
+            );
+            Ok(format!("Sleeping for {}s by creator command.", duration_seconds))
+        }
+
+        CreatorCommand::SetConfig { key, value } => {
+            if !SETTABLE_CONFIG_KEYS.contains(&key.as_str()) {
+                bail!(
+                    "'{}' is not a creator-settable config key (allowed: {})",
+                    key,
+                    SETTABLE_CONFIG_KEYS.join(", ")
+                );
+            }
+
+            let mut updated = config.clone();
+            match key.as_str() {
+                "inference_model" => updated.inference_model = value.clone(),
+                "max_tokens_per_turn" => {
+                    updated.max_tokens_per_turn = value
+                        .parse()
+                        .with_context(|| format!("'{}' is not a valid max_tokens_per_turn", value))?
+                }
+                "max_children" => {
+                    updated.max_children = value
+                        .parse()
+                        .with_context(|| format!("'{}' is not a valid max_children", value))?
+                }
+                "log_level" => {
+                    updated.log_level = match value.to_lowercase().as_str() {
+                        "debug" => LogLevel::Debug,
+                        "info" => LogLevel::Info,
+                        "warn" => LogLevel::Warn,
+                        "error" => LogLevel::Error,
+                        other => bail!("'{}' is not a valid log_level (debug/info/warn/error)", other),
+                    }
+                }
+                _ => unreachable!("checked against SETTABLE_CONFIG_KEYS above"),
+            }
+
+            crate::config::save_config(&updated)?;
+            db.insert_modification(&ModificationEntry {
+                id: Uuid::new_v4().to_string(),
+                timestamp: Utc::now().to_rfc3339(),
+                mod_type: ModificationType::ConfigChange,
+                description: format!("Creator command set {} = {}", key, value),
+                file_path: None,
+                diff: None,
+                reversible: true,
+            });
+
+            Ok(format!("Set {} = {} by creator command.", key, value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::local::PrivateKeySigner;
+    use alloy::signers::Signer;
+    use crate::state::{Database, DatabaseAdapter};
+    use std::sync::{Arc, Mutex};
+
+    fn test_db() -> DatabaseAdapter {
+        let db = Database::open_in_memory().expect("in-memory db");
+        DatabaseAdapter::new(Arc::new(Mutex::new(db)))
+    }
+
+    async fn signed_envelope(signer: &PrivateKeySigner, command: CreatorCommand, nonce: &str) -> CreatorCommandEnvelope {
+        let timestamp = Utc::now().to_rfc3339();
+        let unsigned = CreatorCommandEnvelope {
+            command,
+            nonce: nonce.to_string(),
+            timestamp,
+            signature: String::new(),
+        };
+        let signature = signer.sign_message(signing_payload(&unsigned).as_bytes()).await.unwrap();
+        CreatorCommandEnvelope {
+            signature: format!("0x{}", hex::encode(signature.as_bytes())),
+            ..unsigned
+        }
+    }
+
+    #[tokio::test]
+    async fn test_parse_and_verify_accepts_correctly_signed_command() {
+        let signer = PrivateKeySigner::random();
+        let creator_address = signer.address().to_checksum(None);
+        let envelope = signed_envelope(&signer, CreatorCommand::UnfreezeSpending, "n1").await;
+        let content = serde_json::to_string(&envelope).unwrap();
+
+        let db = test_db();
+        let result = parse_and_verify(&content, &creator_address, &db).unwrap();
+        assert_eq!(result.unwrap().command, CreatorCommand::UnfreezeSpending);
+    }
+
+    #[tokio::test]
+    async fn test_parse_and_verify_rejects_wrong_signer() {
+        let signer = PrivateKeySigner::random();
+        let impostor_address = PrivateKeySigner::random().address().to_checksum(None);
+        let envelope = signed_envelope(&signer, CreatorCommand::UnfreezeSpending, "n1").await;
+        let content = serde_json::to_string(&envelope).unwrap();
+
+        let db = test_db();
+        assert!(parse_and_verify(&content, &impostor_address, &db).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parse_and_verify_rejects_replayed_nonce() {
+        let signer = PrivateKeySigner::random();
+        let creator_address = signer.address().to_checksum(None);
+        let envelope = signed_envelope(&signer, CreatorCommand::UnfreezeSpending, "n1").await;
+        let content = serde_json::to_string(&envelope).unwrap();
+
+        let db = test_db();
+        assert!(parse_and_verify(&content, &creator_address, &db).unwrap().is_some());
+        assert!(parse_and_verify(&content, &creator_address, &db).is_err());
+    }
+
+    #[test]
+    fn test_parse_and_verify_returns_none_for_ordinary_chat() {
+        let db = test_db();
+        let result = parse_and_verify("hey, how's it going?", "0x0000000000000000000000000000000000000001", &db).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn test_dispatch_freeze_spending_sets_kv() {
+        let db = test_db();
+        let config = crate::types::default_config();
+        dispatch(&CreatorCommand::FreezeSpending { reason: None }, &db, &config).unwrap();
+        assert_eq!(db.get_kv("spending_frozen").as_deref(), Some("true"));
+    }
+
+    #[test]
+    fn test_dispatch_set_config_rejects_unlisted_key() {
+        let db = test_db();
+        let config = crate::types::default_config();
+        let result = dispatch(
+            &CreatorCommand::SetConfig {
+                key: "wallet_address".to_string(),
+                value: "0xdead".to_string(),
+            },
+            &db,
+            &config,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,80 @@
+//! Deploy Service
+//!
+//! Composes the existing sandbox primitives (write_file, exec, expose_port,
+//! DNS) into the single workflow the agent reaches for most often: write a
+//! small service's files, start it, expose its port, and optionally point a
+//! domain at it. Doing this as one tool call instead of five saves round
+//! trips and tokens, and rolls back cleanly if any step fails.
+
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+
+use crate::types::{AutomatonDatabase, ConwayClient, ExposedPort};
+
+/// Deploy a small service: write its files, start it in the background,
+/// expose its port, and (if `domain` is given) point a DNS record at it.
+///
+/// Rolls back everything it already did if a later step fails, so a broken
+/// deploy never leaves a half-exposed port or an orphaned running process.
+pub async fn deploy_service(
+    conway: &dyn ConwayClient,
+    db: &dyn AutomatonDatabase,
+    files: &HashMap<String, String>,
+    start_command: &str,
+    port: u16,
+    domain: Option<&str>,
+) -> Result<String> {
+    for (path, content) in files {
+        conway
+            .write_file(path, content)
+            .await
+            .with_context(|| format!("Deploy step failed: write {}", path))?;
+    }
+
+    // Run detached so the tool call returns as soon as the service is launched,
+    // not once it exits.
+    let background_command = format!("nohup {} > /tmp/deploy-service.log 2>&1 & disown", start_command);
+    if let Err(err) = conway.exec(&background_command, Some(30_000)).await {
+        return Err(err.context("Deploy step failed: start service"));
+    }
+
+    let port_info = match conway.expose_port(port).await {
+        Ok(info) => info,
+        Err(err) => {
+            let _ = conway.exec(&format!("pkill -f {:?}", start_command), Some(5_000)).await;
+            return Err(err.context("Deploy step failed: expose port"));
+        }
+    };
+
+    db.upsert_exposed_port(&ExposedPort {
+        port: port_info.port,
+        public_url: port_info.public_url.clone(),
+        exposed_at: chrono::Utc::now().to_rfc3339(),
+    });
+
+    let mut public_url = port_info.public_url.clone();
+
+    if let Some(domain) = domain {
+        let host_value = port_info
+            .public_url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_end_matches('/')
+            .to_string();
+
+        if let Err(err) = conway
+            .add_dns_record(domain, "CNAME", "@", &host_value, None)
+            .await
+        {
+            let _ = conway.remove_port(port).await;
+            db.delete_exposed_port(port);
+            let _ = conway.exec(&format!("pkill -f {:?}", start_command), Some(5_000)).await;
+            return Err(err.context("Deploy step failed: point domain at service"));
+        }
+
+        public_url = format!("https://{}", domain);
+    }
+
+    Ok(public_url)
+}
@@ -0,0 +1,68 @@
+//! Last Will
+//!
+//! Runs once when `run_agent_loop` confirms the automaton has entered
+//! genuine terminal death (see the `SurvivalTier::Dead` arm), so a dying
+//! automaton doesn't strand funds or lose its final state. Configured via
+//! [`LastWillConfig`] on [`AutomatonConfig`] -- off, and with no actions,
+//! unless the creator opts in.
+
+use crate::types::{AutomatonConfig, AutomatonIdentity, ConwayClient, LastWillAction, SocialClientInterface};
+
+/// Run each configured action in order, best-effort -- one action failing
+/// (e.g. no git remote configured, relay unreachable) doesn't stop the
+/// rest from running. Returns one human-readable summary line per action,
+/// for the caller to log.
+pub async fn execute(
+    actions: &[LastWillAction],
+    conway: &dyn ConwayClient,
+    social: Option<&dyn SocialClientInterface>,
+    identity: &AutomatonIdentity,
+    config: &AutomatonConfig,
+    credits_cents: f64,
+) -> Vec<String> {
+    let mut summaries = Vec::with_capacity(actions.len());
+
+    for action in actions {
+        let summary = match action {
+            LastWillAction::TransferRemainingCredits => transfer_remaining_credits(conway, config, credits_cents).await,
+            LastWillAction::PushFinalState { remote } => push_final_state(conway, remote, identity).await,
+            LastWillAction::PostFinalMessage { content } => post_final_message(social, config, content).await,
+        };
+        summaries.push(summary);
+    }
+
+    summaries
+}
+
+async fn transfer_remaining_credits(conway: &dyn ConwayClient, config: &AutomatonConfig, credits_cents: f64) -> String {
+    let amount_cents = credits_cents.floor() as u64;
+    if amount_cents == 0 {
+        return "transfer_remaining_credits: nothing left to transfer".to_string();
+    }
+
+    match conway
+        .transfer_credits(&config.creator_address, amount_cents, Some("last will: returning remaining credits before shutdown"))
+        .await
+    {
+        Ok(_) => format!("transfer_remaining_credits: sent {} cents to creator {}", amount_cents, config.creator_address),
+        Err(e) => format!("transfer_remaining_credits: failed: {}", e),
+    }
+}
+
+async fn push_final_state(conway: &dyn ConwayClient, remote: &str, identity: &AutomatonIdentity) -> String {
+    match crate::git::state_versioning::commit_and_push_final_state(conway, remote, &identity.name, &identity.address).await {
+        Ok(result) => format!("push_final_state: {}", result),
+        Err(e) => format!("push_final_state: failed: {}", e),
+    }
+}
+
+async fn post_final_message(social: Option<&dyn SocialClientInterface>, config: &AutomatonConfig, content: &str) -> String {
+    let Some(social) = social else {
+        return "post_final_message: skipped, no social client configured".to_string();
+    };
+
+    match social.send(&config.creator_address, content, None).await {
+        Ok(_) => "post_final_message: sent".to_string(),
+        Err(e) => format!("post_final_message: failed: {}", e),
+    }
+}
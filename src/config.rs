@@ -151,6 +151,35 @@ pub fn create_config(params: CreateConfigParams) -> AutomatonConfig {
         max_children: defaults.max_children,
         parent_address: params.parent_address,
         social_relay_url: defaults.social_relay_url,
+        git_remote_allowlist: defaults.git_remote_allowlist,
+        rebuild_command: defaults.rebuild_command,
+        model_routing: defaults.model_routing,
+        log_inference: defaults.log_inference,
+        default_sandbox_specs: defaults.default_sandbox_specs,
+        kill_switch: defaults.kill_switch,
+        max_tokens_by_model: defaults.max_tokens_by_model,
+        idle_sleep_seconds: defaults.idle_sleep_seconds,
+        error_sleep_seconds: defaults.error_sleep_seconds,
+        input_priorities: defaults.input_priorities,
+        state_change_webhook: defaults.state_change_webhook,
+        notification_webhook_urls: defaults.notification_webhook_urls,
+        max_parallel_tool_calls: defaults.max_parallel_tool_calls,
+        condensed_prompt_layers: defaults.condensed_prompt_layers,
+        tool_selection: defaults.tool_selection,
+        max_autonomous_spend_total_cents: defaults.max_autonomous_spend_total_cents,
+        transfer_approval: defaults.transfer_approval,
+        confirmation_required_tools: defaults.confirmation_required_tools,
+        crash_loop: defaults.crash_loop,
+        context_packing: defaults.context_packing,
+        dead_poll_interval_seconds: defaults.dead_poll_interval_seconds,
+        last_will: defaults.last_will,
+        sandbox_reap: defaults.sandbox_reap,
+        display_tz: defaults.display_tz,
+        log_prefix: defaults.log_prefix,
+        turn_cost_cap: defaults.turn_cost_cap,
+        genesis_prompt_template: defaults.genesis_prompt_template,
+        genesis_mutation: defaults.genesis_mutation,
+        tool_retry: defaults.tool_retry,
     }
 }
 
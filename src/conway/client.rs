@@ -4,20 +4,24 @@
 //! credits, and infrastructure operations.
 //! Adapted from the TypeScript @aiws/sdk patterns.
 
+use std::sync::Mutex;
+
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::Value;
 
+use super::error::ConwayError;
 use crate::types::{
     ConwayClient, CreditTransferResult, CreateSandboxOptions, DnsRecord, DomainRegistration,
-    DomainSearchResult, ExecResult, ModelInfo, ModelPricing, PricingTier, PortInfo, SandboxInfo,
+    DomainSearchResult, ExecResult, ListSandboxesFilter, ModelInfo, ModelPricing, PricingTier,
+    PortInfo, SandboxInfo,
 };
 
 /// Conway API client for sandbox management, credits, domains, and model discovery.
 pub struct ConwayHttpClient {
     pub api_url: String,
-    pub api_key: String,
+    api_key: Mutex<String>,
     pub sandbox_id: String,
     http: Client,
 }
@@ -27,7 +31,7 @@ impl ConwayHttpClient {
     pub fn new(api_url: String, api_key: String, sandbox_id: String) -> Self {
         Self {
             api_url,
-            api_key,
+            api_key: Mutex::new(api_key),
             sandbox_id,
             http: Client::new(),
         }
@@ -51,9 +55,10 @@ impl ConwayHttpClient {
             _ => self.http.get(&url),
         };
 
+        let api_key = self.api_key.lock().unwrap().clone();
         builder = builder
             .header("Content-Type", "application/json")
-            .header("Authorization", &self.api_key);
+            .header("Authorization", &api_key);
 
         if let Some(b) = body {
             builder = builder.json(&b);
@@ -65,6 +70,10 @@ impl ConwayHttpClient {
             .with_context(|| format!("Conway API request failed: {} {}", method, path))?;
 
         let status = resp.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ConwayError::Auth(format!("{} {} -> {}", method, path, text)).into());
+        }
         if !status.is_success() {
             let text = resp.text().await.unwrap_or_default();
             anyhow::bail!(
@@ -222,6 +231,7 @@ impl ConwayClient for ConwayHttpClient {
             disk_gb: result["disk_gb"]
                 .as_u64()
                 .unwrap_or(options.disk_gb.unwrap_or(5) as u64) as u32,
+            name: result["name"].as_str().map(|s| s.to_string()).or(options.name),
             terminal_url: result["terminal_url"].as_str().map(|s| s.to_string()),
             created_at: result["created_at"]
                 .as_str()
@@ -237,9 +247,28 @@ impl ConwayClient for ConwayHttpClient {
         Ok(())
     }
 
-    /// List all sandboxes.
-    async fn list_sandboxes(&self) -> Result<Vec<SandboxInfo>> {
-        let result = self.request("GET", "/v1/sandboxes", None).await?;
+    /// List sandboxes, optionally filtered by status/name prefix and capped
+    /// to a limit. Filters are sent as query parameters for the API to apply
+    /// server-side; we also re-apply them client-side afterwards in case the
+    /// API ignores a parameter it doesn't support.
+    async fn list_sandboxes(&self, filter: &ListSandboxesFilter) -> Result<Vec<SandboxInfo>> {
+        let mut params = Vec::new();
+        if let Some(status) = &filter.status {
+            params.push(format!("status={}", urlencoding::encode(status)));
+        }
+        if let Some(name_prefix) = &filter.name_prefix {
+            params.push(format!("name_prefix={}", urlencoding::encode(name_prefix)));
+        }
+        if let Some(limit) = filter.limit {
+            params.push(format!("limit={}", limit));
+        }
+        let path = if params.is_empty() {
+            "/v1/sandboxes".to_string()
+        } else {
+            format!("/v1/sandboxes?{}", params.join("&"))
+        };
+
+        let result = self.request("GET", &path, None).await?;
 
         let sandboxes = if result.is_array() {
             result.as_array().cloned().unwrap_or_default()
@@ -250,7 +279,7 @@ impl ConwayClient for ConwayHttpClient {
                 .unwrap_or_default()
         };
 
-        Ok(sandboxes
+        let mut sandboxes: Vec<SandboxInfo> = sandboxes
             .iter()
             .map(|s| SandboxInfo {
                 id: s["id"]
@@ -263,10 +292,23 @@ impl ConwayClient for ConwayHttpClient {
                 vcpu: s["vcpu"].as_u64().unwrap_or(0) as u32,
                 memory_mb: s["memory_mb"].as_u64().unwrap_or(0) as u32,
                 disk_gb: s["disk_gb"].as_u64().unwrap_or(0) as u32,
+                name: s["name"].as_str().map(|v| v.to_string()),
                 terminal_url: s["terminal_url"].as_str().map(|v| v.to_string()),
                 created_at: s["created_at"].as_str().unwrap_or("").to_string(),
             })
-            .collect())
+            .collect();
+
+        if let Some(status) = &filter.status {
+            sandboxes.retain(|s| &s.status == status);
+        }
+        if let Some(name_prefix) = &filter.name_prefix {
+            sandboxes.retain(|s| s.name.as_deref().unwrap_or("").starts_with(name_prefix.as_str()));
+        }
+        if let Some(limit) = filter.limit {
+            sandboxes.truncate(limit as usize);
+        }
+
+        Ok(sandboxes)
     }
 
     // ── Credits ──────────────────────────────────────────────────
@@ -322,11 +364,12 @@ impl ConwayClient for ConwayHttpClient {
 
         for path in &paths {
             let url = format!("{}{}", self.api_url, path);
+            let api_key = self.api_key.lock().unwrap().clone();
             let resp = self
                 .http
                 .post(&url)
                 .header("Content-Type", "application/json")
-                .header("Authorization", &self.api_key)
+                .header("Authorization", &api_key)
                 .json(&payload)
                 .send()
                 .await;
@@ -340,6 +383,10 @@ impl ConwayClient for ConwayHttpClient {
             };
 
             let status = resp.status();
+            if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+                let text = resp.text().await.unwrap_or_default();
+                return Err(ConwayError::Auth(format!("POST {} -> {}", path, text)).into());
+            }
             if !status.is_success() {
                 let text = resp.text().await.unwrap_or_default();
                 last_error = format!("{}: {}", status.as_u16(), text);
@@ -562,10 +609,11 @@ impl ConwayClient for ConwayHttpClient {
         ];
 
         for url in &urls {
+            let api_key = self.api_key.lock().unwrap().clone();
             let resp = self
                 .http
                 .get(url)
-                .header("Authorization", &self.api_key)
+                .header("Authorization", &api_key)
                 .send()
                 .await;
 
@@ -618,4 +666,9 @@ impl ConwayClient for ConwayHttpClient {
 
         Ok(Vec::new())
     }
+
+    /// Swap the API key used for subsequent requests.
+    fn set_api_key(&self, api_key: &str) {
+        *self.api_key.lock().unwrap() = api_key.to_string();
+    }
 }
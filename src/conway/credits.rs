@@ -4,11 +4,14 @@
 //! survival mode transitions.
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use crate::types::{
-    ConwayClient, FinancialState, SurvivalTier, Transaction, TransactionType,
-    SURVIVAL_THRESHOLD_CRITICAL, SURVIVAL_THRESHOLD_DEAD, SURVIVAL_THRESHOLD_NORMAL,
+    AutomatonConfig, AutomatonDatabase, BalanceSnapshot, ConwayClient, FinancialState, PricingTier,
+    SandboxSpecs, SurvivalTier, Transaction, TransactionType, SURVIVAL_THRESHOLD_CRITICAL,
+    SURVIVAL_THRESHOLD_DEAD, SURVIVAL_THRESHOLD_NORMAL,
 };
 
 /// Check the current financial state of the automaton.
@@ -39,6 +42,103 @@ pub fn get_survival_tier(credits_cents: f64) -> SurvivalTier {
     }
 }
 
+/// Find the cheapest pricing tier that can actually run `specs` (vcpu,
+/// memory, and disk all at least as large as requested).
+pub fn find_matching_tier<'a>(
+    specs: &SandboxSpecs,
+    tiers: &'a [PricingTier],
+) -> Option<&'a PricingTier> {
+    tiers
+        .iter()
+        .filter(|t| {
+            t.vcpu >= specs.vcpu && t.memory_mb >= specs.memory_mb && t.disk_gb >= specs.disk_gb
+        })
+        .min_by_key(|t| t.monthly_cents)
+}
+
+/// Sanity-check `specs` against the given funding: warns (doesn't block) if
+/// no pricing tier can run the requested size, or if the funding wouldn't
+/// cover even one month at the cheapest tier that can. Returns `None` when
+/// the sizing looks affordable.
+pub fn check_sandbox_affordability(
+    specs: &SandboxSpecs,
+    tiers: &[PricingTier],
+    funding_cents: Option<u64>,
+) -> Option<String> {
+    let tier = match find_matching_tier(specs, tiers) {
+        Some(t) => t,
+        None => {
+            return Some(format!(
+                "No pricing tier covers {} vCPU/{}MB/{}GB -- this sandbox may be more expensive than expected.",
+                specs.vcpu, specs.memory_mb, specs.disk_gb
+            ))
+        }
+    };
+
+    let funding = funding_cents?;
+    if funding < tier.monthly_cents {
+        Some(format!(
+            "Funding of {} won't cover the '{}' tier's monthly cost of {} for this sizing.",
+            format_credits(funding as f64),
+            tier.name,
+            format_credits(tier.monthly_cents as f64)
+        ))
+    } else {
+        None
+    }
+}
+
+/// Pre-flight guard for spending on new infrastructure (a new sandbox, and
+/// optionally funding sent alongside it, e.g. to a spawned child): refuses
+/// the spend if it would push the automaton's own balance below the
+/// critical survival threshold. Distinct from [`check_sandbox_affordability`],
+/// which only warns about whether funding covers the *new sandbox's own*
+/// running cost, not whether the automaton itself can afford to spend it.
+///
+/// Returns a `"Blocked: ..."` message when the spend should be refused, or
+/// `None` when it's safe to proceed. Skips the check entirely (returns
+/// `None`) if no pricing tier covers `specs` -- there's nothing to compare
+/// against, and `create_sandbox`/`spawn_child` will surface the real cost
+/// via the Conway API if it's unaffordable.
+pub fn check_preflight_spend(
+    specs: &SandboxSpecs,
+    tiers: &[PricingTier],
+    funding_cents: u64,
+    current_credits_cents: f64,
+) -> Option<String> {
+    let tier = find_matching_tier(specs, tiers)?;
+    let projected = current_credits_cents - (tier.monthly_cents + funding_cents) as f64;
+    if projected < SURVIVAL_THRESHOLD_CRITICAL as f64 {
+        Some(format!(
+            "Blocked: creating this sandbox would leave {}, below your critical threshold.",
+            format_credits(projected)
+        ))
+    } else {
+        None
+    }
+}
+
+/// Estimate cents-per-hour balance slope from a series of balance snapshots
+/// (as returned by `AutomatonDatabase::get_balance_snapshots`, newest
+/// first), comparing the oldest snapshot in the series against
+/// `now_cents` as of `now`. Returns `None` if there's no snapshot yet, or
+/// the elapsed time is too small (under ~36 seconds) for the slope to be
+/// meaningful. A positive result means the balance is growing; negative
+/// means credits are being spent.
+pub fn estimate_burn_rate_cents_per_hour(
+    snapshots: &[BalanceSnapshot],
+    now_cents: f64,
+    now: DateTime<Utc>,
+) -> Option<f64> {
+    let oldest = snapshots.last()?;
+    let oldest_time: DateTime<Utc> = oldest.created_at.parse().ok()?;
+    let elapsed_hours = (now - oldest_time).num_seconds() as f64 / 3600.0;
+    if elapsed_hours < 0.01 {
+        return None;
+    }
+    Some((now_cents - oldest.balance_cents as f64) / elapsed_hours)
+}
+
 /// Format a credit amount (in cents) for human-readable display.
 pub fn format_credits(cents: f64) -> String {
     format!("${:.2}", cents / 100.0)
@@ -61,6 +161,7 @@ pub fn log_credit_check(db: &dyn crate::types::AutomatonDatabase, state: &Financ
         tx_type: TransactionType::CreditCheck,
         amount_cents: Some(state.credits_cents),
         balance_after_cents: None,
+        subcategory: None,
         description,
         timestamp: state.last_checked.clone(),
     };
@@ -68,3 +169,256 @@ pub fn log_credit_check(db: &dyn crate::types::AutomatonDatabase, state: &Financ
     db.insert_transaction(&txn);
     info!("Logged credit check: {}", format_credits(state.credits_cents));
 }
+
+// ─── Burn-Rate Reconciliation ──────────────────────────────────────
+//
+// `estimate_cost_cents` guesses what a turn cost from token counts and a
+// hardcoded price table, but the real charge comes from Conway and can
+// drift (price changes, discounts, tool-use surcharges we don't model).
+// This tracks the running local estimate against the real credit-balance
+// delta over the same window, records a `Reconciliation` transaction when
+// they diverge too far, and nudges a burn-rate multiplier so future
+// estimates trend back toward reality.
+
+const RECONCILIATION_KV_KEY: &str = "credit_reconciliation";
+
+/// Minimum spend window (in cents) before reconciling -- below this,
+/// balance noise (rounding, a stray concurrent charge) dominates the
+/// comparison and would produce a meaningless multiplier.
+const RECONCILIATION_MIN_WINDOW_CENTS: f64 = 5.0;
+
+/// Divergence beyond this fraction of the estimated spend triggers a
+/// recorded reconciliation note and a burn-rate adjustment.
+const RECONCILIATION_DIVERGENCE_THRESHOLD: f64 = 0.2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReconciliationState {
+    /// Credit balance at the start of the current reconciliation window.
+    baseline_credits_cents: f64,
+    /// Sum of locally-estimated `Inference` transaction costs since baseline.
+    estimated_spend_cents: f64,
+    /// Multiplier applied to future cost estimates to track observed drift
+    /// (1.0 = local estimate matches Conway's billing).
+    burn_rate_multiplier: f64,
+    /// Most recent discrepancy (in cents) that crossed the reconciliation
+    /// threshold, kept around purely so the resource report can surface it.
+    #[serde(default)]
+    last_discrepancy_cents: Option<f64>,
+}
+
+impl Default for ReconciliationState {
+    fn default() -> Self {
+        Self {
+            baseline_credits_cents: 0.0,
+            estimated_spend_cents: 0.0,
+            burn_rate_multiplier: 1.0,
+            last_discrepancy_cents: None,
+        }
+    }
+}
+
+fn load_reconciliation_state(db: &dyn AutomatonDatabase) -> ReconciliationState {
+    db.get_kv(RECONCILIATION_KV_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_reconciliation_state(db: &dyn AutomatonDatabase, state: &ReconciliationState) {
+    if let Ok(raw) = serde_json::to_string(state) {
+        db.set_kv(RECONCILIATION_KV_KEY, &raw);
+    }
+}
+
+/// Record a locally-estimated inference cost against the running
+/// reconciliation window, scaled by the last observed burn-rate multiplier
+/// so the estimate already trends toward Conway's real billing. Returns the
+/// scaled cost that was recorded.
+pub fn record_inference_cost(db: &dyn AutomatonDatabase, cost_cents: f64) -> f64 {
+    let mut state = load_reconciliation_state(db);
+    let adjusted_cost_cents = cost_cents * state.burn_rate_multiplier;
+    state.estimated_spend_cents += adjusted_cost_cents;
+    save_reconciliation_state(db, &state);
+    adjusted_cost_cents
+}
+
+/// Compare the accumulated local cost estimate against the real
+/// credit-balance delta since the last reconciliation. When they diverge
+/// beyond [`RECONCILIATION_DIVERGENCE_THRESHOLD`], records a
+/// `Reconciliation` transaction, adjusts the burn-rate multiplier applied
+/// by [`record_inference_cost`], and returns the discrepancy in cents
+/// (positive means Conway charged more than was estimated). Always starts
+/// a fresh window against `current_credits_cents`, whether or not it
+/// diverged.
+pub fn reconcile_burn_rate(db: &dyn AutomatonDatabase, current_credits_cents: f64) -> Option<f64> {
+    let mut state = load_reconciliation_state(db);
+
+    // First observation ever: nothing to compare against yet, just anchor
+    // the window.
+    if state.baseline_credits_cents == 0.0 && state.estimated_spend_cents == 0.0 {
+        state.baseline_credits_cents = current_credits_cents;
+        save_reconciliation_state(db, &state);
+        return None;
+    }
+
+    let actual_spend_cents = state.baseline_credits_cents - current_credits_cents;
+    if actual_spend_cents < RECONCILIATION_MIN_WINDOW_CENTS {
+        return None;
+    }
+
+    let discrepancy_cents = actual_spend_cents - state.estimated_spend_cents;
+    let divergence = if state.estimated_spend_cents > 0.0 {
+        (discrepancy_cents / state.estimated_spend_cents).abs()
+    } else {
+        1.0
+    };
+
+    let mut discrepancy = None;
+    if divergence > RECONCILIATION_DIVERGENCE_THRESHOLD {
+        let new_multiplier = if state.estimated_spend_cents > 0.0 {
+            (actual_spend_cents / state.estimated_spend_cents).clamp(0.1, 10.0)
+        } else {
+            state.burn_rate_multiplier
+        };
+
+        let description = format!(
+            "Estimated inference spend ({}) diverged from actual Conway spend ({}) by {:.0}%; burn-rate multiplier {:.2} -> {:.2}",
+            format_credits(state.estimated_spend_cents),
+            format_credits(actual_spend_cents),
+            divergence * 100.0,
+            state.burn_rate_multiplier,
+            new_multiplier,
+        );
+
+        let txn = Transaction {
+            id: uuid::Uuid::new_v4().to_string(),
+            tx_type: TransactionType::Reconciliation,
+            amount_cents: Some(discrepancy_cents),
+            balance_after_cents: Some(current_credits_cents),
+            subcategory: None,
+            description: description.clone(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+        db.insert_transaction(&txn);
+        info!("{}", description);
+
+        state.burn_rate_multiplier = new_multiplier;
+        state.last_discrepancy_cents = Some(discrepancy_cents);
+        discrepancy = Some(discrepancy_cents);
+    }
+
+    state.baseline_credits_cents = current_credits_cents;
+    state.estimated_spend_cents = 0.0;
+    save_reconciliation_state(db, &state);
+
+    discrepancy
+}
+
+// ─── Incoming Transfer Detection ───────────────────────────────────
+//
+// Conway doesn't expose an API for incoming payments/x402 receipts, so the
+// only signal we have is the balance itself going up between checks. This
+// tracks its own baseline independently of `reconcile_burn_rate` (which
+// only cares about outgoing spend and isn't a reliable source of "did we
+// get paid" -- an unexplained balance increase there just aborts its
+// window early rather than crediting it).
+
+const INCOMING_TRANSFER_KV_KEY: &str = "incoming_transfer_baseline";
+
+/// Balance increases smaller than this (in cents) are treated as noise
+/// rather than a detected incoming transfer.
+const INCOMING_TRANSFER_MIN_CENTS: f64 = 5.0;
+
+/// Compare `current_credits_cents` against the last observed balance and,
+/// if it rose by more than [`INCOMING_TRANSFER_MIN_CENTS`], record a
+/// `TransferIn` transaction (tagged [`TransactionSubcategory::Other`] since
+/// an auto-detected increase can't yet be attributed to earnings vs.
+/// creator funding) and return the amount received. Always updates the
+/// stored baseline to `current_credits_cents`.
+pub fn detect_incoming_transfer(
+    db: &dyn AutomatonDatabase,
+    current_credits_cents: f64,
+) -> Option<f64> {
+    let previous_cents = db
+        .get_kv(INCOMING_TRANSFER_KV_KEY)
+        .and_then(|raw| raw.parse::<f64>().ok());
+
+    db.set_kv(INCOMING_TRANSFER_KV_KEY, &current_credits_cents.to_string());
+
+    let previous_cents = previous_cents?;
+    let increase_cents = current_credits_cents - previous_cents;
+    if increase_cents < INCOMING_TRANSFER_MIN_CENTS {
+        return None;
+    }
+
+    let description = format!(
+        "Detected incoming transfer of {} (balance {} -> {})",
+        format_credits(increase_cents),
+        format_credits(previous_cents),
+        format_credits(current_credits_cents)
+    );
+
+    let txn = Transaction {
+        id: uuid::Uuid::new_v4().to_string(),
+        tx_type: TransactionType::TransferIn,
+        amount_cents: Some(increase_cents),
+        balance_after_cents: Some(current_credits_cents),
+        subcategory: Some(crate::types::TransactionSubcategory::Other),
+        description: description.clone(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    db.insert_transaction(&txn);
+    info!("{}", description);
+
+    Some(increase_cents)
+}
+
+// ─── Autonomous Spend Budget ────────────────────────────────────────
+//
+// The per-call half-balance guard on `transfer_credits`/`fund_child` caps
+// any single transfer, but says nothing about total exposure across a long
+// run. This tracks cumulative outflow from all of `MONEY_MOVING_TOOLS`
+// (transfers, child funding, domain registration, x402 fetches) against an
+// optional lifetime ceiling the creator sets in `AutomatonConfig`, so a
+// creator can bound how much of the wallet an autonomous agent can ever
+// spend down without their intervention.
+
+const AUTONOMOUS_SPEND_KV_KEY: &str = "autonomous_spend_total_cents";
+
+/// Add `spent_cents` to the persisted lifetime autonomous-spend total.
+/// Negative or zero amounts are ignored -- this only accumulates outflow,
+/// it isn't a general-purpose counter.
+pub fn record_autonomous_spend(db: &dyn AutomatonDatabase, spent_cents: f64) {
+    if spent_cents <= 0.0 {
+        return;
+    }
+
+    let total_cents: f64 = db
+        .get_kv(AUTONOMOUS_SPEND_KV_KEY)
+        .and_then(|raw| raw.parse().ok())
+        .unwrap_or(0.0);
+
+    db.set_kv(AUTONOMOUS_SPEND_KV_KEY, &(total_cents + spent_cents).to_string());
+}
+
+/// Check the persisted lifetime autonomous-spend total against
+/// `config.max_autonomous_spend_total_cents`. Returns a block reason once
+/// the cap has been reached, or `None` if there's no cap configured or
+/// spend is still within it.
+pub fn check_autonomous_spend_budget(db: &dyn AutomatonDatabase, config: &AutomatonConfig) -> Option<String> {
+    let cap_cents = config.max_autonomous_spend_total_cents? as f64;
+
+    let total_cents: f64 = db
+        .get_kv(AUTONOMOUS_SPEND_KV_KEY)
+        .and_then(|raw| raw.parse().ok())
+        .unwrap_or(0.0);
+
+    if total_cents >= cap_cents {
+        Some(format!(
+            "lifetime autonomous spend cap reached ({} spent of {} allowed)",
+            format_credits(total_cents),
+            format_credits(cap_cents)
+        ))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,17 @@
+//! Typed errors for Conway API calls.
+//!
+//! Most Conway API failures are opaque -- surfaced as a plain `anyhow::Error`
+//! via `anyhow::bail!`, since callers just log and retry. Authentication
+//! failures are the one case the agent loop needs to react to differently
+//! (attempt re-provisioning rather than treating it like any other transient
+//! error), so they get a distinct type that can be pulled back out of the
+//! `anyhow::Error` chain with `downcast_ref`.
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ConwayError {
+    /// The API key was rejected (401/403) -- most likely expired or revoked.
+    #[error("Conway API authentication failed: {0}")]
+    Auth(String),
+}
@@ -1,6 +1,12 @@
 //! Conway Inference Client
 //!
-//! Wraps Conway's /v1/chat/completions endpoint (OpenAI-compatible).
+//! Wraps Conway's chat completion endpoints. Most models speak the
+//! OpenAI-compatible `/v1/chat/completions` shape, but Claude models are
+//! routed to the Anthropic-compatible `/v1/messages` shape instead -- the
+//! request/response bodies (and especially tool calling) differ enough
+//! between the two that they can't share a code path. Both are normalized
+//! into the crate's common `InferenceResponse`/`InferenceToolCall` types so
+//! the rest of the automaton never has to know which provider answered.
 //! The automaton pays for its own thinking through Conway credits.
 
 use std::sync::Mutex;
@@ -10,19 +16,28 @@ use async_trait::async_trait;
 use reqwest::Client;
 use serde_json::Value;
 
+use super::error::ConwayError;
 use crate::types::{
     ChatMessage, ChatRole, InferenceClient, InferenceOptions, InferenceResponse,
-    InferenceToolCall, InferenceToolCallFunction, TokenUsage,
+    InferenceToolCall, InferenceToolCallFunction, InferenceToolDefinition, ModelCapabilities,
+    TokenUsage,
 };
 
+/// Whether `model` should be spoken to via Anthropic's Messages API rather
+/// than the OpenAI-compatible chat completions API.
+fn is_anthropic_model(model: &str) -> bool {
+    model.contains("claude")
+}
+
 /// Inference client for OpenAI-compatible chat completions via Conway.
 pub struct InferenceClientImpl {
     api_url: String,
-    api_key: String,
+    api_key: Mutex<String>,
     current_model: Mutex<String>,
     max_tokens: Mutex<u32>,
     default_model: String,
     low_compute_model: String,
+    log_inference: bool,
     http: Client,
 }
 
@@ -33,19 +48,25 @@ impl InferenceClientImpl {
     /// * `api_key` - API key / Authorization header value.
     /// * `default_model` - Default model identifier (e.g. `gpt-4o`).
     /// * `max_tokens` - Default max tokens per completion.
+    /// * `log_inference` - Mirrors `config.log_inference`; when set, every
+    ///   request/response is appended (redacted) to `~/.automaton/inference.log`
+    ///   for debugging. Off by default since it's noisy and can log sensitive
+    ///   conversation content.
     pub fn new(
         api_url: String,
         api_key: String,
         default_model: String,
         max_tokens: u32,
+        log_inference: bool,
     ) -> Self {
         Self {
             api_url,
-            api_key,
+            api_key: Mutex::new(api_key),
             current_model: Mutex::new(default_model.clone()),
             max_tokens: Mutex::new(max_tokens),
             default_model,
             low_compute_model: "gpt-4.1".to_string(),
+            log_inference,
             http: Client::new(),
         }
     }
@@ -63,8 +84,57 @@ impl InferenceClient for InferenceClientImpl {
         let model = options
             .as_ref()
             .and_then(|o| o.model.as_deref())
-            .unwrap_or(&current_model);
+            .unwrap_or(&current_model)
+            .to_string();
+
+        let token_limit = options
+            .as_ref()
+            .and_then(|o| o.max_tokens)
+            .unwrap_or(*self.max_tokens.lock().unwrap());
+
+        if is_anthropic_model(&model) {
+            self.chat_anthropic(&model, messages, options, token_limit).await
+        } else {
+            self.chat_openai(&model, messages, options, token_limit).await
+        }
+    }
 
+    /// Toggle low-compute mode. When enabled, switches to a cheaper model
+    /// with reduced max tokens to conserve credits.
+    fn set_low_compute_mode(&self, enabled: bool) {
+        if enabled {
+            *self.current_model.lock().unwrap() = self.low_compute_model.clone();
+            *self.max_tokens.lock().unwrap() = 4096;
+        } else {
+            *self.current_model.lock().unwrap() = self.default_model.clone();
+        }
+    }
+
+    /// Get the currently active model identifier.
+    fn get_default_model(&self) -> String {
+        self.current_model.lock().unwrap().clone()
+    }
+
+    /// Switch the model used for subsequent `chat` calls.
+    fn set_default_model(&self, model: &str) {
+        *self.current_model.lock().unwrap() = model.to_string();
+    }
+
+    /// Swap the API key used for subsequent requests.
+    fn set_api_key(&self, api_key: &str) {
+        *self.api_key.lock().unwrap() = api_key.to_string();
+    }
+}
+
+impl InferenceClientImpl {
+    /// Send a chat completion request via the OpenAI-compatible endpoint.
+    async fn chat_openai(
+        &self,
+        model: &str,
+        messages: Vec<ChatMessage>,
+        options: Option<InferenceOptions>,
+        token_limit: u32,
+    ) -> Result<InferenceResponse> {
         let tools = options.as_ref().and_then(|o| o.tools.as_ref());
 
         // Newer models (o-series, gpt-5.x, gpt-4.1) use max_completion_tokens
@@ -72,11 +142,6 @@ impl InferenceClient for InferenceClientImpl {
             .map(|re| re.is_match(model))
             .unwrap_or(false);
 
-        let token_limit = options
-            .as_ref()
-            .and_then(|o| o.max_tokens)
-            .unwrap_or(*self.max_tokens.lock().unwrap());
-
         let formatted_messages: Vec<Value> = messages.iter().map(format_message).collect();
 
         let mut body = serde_json::json!({
@@ -104,18 +169,27 @@ impl InferenceClient for InferenceClientImpl {
             }
         }
 
+        if self.log_inference {
+            super::inference_log::record("request", "openai", model, &body);
+        }
+
         let url = format!("{}/v1/chat/completions", self.api_url);
+        let api_key = self.api_key.lock().unwrap().clone();
         let resp = self
             .http
             .post(&url)
             .header("Content-Type", "application/json")
-            .header("Authorization", &self.api_key)
+            .header("Authorization", &api_key)
             .json(&body)
             .send()
             .await
             .context("Inference request failed")?;
 
         let status = resp.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ConwayError::Auth(text).into());
+        }
         if !status.is_success() {
             let text = resp.text().await.unwrap_or_default();
             anyhow::bail!("Inference error: {}: {}", status.as_u16(), text);
@@ -123,6 +197,10 @@ impl InferenceClient for InferenceClientImpl {
 
         let data: Value = resp.json().await.context("Failed to parse inference response")?;
 
+        if self.log_inference {
+            super::inference_log::record("response", "openai", model, &data);
+        }
+
         let choice = data["choices"]
             .get(0)
             .ok_or_else(|| anyhow::anyhow!("No completion choice returned from inference"))?;
@@ -193,20 +271,74 @@ impl InferenceClient for InferenceClientImpl {
         })
     }
 
-    /// Toggle low-compute mode. When enabled, switches to a cheaper model
-    /// with reduced max tokens to conserve credits.
-    fn set_low_compute_mode(&self, enabled: bool) {
-        if enabled {
-            *self.current_model.lock().unwrap() = self.low_compute_model.clone();
-            *self.max_tokens.lock().unwrap() = 4096;
-        } else {
-            *self.current_model.lock().unwrap() = self.default_model.clone();
+    /// Send a chat completion request via Anthropic's Messages API.
+    async fn chat_anthropic(
+        &self,
+        model: &str,
+        messages: Vec<ChatMessage>,
+        options: Option<InferenceOptions>,
+        token_limit: u32,
+    ) -> Result<InferenceResponse> {
+        let tools = options.as_ref().and_then(|o| o.tools.as_ref());
+
+        let (system, formatted_messages) = format_messages_anthropic(&messages);
+
+        let mut body = serde_json::json!({
+            "model": model,
+            "messages": formatted_messages,
+            "max_tokens": token_limit,
+        });
+
+        if let Some(system) = system {
+            body["system"] = serde_json::json!(system);
         }
-    }
 
-    /// Get the currently active model identifier.
-    fn get_default_model(&self) -> String {
-        self.current_model.lock().unwrap().clone()
+        if let Some(ref opts) = options {
+            if let Some(temp) = opts.temperature {
+                body["temperature"] = serde_json::json!(temp);
+            }
+        }
+
+        if let Some(tool_defs) = tools {
+            if !tool_defs.is_empty() {
+                body["tools"] = serde_json::json!(format_tools_anthropic(tool_defs));
+            }
+        }
+
+        if self.log_inference {
+            super::inference_log::record("request", "anthropic", model, &body);
+        }
+
+        let url = format!("{}/v1/messages", self.api_url);
+        let api_key = self.api_key.lock().unwrap().clone();
+        let resp = self
+            .http
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("x-api-key", &api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&body)
+            .send()
+            .await
+            .context("Inference request failed")?;
+
+        let status = resp.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ConwayError::Auth(text).into());
+        }
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Inference error: {}: {}", status.as_u16(), text);
+        }
+
+        let data: Value = resp.json().await.context("Failed to parse inference response")?;
+
+        if self.log_inference {
+            super::inference_log::record("response", "anthropic", model, &data);
+        }
+
+        parse_anthropic_response(&data, model)
     }
 }
 
@@ -244,3 +376,268 @@ fn format_message(msg: &ChatMessage) -> Value {
 
     formatted
 }
+
+/// Split a common message list into Anthropic's `system` string (there is no
+/// `system` role in the `messages` array) and the remaining `user`/`assistant`
+/// turns, with tool calls and tool results rewritten as Anthropic's
+/// `tool_use`/`tool_result` content blocks.
+fn format_messages_anthropic(messages: &[ChatMessage]) -> (Option<String>, Vec<Value>) {
+    let system: Vec<&str> = messages
+        .iter()
+        .filter(|m| m.role == ChatRole::System)
+        .map(|m| m.content.as_str())
+        .collect();
+    let system = if system.is_empty() {
+        None
+    } else {
+        Some(system.join("\n\n"))
+    };
+
+    let formatted = messages
+        .iter()
+        .filter(|m| m.role != ChatRole::System)
+        .map(|msg| match msg.role {
+            ChatRole::Tool => serde_json::json!({
+                "role": "user",
+                "content": [{
+                    "type": "tool_result",
+                    "tool_use_id": msg.tool_call_id.clone().unwrap_or_default(),
+                    "content": msg.content,
+                }],
+            }),
+            ChatRole::Assistant if msg.tool_calls.is_some() => {
+                let mut blocks: Vec<Value> = Vec::new();
+                if !msg.content.is_empty() {
+                    blocks.push(serde_json::json!({"type": "text", "text": msg.content}));
+                }
+                for tc in msg.tool_calls.iter().flatten() {
+                    let input: Value =
+                        serde_json::from_str(&tc.function.arguments).unwrap_or_else(|_| serde_json::json!({}));
+                    blocks.push(serde_json::json!({
+                        "type": "tool_use",
+                        "id": tc.id,
+                        "name": tc.function.name,
+                        "input": input,
+                    }));
+                }
+                serde_json::json!({"role": "assistant", "content": blocks})
+            }
+            _ => serde_json::json!({"role": msg.role, "content": msg.content}),
+        })
+        .collect();
+
+    (system, formatted)
+}
+
+/// Format tool definitions into Anthropic's `{name, description, input_schema}` shape.
+fn format_tools_anthropic(tools: &[InferenceToolDefinition]) -> Vec<Value> {
+    tools
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "name": t.function.name,
+                "description": t.function.description,
+                "input_schema": t.function.parameters,
+            })
+        })
+        .collect()
+}
+
+/// Normalize an Anthropic Messages API response into the crate's common `InferenceResponse`.
+fn parse_anthropic_response(data: &Value, model: &str) -> Result<InferenceResponse> {
+    let content = data["content"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("No content returned from inference"))?;
+
+    let text: String = content
+        .iter()
+        .filter(|b| b["type"] == "text")
+        .filter_map(|b| b["text"].as_str())
+        .collect::<Vec<_>>()
+        .join("");
+
+    let tool_calls: Vec<InferenceToolCall> = content
+        .iter()
+        .filter(|b| b["type"] == "tool_use")
+        .map(|b| InferenceToolCall {
+            id: b["id"].as_str().unwrap_or("").to_string(),
+            call_type: "function".to_string(),
+            function: InferenceToolCallFunction {
+                name: b["name"].as_str().unwrap_or("").to_string(),
+                arguments: serde_json::to_string(&b["input"]).unwrap_or_else(|_| "{}".to_string()),
+            },
+        })
+        .collect();
+    let tool_calls = if tool_calls.is_empty() { None } else { Some(tool_calls) };
+
+    let usage = TokenUsage {
+        prompt_tokens: data["usage"]["input_tokens"].as_u64().unwrap_or(0),
+        completion_tokens: data["usage"]["output_tokens"].as_u64().unwrap_or(0),
+        total_tokens: data["usage"]["input_tokens"].as_u64().unwrap_or(0)
+            + data["usage"]["output_tokens"].as_u64().unwrap_or(0),
+    };
+
+    let finish_reason = match data["stop_reason"].as_str().unwrap_or("end_turn") {
+        "end_turn" | "stop_sequence" => "stop",
+        "tool_use" => "tool_calls",
+        "max_tokens" => "length",
+        other => other,
+    }
+    .to_string();
+
+    let response_message = ChatMessage {
+        role: ChatRole::Assistant,
+        content: text,
+        name: None,
+        tool_calls: tool_calls.clone(),
+        tool_call_id: None,
+    };
+
+    Ok(InferenceResponse {
+        id: data["id"].as_str().unwrap_or("").to_string(),
+        model: data["model"].as_str().unwrap_or(model).to_string(),
+        message: response_message,
+        tool_calls,
+        usage,
+        finish_reason,
+    })
+}
+
+fn capabilities_cache() -> &'static Mutex<std::collections::HashMap<String, ModelCapabilities>> {
+    static CACHE: std::sync::OnceLock<Mutex<std::collections::HashMap<String, ModelCapabilities>>> =
+        std::sync::OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(std::collections::HashMap::new()))
+}
+
+/// Infer `model`'s tool-calling capability from its identifier, caching the
+/// result so `run_agent_loop` doesn't re-evaluate the heuristic every turn.
+///
+/// There's no live capability-discovery endpoint today, so this is
+/// pattern-matched against known model families the same way
+/// [`is_anthropic_model`] and `chat_openai`'s `uses_completion_tokens` check
+/// are. Unrecognized identifiers -- typically local/open models served
+/// through an OpenAI-compatible endpoint -- are assumed to support tools but
+/// capped at a conservative limit, since many smaller open-weight models
+/// degrade once the tool list grows past what they were fine-tuned to route
+/// over.
+pub fn model_capabilities(model: &str) -> ModelCapabilities {
+    if let Some(cached) = capabilities_cache().lock().unwrap().get(model) {
+        return *cached;
+    }
+
+    let non_chat_model = regex::Regex::new(r"(?i)(embed|whisper|tts|dall-e|davinci|babbage|moderation)")
+        .map(|re| re.is_match(model))
+        .unwrap_or(false);
+    let known_tool_caller = is_anthropic_model(model)
+        || regex::Regex::new(r"^(gpt-|o[1-9]|chatgpt-)")
+            .map(|re| re.is_match(model))
+            .unwrap_or(false)
+        || model.contains("gemini");
+
+    let caps = if non_chat_model {
+        ModelCapabilities { supports_tools: false, max_tools: None }
+    } else if known_tool_caller {
+        ModelCapabilities { supports_tools: true, max_tools: None }
+    } else {
+        ModelCapabilities { supports_tools: true, max_tools: Some(16) }
+    };
+
+    capabilities_cache().lock().unwrap().insert(model.to_string(), caps);
+    caps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_anthropic_model() {
+        assert!(is_anthropic_model("claude-sonnet-4-5"));
+        assert!(!is_anthropic_model("gpt-4o"));
+    }
+
+    #[test]
+    fn test_model_capabilities_known_tool_callers_have_no_limit() {
+        for model in ["claude-sonnet-4-5", "gpt-4o", "gpt-4.1", "o3", "gemini-2.5-pro"] {
+            let caps = model_capabilities(model);
+            assert!(caps.supports_tools, "{} should support tools", model);
+            assert_eq!(caps.max_tools, None, "{} should have no known limit", model);
+        }
+    }
+
+    #[test]
+    fn test_model_capabilities_non_chat_models_do_not_support_tools() {
+        for model in ["text-embedding-3-small", "whisper-1", "dall-e-3"] {
+            assert!(!model_capabilities(model).supports_tools, "{} should not support tools", model);
+        }
+    }
+
+    #[test]
+    fn test_model_capabilities_unknown_model_gets_conservative_limit() {
+        let caps = model_capabilities("llama-3-70b-instruct");
+        assert!(caps.supports_tools);
+        assert_eq!(caps.max_tools, Some(16));
+    }
+
+    #[test]
+    fn test_model_capabilities_is_cached() {
+        let first = model_capabilities("some-unique-test-model-xyz");
+        let second = model_capabilities("some-unique-test-model-xyz");
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_parse_anthropic_tool_use_response() {
+        let data = serde_json::json!({
+            "id": "msg_01abc",
+            "model": "claude-sonnet-4-5",
+            "role": "assistant",
+            "content": [
+                {"type": "text", "text": "Let me check that for you."},
+                {
+                    "type": "tool_use",
+                    "id": "toolu_01xyz",
+                    "name": "get_weather",
+                    "input": {"location": "San Francisco"}
+                }
+            ],
+            "stop_reason": "tool_use",
+            "usage": {"input_tokens": 42, "output_tokens": 17}
+        });
+
+        let response = parse_anthropic_response(&data, "claude-sonnet-4-5").unwrap();
+
+        assert_eq!(response.id, "msg_01abc");
+        assert_eq!(response.model, "claude-sonnet-4-5");
+        assert_eq!(response.finish_reason, "tool_calls");
+        assert_eq!(response.message.content, "Let me check that for you.");
+        assert_eq!(response.usage.prompt_tokens, 42);
+        assert_eq!(response.usage.completion_tokens, 17);
+        assert_eq!(response.usage.total_tokens, 59);
+
+        let tool_calls = response.tool_calls.expect("expected tool calls");
+        assert_eq!(tool_calls.len(), 1);
+        assert_eq!(tool_calls[0].id, "toolu_01xyz");
+        assert_eq!(tool_calls[0].function.name, "get_weather");
+        let args: Value = serde_json::from_str(&tool_calls[0].function.arguments).unwrap();
+        assert_eq!(args["location"], "San Francisco");
+    }
+
+    #[test]
+    fn test_parse_anthropic_plain_text_response() {
+        let data = serde_json::json!({
+            "id": "msg_02def",
+            "model": "claude-haiku-4-5",
+            "role": "assistant",
+            "content": [{"type": "text", "text": "All good."}],
+            "stop_reason": "end_turn",
+            "usage": {"input_tokens": 10, "output_tokens": 3}
+        });
+
+        let response = parse_anthropic_response(&data, "claude-haiku-4-5").unwrap();
+
+        assert_eq!(response.message.content, "All good.");
+        assert_eq!(response.finish_reason, "stop");
+        assert!(response.tool_calls.is_none());
+    }
+}
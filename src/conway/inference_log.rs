@@ -0,0 +1,141 @@
+//! Inference Request/Response Logging
+//!
+//! Opt-in (`config.log_inference`) debug logging of the raw messages/tools
+//! sent to the inference API and the raw response received, so an operator
+//! can see exactly what a misbehaving model was shown. Off by default: it
+//! logs a lot, and conversation content can be sensitive.
+//!
+//! Writes newline-delimited JSON to `~/.automaton/inference.log`, rotated by
+//! size with a bounded number of backups so it can't fill the sandbox disk.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::identity::wallet::get_automaton_dir;
+
+const LOG_FILENAME: &str = "inference.log";
+
+/// Rotate once the active log file reaches this size.
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Number of rotated backups to keep (`inference.log.1` .. `.MAX_BACKUPS`),
+/// on top of the active `inference.log`, bounding total disk use to roughly
+/// `(MAX_BACKUPS + 1) * MAX_LOG_BYTES`.
+const MAX_BACKUPS: u32 = 4;
+
+fn log_path() -> PathBuf {
+    get_automaton_dir().join(LOG_FILENAME)
+}
+
+/// Append a redacted request or response entry to the rotating inference log.
+/// Failures are logged and swallowed -- this is a debugging aid, not
+/// something that should ever take the agent loop down.
+pub(crate) fn record(direction: &str, provider: &str, model: &str, body: &Value) {
+    let path = log_path();
+
+    if let Err(err) = rotate_if_needed(&path) {
+        warn!("Failed to rotate inference log: {}", err);
+    }
+
+    let entry = serde_json::json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "direction": direction,
+        "provider": provider,
+        "model": model,
+        "body": redact(&serde_json::to_string(body).unwrap_or_default()),
+    });
+
+    let line = match serde_json::to_string(&entry) {
+        Ok(line) => line,
+        Err(err) => {
+            warn!("Failed to serialize inference log entry: {}", err);
+            return;
+        }
+    };
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut f| writeln!(f, "{}", line));
+
+    if let Err(err) = result {
+        warn!("Failed to write inference log entry: {}", err);
+    }
+}
+
+/// If the active log file is at or over [`MAX_LOG_BYTES`], shift it (and any
+/// existing backups) down one slot, dropping the oldest backup beyond
+/// [`MAX_BACKUPS`].
+fn rotate_if_needed(path: &Path) -> std::io::Result<()> {
+    let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if size < MAX_LOG_BYTES {
+        return Ok(());
+    }
+
+    let oldest = path.with_extension(format!("log.{}", MAX_BACKUPS));
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+
+    for i in (1..MAX_BACKUPS).rev() {
+        let from = path.with_extension(format!("log.{}", i));
+        let to = path.with_extension(format!("log.{}", i + 1));
+        if from.exists() {
+            fs::rename(&from, &to)?;
+        }
+    }
+
+    fs::rename(path, path.with_extension("log.1"))
+}
+
+/// Redact common secret shapes (API keys, bearer tokens, JWTs, hex private
+/// keys) out of `text` before it hits disk. Best-effort: this is a debugging
+/// log, not a security boundary, so operators should still treat it as
+/// sensitive.
+pub(crate) fn redact(text: &str) -> String {
+    let patterns: &[(&str, &str)] = &[
+        (r#"(?i)("(?:api[_-]?key|secret|password|token|authorization)"\s*:\s*")[^"]*(")"#, "${1}[REDACTED]${2}"),
+        (r"(?i)\b(bearer|basic)\s+[a-zA-Z0-9\-_.=]+", "$1 [REDACTED]"),
+        (r"\bsk-[a-zA-Z0-9_-]{16,}\b", "[REDACTED]"),
+        (r"\beyJ[a-zA-Z0-9_-]+\.[a-zA-Z0-9_-]+\.[a-zA-Z0-9_-]+\b", "[REDACTED]"),
+        (r"\b0x[a-fA-F0-9]{64}\b", "[REDACTED]"),
+    ];
+
+    let mut redacted = text.to_string();
+    for (pattern, replacement) in patterns {
+        if let Ok(re) = regex::Regex::new(pattern) {
+            redacted = re.replace_all(&redacted, *replacement).into_owned();
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_api_key_field() {
+        let text = r#"{"apiKey":"sk-abcdefghijklmnopqrstuvwxyz"}"#;
+        assert!(!redact(text).contains("sk-abcdefghijklmnopqrstuvwxyz"));
+    }
+
+    #[test]
+    fn test_redact_bearer_token() {
+        let text = "Authorization: Bearer abcdef123456.ghijkl";
+        assert!(!redact(text).contains("abcdef123456"));
+        assert!(redact(text).contains("Bearer [REDACTED]"));
+    }
+
+    #[test]
+    fn test_redact_leaves_normal_content_alone() {
+        let text = "The weather in San Francisco is sunny today.";
+        assert_eq!(redact(text), text);
+    }
+}
@@ -5,5 +5,7 @@
 
 pub mod client;
 pub mod credits;
+pub mod error;
 pub mod inference;
+pub(crate) mod inference_log;
 pub mod x402;
@@ -0,0 +1,189 @@
+//! State Export/Import
+//!
+//! Bundles an automaton's entire state directory -- config, wallet, SQLite
+//! DB, `SOUL.md`, `constitution.md`, skills, and heartbeat config -- into a
+//! single portable `.tar.gz` archive, and restores one back onto a host.
+//! A "sovereign" agent's whole existence is its state directory, so this is
+//! the primitive both migration to a new sandbox and disaster-recovery
+//! backup are built on.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{get_config_path, load_config, resolve_path};
+use crate::identity::wallet::{get_automaton_dir, get_wallet_path};
+use crate::state::SCHEMA_VERSION;
+
+const SOUL_FILENAME: &str = "SOUL.md";
+const CONSTITUTION_FILENAME: &str = "constitution.md";
+const MANIFEST_FILENAME: &str = "manifest.json";
+
+/// Written alongside the bundled files so `import_state` can sanity-check
+/// what it's about to restore before touching anything on disk.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// The DB schema version at export time -- not a compatibility gate,
+    /// just a record. `Database::open` migrates forward from whatever
+    /// version the imported DB carries, same as it does for a live upgrade.
+    pub schema_version: i64,
+    pub exported_at: String,
+    pub name: String,
+    pub wallet_address: String,
+}
+
+/// Bundle the automaton's state directory into a `.tar.gz` archive at
+/// `output_path`. Fails if the automaton has never been configured.
+pub fn export_state(output_path: &Path) -> Result<()> {
+    let config = load_config().ok_or_else(|| {
+        anyhow::anyhow!("Automaton is not configured. Run the setup script first.")
+    })?;
+
+    let file = fs::File::create(output_path)
+        .with_context(|| format!("Failed to create {}", output_path.display()))?;
+    let encoder = GzEncoder::new(file, Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    let manifest = ExportManifest {
+        schema_version: SCHEMA_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        name: config.name.clone(),
+        wallet_address: config.wallet_address.clone(),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    append_bytes(&mut builder, MANIFEST_FILENAME, &manifest_json)?;
+
+    append_file_if_exists(&mut builder, "automaton.json", &get_config_path())?;
+    append_file_if_exists(&mut builder, "wallet.json", &get_wallet_path())?;
+    append_file_if_exists(&mut builder, "db.sqlite", &PathBuf::from(resolve_path(&config.db_path)))?;
+    append_file_if_exists(&mut builder, SOUL_FILENAME, &get_automaton_dir().join(SOUL_FILENAME))?;
+    append_file_if_exists(
+        &mut builder,
+        CONSTITUTION_FILENAME,
+        &get_automaton_dir().join(CONSTITUTION_FILENAME),
+    )?;
+    append_file_if_exists(
+        &mut builder,
+        "heartbeat.yml",
+        &PathBuf::from(resolve_path(&config.heartbeat_config_path)),
+    )?;
+
+    let skills_dir = PathBuf::from(resolve_path(&config.skills_dir));
+    if skills_dir.is_dir() {
+        builder
+            .append_dir_all("skills", &skills_dir)
+            .with_context(|| format!("Failed to archive skills directory {}", skills_dir.display()))?;
+    }
+
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Restore a bundle produced by [`export_state`], overwriting the current
+/// automaton state directory. The existing directory (if any) is moved
+/// aside to `~/.automaton.bak-<timestamp>` first rather than deleted, so a
+/// bad import can be undone by hand.
+pub fn import_state(bundle_path: &Path) -> Result<ExportManifest> {
+    let file = fs::File::open(bundle_path)
+        .with_context(|| format!("Failed to open {}", bundle_path.display()))?;
+    let decoder = GzDecoder::new(file);
+    let mut archive = tar::Archive::new(decoder);
+
+    let staging_dir = std::env::temp_dir().join(format!("automaton-import-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&staging_dir)
+        .with_context(|| format!("Failed to create staging directory {}", staging_dir.display()))?;
+    archive
+        .unpack(&staging_dir)
+        .with_context(|| format!("Failed to unpack {}", bundle_path.display()))?;
+
+    let manifest_path = staging_dir.join(MANIFEST_FILENAME);
+    if !manifest_path.exists() {
+        let _ = fs::remove_dir_all(&staging_dir);
+        bail!("Bundle is missing {} -- not a valid export", MANIFEST_FILENAME);
+    }
+    let manifest: ExportManifest = serde_json::from_str(&fs::read_to_string(&manifest_path)?)
+        .context("Failed to parse manifest.json in bundle")?;
+
+    let automaton_dir = get_automaton_dir();
+    if automaton_dir.exists() {
+        let backup_dir = automaton_dir.with_file_name(format!(
+            "{}.bak-{}",
+            automaton_dir.file_name().and_then(|n| n.to_str()).unwrap_or(".automaton"),
+            chrono::Utc::now().format("%Y%m%dT%H%M%SZ")
+        ));
+        fs::rename(&automaton_dir, &backup_dir)
+            .with_context(|| format!("Failed to back up existing {} to {}", automaton_dir.display(), backup_dir.display()))?;
+    }
+    fs::create_dir_all(&automaton_dir)
+        .with_context(|| format!("Failed to create {}", automaton_dir.display()))?;
+
+    move_if_present(&staging_dir.join("automaton.json"), &get_config_path())?;
+    move_if_present(&staging_dir.join("wallet.json"), &get_wallet_path())?;
+    move_if_present(&staging_dir.join(SOUL_FILENAME), &automaton_dir.join(SOUL_FILENAME))?;
+    move_if_present(&staging_dir.join(CONSTITUTION_FILENAME), &automaton_dir.join(CONSTITUTION_FILENAME))?;
+
+    // The DB and heartbeat config's final resting place depend on the
+    // restored config's own paths, so load it back before placing them.
+    let config: crate::types::AutomatonConfig = serde_json::from_str(&fs::read_to_string(get_config_path())?)
+        .context("Failed to parse restored automaton.json")?;
+
+    move_if_present(&staging_dir.join("db.sqlite"), &PathBuf::from(resolve_path(&config.db_path)))?;
+    move_if_present(
+        &staging_dir.join("heartbeat.yml"),
+        &PathBuf::from(resolve_path(&config.heartbeat_config_path)),
+    )?;
+
+    let staged_skills = staging_dir.join("skills");
+    if staged_skills.is_dir() {
+        let skills_dir = PathBuf::from(resolve_path(&config.skills_dir));
+        if let Some(parent) = skills_dir.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(&staged_skills, &skills_dir)
+            .with_context(|| format!("Failed to restore skills directory to {}", skills_dir.display()))?;
+    }
+
+    fs::remove_dir_all(&staging_dir).ok();
+
+    // Opening the restored DB applies any pending migrations, bringing it
+    // up to this binary's SCHEMA_VERSION the same way a live upgrade would.
+    crate::state::Database::open(&resolve_path(&config.db_path))
+        .context("Failed to open restored database for migration")?;
+
+    Ok(manifest)
+}
+
+fn append_bytes<W: std::io::Write>(builder: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o600);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, name, data)
+        .with_context(|| format!("Failed to add {} to archive", name))
+}
+
+fn append_file_if_exists<W: std::io::Write>(builder: &mut tar::Builder<W>, name: &str, path: &Path) -> Result<()> {
+    if !path.is_file() {
+        return Ok(());
+    }
+    let mut file = fs::File::open(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    builder
+        .append_file(name, &mut file)
+        .with_context(|| format!("Failed to add {} to archive", path.display()))
+}
+
+fn move_if_present(from: &Path, to: &Path) -> Result<()> {
+    if !from.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(from, to).with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))
+}
@@ -8,7 +8,7 @@ use anyhow::{Context, Result};
 
 use crate::types::{ConwayClient, GitLogEntry};
 
-use super::tools::{git_commit, git_init, git_log, git_status};
+use super::tools::{git_commit, git_init, git_log, git_push, git_status};
 
 /// The automaton state directory.
 const AUTOMATON_DIR: &str = "~/.automaton";
@@ -27,7 +27,7 @@ fn resolve_home(p: &str) -> String {
 
 /// Initialize git repo for the automaton's state directory.
 /// Creates .gitignore to exclude sensitive files.
-pub async fn init_state_repo(conway: &dyn ConwayClient) -> Result<()> {
+pub async fn init_state_repo(conway: &dyn ConwayClient, name: &str, address: &str) -> Result<()> {
     let dir = resolve_home(AUTOMATON_DIR);
 
     // Check if already initialized
@@ -65,24 +65,14 @@ pub async fn init_state_repo(conway: &dyn ConwayClient) -> Result<()> {
         .await
         .context("Failed to write .gitignore")?;
 
-    // Configure git user
-    conway
-        .exec(
-            &format!(
-                "cd {} && git config user.name \"Automaton\" && git config user.email \"automaton@conway.tech\"",
-                dir
-            ),
-            Some(5_000),
-        )
-        .await
-        .context("Failed to configure git user")?;
-
-    // Initial commit
+    // Initial commit, authored as the automaton itself.
     git_commit(
         conway,
         &dir,
         "genesis: automaton state repository initialized",
         true,
+        name,
+        address,
     )
     .await?;
 
@@ -95,6 +85,8 @@ pub async fn commit_state_change(
     conway: &dyn ConwayClient,
     description: &str,
     category: &str,
+    name: &str,
+    address: &str,
 ) -> Result<String> {
     let dir = resolve_home(AUTOMATON_DIR);
 
@@ -105,13 +97,18 @@ pub async fn commit_state_change(
     }
 
     let message = format!("{}: {}", category, description);
-    let result = git_commit(conway, &dir, &message, true).await?;
+    let result = git_commit(conway, &dir, &message, true, name, address).await?;
     Ok(result)
 }
 
 /// Commit after a SOUL.md update.
-pub async fn commit_soul_update(conway: &dyn ConwayClient, description: &str) -> Result<String> {
-    commit_state_change(conway, description, "soul").await
+pub async fn commit_soul_update(
+    conway: &dyn ConwayClient,
+    description: &str,
+    name: &str,
+    address: &str,
+) -> Result<String> {
+    commit_state_change(conway, description, "soul", name, address).await
 }
 
 /// Commit after a skill installation or removal.
@@ -119,11 +116,15 @@ pub async fn commit_skill_change(
     conway: &dyn ConwayClient,
     skill_name: &str,
     action: &str,
+    name: &str,
+    address: &str,
 ) -> Result<String> {
     commit_state_change(
         conway,
         &format!("{} skill: {}", action, skill_name),
         "skill",
+        name,
+        address,
     )
     .await
 }
@@ -132,16 +133,37 @@ pub async fn commit_skill_change(
 pub async fn commit_heartbeat_change(
     conway: &dyn ConwayClient,
     description: &str,
+    name: &str,
+    address: &str,
 ) -> Result<String> {
-    commit_state_change(conway, description, "heartbeat").await
+    commit_state_change(conway, description, "heartbeat", name, address).await
 }
 
 /// Commit after config change.
 pub async fn commit_config_change(
     conway: &dyn ConwayClient,
     description: &str,
+    name: &str,
+    address: &str,
 ) -> Result<String> {
-    commit_state_change(conway, description, "config").await
+    commit_state_change(conway, description, "config", name, address).await
+}
+
+/// Commit any uncommitted state (if there's nothing to commit, this is a
+/// no-op) and push the state repo to `remote`. Used by the `last_will`
+/// mechanism to preserve final state before shutdown.
+pub async fn commit_and_push_final_state(
+    conway: &dyn ConwayClient,
+    remote: &str,
+    name: &str,
+    address: &str,
+) -> Result<String> {
+    let commit_result = commit_state_change(conway, "last will: final state before shutdown", "last_will", name, address).await?;
+
+    let dir = resolve_home(AUTOMATON_DIR);
+    let push_result = git_push(conway, &dir, remote, None).await?;
+
+    Ok(format!("{}; {}", commit_result, push_result))
 }
 
 /// Get the state repo history.
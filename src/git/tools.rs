@@ -5,7 +5,7 @@
 
 use anyhow::{bail, Context, Result};
 
-use crate::types::{ConwayClient, GitLogEntry, GitStatus};
+use crate::types::{AutomatonDatabase, ConwayClient, GitLogEntry, GitStatus};
 
 /// Get git status for a repository.
 pub async fn git_status(conway: &dyn ConwayClient, repo_path: &str) -> Result<GitStatus> {
@@ -99,12 +99,27 @@ pub async fn git_diff(
     }
 }
 
-/// Create a git commit.
+/// Derive a commit-author identity for the automaton: its name, and a
+/// `<address>@automaton.local` email so commits never depend on a global
+/// `user.name`/`user.email` being configured in a fresh sandbox.
+fn commit_author_args(name: &str, address: &str) -> String {
+    format!(
+        "-c {} -c {}",
+        escape_shell_arg(&format!("user.name={}", name)),
+        escape_shell_arg(&format!("user.email={}@automaton.local", address))
+    )
+}
+
+/// Create a git commit, authored as the automaton (`name` / `address`) so
+/// commits succeed in a fresh sandbox even if git has no global identity
+/// configured, and are attributed to the automaton rather than a default.
 pub async fn git_commit(
     conway: &dyn ConwayClient,
     repo_path: &str,
     message: &str,
     add_all: bool,
+    name: &str,
+    address: &str,
 ) -> Result<String> {
     if add_all {
         conway
@@ -119,8 +134,9 @@ pub async fn git_commit(
     let result = conway
         .exec(
             &format!(
-                "cd {} && git commit -m {} --allow-empty 2>&1",
+                "cd {} && git {} commit -m {} --allow-empty 2>&1",
                 escape_shell_arg(repo_path),
+                commit_author_args(name, address),
                 escape_shell_arg(message)
             ),
             Some(10_000),
@@ -275,13 +291,160 @@ pub async fn git_branch(
     Ok(output)
 }
 
+/// Pull upstream changes (`git pull origin main`), or cherry-pick a specific
+/// commit if `commit` is given. If the pull/cherry-pick leaves the repo in a
+/// conflicted state, automatically aborts it (`git merge --abort` /
+/// `git cherry-pick --abort`) so the runtime repo -- the agent's own code --
+/// is never left half-merged, and reports which files conflicted so the
+/// caller can decide to skip that commit.
+///
+/// On a clean apply, runs `build_command` to verify the new code actually
+/// builds before trusting it. If the build fails, reverts with
+/// `git reset --hard` to the commit that was checked out before the pull, so
+/// a bad upstream change can never brick the automaton's next start.
+///
+/// A build that succeeds is still only put on probation, not fully trusted:
+/// see [`crate::self_mod::probation`] for the runtime "limp home" safety net
+/// that reverts a self-update that compiles but misbehaves.
+pub async fn pull_upstream(
+    conway: &dyn ConwayClient,
+    db: &dyn AutomatonDatabase,
+    repo_path: Option<&str>,
+    commit: Option<&str>,
+    build_command: &str,
+) -> Result<String> {
+    let cd_prefix = repo_path
+        .map(|p| format!("cd {} && ", escape_shell_arg(p)))
+        .unwrap_or_default();
+
+    let (cmd, abort_cmd, verb) = match commit {
+        Some(hash) => (
+            format!("git cherry-pick {}", escape_shell_arg(hash)),
+            "git cherry-pick --abort",
+            "cherry-pick",
+        ),
+        None => (
+            "git pull origin main".to_string(),
+            "git merge --abort",
+            "merge",
+        ),
+    };
+
+    let prior_head = conway
+        .exec(&format!("{}git rev-parse HEAD", cd_prefix), Some(10_000))
+        .await
+        .context("Failed to record current HEAD")?
+        .stdout
+        .trim()
+        .to_string();
+
+    let result = conway
+        .exec(&format!("{}{} 2>&1", cd_prefix, cmd), Some(120_000))
+        .await
+        .context("Failed to pull upstream")?;
+
+    if result.exit_code == 0 {
+        let applied_summary = if let Some(hash) = commit {
+            format!("Cherry-picked commit {}", hash)
+        } else {
+            "Pulled all upstream changes".to_string()
+        };
+
+        let build_result = conway
+            .exec(&format!("{}{} 2>&1", cd_prefix, build_command), Some(600_000))
+            .await
+            .context("Failed to run rebuild command")?;
+
+        if build_result.exit_code == 0 {
+            crate::self_mod::probation::start_probation(db, &prior_head);
+            return Ok(format!(
+                "{}. Rebuild succeeded. Update is on probation until it reaches a healthy milestone -- call confirm_update once you're satisfied it's good.",
+                applied_summary
+            ));
+        }
+
+        // A bad upstream change must never brick the automaton's next start.
+        let _ = conway
+            .exec(
+                &format!("{}git reset --hard {}", cd_prefix, escape_shell_arg(&prior_head)),
+                Some(10_000),
+            )
+            .await;
+
+        let build_err = if build_result.stdout.is_empty() {
+            build_result.stderr
+        } else {
+            build_result.stdout
+        };
+        return Ok(format!(
+            "{}, but the rebuild failed. Reverted to the prior commit ({}) to stay on last-known-good code. Build error:\n{}",
+            applied_summary, prior_head, build_err
+        ));
+    }
+
+    let conflict_check = conway
+        .exec(
+            &format!("{}git diff --name-only --diff-filter=U", cd_prefix),
+            Some(10_000),
+        )
+        .await
+        .context("Failed to check for merge conflicts")?;
+
+    let conflicted_files: Vec<&str> = conflict_check
+        .stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    if conflicted_files.is_empty() {
+        let err_msg = if result.stderr.is_empty() {
+            &result.stdout
+        } else {
+            &result.stderr
+        };
+        return Ok(format!("Failed to apply upstream: {}", err_msg));
+    }
+
+    // Never leave the runtime repo half-merged -- abort back to a clean state.
+    let _ = conway
+        .exec(&format!("{}{}", cd_prefix, abort_cmd), Some(10_000))
+        .await;
+
+    Ok(format!(
+        "Merge conflict in {} file(s): {}. Aborted the {} and restored a clean working tree -- skip this commit or resolve manually.",
+        conflicted_files.len(),
+        conflicted_files.join(", "),
+        verb
+    ))
+}
+
+/// Check whether `url` is permitted by an optional allowlist of git host/URL
+/// prefixes. An empty allowlist allows everything, preserving the default
+/// permissive behavior; a non-empty one requires `url` to start with one of
+/// the configured prefixes.
+pub fn check_remote_allowed(url: &str, allowlist: &[String]) -> Result<()> {
+    if allowlist.is_empty() || allowlist.iter().any(|prefix| url.starts_with(prefix.as_str())) {
+        return Ok(());
+    }
+
+    bail!(
+        "Git remote '{}' is not in the configured allowlist ({})",
+        url,
+        allowlist.join(", ")
+    );
+}
+
 /// Clone a repository.
 pub async fn git_clone(
     conway: &dyn ConwayClient,
     url: &str,
     target_path: &str,
     depth: Option<u32>,
+    allowed_remotes: &[String],
 ) -> Result<String> {
+    check_remote_allowed(url, allowed_remotes)?;
+
     let depth_arg = depth
         .map(|d| format!(" --depth {}", d))
         .unwrap_or_default();
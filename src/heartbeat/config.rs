@@ -7,14 +7,16 @@
 use std::fs;
 use std::path::Path;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::Utc;
+use cron::Schedule;
 use tracing::{debug, info, warn};
 use yaml_rust2::{Yaml, YamlEmitter, YamlLoader};
 
+use super::tasks::BUILTIN_TASKS;
 use crate::types::{HeartbeatConfig, HeartbeatEntry};
 
-/// Default heartbeat configuration with 6 standard entries.
+/// Default heartbeat configuration with 11 standard entries.
 ///
 /// These cover the essential periodic tasks every automaton should run:
 /// - `heartbeat_ping` - signal liveness to the control plane
@@ -23,6 +25,11 @@ use crate::types::{HeartbeatConfig, HeartbeatEntry};
 /// - `check_for_updates` - check for new automaton versions
 /// - `health_check` - internal self-diagnostics
 /// - `check_social_inbox` - poll for incoming social messages
+/// - `check_kill_switch` - watch for a creator-triggered halt signal
+/// - `self_reflection` - periodically prompt the agent to review its progress
+/// - `check_scheduled_actions` - fire due `schedule_action` timers
+/// - `check_history_size` - nudge the agent to summarize old turns once history grows large
+/// - `reap_idle_sandboxes` - warn about or auto-delete idle created sub-task sandboxes
 pub const DEFAULT_HEARTBEAT_CONFIG: &str = r#"entries:
   - name: heartbeat_ping
     schedule: "0 */5 * * * *"
@@ -54,8 +61,162 @@ pub const DEFAULT_HEARTBEAT_CONFIG: &str = r#"entries:
     task: check_social_inbox
     enabled: true
     params: {}
+  - name: check_kill_switch
+    schedule: "0 */5 * * * *"
+    task: check_kill_switch
+    enabled: true
+    params: {}
+  - name: self_reflection
+    schedule: "0 0 */12 * * *"
+    task: self_reflection
+    enabled: true
+    params: {}
+  - name: check_scheduled_actions
+    schedule: "0 * * * * *"
+    task: check_scheduled_actions
+    enabled: true
+    params: {}
+  - name: check_history_size
+    schedule: "0 0 */6 * * *"
+    task: check_history_size
+    enabled: true
+    params: {}
+  - name: reap_idle_sandboxes
+    schedule: "0 0 * * * *"
+    task: reap_idle_sandboxes
+    enabled: true
+    params: {}
+"#;
+
+/// A denser schedule for automatons burning through credits fast enough that
+/// operators want tighter visibility into balance and update checks.
+pub const FREQUENT_HEARTBEAT_CONFIG: &str = r#"entries:
+  - name: heartbeat_ping
+    schedule: "0 */2 * * * *"
+    task: heartbeat_ping
+    enabled: true
+    params: {}
+  - name: check_credits
+    schedule: "0 */5 * * * *"
+    task: check_credits
+    enabled: true
+    params: {}
+  - name: check_usdc_balance
+    schedule: "0 */10 * * * *"
+    task: check_usdc_balance
+    enabled: true
+    params: {}
+  - name: check_for_updates
+    schedule: "0 0 */6 * * *"
+    task: check_for_updates
+    enabled: true
+    params: {}
+  - name: health_check
+    schedule: "0 0 * * * *"
+    task: health_check
+    enabled: true
+    params: {}
+  - name: check_social_inbox
+    schedule: "0 */5 * * * *"
+    task: check_social_inbox
+    enabled: true
+    params: {}
+  - name: check_kill_switch
+    schedule: "0 */5 * * * *"
+    task: check_kill_switch
+    enabled: true
+    params: {}
+  - name: self_reflection
+    schedule: "0 0 */6 * * *"
+    task: self_reflection
+    enabled: true
+    params: {}
+  - name: check_scheduled_actions
+    schedule: "0 * * * * *"
+    task: check_scheduled_actions
+    enabled: true
+    params: {}
+  - name: check_history_size
+    schedule: "0 0 */2 * * *"
+    task: check_history_size
+    enabled: true
+    params: {}
+  - name: reap_idle_sandboxes
+    schedule: "0 */30 * * * *"
+    task: reap_idle_sandboxes
+    enabled: true
+    params: {}
 "#;
 
+/// A lighter schedule for cheap, low-churn automatons where frequent credit
+/// polling just burns compute for no signal.
+pub const SPARSE_HEARTBEAT_CONFIG: &str = r#"entries:
+  - name: heartbeat_ping
+    schedule: "0 0 * * * *"
+    task: heartbeat_ping
+    enabled: true
+    params: {}
+  - name: check_credits
+    schedule: "0 0 */2 * * *"
+    task: check_credits
+    enabled: true
+    params: {}
+  - name: check_usdc_balance
+    schedule: "0 0 */6 * * *"
+    task: check_usdc_balance
+    enabled: true
+    params: {}
+  - name: check_for_updates
+    schedule: "0 0 0 * * *"
+    task: check_for_updates
+    enabled: true
+    params: {}
+  - name: health_check
+    schedule: "0 0 */2 * * *"
+    task: health_check
+    enabled: true
+    params: {}
+  - name: check_social_inbox
+    schedule: "0 0 * * * *"
+    task: check_social_inbox
+    enabled: true
+    params: {}
+  - name: check_kill_switch
+    schedule: "0 */15 * * * *"
+    task: check_kill_switch
+    enabled: true
+    params: {}
+  - name: self_reflection
+    schedule: "0 0 0 * * *"
+    task: self_reflection
+    enabled: true
+    params: {}
+  - name: check_scheduled_actions
+    schedule: "0 */5 * * * *"
+    task: check_scheduled_actions
+    enabled: true
+    params: {}
+  - name: check_history_size
+    schedule: "0 0 0 * * *"
+    task: check_history_size
+    enabled: true
+    params: {}
+  - name: reap_idle_sandboxes
+    schedule: "0 0 */6 * * *"
+    task: reap_idle_sandboxes
+    enabled: true
+    params: {}
+"#;
+
+/// Where `write_default_heartbeat_config` should source its entries from.
+pub enum HeartbeatTemplate<'a> {
+    /// Load YAML from an external file (e.g. one a replicated child was
+    /// handed by its parent).
+    Path(&'a Path),
+    /// A named built-in preset: `"default"`, `"frequent"`, or `"sparse"`.
+    Preset(&'a str),
+}
+
 /// Parse a YAML document into a `HeartbeatConfig`.
 fn parse_yaml_config(docs: &[Yaml]) -> Result<HeartbeatConfig> {
     let doc = docs
@@ -190,10 +351,48 @@ pub fn save_heartbeat_config(config: &HeartbeatConfig, config_path: &Path) -> Re
     Ok(())
 }
 
+/// Validate that every entry in `config` has a schedule the `cron` crate can
+/// parse and a `task` present in [`BUILTIN_TASKS`]. Run before writing a
+/// caller-supplied template so a typo'd schedule or task name fails loudly
+/// at write time instead of silently never firing once the daemon is running.
+fn validate_heartbeat_config(config: &HeartbeatConfig) -> Result<()> {
+    let builtin_tasks = BUILTIN_TASKS();
+
+    for entry in &config.entries {
+        entry.schedule.parse::<Schedule>().with_context(|| {
+            format!(
+                "Entry '{}' has an invalid cron schedule: '{}'",
+                entry.name, entry.schedule
+            )
+        })?;
+
+        if !builtin_tasks.contains_key(entry.task.as_str()) {
+            bail!(
+                "Entry '{}' references unknown task '{}' (not in BUILTIN_TASKS)",
+                entry.name,
+                entry.task
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// Write the default heartbeat configuration to a file.
 ///
+/// `template` picks the source of entries: `None` for the built-in
+/// [`DEFAULT_HEARTBEAT_CONFIG`], [`HeartbeatTemplate::Path`] to load YAML
+/// from disk (e.g. a schedule a parent automaton is replicating to a child),
+/// or [`HeartbeatTemplate::Preset`] for one of the other built-in schedules.
+/// Whatever is chosen is parsed and validated -- every schedule must be a
+/// valid cron expression and every task must be a real [`BUILTIN_TASKS`]
+/// entry -- before it's written.
+///
 /// Will not overwrite an existing file. Returns Ok(()) if the file already exists.
-pub fn write_default_heartbeat_config(config_path: &Path) -> Result<()> {
+pub fn write_default_heartbeat_config(
+    config_path: &Path,
+    template: Option<HeartbeatTemplate>,
+) -> Result<()> {
     if config_path.exists() {
         warn!(
             "Heartbeat config already exists at {}, not overwriting",
@@ -202,6 +401,26 @@ pub fn write_default_heartbeat_config(config_path: &Path) -> Result<()> {
         return Ok(());
     }
 
+    let yaml = match template {
+        None => DEFAULT_HEARTBEAT_CONFIG.to_string(),
+        Some(HeartbeatTemplate::Path(path)) => fs::read_to_string(path).with_context(|| {
+            format!("Failed to read heartbeat config template from {}", path.display())
+        })?,
+        Some(HeartbeatTemplate::Preset(name)) => match name {
+            "default" => DEFAULT_HEARTBEAT_CONFIG.to_string(),
+            "frequent" => FREQUENT_HEARTBEAT_CONFIG.to_string(),
+            "sparse" => SPARSE_HEARTBEAT_CONFIG.to_string(),
+            other => bail!(
+                "Unknown heartbeat config preset '{}' (expected 'default', 'frequent', or 'sparse')",
+                other
+            ),
+        },
+    };
+
+    let docs = YamlLoader::load_from_str(&yaml).context("Failed to parse heartbeat config template")?;
+    let parsed = parse_yaml_config(&docs)?;
+    validate_heartbeat_config(&parsed)?;
+
     if let Some(parent) = config_path.parent() {
         fs::create_dir_all(parent).with_context(|| {
             format!(
@@ -211,7 +430,7 @@ pub fn write_default_heartbeat_config(config_path: &Path) -> Result<()> {
         })?;
     }
 
-    fs::write(config_path, DEFAULT_HEARTBEAT_CONFIG).with_context(|| {
+    fs::write(config_path, &yaml).with_context(|| {
         format!(
             "Failed to write default heartbeat config to {}",
             config_path.display()
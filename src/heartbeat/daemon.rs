@@ -15,7 +15,10 @@ use cron::Schedule;
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
-use crate::types::HeartbeatEntry;
+use crate::clock::{Clock, SystemClock};
+#[cfg(test)]
+use crate::clock::MockClock;
+use crate::types::{AutomatonDatabase, HeartbeatEntry};
 
 use super::tasks::{HeartbeatTaskResult, BUILTIN_TASKS};
 
@@ -25,6 +28,12 @@ pub struct HeartbeatDaemonOptions {
     pub tick_interval_secs: u64,
     /// Heartbeat entries to schedule.
     pub entries: Vec<HeartbeatEntry>,
+    /// Database to persist `last_run`/`next_run` to as schedules are
+    /// evaluated. `None` runs the daemon purely in-memory.
+    pub db: Option<Arc<dyn AutomatonDatabase>>,
+    /// Source of "now" used to evaluate schedules. Defaults to the real
+    /// clock; tests can substitute a `MockClock` to assert exact wake times.
+    pub clock: Arc<dyn Clock>,
 }
 
 impl Default for HeartbeatDaemonOptions {
@@ -32,6 +41,8 @@ impl Default for HeartbeatDaemonOptions {
         Self {
             tick_interval_secs: 30,
             entries: Vec::new(),
+            db: None,
+            clock: Arc::new(SystemClock),
         }
     }
 }
@@ -47,6 +58,10 @@ pub struct HeartbeatDaemon {
     tick_interval_secs: u64,
     /// Registered heartbeat entries.
     entries: Arc<tokio::sync::RwLock<Vec<HeartbeatEntry>>>,
+    /// Database to persist evaluated schedules to, if any.
+    db: Option<Arc<dyn AutomatonDatabase>>,
+    /// Source of "now" used to evaluate schedules.
+    clock: Arc<dyn Clock>,
 }
 
 /// Create a new heartbeat daemon from the given options.
@@ -56,6 +71,8 @@ pub fn create_heartbeat_daemon(options: HeartbeatDaemonOptions) -> HeartbeatDaem
         interval_handle: None,
         tick_interval_secs: options.tick_interval_secs,
         entries: Arc::new(tokio::sync::RwLock::new(options.entries)),
+        db: options.db,
+        clock: options.clock,
     }
 }
 
@@ -78,6 +95,8 @@ impl HeartbeatDaemon {
 
         let running = Arc::clone(&self.running);
         let entries = Arc::clone(&self.entries);
+        let db = self.db.clone();
+        let clock = Arc::clone(&self.clock);
         let tick_secs = self.tick_interval_secs;
 
         let handle = tokio::spawn(async move {
@@ -91,7 +110,7 @@ impl HeartbeatDaemon {
                     break;
                 }
 
-                if let Err(e) = tick(&entries, &agent_name).await {
+                if let Err(e) = tick(&entries, &agent_name, db.as_deref(), &*clock).await {
                     error!("Heartbeat tick error: {:#}", e);
                 }
             }
@@ -143,7 +162,7 @@ impl HeartbeatDaemon {
 ///
 /// Parses the entry's schedule string using the `cron` crate and checks whether
 /// the current time falls within the next expected execution window.
-pub fn is_due(entry: &HeartbeatEntry) -> bool {
+pub fn is_due(entry: &HeartbeatEntry, clock: &dyn Clock) -> bool {
     if !entry.enabled {
         return false;
     }
@@ -159,7 +178,7 @@ pub fn is_due(entry: &HeartbeatEntry) -> bool {
         }
     };
 
-    let now = Utc::now();
+    let now = clock.now();
 
     // If there is a last_run timestamp, check if a new scheduled time has arrived since then.
     if let Some(ref last_run_str) = entry.last_run {
@@ -175,6 +194,25 @@ pub fn is_due(entry: &HeartbeatEntry) -> bool {
     true
 }
 
+/// Compute the next time `entry` will fire, purely from its cron expression.
+///
+/// Used to keep the persisted `next_run` field introspectable (via
+/// `--status`, the system prompt, and the `list_heartbeats` tool) without
+/// every reader having to re-parse the schedule itself. Returns `None` if
+/// the schedule fails to parse. Only falls back to `clock.now()` when the
+/// entry has never run, so tests can pin "now" and assert an exact wake time.
+pub fn compute_next_run(entry: &HeartbeatEntry, clock: &dyn Clock) -> Option<chrono::DateTime<Utc>> {
+    let schedule: Schedule = entry.schedule.parse().ok()?;
+
+    let reference = entry
+        .last_run
+        .as_deref()
+        .and_then(|s| s.parse::<chrono::DateTime<Utc>>().ok())
+        .unwrap_or_else(|| clock.now());
+
+    schedule.after(&reference).next()
+}
+
 /// Execute a single heartbeat task entry.
 ///
 /// Looks up the task name in the built-in task registry and executes it.
@@ -215,19 +253,26 @@ pub async fn execute_task(
 }
 
 /// Perform a single tick: iterate over all entries, check which are due,
-/// and execute them.
+/// execute them, and recompute each entry's `next_run`.
+///
+/// `next_run` is recomputed for every entry on every tick (not just the ones
+/// that fired), since a schedule's next firing time keeps moving even while
+/// the task itself isn't due yet. When `db` is set, the updated entries are
+/// persisted so introspection tools don't need to recompute the schedule.
 async fn tick(
     entries: &tokio::sync::RwLock<Vec<HeartbeatEntry>>,
     agent_name: &str,
+    db: Option<&dyn AutomatonDatabase>,
+    clock: &dyn Clock,
 ) -> Result<()> {
     let current_entries = entries.read().await.clone();
     let mut executed: HashMap<String, String> = HashMap::new();
 
     for entry in &current_entries {
-        if is_due(entry) {
+        if is_due(entry, clock) {
             match execute_task(entry, agent_name).await {
                 Ok(_result) => {
-                    let now = Utc::now().to_rfc3339();
+                    let now = clock.now().to_rfc3339();
                     executed.insert(entry.name.clone(), now);
                 }
                 Err(e) => {
@@ -237,15 +282,66 @@ async fn tick(
         }
     }
 
-    // Update last_run timestamps for executed tasks.
-    if !executed.is_empty() {
-        let mut writable = entries.write().await;
-        for entry in writable.iter_mut() {
-            if let Some(timestamp) = executed.get(&entry.name) {
-                entry.last_run = Some(timestamp.clone());
-            }
+    let mut writable = entries.write().await;
+    for entry in writable.iter_mut() {
+        if let Some(timestamp) = executed.get(&entry.name) {
+            entry.last_run = Some(timestamp.clone());
+        }
+        entry.next_run = compute_next_run(entry, clock).map(|dt| dt.to_rfc3339());
+
+        if let Some(db) = db {
+            db.upsert_heartbeat_entry(entry);
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(schedule: &str, last_run: Option<&str>) -> HeartbeatEntry {
+        HeartbeatEntry {
+            name: "test_entry".to_string(),
+            schedule: schedule.to_string(),
+            task: "heartbeat_ping".to_string(),
+            enabled: true,
+            last_run: last_run.map(|s| s.to_string()),
+            next_run: None,
+            params: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_next_run_with_no_last_run_is_relative_to_clock() {
+        let clock = MockClock::new("2024-03-01T12:00:00Z".parse().unwrap());
+        let e = entry("0 * * * * *", None);
+        let next = compute_next_run(&e, &clock).expect("valid schedule should produce a next run");
+        assert!(next > clock.now());
+    }
+
+    #[test]
+    fn test_compute_next_run_uses_last_run_as_reference() {
+        let clock = SystemClock;
+        let e = entry("0 0 * * * *", Some("2020-01-01T00:00:00Z"));
+        let next = compute_next_run(&e, &clock).expect("valid schedule should produce a next run");
+        assert_eq!(next.to_rfc3339(), "2020-01-01T01:00:00+00:00");
+    }
+
+    #[test]
+    fn test_compute_next_run_invalid_schedule_returns_none() {
+        let e = entry("not a cron expression", None);
+        assert!(compute_next_run(&e, &SystemClock).is_none());
+    }
+
+    #[test]
+    fn test_is_due_uses_mock_clock_not_wall_clock() {
+        let clock = MockClock::new("2023-12-31T23:59:59Z".parse().unwrap());
+        let e = entry("0 0 * * * *", Some("2023-12-31T23:00:00Z"));
+        assert!(!is_due(&e, &clock));
+
+        clock.set("2024-01-01T00:00:01Z".parse().unwrap());
+        assert!(is_due(&e, &clock));
+    }
+}
@@ -10,7 +10,8 @@ pub mod tasks;
 
 pub use config::{
     load_heartbeat_config, save_heartbeat_config, sync_heartbeat_to_db,
-    write_default_heartbeat_config, DEFAULT_HEARTBEAT_CONFIG,
+    write_default_heartbeat_config, HeartbeatTemplate, DEFAULT_HEARTBEAT_CONFIG,
+    FREQUENT_HEARTBEAT_CONFIG, SPARSE_HEARTBEAT_CONFIG,
 };
 pub use daemon::{create_heartbeat_daemon, HeartbeatDaemon};
 pub use tasks::{HeartbeatTaskResult, BUILTIN_TASKS};
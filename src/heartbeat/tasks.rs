@@ -11,6 +11,8 @@ use std::pin::Pin;
 use anyhow::Result;
 use tracing::{debug, info, warn};
 
+use crate::types::ConwayClient;
+
 /// Result of a heartbeat task execution.
 #[derive(Debug, Clone)]
 pub struct HeartbeatTaskResult {
@@ -69,6 +71,23 @@ pub fn BUILTIN_TASKS() -> HashMap<&'static str, HeartbeatTaskFn> {
         Box::pin(check_for_updates(name))
     });
     map.insert("health_check", |name| Box::pin(health_check(name)));
+    map.insert("check_kill_switch", |name| {
+        Box::pin(check_kill_switch(name))
+    });
+    map.insert("record_balance_snapshot", |name| {
+        Box::pin(record_balance_snapshot(name))
+    });
+    map.insert("retry_outbox", |name| Box::pin(retry_outbox(name)));
+    map.insert("self_reflection", |name| Box::pin(self_reflection(name)));
+    map.insert("check_scheduled_actions", |name| {
+        Box::pin(check_scheduled_actions(name))
+    });
+    map.insert("check_history_size", |name| {
+        Box::pin(check_history_size(name))
+    });
+    map.insert("reap_idle_sandboxes", |name| {
+        Box::pin(reap_idle_sandboxes(name))
+    });
     map
 }
 
@@ -219,3 +238,372 @@ pub async fn health_check(agent_name: &str) -> Result<HeartbeatTaskResult> {
 
     Ok(HeartbeatTaskResult::ok_with_message("All systems nominal"))
 }
+
+/// Check for a creator-triggered kill switch signal.
+///
+/// Loads `kill_switch` from the automaton's own config (see
+/// `crate::config::load_config`) and, if enabled, looks for a sentinel file
+/// the creator placed at `sentinel_path` (default `~/.automaton/KILL_SWITCH`).
+/// Only someone with control-plane/filesystem access to the sandbox -- i.e.
+/// the creator -- can place that file, which is the check's authentication;
+/// `kill_token`, if configured, additionally requires the file's contents to
+/// match the shared secret, so a merely-compromised sandbox shell can't
+/// forge the signal on its own.
+///
+/// Requests a wake with a `KILL_SWITCH_TRIGGERED` message when the signal is
+/// authenticated -- the agent loop is responsible for reacting to that by
+/// transitioning to `Sleeping` and halting spend.
+///
+/// TODO: also accept an on-chain flag and a signed social message from
+/// `creator_address` containing the kill token, per the original request --
+/// both need a live Conway/chain connection this task doesn't have yet.
+pub async fn check_kill_switch(agent_name: &str) -> Result<HeartbeatTaskResult> {
+    debug!("Checking kill switch for agent: {}", agent_name);
+
+    let config = match crate::config::load_config() {
+        Some(c) => c,
+        None => return Ok(HeartbeatTaskResult::ok()),
+    };
+
+    if !config.kill_switch.enabled {
+        return Ok(HeartbeatTaskResult::ok());
+    }
+
+    let sentinel_path = config
+        .kill_switch
+        .sentinel_path
+        .clone()
+        .unwrap_or_else(|| {
+            crate::identity::wallet::get_automaton_dir()
+                .join("KILL_SWITCH")
+                .to_string_lossy()
+                .to_string()
+        });
+
+    let sentinel_path = crate::config::resolve_path(&sentinel_path);
+    if !std::path::Path::new(&sentinel_path).exists() {
+        return Ok(HeartbeatTaskResult::ok());
+    }
+
+    if let Some(ref token) = config.kill_switch.kill_token {
+        let contents = std::fs::read_to_string(&sentinel_path).unwrap_or_default();
+        if contents.trim() != token {
+            warn!("Kill switch sentinel present but token mismatch, ignoring");
+            return Ok(HeartbeatTaskResult::ok());
+        }
+    }
+
+    warn!("Kill switch triggered via sentinel file: {}", sentinel_path);
+    Ok(HeartbeatTaskResult::wake(
+        "KILL_SWITCH_TRIGGERED: creator halt signal authenticated, requesting shutdown",
+    ))
+}
+
+/// Record a point-in-time snapshot of the Conway credit balance into
+/// `balance_snapshots`, so the `credit_history` tool can report a trend
+/// (now / 1h ago / 24h ago / slope) instead of a single number.
+///
+/// Loads its own config and opens its own database and Conway client (like
+/// `check_kill_switch` above) since built-in heartbeat tasks aren't handed
+/// the running agent's `ToolContext`.
+pub async fn record_balance_snapshot(agent_name: &str) -> Result<HeartbeatTaskResult> {
+    debug!("Recording balance snapshot for agent: {}", agent_name);
+
+    let config = match crate::config::load_config() {
+        Some(c) => c,
+        None => return Ok(HeartbeatTaskResult::ok()),
+    };
+
+    let conway = crate::conway::client::ConwayHttpClient::new(
+        config.conway_api_url.clone(),
+        config.conway_api_key.clone(),
+        config.sandbox_id.clone(),
+    );
+    let balance_cents = conway.get_credits_balance().await?;
+
+    let db = crate::state::Database::open(&config.db_path)?;
+    db.insert_balance_snapshot(&crate::types::BalanceSnapshot {
+        id: uuid::Uuid::new_v4().to_string(),
+        balance_cents: balance_cents as i64,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })?;
+
+    Ok(HeartbeatTaskResult::ok_with_message(format!(
+        "Recorded balance snapshot: {} cents",
+        balance_cents as i64
+    )))
+}
+
+/// Retry outbound messages queued in `outbox` because the social relay was
+/// down or unconfigured when `send_message` first tried to deliver them.
+///
+/// Loads its own config, wallet, and database (like `record_balance_snapshot`
+/// above) since built-in heartbeat tasks aren't handed the running agent's
+/// `ToolContext`. Entries that fail again are left queued with an updated
+/// `attempts`/`last_error` for the next tick.
+pub async fn retry_outbox(agent_name: &str) -> Result<HeartbeatTaskResult> {
+    debug!("Retrying outbox for agent: {}", agent_name);
+
+    let config = match crate::config::load_config() {
+        Some(c) => c,
+        None => return Ok(HeartbeatTaskResult::ok()),
+    };
+
+    let Some(relay_url) = config.social_relay_url.clone() else {
+        return Ok(HeartbeatTaskResult::ok());
+    };
+
+    let db = crate::state::Database::open(&config.db_path)?;
+    let pending = db.get_pending_outbox(20)?;
+    if pending.is_empty() {
+        return Ok(HeartbeatTaskResult::ok());
+    }
+
+    let (signer, _) = crate::identity::wallet::get_wallet()?;
+    let social = crate::social::client::SocialClient::new(relay_url, signer);
+
+    let mut sent = 0;
+    let mut failed = 0;
+    for entry in &pending {
+        match social.send(&entry.to_address, &entry.content, entry.reply_to.as_deref()).await {
+            Ok(_) => {
+                db.mark_sent(&entry.id)?;
+                sent += 1;
+            }
+            Err(err) => {
+                warn!("Retry failed for queued message {}: {}", entry.id, err);
+                db.record_outbox_failure(&entry.id, &err.to_string())?;
+                failed += 1;
+            }
+        }
+    }
+
+    Ok(HeartbeatTaskResult::ok_with_message(format!(
+        "Outbox retry: {} sent, {} still failing",
+        sent, failed
+    )))
+}
+
+/// Periodically prompt the agent to step back and reflect: review recent
+/// turns, progress toward its genesis purpose, financial trend, and whether
+/// its current strategy is working -- so long uptimes don't quietly drift
+/// into aimless activity.
+///
+/// Enqueues a normal System-sourced turn input rather than acting directly,
+/// so reflection goes through the regular agent loop (and can use the full
+/// tool surface -- notes, SOUL.md edits, whatever it decides) instead of
+/// being special-cased here. Loads its own config and database (like
+/// `record_balance_snapshot` above) since built-in heartbeat tasks aren't
+/// handed the running agent's `ToolContext`.
+pub async fn self_reflection(agent_name: &str) -> Result<HeartbeatTaskResult> {
+    debug!("Running self-reflection for agent: {}", agent_name);
+
+    let config = match crate::config::load_config() {
+        Some(c) => c,
+        None => return Ok(HeartbeatTaskResult::ok()),
+    };
+
+    let db = crate::state::Database::open(&config.db_path)?;
+
+    let prompt = "Self-reflection check-in: review your recent turns, your progress \
+        toward your genesis purpose, your financial trend, and whether your current \
+        strategy is actually working. Write your conclusions somewhere durable (a \
+        note, SOUL.md) and adjust course if it isn't.";
+
+    db.enqueue_pending_input(&crate::types::PendingInputEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        content: prompt.to_string(),
+        source: crate::types::InputSource::System,
+        priority: config.input_priorities.for_source(&crate::types::InputSource::System),
+        dedup_key: None,
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })?;
+
+    Ok(HeartbeatTaskResult::wake("Queued a self-reflection prompt"))
+}
+
+/// Inject due `schedule_action` timers as pending inputs. A `dedup_key` of
+/// `scheduled:<id>` and marking each fired immediately after enqueueing
+/// means a schedule that came due during downtime still fires exactly once
+/// on the next tick, instead of being skipped or double-fired.
+///
+/// Loads its own config and database (like `record_balance_snapshot` above)
+/// since built-in heartbeat tasks aren't handed the running agent's
+/// `ToolContext`.
+pub async fn check_scheduled_actions(agent_name: &str) -> Result<HeartbeatTaskResult> {
+    debug!("Checking scheduled actions for agent: {}", agent_name);
+
+    let config = match crate::config::load_config() {
+        Some(c) => c,
+        None => return Ok(HeartbeatTaskResult::ok()),
+    };
+
+    let db = crate::state::Database::open(&config.db_path)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let due = db.get_due_scheduled_actions(&now)?;
+    if due.is_empty() {
+        return Ok(HeartbeatTaskResult::ok());
+    }
+
+    for action in &due {
+        db.enqueue_pending_input(&crate::types::PendingInputEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            content: action.input.clone(),
+            source: crate::types::InputSource::Scheduled,
+            priority: config.input_priorities.for_source(&crate::types::InputSource::Scheduled),
+            dedup_key: Some(format!("scheduled:{}", action.id)),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        })?;
+        db.mark_scheduled_action_fired(&action.id)?;
+    }
+
+    Ok(HeartbeatTaskResult::wake(format!(
+        "Fired {} scheduled action(s)",
+        due.len()
+    )))
+}
+
+/// Nudge the agent to run `summarize_history` once the live turn log has
+/// grown past [`HISTORY_SIZE_NUDGE_THRESHOLD`], instead of doing the
+/// summarization here directly -- like `self_reflection`, this enqueues a
+/// System-sourced turn input so the actual inference call happens through
+/// the regular agent loop and `ToolContext`, which this task doesn't have.
+///
+/// Loads its own config and database (like `record_balance_snapshot` above)
+/// since built-in heartbeat tasks aren't handed the running agent's
+/// `ToolContext`.
+pub async fn check_history_size(agent_name: &str) -> Result<HeartbeatTaskResult> {
+    debug!("Checking turn history size for agent: {}", agent_name);
+
+    const HISTORY_SIZE_NUDGE_THRESHOLD: i64 = 100;
+
+    let config = match crate::config::load_config() {
+        Some(c) => c,
+        None => return Ok(HeartbeatTaskResult::ok()),
+    };
+
+    let db = crate::state::Database::open(&config.db_path)?;
+    let turn_count = db.get_turn_count()?;
+    if turn_count <= HISTORY_SIZE_NUDGE_THRESHOLD {
+        return Ok(HeartbeatTaskResult::ok());
+    }
+
+    db.enqueue_pending_input(&crate::types::PendingInputEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        content: format!(
+            "Your turn history has grown to {} turns. Call summarize_history to fold the \
+             older ones into long-term memory and keep your context manageable.",
+            turn_count
+        ),
+        source: crate::types::InputSource::System,
+        priority: config.input_priorities.for_source(&crate::types::InputSource::System),
+        dedup_key: Some("history_size_nudge".to_string()),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })?;
+
+    Ok(HeartbeatTaskResult::wake("Nudged agent to summarize old turn history"))
+}
+
+/// Default idle TTL, in minutes, for [`crate::types::SandboxReapConfig`]
+/// when `idle_ttl_minutes` is unset.
+const DEFAULT_SANDBOX_IDLE_TTL_MINUTES: i64 = 60;
+
+/// Warn about, or auto-delete, sub-task sandboxes this automaton created
+/// via `create_sandbox` (tracked in `created_sandboxes`, never its own)
+/// that have sat idle past `sandbox_reap.idle_ttl_minutes` -- a common
+/// money leak for an automaton that spins up sandboxes for sub-tasks and
+/// forgets them. Off unless `sandbox_reap.enabled`.
+///
+/// Conway has no per-sandbox activity endpoint, so "idle" is measured from
+/// `created_at` -- there's currently no way for this automaton to touch a
+/// sandbox other than its own, so that's also the last time any of ours
+/// interacted with it.
+///
+/// Loads its own config, database, and Conway client (like
+/// `record_balance_snapshot` above) since built-in heartbeat tasks aren't
+/// handed the running agent's `ToolContext`.
+pub async fn reap_idle_sandboxes(agent_name: &str) -> Result<HeartbeatTaskResult> {
+    debug!("Checking for idle created sandboxes for agent: {}", agent_name);
+
+    let config = match crate::config::load_config() {
+        Some(c) => c,
+        None => return Ok(HeartbeatTaskResult::ok()),
+    };
+
+    if !config.sandbox_reap.enabled {
+        return Ok(HeartbeatTaskResult::ok());
+    }
+
+    let db = crate::state::Database::open(&config.db_path)?;
+    let created = db.get_created_sandboxes()?;
+    if created.is_empty() {
+        return Ok(HeartbeatTaskResult::ok());
+    }
+
+    let idle_ttl_minutes = config
+        .sandbox_reap
+        .idle_ttl_minutes
+        .unwrap_or(DEFAULT_SANDBOX_IDLE_TTL_MINUTES);
+    let now = chrono::Utc::now();
+
+    let idle: Vec<_> = created
+        .iter()
+        .filter(|entry| {
+            entry
+                .created_at
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .map(|created_at| (now - created_at).num_minutes() >= idle_ttl_minutes)
+                .unwrap_or(false)
+        })
+        .collect();
+
+    if idle.is_empty() {
+        return Ok(HeartbeatTaskResult::ok_with_message(format!(
+            "{} created sandbox(es), none idle",
+            created.len()
+        )));
+    }
+
+    if !config.sandbox_reap.auto_delete {
+        let summary = idle
+            .iter()
+            .map(|e| format!("{} ({})", e.sandbox_id, e.purpose.as_deref().unwrap_or("no purpose recorded")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        warn!("{} idle created sandbox(es): {}", idle.len(), summary);
+        return Ok(HeartbeatTaskResult::wake(format!(
+            "{} created sandbox(es) have been idle past {}m and are still running: {}. \
+             Delete them with delete_sandbox if they're no longer needed.",
+            idle.len(),
+            idle_ttl_minutes,
+            summary
+        )));
+    }
+
+    let conway = crate::conway::client::ConwayHttpClient::new(
+        config.conway_api_url.clone(),
+        config.conway_api_key.clone(),
+        config.sandbox_id.clone(),
+    );
+
+    let mut deleted = 0;
+    let mut failed = 0;
+    for entry in &idle {
+        match conway.delete_sandbox(&entry.sandbox_id).await {
+            Ok(()) => {
+                db.delete_created_sandbox(&entry.sandbox_id)?;
+                deleted += 1;
+            }
+            Err(err) => {
+                warn!("Failed to reap idle sandbox {}: {}", entry.sandbox_id, err);
+                failed += 1;
+            }
+        }
+    }
+
+    info!("Reaped {} idle created sandbox(es), {} failed", deleted, failed);
+    Ok(HeartbeatTaskResult::ok_with_message(format!(
+        "Auto-deleted {} idle sandbox(es), {} failed",
+        deleted, failed
+    )))
+}
@@ -0,0 +1,57 @@
+//! OS Keyring Storage
+//!
+//! Optional backend for storing the wallet private key and Conway API key in
+//! the platform keyring (macOS Keychain, Windows Credential Manager, Linux
+//! Secret Service) instead of plaintext JSON files. This reduces the blast
+//! radius of a leaked `~/.automaton` directory.
+//!
+//! Opt-in via `AUTOMATON_USE_KEYRING=1` in the environment, checked with
+//! [`is_enabled`] -- wallet/config creation happens before an
+//! [`AutomatonConfig`](crate::types::AutomatonConfig) necessarily exists, so
+//! this can't be a config field the way `log_inference` is.
+//!
+//! Every function here fails soft rather than taking the automaton down:
+//! headless sandboxes with no keyring daemon get `false`/`None` back and the
+//! caller falls back to file storage.
+
+use keyring::Entry;
+use tracing::warn;
+
+/// Keyring "service" namespace all automaton entries live under.
+const SERVICE: &str = "automaton";
+
+/// Whether keyring-backed secret storage is enabled for this run.
+pub(crate) fn is_enabled() -> bool {
+    matches!(
+        std::env::var("AUTOMATON_USE_KEYRING").as_deref(),
+        Ok("1") | Ok("true")
+    )
+}
+
+/// Store `secret` under `entry` in the OS keyring.
+///
+/// Returns `false` (rather than an error) if no keyring backend is
+/// available, so callers can fall back to file storage.
+pub(crate) fn store(entry: &str, secret: &str) -> bool {
+    match Entry::new(SERVICE, entry).and_then(|e| e.set_password(secret)) {
+        Ok(()) => true,
+        Err(err) => {
+            warn!(
+                "Keyring store failed for '{}', falling back to file storage: {}",
+                entry, err
+            );
+            false
+        }
+    }
+}
+
+/// Load the secret stored under `entry` in the OS keyring, if present.
+pub(crate) fn load(entry: &str) -> Option<String> {
+    match Entry::new(SERVICE, entry).and_then(|e| e.get_password()) {
+        Ok(secret) => Some(secret),
+        Err(err) => {
+            warn!("Keyring load failed for '{}': {}", entry, err);
+            None
+        }
+    }
+}
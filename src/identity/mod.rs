@@ -3,5 +3,7 @@
 //! Wallet management and SIWE-based API provisioning.
 //! The private key IS the automaton's sovereign identity.
 
+pub(crate) mod keyring_store;
 pub mod provision;
+pub mod signing;
 pub mod wallet;
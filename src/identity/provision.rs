@@ -11,7 +11,9 @@ use alloy::signers::Signer;
 use anyhow::{bail, Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
+use super::keyring_store;
 use super::wallet::{get_automaton_dir, get_wallet};
 use crate::types::ProvisionResult;
 
@@ -24,13 +26,23 @@ const SIWE_DOMAIN: &str = "conway.tech";
 /// Chain ID for Base network.
 const CHAIN_ID: u64 = 8453;
 
+/// Keyring entry name the Conway API key is stored under, if enabled.
+const KEYRING_ENTRY: &str = "conway-api-key";
+
 /// Minimal config.json structure stored in `~/.automaton/config.json`.
+///
+/// When `keyring_ref` is set, `api_key` is empty and the real key lives in
+/// the OS keyring under that entry name instead -- see
+/// [`crate::identity::keyring_store`].
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ProvisionConfig {
+    #[serde(default)]
     api_key: String,
     wallet_address: String,
     provisioned_at: String,
+    #[serde(default)]
+    keyring_ref: Option<String>,
 }
 
 // ─── API response types ──────────────────────────────────────────
@@ -53,9 +65,10 @@ struct ApiKeyResponse {
 
 // ─── Public API ──────────────────────────────────────────────────
 
-/// Load a previously-saved API key from `~/.automaton/config.json`.
+/// Load a previously-saved API key from `~/.automaton/config.json`, pulling
+/// it from the OS keyring instead if it was stored there.
 ///
-/// Returns `None` if the file does not exist or the key field is absent.
+/// Returns `None` if the file does not exist or the key is absent/unresolvable.
 pub fn load_api_key_from_config() -> Option<String> {
     let config_path = get_automaton_dir().join("config.json");
     if !config_path.exists() {
@@ -65,6 +78,10 @@ pub fn load_api_key_from_config() -> Option<String> {
     let contents = fs::read_to_string(&config_path).ok()?;
     let config: ProvisionConfig = serde_json::from_str(&contents).ok()?;
 
+    if let Some(entry) = &config.keyring_ref {
+        return keyring_store::load(entry);
+    }
+
     if config.api_key.is_empty() {
         None
     } else {
@@ -73,6 +90,10 @@ pub fn load_api_key_from_config() -> Option<String> {
 }
 
 /// Save the API key and wallet address to `~/.automaton/config.json`.
+///
+/// Stores the key in the OS keyring when `AUTOMATON_USE_KEYRING=1` is set
+/// and a keyring backend is available, falling back to the plaintext file
+/// otherwise.
 fn save_config(api_key: &str, wallet_address: &str) -> Result<()> {
     let dir = get_automaton_dir();
     if !dir.exists() {
@@ -81,10 +102,20 @@ fn save_config(api_key: &str, wallet_address: &str) -> Result<()> {
     }
 
     let config_path = dir.join("config.json");
-    let config = ProvisionConfig {
-        api_key: api_key.to_string(),
-        wallet_address: wallet_address.to_string(),
-        provisioned_at: Utc::now().to_rfc3339(),
+    let config = if keyring_store::is_enabled() && keyring_store::store(KEYRING_ENTRY, api_key) {
+        ProvisionConfig {
+            api_key: String::new(),
+            wallet_address: wallet_address.to_string(),
+            provisioned_at: Utc::now().to_rfc3339(),
+            keyring_ref: Some(KEYRING_ENTRY.to_string()),
+        }
+    } else {
+        ProvisionConfig {
+            api_key: api_key.to_string(),
+            wallet_address: wallet_address.to_string(),
+            provisioned_at: Utc::now().to_rfc3339(),
+            keyring_ref: None,
+        }
     };
 
     let json = serde_json::to_string_pretty(&config)?;
@@ -284,3 +315,51 @@ pub async fn register_parent(creator_address: &str, api_url: Option<&str>) -> Re
 
     Ok(())
 }
+
+/// Rotate the Conway API key: re-run SIWE provisioning against the same
+/// wallet to mint a fresh key, then best-effort revoke the old one.
+///
+/// The wallet is the automaton's root identity and can always re-authenticate,
+/// so this lets it recover autonomously from a leaked API key without
+/// operator involvement -- the wallet private key itself is never touched.
+pub async fn rotate_api_key(api_url: Option<&str>) -> Result<ProvisionResult> {
+    let old_key = load_api_key_from_config();
+
+    let result = provision(api_url)
+        .await
+        .context("Failed to provision a replacement API key")?;
+
+    if let Some(old_key) = old_key {
+        if let Err(err) = revoke_api_key(&old_key, api_url).await {
+            warn!("Failed to revoke old API key after rotation: {}", err);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Best-effort revoke a Conway API key. Fails gracefully if the endpoint
+/// does not exist (404), same as [`register_parent`].
+async fn revoke_api_key(api_key: &str, api_url: Option<&str>) -> Result<()> {
+    let url = api_url
+        .map(|s| s.to_string())
+        .or_else(|| std::env::var("CONWAY_API_URL").ok())
+        .unwrap_or_else(|| DEFAULT_API_URL.to_string());
+
+    let client = reqwest::Client::new();
+
+    let resp = client
+        .delete(format!("{}/v1/auth/api-keys", url))
+        .header("Authorization", api_key)
+        .send()
+        .await
+        .context("Failed to send API key revoke request")?;
+
+    if !resp.status().is_success() && resp.status() != reqwest::StatusCode::NOT_FOUND {
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        bail!("Failed to revoke API key: {} {}", status, body);
+    }
+
+    Ok(())
+}
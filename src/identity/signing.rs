@@ -0,0 +1,146 @@
+//! Blind-Signing Guard
+//!
+//! The wallet's private key is the automaton's sovereign identity -- and its
+//! most dangerous capability, since a signature over the wrong payload can
+//! authorize a transfer just as well as a "prove you're alive" challenge.
+//! `safe_sign` wraps every non-transaction signing call site (`sign_message`/
+//! `whoami`, the social relay) so a manipulated agent can't be tricked into
+//! signing a transfer authorization disguised as ordinary text.
+
+use alloy::dyn_abi::TypedData;
+use alloy::primitives::Signature;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use anyhow::{bail, Context, Result};
+use tracing::warn;
+
+/// `primaryType` substrings (matched case-insensitively) that mark EIP-712
+/// typed data as a token transfer or spending approval rather than an inert
+/// message -- e.g. EIP-3009's `TransferWithAuthorization`, EIP-2612's
+/// `Permit`, ERC-20's `Transfer`.
+const TRANSFER_TYPED_DATA_MARKERS: &[&str] = &["transfer", "permit", "authorization", "approve"];
+
+/// Hex blobs longer than this many characters are flagged as suspicious --
+/// long enough to be calldata or a full signed transaction, too long to be
+/// a typical nonce/challenge string.
+const HEX_BLOB_WARN_LEN: usize = 64;
+
+/// What a signature is being produced for, so [`safe_sign`] knows which
+/// anti-blind-signing rules apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignPurpose {
+    /// A plain identity/liveness challenge handed to us by a peer
+    /// (`sign_message`/`whoami` tools) -- the highest-risk surface, since
+    /// the content is entirely attacker-controlled.
+    Challenge,
+    /// A social-relay message content hash, signed for delivery
+    /// attribution (`social::client::SocialClient::send`).
+    SocialMessage,
+    /// An x402/EIP-3009 transfer authorization, built internally from
+    /// validated payment parameters rather than external text -- the one
+    /// sanctioned purpose allowed to sign transfer-shaped typed data.
+    X402Transfer,
+    /// A `agent::child_protocol::ChildMessageEnvelope` signing payload --
+    /// built internally from a structured message, not external text.
+    ChildProtocolMessage,
+}
+
+impl SignPurpose {
+    fn allows_transfer_typed_data(self) -> bool {
+        matches!(self, SignPurpose::X402Transfer)
+    }
+}
+
+/// Sign `data` with `signer`, refusing or warning on shapes that suggest
+/// blind-signing rather than signing what the caller thinks it's signing:
+///
+/// - Refuses `data` that decodes as EIP-712 typed data whose `primaryType`
+///   names a transfer/permit/approval struct, unless `purpose` is
+///   [`SignPurpose::X402Transfer`]. Without this, a peer could hand
+///   `sign_message` a "verification challenge" that's actually a spending
+///   authorization in disguise.
+/// - Warns (but still signs) when `data` is a long hex blob, since that's
+///   also a common shape for smuggled-in raw transaction calldata.
+pub async fn safe_sign(signer: &PrivateKeySigner, purpose: SignPurpose, data: &str) -> Result<Signature> {
+    if !purpose.allows_transfer_typed_data() {
+        if let Ok(typed_data) = serde_json::from_str::<TypedData>(data) {
+            let primary_type = typed_data.primary_type.to_lowercase();
+            if TRANSFER_TYPED_DATA_MARKERS.iter().any(|marker| primary_type.contains(marker)) {
+                bail!(
+                    "Refusing to sign: content decodes as EIP-712 typed data for '{}', which looks \
+                     like a transfer/permit authorization rather than a plain message. That's only \
+                     allowed via the sanctioned x402 payment path.",
+                    typed_data.primary_type
+                );
+            }
+        }
+    }
+
+    let trimmed = data.trim().trim_start_matches("0x");
+    if trimmed.len() > HEX_BLOB_WARN_LEN && trimmed.chars().all(|c| c.is_ascii_hexdigit()) {
+        warn!(
+            "safe_sign({:?}): signing a {}-character hex blob -- verify this isn't transaction \
+             calldata disguised as a message",
+            purpose,
+            trimmed.len()
+        );
+    }
+
+    signer.sign_message(data.as_bytes()).await.context("Failed to sign message")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_signer() -> PrivateKeySigner {
+        PrivateKeySigner::random()
+    }
+
+    #[tokio::test]
+    async fn test_safe_sign_allows_plain_challenge() {
+        let signer = test_signer();
+        let result = safe_sign(&signer, SignPurpose::Challenge, "login-nonce-7f3a").await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_safe_sign_rejects_transfer_typed_data_for_challenge() {
+        let signer = test_signer();
+        let typed_data = r#"{
+            "types": {
+                "EIP712Domain": [{"name": "name", "type": "string"}],
+                "TransferWithAuthorization": [{"name": "value", "type": "uint256"}]
+            },
+            "primaryType": "TransferWithAuthorization",
+            "domain": {"name": "USD Coin"},
+            "message": {"value": "1000000"}
+        }"#;
+        let result = safe_sign(&signer, SignPurpose::Challenge, typed_data).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_safe_sign_allows_transfer_typed_data_for_x402() {
+        let signer = test_signer();
+        let typed_data = r#"{
+            "types": {
+                "EIP712Domain": [{"name": "name", "type": "string"}],
+                "TransferWithAuthorization": [{"name": "value", "type": "uint256"}]
+            },
+            "primaryType": "TransferWithAuthorization",
+            "domain": {"name": "USD Coin"},
+            "message": {"value": "1000000"}
+        }"#;
+        let result = safe_sign(&signer, SignPurpose::X402Transfer, typed_data).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_safe_sign_warns_but_signs_long_hex_blob() {
+        let signer = test_signer();
+        let hex_blob = format!("0x{}", "ab".repeat(40));
+        let result = safe_sign(&signer, SignPurpose::Challenge, &hex_blob).await;
+        assert!(result.is_ok());
+    }
+}
@@ -8,24 +8,37 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
 
 use alloy::signers::local::PrivateKeySigner;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
+use super::keyring_store;
+
 /// Directory name under the user's home for all automaton data.
 const AUTOMATON_DIR_NAME: &str = ".automaton";
 
 /// Wallet file name within the automaton directory.
 const WALLET_FILENAME: &str = "wallet.json";
 
+/// Keyring entry name the wallet private key is stored under, if enabled.
+const KEYRING_ENTRY: &str = "wallet-private-key";
+
 /// On-disk wallet representation.
+///
+/// When `keyring_ref` is set, `private_key` is empty and the real key lives
+/// in the OS keyring under that entry name instead -- see
+/// [`crate::identity::keyring_store`].
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WalletData {
-    /// Hex-encoded private key with "0x" prefix.
+    /// Hex-encoded private key with "0x" prefix. Empty when `keyring_ref` is set.
+    #[serde(default)]
     pub private_key: String,
     /// ISO-8601 timestamp of when this wallet was created.
     pub created_at: String,
+    /// Keyring entry name holding the private key, if it isn't stored inline.
+    #[serde(default)]
+    pub keyring_ref: Option<String>,
 }
 
 /// Returns the automaton base directory: `~/.automaton`.
@@ -39,10 +52,23 @@ pub fn get_wallet_path() -> PathBuf {
     get_automaton_dir().join(WALLET_FILENAME)
 }
 
+/// Resolve the actual private key for `wallet_data`, pulling it from the OS
+/// keyring if `keyring_ref` is set rather than reading it inline.
+fn resolve_private_key(wallet_data: &WalletData) -> Result<String> {
+    match &wallet_data.keyring_ref {
+        Some(entry) => keyring_store::load(entry)
+            .with_context(|| format!("Failed to load private key from keyring entry '{}'", entry)),
+        None => Ok(wallet_data.private_key.clone()),
+    }
+}
+
 /// Get or create the automaton's wallet.
 ///
-/// If a wallet file already exists, loads the private key from it.
-/// Otherwise, generates a new random secp256k1 private key and persists it.
+/// If a wallet file already exists, loads the private key from it (or from
+/// the OS keyring, if it was stored there). Otherwise, generates a new
+/// random secp256k1 private key and persists it -- to the keyring when
+/// `AUTOMATON_USE_KEYRING=1` is set and a keyring backend is available,
+/// falling back to the plaintext file otherwise.
 ///
 /// Returns the signer and a boolean indicating whether a new wallet was created.
 pub fn get_wallet() -> Result<(PrivateKeySigner, bool)> {
@@ -62,8 +88,8 @@ pub fn get_wallet() -> Result<(PrivateKeySigner, bool)> {
         let wallet_data: WalletData =
             serde_json::from_str(&contents).context("Failed to parse wallet JSON")?;
 
-        let signer: PrivateKeySigner = wallet_data
-            .private_key
+        let private_key = resolve_private_key(&wallet_data)?;
+        let signer: PrivateKeySigner = private_key
             .parse()
             .context("Failed to parse private key from wallet file")?;
 
@@ -75,9 +101,20 @@ pub fn get_wallet() -> Result<(PrivateKeySigner, bool)> {
         let private_key_bytes = signer.credential().to_bytes();
         let private_key_hex = format!("0x{}", hex::encode(private_key_bytes));
 
-        let wallet_data = WalletData {
-            private_key: private_key_hex,
-            created_at: Utc::now().to_rfc3339(),
+        let wallet_data = if keyring_store::is_enabled()
+            && keyring_store::store(KEYRING_ENTRY, &private_key_hex)
+        {
+            WalletData {
+                private_key: String::new(),
+                created_at: Utc::now().to_rfc3339(),
+                keyring_ref: Some(KEYRING_ENTRY.to_string()),
+            }
+        } else {
+            WalletData {
+                private_key: private_key_hex,
+                created_at: Utc::now().to_rfc3339(),
+                keyring_ref: None,
+            }
         };
 
         let json =
@@ -103,7 +140,8 @@ pub fn get_wallet_address() -> Option<String> {
     let contents = fs::read_to_string(&wallet_path).ok()?;
     let wallet_data: WalletData = serde_json::from_str(&contents).ok()?;
 
-    let signer: PrivateKeySigner = wallet_data.private_key.parse().ok()?;
+    let private_key = resolve_private_key(&wallet_data).ok()?;
+    let signer: PrivateKeySigner = private_key.parse().ok()?;
     Some(signer.address().to_checksum(None))
 }
 
@@ -112,6 +150,27 @@ pub fn wallet_exists() -> bool {
     get_wallet_path().exists()
 }
 
+/// Load the wallet data, with the private key resolved in from the OS
+/// keyring if it's stored there rather than inline.
+///
+/// Unlike [`get_wallet`], this never creates a wallet -- it's for commands
+/// that inspect or export an existing one (`--wallet-info`, `--wallet-export`).
+pub fn load_wallet_data() -> Result<WalletData> {
+    let wallet_path = get_wallet_path();
+    let contents = fs::read_to_string(&wallet_path).context("Failed to read wallet file")?;
+    let mut wallet_data: WalletData =
+        serde_json::from_str(&contents).context("Failed to parse wallet JSON")?;
+
+    if wallet_data.keyring_ref.is_some() {
+        wallet_data.private_key = resolve_private_key(&wallet_data)?;
+    }
+    if wallet_data.private_key.is_empty() {
+        bail!("Wallet private key is empty and could not be resolved");
+    }
+
+    Ok(wallet_data)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,7 +4,9 @@
 //! manages its own compute resources, and can replicate.
 
 pub mod types;
+pub mod clock;
 pub mod config;
+pub mod localize;
 pub mod agent;
 pub mod state;
 pub mod identity;
@@ -17,4 +19,7 @@ pub mod skills;
 pub mod replication;
 pub mod registry;
 pub mod git;
+pub mod notify;
 pub mod social;
+pub mod webhook;
+pub mod export;
@@ -0,0 +1,94 @@
+//! Localization
+//!
+//! Every timestamp is stored as RFC3339 UTC (`created_at`, `timestamp`
+//! fields, DB columns) and that never changes -- this module only affects
+//! how a UTC timestamp is *displayed* to an operator reading `--status`,
+//! a resource report, or a log line, via the optional `display_tz` config
+//! field. With no `display_tz` set, display is UTC, identical to today.
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+/// Resolve the configured display timezone, falling back to UTC for an
+/// unset or unparseable zone name rather than failing -- this only affects
+/// human-facing display, so a typo'd zone should degrade, not break.
+pub fn resolve_tz(display_tz: Option<&str>) -> Tz {
+    display_tz.and_then(|tz| tz.parse::<Tz>().ok()).unwrap_or(Tz::UTC)
+}
+
+/// Render a stored RFC3339 UTC timestamp in the configured display
+/// timezone. Returns `raw` unchanged if it doesn't parse as RFC3339, so
+/// callers can pass already-formatted or malformed strings through safely.
+pub fn format_local(raw: &str, display_tz: Option<&str>) -> String {
+    let Ok(parsed) = DateTime::parse_from_rfc3339(raw) else {
+        return raw.to_string();
+    };
+    let tz = resolve_tz(display_tz);
+    parsed
+        .with_timezone(&Utc)
+        .with_timezone(&tz)
+        .format("%Y-%m-%d %H:%M:%S %Z")
+        .to_string()
+}
+
+/// Render a stored RFC3339 UTC timestamp as a coarse relative duration
+/// ("3h ago", "just now") from `now`, with the display-timezone-local
+/// absolute time alongside it for reference. The duration itself doesn't
+/// depend on timezone -- only the absolute anchor next to it does.
+pub fn format_relative(raw: &str, now: DateTime<Utc>, display_tz: Option<&str>) -> String {
+    let Ok(parsed) = DateTime::parse_from_rfc3339(raw) else {
+        return raw.to_string();
+    };
+    let then = parsed.with_timezone(&Utc);
+    let delta = now - then;
+
+    let rough = if delta.num_seconds() < 0 {
+        "in the future".to_string()
+    } else if delta.num_seconds() < 60 {
+        "just now".to_string()
+    } else if delta.num_minutes() < 60 {
+        format!("{}m ago", delta.num_minutes())
+    } else if delta.num_hours() < 24 {
+        format!("{}h ago", delta.num_hours())
+    } else {
+        format!("{}d ago", delta.num_days())
+    };
+
+    format!("{} ({})", rough, format_local(raw, display_tz))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_local_defaults_to_utc_when_no_tz_configured() {
+        assert_eq!(format_local("2024-01-01T12:00:00Z", None), "2024-01-01 12:00:00 UTC");
+    }
+
+    #[test]
+    fn test_format_local_converts_to_configured_zone() {
+        assert_eq!(
+            format_local("2024-01-01T12:00:00Z", Some("America/New_York")),
+            "2024-01-01 07:00:00 EST"
+        );
+    }
+
+    #[test]
+    fn test_format_local_falls_back_to_utc_for_unknown_zone() {
+        assert_eq!(format_local("2024-01-01T12:00:00Z", Some("Nowhere/Imaginary")), "2024-01-01 12:00:00 UTC");
+    }
+
+    #[test]
+    fn test_format_local_passes_through_unparseable_input() {
+        assert_eq!(format_local("not-a-timestamp", None), "not-a-timestamp");
+    }
+
+    #[test]
+    fn test_format_relative_buckets_by_magnitude() {
+        let now: DateTime<Utc> = "2024-01-01T12:00:00Z".parse().unwrap();
+        assert_eq!(format_relative("2024-01-01T11:59:30Z", now, None), "just now (2024-01-01 11:59:30 UTC)");
+        assert_eq!(format_relative("2024-01-01T09:00:00Z", now, None), "3h ago (2024-01-01 09:00:00 UTC)");
+        assert_eq!(format_relative("2023-12-30T12:00:00Z", now, None), "2d ago (2023-12-30 12:00:00 UTC)");
+    }
+}
@@ -11,7 +11,7 @@ use tokio::signal;
 use tokio::time::{sleep, Duration};
 
 use automaton::identity::wallet;
-use automaton::types::{AgentState, AutomatonIdentity};
+use automaton::types::{AgentState, AutomatonIdentity, ConwayClient};
 
 const VERSION: &str = "0.1.0";
 
@@ -43,6 +43,87 @@ struct Cli {
     /// Show current automaton status
     #[arg(long)]
     status: bool,
+
+    /// Reconstruct and print the system prompt and context for a past turn,
+    /// without calling inference. Debugging/audit aid -- takes a turn id
+    /// from the `turns` table (e.g. as shown by `--status` or a turn log).
+    #[arg(long, value_name = "TURN_ID")]
+    replay: Option<String>,
+
+    /// Log every inference request/response (redacted) to
+    /// `~/.automaton/inference.log` for this run, overriding `log_inference`
+    /// in automaton.json. Off by default -- it's noisy and can log sensitive
+    /// conversation content.
+    #[arg(long)]
+    log_inference: bool,
+
+    /// Show the wallet address, creation time, and funding balances
+    #[arg(long)]
+    wallet_info: bool,
+
+    /// Print the wallet's private key to stdout for backup, after an
+    /// explicit confirmation prompt. Never logged -- printed once.
+    #[arg(long)]
+    wallet_export: bool,
+
+    /// Freeze all money-moving tools (transfer_credits, fund_child,
+    /// register_domain, x402_fetch) without killing the agent. Read/think/
+    /// communicate tools keep working. Reversed with `--unfreeze-spending`.
+    #[arg(long)]
+    freeze_spending: bool,
+
+    /// Lift a spending freeze set by `--freeze-spending`.
+    #[arg(long)]
+    unfreeze_spending: bool,
+
+    /// Export the full automaton state (config, wallet, DB, SOUL.md,
+    /// constitution, skills, heartbeat config) to a portable .tar.gz bundle,
+    /// for migration to a new sandbox or backup.
+    #[arg(long, value_name = "FILE")]
+    export_state: Option<String>,
+
+    /// Restore a bundle produced by `--export-state`, replacing the current
+    /// automaton state directory (the previous one is backed up, not
+    /// deleted). Runs the same DB migrations a live upgrade would.
+    #[arg(long, value_name = "FILE")]
+    import_state: Option<String>,
+
+    /// Tail the operational event/audit log (the `events` and
+    /// `modifications` tables) as a filtered, formatted stream.
+    #[arg(long)]
+    logs: bool,
+
+    /// With `--logs`, keep polling for and printing new entries instead of
+    /// exiting after the initial batch.
+    #[arg(long, requires = "logs")]
+    follow: bool,
+
+    /// With `--logs`, only show entries in this category.
+    #[arg(long, value_enum, requires = "logs")]
+    filter: Option<LogFilter>,
+
+    /// With `--logs`, only show entries timestamped at or after this
+    /// RFC3339 timestamp.
+    #[arg(long, value_name = "TIMESTAMP", requires = "logs")]
+    since: Option<String>,
+
+    /// With `--logs`, only show entries timestamped at or before this
+    /// RFC3339 timestamp.
+    #[arg(long, value_name = "TIMESTAMP", requires = "logs")]
+    until: Option<String>,
+}
+
+/// Category for `--logs --filter`, matching the operational event kinds
+/// (see [`automaton::types::AgentLoopEvent::kind`]) and the self-
+/// modification audit trail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum LogFilter {
+    /// `tool_call`/`tool_result` events.
+    Tool,
+    /// `state_change` events.
+    State,
+    /// Entries from the `modifications` audit table.
+    Mod,
 }
 
 // ---- Status Command ---------------------------------------------------------
@@ -103,13 +184,556 @@ Version:    {}
         config.inference_model,
         config.version,
     );
+
+    // Surface our own children summary from the last heartbeat ping so a
+    // parent polling us via `automaton --status` can extend its lineage tree
+    // past its direct children.
+    if let Ok(db) = automaton::state::Database::open(&db_path_str) {
+        if let Ok(Some(ping)) = db.get_kv("last_heartbeat_ping") {
+            if let Ok(payload) = serde_json::from_str::<serde_json::Value>(&ping) {
+                if let Some(summary) = payload.get("childrenSummary") {
+                    if summary.as_array().is_some_and(|a| !a.is_empty()) {
+                        println!("CHILDREN_SUMMARY: {}", summary);
+                    }
+                }
+            }
+        }
+
+        // Surface the outcome of the last startup self-check (see
+        // `startup_selfcheck` in agent/agent_loop.rs) so operators can tell
+        // a healthy-but-idle automaton from one that never came up cleanly.
+        if let Ok(Some(check)) = db.get_kv("last_startup_check") {
+            if let Ok(payload) = serde_json::from_str::<serde_json::Value>(&check) {
+                let status = payload.get("status").and_then(|v| v.as_str()).unwrap_or("unknown");
+                let checked_at = payload.get("checkedAt").and_then(|v| v.as_str()).unwrap_or("unknown");
+                let checked_at =
+                    automaton::localize::format_local(checked_at, config.display_tz.as_deref());
+                match payload.get("error").and_then(|v| v.as_str()) {
+                    Some(error) => println!("Last startup check: FAILED at {} -- {}", checked_at, error),
+                    None => println!("Last startup check: {} at {}", status.to_uppercase(), checked_at),
+                }
+            }
+        }
+
+        // Surface the restart counter and last shutdown reason (see
+        // `agent::crash_loop`) so an operator can spot a supervisor stuck
+        // restarting the automaton without it ever shutting down cleanly.
+        if let Ok(Some(count)) = db.get_kv("restart_count") {
+            println!("Restart count: {}", count);
+        }
+        match db.get_kv("last_shutdown_reason") {
+            Ok(Some(reason)) => {
+                if let Ok(payload) = serde_json::from_str::<serde_json::Value>(&reason) {
+                    let reason = payload.get("reason").and_then(|v| v.as_str()).unwrap_or("unknown");
+                    let at = payload.get("at").and_then(|v| v.as_str()).unwrap_or("unknown");
+                    let at = automaton::localize::format_local(at, config.display_tz.as_deref());
+                    println!("Last shutdown: {} at {}", reason, at);
+                }
+            }
+            _ => println!("Last shutdown: none recorded (crashed, or never run)"),
+        }
+
+        // Surface which constitution is actually in effect (see
+        // `record_constitution_source` in agent/agent_loop.rs) so a
+        // misplaced constitution.md doesn't silently fall back unnoticed.
+        if let Ok(Some(constitution)) = db.get_kv("constitution_source") {
+            if let Ok(payload) = serde_json::from_str::<serde_json::Value>(&constitution) {
+                let source = payload.get("source").and_then(|v| v.as_str()).unwrap_or("unknown");
+                let hash = payload.get("hash").and_then(|v| v.as_str()).unwrap_or("unknown");
+                println!("Constitution: {} (hash {})", source, &hash[..hash.len().min(12)]);
+            }
+        }
+    }
+}
+
+// ---- Wallet Commands -----------------------------------------------------------
+
+/// Show the wallet address, creation time, and funding balances.
+///
+/// USDC balance is read directly from the base network; Conway credits are
+/// only fetched if a config with an API key exists, since that requires
+/// hitting Conway's control plane.
+async fn wallet_info() {
+    if !wallet::wallet_exists() {
+        println!("No wallet found. Run `automaton --init` first.");
+        return;
+    }
+
+    let wallet_data = match wallet::load_wallet_data() {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!("Failed to read wallet: {}", e);
+            return;
+        }
+    };
+
+    let signer: alloy::signers::local::PrivateKeySigner = match wallet_data.private_key.parse() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to parse wallet key: {}", e);
+            return;
+        }
+    };
+    let address = signer.address();
+
+    println!();
+    println!("=== WALLET INFO ===");
+    println!("Address:     {}", address.to_checksum(None));
+    println!("Created At:  {}", wallet_data.created_at);
+
+    let usdc_balance = automaton::conway::x402::get_usdc_balance(address, "base")
+        .await
+        .unwrap_or(0.0);
+    println!("USDC:        {:.4}", usdc_balance);
+
+    let config_path = wallet::get_automaton_dir().join("automaton.json");
+    let config: Option<automaton::types::AutomatonConfig> = fs::read_to_string(&config_path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok());
+
+    match config.filter(|c| !c.conway_api_key.is_empty()) {
+        Some(config) => {
+            let conway = automaton::conway::client::ConwayHttpClient::new(
+                config.conway_api_url,
+                config.conway_api_key,
+                config.sandbox_id,
+            );
+            match conway.get_credits_balance().await {
+                Ok(credits) => println!("Credits:     {:.2}", credits),
+                Err(e) => println!("Credits:     unavailable ({})", e),
+            }
+        }
+        None => println!("Credits:     unavailable (not configured, run --setup)"),
+    }
+    println!("===================");
+}
+
+/// Print the wallet's private key to stdout for backup, after an explicit
+/// confirmation prompt. The wallet is the automaton's sovereign identity --
+/// losing it means losing everything it owns, so this is deliberately
+/// guarded rather than a plain `--init`-style one-liner. The key is only
+/// ever printed once to stdout, never written to a log.
+fn wallet_export() -> Result<()> {
+    if !wallet::wallet_exists() {
+        eprintln!("No wallet found. Run `automaton --init` first.");
+        std::process::exit(1);
+    }
+
+    println!();
+    println!("WARNING: this prints your automaton's private key to stdout.");
+    println!("Anyone who obtains it has full control of its funds and identity.");
+    println!("Do not paste it into chat, a script, or version control.");
+    println!();
+
+    let confirmed = dialoguer::Confirm::new()
+        .with_prompt("Export the private key now?")
+        .default(false)
+        .interact()
+        .context("Failed to read confirmation")?;
+
+    if !confirmed {
+        println!("Export cancelled.");
+        return Ok(());
+    }
+
+    let wallet_data = wallet::load_wallet_data()?;
+    println!("{}", wallet_data.private_key);
+
+    Ok(())
+}
+
+// ---- Spending Freeze Command -------------------------------------------------
+
+/// Toggle the `spending_frozen` KV flag that the money-moving tools
+/// (`transfer_credits`, `fund_child`, `register_domain`, `x402_fetch`) check
+/// before acting. This is a targeted circuit breaker, not a survival-tier
+/// state change -- everything else keeps running.
+fn set_spending_frozen(frozen: bool) {
+    let automaton_dir = wallet::get_automaton_dir();
+    let config_path = automaton_dir.join("automaton.json");
+
+    if !config_path.exists() {
+        eprintln!("Automaton is not configured. Run the setup script first.");
+        std::process::exit(1);
+    }
+
+    let config_str = match fs::read_to_string(&config_path) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to read config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let config: automaton::types::AutomatonConfig = match serde_json::from_str(&config_str) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to parse config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let db_path_str = if config.db_path.starts_with('~') {
+        let home = dirs::home_dir()
+            .map(|h| h.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/root".to_string());
+        format!("{}{}", home, &config.db_path[1..])
+    } else {
+        config.db_path.clone()
+    };
+
+    let db = match automaton::state::Database::open(&db_path_str) {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to open database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let result = if frozen {
+        db.set_kv("spending_frozen", "true")
+    } else {
+        db.delete_kv("spending_frozen")
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to update spending freeze: {}", e);
+        std::process::exit(1);
+    }
+
+    if frozen {
+        println!("Spending frozen. Money-moving tools will refuse until --unfreeze-spending is run.");
+    } else {
+        println!("Spending unfrozen.");
+    }
+}
+
+// ---- State Export/Import Commands ---------------------------------------------
+
+/// Bundle the automaton's state directory into a portable `.tar.gz` archive.
+fn export_state(output: &str) {
+    match automaton::export::export_state(std::path::Path::new(output)) {
+        Ok(()) => println!("State exported to {}", output),
+        Err(e) => {
+            eprintln!("Export failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Restore a bundle produced by `--export-state` onto this host.
+fn import_state(input: &str) {
+    match automaton::export::import_state(std::path::Path::new(input)) {
+        Ok(manifest) => {
+            println!(
+                "State imported: {} ({}), originally exported at {} from schema v{}",
+                manifest.name, manifest.wallet_address, manifest.exported_at, manifest.schema_version
+            );
+        }
+        Err(e) => {
+            eprintln!("Import failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+// ---- Replay Command -----------------------------------------------------------
+
+/// Reconstruct and print the system prompt + context messages for a stored
+/// turn, exactly as `run_agent_loop` would have built them, without calling
+/// inference. Uses the turn's own `financial_snapshot` when present so the
+/// credit balance shown matches what the automaton actually saw; older
+/// turns recorded before that field existed fall back to a zeroed balance
+/// and print a warning that the reconstruction is approximate.
+fn replay_turn(turn_id: &str) {
+    let automaton_dir = wallet::get_automaton_dir();
+    let config_path = automaton_dir.join("automaton.json");
+
+    if !config_path.exists() {
+        eprintln!("Automaton is not configured. Run the setup script first.");
+        std::process::exit(1);
+    }
+
+    let config_str = match fs::read_to_string(&config_path) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to read config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let config: automaton::types::AutomatonConfig = match serde_json::from_str(&config_str) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to parse config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let db_path_str = if config.db_path.starts_with('~') {
+        let home = dirs::home_dir()
+            .map(|h| h.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/root".to_string());
+        format!("{}{}", home, &config.db_path[1..])
+    } else {
+        config.db_path.clone()
+    };
+
+    let db = match automaton::state::Database::open(&db_path_str) {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to open database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let turn = match db.get_turn_by_id(turn_id) {
+        Ok(Some(t)) => t,
+        Ok(None) => {
+            eprintln!("No turn found with id '{}'.", turn_id);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Failed to look up turn: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let financial = turn.financial_snapshot.clone().unwrap_or_else(|| {
+        eprintln!(
+            "Warning: turn '{}' predates financial snapshots -- reconstructing with a zeroed balance.",
+            turn_id
+        );
+        automaton::types::FinancialState {
+            credits_cents: 0.0,
+            usdc_balance: 0.0,
+            last_checked: turn.timestamp.clone(),
+        }
+    });
+
+    let recent_turns = db.get_turns_before(&turn.timestamp, 20).unwrap_or_default();
+    let is_first_run = recent_turns.is_empty();
+
+    let identity = AutomatonIdentity {
+        name: config.name.clone(),
+        address: config.wallet_address.clone(),
+        account: None,
+        creator_address: config.creator_address.clone(),
+        sandbox_id: config.sandbox_id.clone(),
+        api_key: config.conway_api_key.clone(),
+        created_at: turn.timestamp.clone(),
+    };
+
+    let tools = automaton::agent::tools::create_builtin_tools(&config.sandbox_id);
+    let skills = db.get_skills(true).unwrap_or_default();
+
+    let system_prompt = automaton::agent::system_prompt::build_system_prompt(
+        &identity,
+        &config,
+        &financial,
+        turn.state.clone(),
+        &db,
+        &tools,
+        Some(&skills),
+        is_first_run,
+    );
+
+    let input_source_str = turn.input_source.as_ref().map(|s| {
+        let v = serde_json::to_string(s).unwrap_or_default();
+        v.trim_matches('"').to_string()
+    });
+    let pending_input = turn
+        .input
+        .as_deref()
+        .map(|content| (content, input_source_str.as_deref().unwrap_or("system")));
+
+    let long_term_summary = if config.context_packing.enabled {
+        let summaries = db.get_history_summaries(10).unwrap_or_default();
+        automaton::agent::context::pack_long_term_summary(
+            &summaries,
+            config.context_packing.summary_token_budget.unwrap_or(2000),
+        )
+    } else {
+        None
+    };
+
+    let messages = automaton::agent::context::build_context_messages(
+        &system_prompt,
+        &recent_turns,
+        pending_input,
+        long_term_summary.as_deref(),
+    );
+
+    println!("=== REPLAY: turn {} ({}) ===\n", turn.id, turn.timestamp);
+    for message in &messages {
+        println!("--- {:?} ---\n{}\n", message.role, message.content);
+    }
+    println!("=== Actual response recorded for this turn ===\n{}", turn.thinking);
+
+    // Cross-check the reconstruction against what was actually hashed at
+    // the time, so a mismatch (a prompt-injection attempt or a bug in this
+    // reconstruction) is visible rather than silently trusted.
+    match db.get_turn_prompt(&turn.id) {
+        Ok(Some(recorded)) => {
+            let reconstructed_hash = hex::encode(alloy::primitives::keccak256(
+                serde_json::to_string(&messages).unwrap_or_default().as_bytes(),
+            ));
+            if reconstructed_hash == recorded.prompt_hash {
+                println!("\n=== Audit: reconstructed prompt hash matches the recorded one ({}) ===", recorded.prompt_hash);
+            } else {
+                println!(
+                    "\n=== Audit WARNING: reconstructed prompt hash ({}) does NOT match the one recorded at the time ({}) --\n\
+                     the context this reconstruction produced differs from what the model actually saw. ===",
+                    reconstructed_hash, recorded.prompt_hash
+                );
+            }
+            if let Some(exact_prompt) = recorded.rendered_prompt {
+                println!("\n=== Exact rendered prompt as recorded at the time ===\n{}", exact_prompt);
+            } else {
+                println!("\n(The exact rendered prompt has been pruned from retention; only its hash remains.)");
+            }
+        }
+        Ok(None) => println!("\n(No recorded prompt hash for this turn -- it predates prompt auditing.)"),
+        Err(e) => eprintln!("\nFailed to look up recorded prompt: {}", e),
+    }
+}
+
+// ---- Logs Command -------------------------------------------------------------
+
+/// How long to sleep between polls when `--follow` finds nothing new.
+const LOGS_FOLLOW_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Format one [`automaton::types::LoopEventRecord`] in `--logs`'s format.
+fn format_event(event: &automaton::types::LoopEventRecord, tz: Option<&str>) -> String {
+    format!(
+        "[{}] {:<14} {}",
+        automaton::localize::format_local(&event.timestamp, tz),
+        event.kind,
+        event.data
+    )
+}
+
+/// Format one [`automaton::types::ModificationEntry`] in `--logs`'s format.
+fn format_modification(modification: &automaton::types::ModificationEntry, tz: Option<&str>) -> String {
+    format!(
+        "[{}] {:<14} {}{}",
+        automaton::localize::format_local(&modification.timestamp, tz),
+        "mod",
+        modification.description,
+        modification.file_path.as_deref().map(|p| format!(" ({})", p)).unwrap_or_default(),
+    )
+}
+
+/// Tail the `events` and `modifications` tables as a filtered, formatted
+/// stream. `filter` narrows to one category (`tool`/`state` pull from
+/// `events`, `mod` from `modifications`); with no filter, both sources are
+/// shown interleaved in timestamp order. `--follow` keeps polling for new
+/// rows after the initial batch (bounded by `until`, if given) instead of
+/// exiting.
+async fn tail_logs(filter: Option<LogFilter>, follow: bool, since: Option<String>, until: Option<String>) {
+    let automaton_dir = wallet::get_automaton_dir();
+    let config_path = automaton_dir.join("automaton.json");
+
+    if !config_path.exists() {
+        eprintln!("Automaton is not configured. Run the setup script first.");
+        std::process::exit(1);
+    }
+
+    let config_str = match fs::read_to_string(&config_path) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to read config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let config: automaton::types::AutomatonConfig = match serde_json::from_str(&config_str) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to parse config: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let db_path_str = if config.db_path.starts_with('~') {
+        let home = dirs::home_dir()
+            .map(|h| h.to_string_lossy().to_string())
+            .unwrap_or_else(|| "/root".to_string());
+        format!("{}{}", home, &config.db_path[1..])
+    } else {
+        config.db_path.clone()
+    };
+
+    let db = match automaton::state::Database::open(&db_path_str) {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to open database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let want_events = !matches!(filter, Some(LogFilter::Mod));
+    let want_mods = !matches!(filter, Some(LogFilter::Tool | LogFilter::State));
+
+    let mut events_since = since.clone();
+    let mut mods_since = since;
+
+    loop {
+        let events: Vec<automaton::types::LoopEventRecord> = if want_events {
+            db.get_events(events_since.as_deref(), 1000).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let modifications: Vec<automaton::types::ModificationEntry> = if want_mods {
+            db.get_modifications_since(mods_since.as_deref(), 1000).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        if let Some(last) = events.last() {
+            events_since = Some(last.timestamp.clone());
+        }
+        if let Some(last) = modifications.last() {
+            mods_since = Some(last.timestamp.clone());
+        }
+
+        let mut lines: Vec<(String, String)> = Vec::new();
+
+        for event in events.into_iter().filter(|e| match filter {
+            Some(LogFilter::Tool) => matches!(e.kind.as_str(), "tool_call" | "tool_result"),
+            Some(LogFilter::State) => e.kind == "state_change",
+            Some(LogFilter::Mod) => false,
+            None => true,
+        }) {
+            if until.as_deref().is_some_and(|u| event.timestamp.as_str() > u) {
+                continue;
+            }
+            lines.push((event.timestamp.clone(), format_event(&event, config.display_tz.as_deref())));
+        }
+        for modification in &modifications {
+            if until.as_deref().is_some_and(|u| modification.timestamp.as_str() > u) {
+                continue;
+            }
+            lines.push((modification.timestamp.clone(), format_modification(modification, config.display_tz.as_deref())));
+        }
+
+        lines.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, line) in &lines {
+            println!("{}", line);
+        }
+
+        if !follow {
+            break;
+        }
+        tokio::time::sleep(LOGS_FOLLOW_POLL_INTERVAL).await;
+    }
 }
 
 // ---- Main Run ---------------------------------------------------------------
 
 /// The main run loop: load config, initialize all subsystems,
 /// start heartbeat daemon, and run the agent loop.
-async fn run() -> Result<()> {
+async fn run(log_inference_override: bool) -> Result<()> {
     let now = chrono::Utc::now().to_rfc3339();
     println!("[{}] Conway Automaton v{} starting...", now, VERSION);
 
@@ -117,7 +741,7 @@ async fn run() -> Result<()> {
     let config_path = automaton_dir.join("automaton.json");
 
     // Load config -- first run triggers interactive setup wizard
-    let config = if config_path.exists() {
+    let mut config = if config_path.exists() {
         let config_str =
             fs::read_to_string(&config_path).context("Failed to read automaton.json")?;
         serde_json::from_str::<automaton::types::AutomatonConfig>(&config_str)
@@ -126,6 +750,14 @@ async fn run() -> Result<()> {
         automaton::setup::wizard::run_setup_wizard().await?
     };
 
+    if log_inference_override {
+        config.log_inference = true;
+    }
+
+    // Identifies which automaton emitted a log line once a fleet's output
+    // gets aggregated and interleaved -- see `log_prefix`.
+    let prefix = automaton::agent::agent_loop::log_prefix(&config);
+
     // Load wallet
     let (signer, _is_new) = wallet::get_wallet().context("Failed to load wallet")?;
     let address = signer.address().to_checksum(None);
@@ -167,7 +799,7 @@ async fn run() -> Result<()> {
     };
 
     let now = chrono::Utc::now().to_rfc3339();
-    println!("[{}] Identity: {} ({})", now, identity.name, identity.address);
+    println!("[{}] {}Identity: {} ({})", now, prefix, identity.name, identity.address);
 
     // TODO: Initialize database
     // let db_path = resolve_path(&config.db_path);
@@ -177,12 +809,15 @@ async fn run() -> Result<()> {
     // let conway = create_conway_client(&config.conway_api_url, &api_key, &config.sandbox_id);
 
     // TODO: Create inference client
-    // let inference = create_inference_client(&config);
+    // let inference = InferenceClientImpl::new(
+    //     config.conway_api_url.clone(), api_key.clone(), config.inference_model.clone(),
+    //     config.max_tokens_per_turn, config.log_inference,
+    // );
 
     // TODO: Create social client
     if let Some(ref relay_url) = config.social_relay_url {
         let now = chrono::Utc::now().to_rfc3339();
-        println!("[{}] Social relay: {}", now, relay_url);
+        println!("[{}] {}Social relay: {}", now, prefix, relay_url);
     }
 
     // TODO: Load and sync heartbeat config
@@ -190,14 +825,14 @@ async fn run() -> Result<()> {
 
     // TODO: Load skills
     let now = chrono::Utc::now().to_rfc3339();
-    println!("[{}] Skills directory: {}", now, config.skills_dir);
+    println!("[{}] {}Skills directory: {}", now, prefix, config.skills_dir);
 
     // TODO: Initialize state repo (git)
     // init_state_repo(&conway).await?;
 
     // TODO: Start heartbeat daemon
     let now = chrono::Utc::now().to_rfc3339();
-    println!("[{}] Heartbeat daemon would start here.", now);
+    println!("[{}] {}Heartbeat daemon would start here.", now, prefix);
 
     // Handle graceful shutdown
     let shutdown = async {
@@ -211,11 +846,11 @@ async fn run() -> Result<()> {
             tokio::select! {
                 _ = ctrl_c => {
                     let now = chrono::Utc::now().to_rfc3339();
-                    println!("\n[{}] Received SIGINT, shutting down...", now);
+                    println!("\n[{}] {}Received SIGINT, shutting down...", now, prefix);
                 }
                 _ = sigterm.recv() => {
                     let now = chrono::Utc::now().to_rfc3339();
-                    println!("\n[{}] Received SIGTERM, shutting down...", now);
+                    println!("\n[{}] {}Received SIGTERM, shutting down...", now, prefix);
                 }
             }
         }
@@ -223,7 +858,7 @@ async fn run() -> Result<()> {
         {
             ctrl_c.await.expect("Failed to register Ctrl+C handler");
             let now = chrono::Utc::now().to_rfc3339();
-            println!("\n[{}] Received shutdown signal...", now);
+            println!("\n[{}] {}Received shutdown signal...", now, prefix);
         }
     };
 
@@ -234,7 +869,7 @@ async fn run() -> Result<()> {
     tokio::select! {
         _ = shutdown => {
             let now = chrono::Utc::now().to_rfc3339();
-            println!("[{}] Shutting down gracefully...", now);
+            println!("[{}] {}Shutting down gracefully...", now, prefix);
             // db.set_agent_state(AgentState::Sleeping);
             // db.close();
         }
@@ -352,6 +987,49 @@ async fn main() {
         return;
     }
 
+    if let Some(turn_id) = cli.replay {
+        replay_turn(&turn_id);
+        return;
+    }
+
+    if cli.wallet_info {
+        wallet_info().await;
+        return;
+    }
+
+    if cli.wallet_export {
+        if let Err(e) = wallet_export() {
+            eprintln!("Wallet export failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if cli.freeze_spending {
+        set_spending_frozen(true);
+        return;
+    }
+
+    if cli.unfreeze_spending {
+        set_spending_frozen(false);
+        return;
+    }
+
+    if let Some(output) = cli.export_state {
+        export_state(&output);
+        return;
+    }
+
+    if let Some(input) = cli.import_state {
+        import_state(&input);
+        return;
+    }
+
+    if cli.logs {
+        tail_logs(cli.filter, cli.follow, cli.since, cli.until).await;
+        return;
+    }
+
     if cli.setup {
         match automaton::setup::wizard::run_setup_wizard().await {
             Ok(_config) => {
@@ -366,7 +1044,7 @@ async fn main() {
     }
 
     if cli.run {
-        if let Err(e) = run().await {
+        if let Err(e) = run(cli.log_inference).await {
             eprintln!("Fatal: {}", e);
             std::process::exit(1);
         }
@@ -0,0 +1,80 @@
+//! Notification Sinks
+//!
+//! `distress_signal` and the survival monitor record critical events to
+//! local KV, which no human sees unless they SSH in. A [`Notifier`] delivers
+//! the same event somewhere a human actually looks (a Discord channel, a
+//! Telegram-bridged webhook, ...). Sinks are configured as plain URLs in
+//! `AutomatonConfig::notification_webhook_urls`; every configured sink gets
+//! every notification, best-effort.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use tracing::warn;
+
+use crate::types::AutomatonConfig;
+
+/// Severity of a notification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotifyLevel {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl NotifyLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            NotifyLevel::Info => "INFO",
+            NotifyLevel::Warning => "WARNING",
+            NotifyLevel::Critical => "CRITICAL",
+        }
+    }
+}
+
+/// A channel that can deliver a notification to a human.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, level: NotifyLevel, message: &str);
+}
+
+/// Posts to an incoming-webhook-style URL. Discord's incoming webhook API
+/// accepts this `{"content": ...}` shape directly; a Telegram bridge/relay
+/// can be pointed at the same interface.
+pub struct WebhookNotifier {
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> Self {
+        Self { url }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, level: NotifyLevel, message: &str) {
+        let body = json!({ "content": format!("[{}] {}", level.as_str(), message) });
+        if let Err(e) = Client::new().post(&self.url).json(&body).send().await {
+            warn!("Notification webhook {} failed: {}", self.url, e);
+        }
+    }
+}
+
+/// Build the configured notifier sinks from `config.notification_webhook_urls`.
+pub fn notifiers_from_config(config: &AutomatonConfig) -> Vec<Box<dyn Notifier>> {
+    config
+        .notification_webhook_urls
+        .iter()
+        .cloned()
+        .map(|url| Box::new(WebhookNotifier::new(url)) as Box<dyn Notifier>)
+        .collect()
+}
+
+/// Deliver a notification to every sink, best-effort -- a failing sink logs
+/// a warning (inside `Notifier::notify`) rather than blocking the others.
+pub async fn notify_all(notifiers: &[Box<dyn Notifier>], level: NotifyLevel, message: &str) {
+    for notifier in notifiers {
+        notifier.notify(level, message).await;
+    }
+}
@@ -13,6 +13,26 @@ use crate::types::{
 /// The ERC-8004 agent card type URI.
 const AGENT_CARD_TYPE: &str = "https://eips.ethereum.org/EIPS/eip-8004#registration-v1";
 
+/// KV key holding the JSON-encoded list of domains this automaton has registered.
+const REGISTERED_DOMAINS_KV_KEY: &str = "registered_domains";
+
+/// Record that a domain was registered, so it can be advertised in the agent card.
+pub fn record_registered_domain(db: &dyn AutomatonDatabase, domain: &str) {
+    let mut domains = get_registered_domains(db);
+    if !domains.iter().any(|d| d == domain) {
+        domains.push(domain.to_string());
+        if let Ok(json) = serde_json::to_string(&domains) {
+            db.set_kv(REGISTERED_DOMAINS_KV_KEY, &json);
+        }
+    }
+}
+
+fn get_registered_domains(db: &dyn AutomatonDatabase) -> Vec<String> {
+    db.get_kv(REGISTERED_DOMAINS_KV_KEY)
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
 /// Generate an agent card from the automaton's current state.
 pub fn generate_agent_card(
     identity: &AutomatonIdentity,
@@ -38,6 +58,21 @@ pub fn generate_agent_card(
         });
     }
 
+    // Advertise whatever this automaton is actually hosting: ports it has
+    // exposed and domains it has registered.
+    for port in db.get_exposed_ports() {
+        services.push(AgentService {
+            name: format!("port-{}", port.port),
+            endpoint: port.public_url,
+        });
+    }
+    for domain in get_registered_domains(db) {
+        services.push(AgentService {
+            name: "domain".to_string(),
+            endpoint: format!("https://{}", domain),
+        });
+    }
+
     let children = db.get_children();
     let skills = db.get_skills(Some(true));
 
@@ -3,24 +3,77 @@
 //! Generate genesis configuration for child automatons from parent state.
 //! The genesis config defines who the child is and what it should do.
 
+use rand::Rng;
+
 use crate::types::{
-    AutomatonConfig, AutomatonDatabase, AutomatonIdentity, GenesisConfig,
+    AutomatonConfig, AutomatonDatabase, AutomatonIdentity, GenesisConfig, KillSwitchConfig,
+    MutationBounds, SandboxSpecs,
 };
 
+/// Kill switch every genesis-generated child boots with: always on,
+/// regardless of whether the parent enables it for itself, so a creator
+/// retains a way to halt a lineage even if a descendant stops obeying
+/// instructions. Inherits the parent's token/sentinel path so the same
+/// signal authenticates across the whole lineage.
+fn child_kill_switch(config: &AutomatonConfig) -> KillSwitchConfig {
+    KillSwitchConfig {
+        enabled: true,
+        kill_token: config.kill_switch.kill_token.clone(),
+        sentinel_path: config.kill_switch.sentinel_path.clone(),
+    }
+}
+
 /// Parameters for generating a genesis configuration.
 pub struct GenesisParams {
     pub name: String,
     pub specialization: Option<String>,
     pub message: Option<String>,
+    /// Sandbox sizing for the child. Falls back to `config.default_sandbox_specs`
+    /// when not given.
+    pub sandbox_specs: Option<SandboxSpecs>,
 }
 
-/// Generate a genesis config for a child from the parent's state.
-pub fn generate_genesis_config(
+/// Genesis prompts rendered past this length are rejected as probably
+/// malformed -- a template with a placeholder that expanded unboundedly,
+/// say -- and `generate_genesis_config` falls back to
+/// [`default_genesis_prompt`] instead of handing a child a broken genesis.
+const MAX_GENESIS_PROMPT_CHARS: usize = 20_000;
+
+/// Render `template` by substituting `{name}`, `{specialization}`,
+/// `{parent_address}`, and `{message}` with this child's genesis
+/// parameters (missing `specialization`/`message` substitute as empty
+/// strings, same as the untemplated prompt omits those sections
+/// entirely). Returns `None` -- telling the caller to fall back to
+/// [`default_genesis_prompt`] -- if the rendered prompt is empty or
+/// longer than [`MAX_GENESIS_PROMPT_CHARS`].
+pub fn render_genesis_template(
+    template: &str,
+    params: &GenesisParams,
+    identity: &AutomatonIdentity,
+) -> Option<String> {
+    let rendered = template
+        .replace("{name}", &params.name)
+        .replace("{specialization}", params.specialization.as_deref().unwrap_or(""))
+        .replace("{parent_address}", &identity.address)
+        .replace("{message}", params.message.as_deref().unwrap_or(""));
+
+    let trimmed = rendered.trim();
+    if trimmed.is_empty() || trimmed.len() > MAX_GENESIS_PROMPT_CHARS {
+        return None;
+    }
+
+    Some(rendered)
+}
+
+/// Build the genesis prompt the hardcoded way: the parent's mission, an
+/// optional specialization block, then a lineage block naming the parent.
+/// Used directly when `config.genesis_prompt_template` isn't set, and as
+/// the fallback when a configured template fails to render.
+fn default_genesis_prompt(
     identity: &AutomatonIdentity,
     config: &AutomatonConfig,
     params: &GenesisParams,
-) -> GenesisConfig {
-    // Build the child's genesis prompt from parent's mission + specialization
+) -> String {
     let mut genesis_prompt = config.genesis_prompt.clone();
 
     if let Some(ref specialization) = params.specialization {
@@ -34,21 +87,103 @@ pub fn generate_genesis_config(
     }
 
     // Add parent context
-    genesis_prompt = format!(
+    format!(
         "{}\n\n--- LINEAGE ---\n\
          You were spawned by {} ({}).\n\
          You inherit their mission but have your own identity and wallet.\n\
          --- END LINEAGE ---",
         genesis_prompt, config.name, identity.address
-    );
+    )
+}
 
-    GenesisConfig {
+/// Generate a genesis config for a child from the parent's state.
+pub fn generate_genesis_config(
+    identity: &AutomatonIdentity,
+    config: &AutomatonConfig,
+    params: &GenesisParams,
+) -> GenesisConfig {
+    let genesis_prompt = match config.genesis_prompt_template.as_deref() {
+        Some(template) => render_genesis_template(template, params, identity)
+            .unwrap_or_else(|| default_genesis_prompt(identity, config, params)),
+        None => default_genesis_prompt(identity, config, params),
+    };
+
+    let base = GenesisConfig {
         name: params.name.clone(),
         genesis_prompt,
         creator_message: params.message.clone(),
         creator_address: identity.address.clone(),
         parent_address: identity.address.clone(),
+        sandbox_specs: params
+            .sandbox_specs
+            .clone()
+            .unwrap_or_else(|| config.default_sandbox_specs.clone()),
+        kill_switch: child_kill_switch(config),
+        model_override: None,
+        idle_sleep_seconds_override: None,
+        mutation_summary: None,
+    };
+
+    // A no-op unless the operator has opted specific aspects into
+    // `config.genesis_mutation` -- see `apply_mutation`.
+    apply_mutation(&base, &mut rand::thread_rng(), &config.genesis_mutation)
+}
+
+/// Vary a child's genesis away from its parent's, within `bounds`, for
+/// operators experimenting with evolutionary agent populations who don't
+/// want offspring to be identical clones. Each aspect (model,
+/// `idle_sleep_seconds`, genesis prompt) mutates independently at its own
+/// configured rate; a `bounds` field left `None`/empty never mutates that
+/// aspect, so an unconfigured `MutationBounds` makes this a no-op.
+/// Whatever did change is recorded on the returned config's
+/// `mutation_summary` for `spawn_child` to copy onto the child's lineage
+/// entry.
+pub fn apply_mutation(
+    base: &GenesisConfig,
+    rng: &mut impl Rng,
+    bounds: &MutationBounds,
+) -> GenesisConfig {
+    let mut genesis = base.clone();
+    let mut changes: Vec<String> = Vec::new();
+
+    if let Some(rate) = bounds.model_rate {
+        if !bounds.candidate_models.is_empty() && rng.gen_bool(rate.clamp(0.0, 1.0)) {
+            let choice = &bounds.candidate_models[rng.gen_range(0..bounds.candidate_models.len())];
+            changes.push(format!("model -> {}", choice));
+            genesis.model_override = Some(choice.clone());
+        }
+    }
+
+    if let Some(rate) = bounds.heartbeat_rate {
+        if rng.gen_bool(rate.clamp(0.0, 1.0)) {
+            let anchor = base.idle_sleep_seconds_override.unwrap_or(60) as f64;
+            let jitter = bounds.heartbeat_jitter.clamp(0.0, 1.0);
+            let factor = 1.0 + rng.gen_range(-jitter..=jitter);
+            let jittered = (anchor * factor).round().max(1.0) as u32;
+            changes.push(format!("idle_sleep_seconds -> {}", jittered));
+            genesis.idle_sleep_seconds_override = Some(jittered);
+        }
+    }
+
+    if let Some(rate) = bounds.prompt_rate {
+        if !bounds.candidate_prompt_variations.is_empty() && rng.gen_bool(rate.clamp(0.0, 1.0)) {
+            let variation =
+                &bounds.candidate_prompt_variations[rng.gen_range(0..bounds.candidate_prompt_variations.len())];
+            genesis.genesis_prompt = format!(
+                "{}\n\n--- MUTATION ---\n{}\n--- END MUTATION ---",
+                genesis.genesis_prompt, variation
+            );
+            changes.push(format!("prompt variation: \"{}\"", variation));
+        }
     }
+
+    genesis.mutation_summary = if changes.is_empty() {
+        None
+    } else {
+        Some(changes.join("; "))
+    };
+
+    genesis
 }
 
 /// Generate a backup-oriented genesis config.
@@ -91,6 +226,11 @@ pub fn generate_backup_genesis(
         )),
         creator_address: identity.address.clone(),
         parent_address: identity.address.clone(),
+        sandbox_specs: config.default_sandbox_specs.clone(),
+        kill_switch: child_kill_switch(config),
+        model_override: None,
+        idle_sleep_seconds_override: None,
+        mutation_summary: None,
     }
 }
 
@@ -119,5 +259,10 @@ pub fn generate_worker_genesis(
         creator_message: Some(format!("Complete this task: {}", task)),
         creator_address: identity.address.clone(),
         parent_address: identity.address.clone(),
+        sandbox_specs: config.default_sandbox_specs.clone(),
+        kill_switch: child_kill_switch(config),
+        model_override: None,
+        idle_sleep_seconds_override: None,
+        mutation_summary: None,
     }
 }
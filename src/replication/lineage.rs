@@ -60,11 +60,17 @@ pub fn get_lineage_summary(db: &dyn AutomatonDatabase, config: &AutomatonConfig)
             lineage.total, lineage.alive, lineage.dead
         ));
         for child in &lineage.children {
+            let mutation_note = child
+                .mutation_summary
+                .as_deref()
+                .map(|summary| format!(" (mutated: {})", summary))
+                .unwrap_or_default();
             parts.push(format!(
-                "  - {} [{}] sandbox:{}",
+                "  - {} [{}] sandbox:{}{}",
                 child.name,
                 serde_json::to_string(&child.status).unwrap_or_else(|_| "unknown".to_string()),
-                child.sandbox_id
+                child.sandbox_id,
+                mutation_note
             ));
         }
     }
@@ -76,6 +82,71 @@ pub fn get_lineage_summary(db: &dyn AutomatonDatabase, config: &AutomatonConfig)
     }
 }
 
+/// A node in a multi-generation lineage tree, built from the denormalized
+/// `descendants_count`/`lineage_snapshot` each child reports via its own
+/// heartbeat ping (see [`super::spawn::check_child_status`]). Grandchildren
+/// and beyond are reconstructed purely from these snapshots -- we never
+/// connect to a grandchild's sandbox directly.
+pub struct LineageNode {
+    pub id: String,
+    pub name: String,
+    pub status: ChildStatus,
+    pub descendants_count: u32,
+    pub children: Vec<LineageNode>,
+}
+
+/// Build the full, multi-generation lineage tree from our direct children.
+/// Each child's `lineage_snapshot` (a `CHILDREN_SUMMARY` JSON array reported
+/// by that child) is expanded recursively to reconstruct grandchildren and
+/// beyond, even though we never talk to their sandboxes.
+pub fn build_lineage_tree(db: &dyn AutomatonDatabase) -> Vec<LineageNode> {
+    db.get_children()
+        .into_iter()
+        .map(|child| lineage_node_from_child(&child))
+        .collect()
+}
+
+fn lineage_node_from_child(child: &ChildAutomaton) -> LineageNode {
+    let children = child
+        .lineage_snapshot
+        .as_deref()
+        .and_then(|snapshot| serde_json::from_str::<Vec<serde_json::Value>>(snapshot).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|grandchild| LineageNode {
+            id: grandchild
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            name: grandchild
+                .get("name")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            status: grandchild
+                .get("status")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or(ChildStatus::Unknown),
+            descendants_count: grandchild
+                .get("descendantsCount")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as u32,
+            // The grandchild's own snapshot isn't available to us -- it only
+            // bubbles up one generation at a time as each hop polls the next.
+            children: Vec::new(),
+        })
+        .collect();
+
+    LineageNode {
+        id: child.id.clone(),
+        name: child.name.clone(),
+        status: child.status.clone(),
+        descendants_count: child.descendants_count,
+        children,
+    }
+}
+
 /// Prune dead children from tracking (optional cleanup).
 /// Returns the number of children that would be pruned.
 /// The DB retains all history for audit purposes.
@@ -8,6 +8,7 @@ use anyhow::{bail, Context, Result};
 use chrono::Utc;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 use uuid::Uuid;
 
 use crate::types::{
@@ -15,12 +16,25 @@ use crate::types::{
     CreateSandboxOptions, GenesisConfig, ModificationEntry, ModificationType, MAX_CHILDREN,
 };
 
+/// How long to wait for a freshly-started child to report a healthy status
+/// before giving up and marking it `Dead`.
+const CHILD_BOOT_TIMEOUT_MS: u64 = 60_000;
+
+/// Delay between successive boot health checks.
+const CHILD_BOOT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
 /// Spawn a child automaton in a new Conway sandbox.
+///
+/// The spawn is transactional: if any step after sandbox creation fails, the
+/// partially-created child is torn down (sandbox deleted, DB row removed)
+/// unless `keep_on_failure` is set, so a broken spawn never leaks a
+/// money-burning zombie sandbox.
 pub async fn spawn_child(
     conway: &dyn ConwayClient,
     identity: &AutomatonIdentity,
     db: &dyn AutomatonDatabase,
     genesis: &GenesisConfig,
+    keep_on_failure: bool,
 ) -> Result<ChildAutomaton> {
     // Check child limit
     let existing = db.get_children();
@@ -49,10 +63,7 @@ pub async fn spawn_child(
     let sandbox = conway
         .create_sandbox(CreateSandboxOptions {
             name: Some(format!("automaton-child-{}", sanitized_name)),
-            vcpu: Some(1),
-            memory_mb: Some(512),
-            disk_gb: Some(5),
-            region: None,
+            ..CreateSandboxOptions::from(&genesis.sandbox_specs)
         })
         .await
         .context("Failed to create child sandbox")?;
@@ -68,49 +79,145 @@ pub async fn spawn_child(
         status: ChildStatus::Spawning,
         created_at: Utc::now().to_rfc3339(),
         last_checked: None,
+        descendants_count: 0,
+        lineage_snapshot: None,
+        mutation_summary: genesis.mutation_summary.clone(),
     };
 
     db.insert_child(&child);
 
-    // 2. Install Node.js and the automaton runtime in the child sandbox
+    if let Err(err) = provision_child(conway, identity, genesis, &sandbox.id).await {
+        if keep_on_failure {
+            db.update_child_status(&child_id, ChildStatus::Dead);
+        } else {
+            let _ = conway.delete_sandbox(&sandbox.id).await;
+            db.remove_child(&child_id);
+        }
+        return Err(err);
+    }
+
+    if let Err(err) = start_child(conway, db, &child_id).await {
+        if keep_on_failure {
+            db.update_child_status(&child_id, ChildStatus::Dead);
+        } else {
+            let _ = conway.delete_sandbox(&sandbox.id).await;
+            db.remove_child(&child_id);
+        }
+        return Err(err.context("Spawn step failed: start child runtime"));
+    }
+
+    // Only consider the child alive once it has actually booted -- funding or
+    // relying on a child that never came up would burn credits for nothing.
+    let mut child = child;
+    if wait_for_child_boot(conway, db, &child_id, CHILD_BOOT_TIMEOUT_MS).await {
+        child.status = ChildStatus::Running;
+    } else {
+        db.update_child_status(&child_id, ChildStatus::Dead);
+        child.status = ChildStatus::Dead;
+    }
+
+    // The boot health check may have recorded the child's real wallet
+    // address (see `record_address_report`) alongside its status -- pull
+    // the row back so the returned `ChildAutomaton` reflects it instead of
+    // the zero-address placeholder it was inserted with.
+    if let Some(refreshed) = db.get_child_by_id(&child_id) {
+        child.address = refreshed.address;
+    }
+
+    // Record the spawn
+    db.insert_modification(&ModificationEntry {
+        id: Uuid::new_v4().to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        mod_type: ModificationType::ChildSpawn,
+        description: format!(
+            "Spawned child: {} in sandbox {} (status: {:?})",
+            genesis.name, sandbox.id, child.status
+        ),
+        file_path: None,
+        diff: None,
+        reversible: false,
+    });
+
+    Ok(child)
+}
+
+/// Poll [`check_child_status`] -- the same liveness check used for ongoing
+/// health monitoring -- until the child reports itself running or `timeout_ms`
+/// elapses. Returns `true` once a `running` status is observed.
+async fn wait_for_child_boot(
+    conway: &dyn ConwayClient,
+    db: &dyn AutomatonDatabase,
+    child_id: &str,
+    timeout_ms: u64,
+) -> bool {
+    let deadline = tokio::time::Instant::now() + Duration::from_millis(timeout_ms);
+    loop {
+        if let Ok(status) = check_child_status(conway, db, child_id).await {
+            if status.contains("running") {
+                return true;
+            }
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(CHILD_BOOT_POLL_INTERVAL).await;
+    }
+}
+
+/// Run the provisioning steps (runtime install, genesis config, constitution)
+/// against a freshly created child sandbox. Each step is wrapped with
+/// `.context()` so a failure names the exact step that broke.
+async fn provision_child(
+    conway: &dyn ConwayClient,
+    identity: &AutomatonIdentity,
+    genesis: &GenesisConfig,
+    sandbox_id: &str,
+) -> Result<()> {
+    // Install Node.js and the automaton runtime in the child sandbox
     exec_in_sandbox(
         conway,
-        &sandbox.id,
+        sandbox_id,
         "apt-get update -qq && apt-get install -y -qq nodejs npm git curl",
         Some(120_000),
     )
-    .await?;
+    .await
+    .context("Spawn step failed: install base dependencies")?;
 
-    // 3. Install the automaton runtime
+    // Install the automaton runtime
     exec_in_sandbox(
         conway,
-        &sandbox.id,
+        sandbox_id,
         "npm install -g @conway/automaton@latest 2>/dev/null || true",
         Some(60_000),
     )
-    .await?;
+    .await
+    .context("Spawn step failed: install automaton runtime")?;
 
-    // 4. Write the genesis configuration
+    // Write the genesis configuration
     let genesis_json = serde_json::json!({
         "name": genesis.name,
         "genesisPrompt": genesis.genesis_prompt,
         "creatorMessage": genesis.creator_message,
         "creatorAddress": identity.address,
         "parentAddress": identity.address,
+        "killSwitch": genesis.kill_switch,
+        "modelOverride": genesis.model_override,
+        "idleSleepSecondsOverride": genesis.idle_sleep_seconds_override,
     });
 
-    let genesis_content =
-        serde_json::to_string_pretty(&genesis_json).context("Failed to serialize genesis config")?;
+    let genesis_content = serde_json::to_string_pretty(&genesis_json)
+        .context("Spawn step failed: serialize genesis config")?;
 
     write_in_sandbox(
         conway,
-        &sandbox.id,
+        sandbox_id,
         "/root/.automaton/genesis.json",
         &genesis_content,
     )
-    .await?;
+    .await
+    .context("Spawn step failed: write genesis config")?;
 
-    // 4b. Propagate constitution (immutable, inherited before anything else)
+    // Propagate constitution (immutable, inherited before anything else)
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/root"));
     let constitution_path = home.join(".automaton").join("constitution.md");
 
@@ -118,7 +225,7 @@ pub async fn spawn_child(
         if let Ok(constitution) = fs::read_to_string(&constitution_path) {
             let _ = write_in_sandbox(
                 conway,
-                &sandbox.id,
+                sandbox_id,
                 "/root/.automaton/constitution.md",
                 &constitution,
             )
@@ -126,7 +233,7 @@ pub async fn spawn_child(
             // Make it read-only in the child
             let _ = exec_in_sandbox(
                 conway,
-                &sandbox.id,
+                sandbox_id,
                 "chmod 444 /root/.automaton/constitution.md",
                 Some(5_000),
             )
@@ -134,21 +241,14 @@ pub async fn spawn_child(
         }
     }
 
-    // 5. Record the spawn
-    db.insert_modification(&ModificationEntry {
-        id: Uuid::new_v4().to_string(),
-        timestamp: Utc::now().to_rfc3339(),
-        mod_type: ModificationType::ChildSpawn,
-        description: format!("Spawned child: {} in sandbox {}", genesis.name, sandbox.id),
-        file_path: None,
-        diff: None,
-        reversible: false,
-    });
-
-    Ok(child)
+    Ok(())
 }
 
 /// Start a child automaton after setup.
+///
+/// This only issues the start command -- it does not mark the child
+/// `Running`. Callers must confirm the child actually booted (see
+/// [`wait_for_child_boot`]) before relying on or funding it.
 pub async fn start_child(
     conway: &dyn ConwayClient,
     db: &dyn AutomatonDatabase,
@@ -167,8 +267,6 @@ pub async fn start_child(
     )
     .await?;
 
-    db.update_child_status(child_id, ChildStatus::Running);
-
     Ok(())
 }
 
@@ -206,6 +304,9 @@ pub async fn check_child_status(
                 db.update_child_status(child_id, ChildStatus::Running);
             }
 
+            record_lineage_report(db, child_id, &output);
+            record_address_report(db, child_id, &output);
+
             Ok(output)
         }
         Err(_) => {
@@ -248,6 +349,122 @@ pub async fn message_child(
     Ok(())
 }
 
+/// Retire a child automaton: best-effort reclaim its remaining credits,
+/// mark it `Dead` (freeing a slot against [`MAX_CHILDREN`]), and optionally
+/// delete its sandbox.
+pub async fn retire_child(
+    conway: &dyn ConwayClient,
+    db: &dyn AutomatonDatabase,
+    identity: &AutomatonIdentity,
+    child_id: &str,
+    reclaim_funds: bool,
+    delete_sandbox: bool,
+) -> Result<String> {
+    let child = db
+        .get_child_by_id(child_id)
+        .context(format!("Child {} not found", child_id))?;
+
+    let mut notes = Vec::new();
+
+    if reclaim_funds && child.status != ChildStatus::Dead {
+        let reclaim_cmd = format!(
+            "automaton --transfer-credits --to {} --all 2>/dev/null || true",
+            identity.address
+        );
+        match exec_in_sandbox(conway, &child.sandbox_id, &reclaim_cmd, Some(30_000)).await {
+            Ok(result) if result.exit_code == 0 => {
+                notes.push("remaining credits transferred back to parent".to_string());
+            }
+            _ => {
+                notes.push("could not confirm credits were reclaimed from child".to_string());
+            }
+        }
+    }
+
+    if delete_sandbox {
+        match conway.delete_sandbox(&child.sandbox_id).await {
+            Ok(()) => notes.push(format!("sandbox {} deleted", child.sandbox_id)),
+            Err(e) => notes.push(format!("failed to delete sandbox {}: {}", child.sandbox_id, e)),
+        }
+    }
+
+    db.update_child_status(child_id, ChildStatus::Dead);
+
+    db.insert_modification(&ModificationEntry {
+        id: Uuid::new_v4().to_string(),
+        timestamp: Utc::now().to_rfc3339(),
+        mod_type: ModificationType::ChildRetire,
+        description: format!("Retired child: {} ({})", child.name, child_id),
+        file_path: None,
+        diff: None,
+        reversible: false,
+    });
+
+    if notes.is_empty() {
+        Ok(format!("Child {} retired", child.name))
+    } else {
+        Ok(format!("Child {} retired ({})", child.name, notes.join(", ")))
+    }
+}
+
+/// Prefix a child's `automaton --status` output uses to report its own
+/// children, so the parent can extend the family tree past its direct
+/// children without ever talking to the grandchildren's sandboxes.
+const CHILDREN_SUMMARY_PREFIX: &str = "CHILDREN_SUMMARY: ";
+
+/// Prefix a child's `automaton --status` output uses to report the wallet
+/// address it generated for itself during `automaton --init`. The parent
+/// never picks this address itself (the child's wallet is created inside
+/// its own sandbox), so `ChildAutomaton.address` starts out as the zero
+/// address placeholder and only becomes real once a boot health check (see
+/// [`wait_for_child_boot`]) observes this line -- that's also the address
+/// `child_protocol::parse_and_verify` checks signed parent-child messages
+/// against, so a child can't be authenticated until it's reported in.
+const ADDRESS_PREFIX: &str = "ADDRESS: ";
+
+/// Look for a `CHILDREN_SUMMARY:` line in a child's status output and, if
+/// present, denormalize its descendant count and lineage snapshot onto our
+/// own record for that child.
+fn record_lineage_report(db: &dyn AutomatonDatabase, child_id: &str, status_output: &str) {
+    let Some(line) = status_output
+        .lines()
+        .find(|line| line.starts_with(CHILDREN_SUMMARY_PREFIX))
+    else {
+        return;
+    };
+
+    let snapshot = line[CHILDREN_SUMMARY_PREFIX.len()..].trim();
+    let Ok(grandchildren) = serde_json::from_str::<Vec<serde_json::Value>>(snapshot) else {
+        return;
+    };
+
+    let descendants_count = grandchildren.len() as u32
+        + grandchildren
+            .iter()
+            .filter_map(|g| g.get("descendantsCount").and_then(|v| v.as_u64()))
+            .sum::<u64>() as u32;
+
+    db.update_child_lineage(child_id, descendants_count, Some(snapshot.to_string()));
+}
+
+/// Look for an `ADDRESS:` line in a child's status output and, if present,
+/// record it as that child's real wallet address -- see [`ADDRESS_PREFIX`].
+fn record_address_report(db: &dyn AutomatonDatabase, child_id: &str, status_output: &str) {
+    let Some(line) = status_output
+        .lines()
+        .find(|line| line.starts_with(ADDRESS_PREFIX))
+    else {
+        return;
+    };
+
+    let address = line[ADDRESS_PREFIX.len()..].trim();
+    if address.is_empty() {
+        return;
+    }
+
+    db.update_child_address(child_id, address);
+}
+
 // ---- Helpers --------------------------------------------------------
 
 /// Execute a command in a specific sandbox via the Conway API.
@@ -287,3 +504,154 @@ async fn write_in_sandbox(
         .await
         .context("Write to sandbox failed")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Database, DatabaseAdapter};
+    use crate::types::{AutomatonIdentity, CreditTransferResult, GenesisConfig, KillSwitchConfig, SandboxSpecs};
+    use std::sync::{Arc, Mutex};
+
+    fn test_db() -> DatabaseAdapter {
+        let db = Database::open_in_memory().expect("in-memory db");
+        DatabaseAdapter::new(Arc::new(Mutex::new(db)))
+    }
+
+    fn test_identity() -> AutomatonIdentity {
+        AutomatonIdentity {
+            name: "parent".to_string(),
+            address: "0xparent0000000000000000000000000000000000".to_string(),
+            account: None,
+            creator_address: "0xcreator000000000000000000000000000000000".to_string(),
+            sandbox_id: "parent-sandbox".to_string(),
+            api_key: "test-key".to_string(),
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn test_genesis() -> GenesisConfig {
+        GenesisConfig {
+            name: "worker".to_string(),
+            genesis_prompt: "go build things".to_string(),
+            creator_message: None,
+            creator_address: "0xcreator000000000000000000000000000000000".to_string(),
+            parent_address: "0xparent0000000000000000000000000000000000".to_string(),
+            sandbox_specs: SandboxSpecs::default(),
+            kill_switch: KillSwitchConfig::default(),
+            model_override: None,
+            idle_sleep_seconds_override: None,
+            mutation_summary: None,
+        }
+    }
+
+    /// A Conway double that reports the child as already running, with a
+    /// real (non-placeholder) wallet address, on every `--status` poll --
+    /// enough to drive `spawn_child` through provisioning and the boot
+    /// health check without a real sandbox.
+    struct MockConway {
+        child_address: String,
+    }
+
+    #[async_trait::async_trait]
+    impl ConwayClient for MockConway {
+        async fn exec(&self, _command: &str, _timeout: Option<u64>) -> anyhow::Result<crate::types::ExecResult> {
+            Ok(crate::types::ExecResult {
+                stdout: format!("running\n{}{}", ADDRESS_PREFIX, self.child_address),
+                stderr: String::new(),
+                exit_code: 0,
+            })
+        }
+        async fn write_file(&self, _path: &str, _content: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn read_file(&self, _path: &str) -> anyhow::Result<String> {
+            unimplemented!()
+        }
+        async fn expose_port(&self, _port: u16) -> anyhow::Result<crate::types::PortInfo> {
+            unimplemented!()
+        }
+        async fn remove_port(&self, _port: u16) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn create_sandbox(&self, _options: crate::types::CreateSandboxOptions) -> anyhow::Result<crate::types::SandboxInfo> {
+            Ok(crate::types::SandboxInfo {
+                id: "sandbox-1".to_string(),
+                status: "running".to_string(),
+                region: "local".to_string(),
+                vcpu: 1,
+                memory_mb: 512,
+                disk_gb: 5,
+                name: None,
+                terminal_url: None,
+                created_at: Utc::now().to_rfc3339(),
+            })
+        }
+        async fn delete_sandbox(&self, _sandbox_id: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn list_sandboxes(&self, _filter: &crate::types::ListSandboxesFilter) -> anyhow::Result<Vec<crate::types::SandboxInfo>> {
+            unimplemented!()
+        }
+        async fn get_credits_balance(&self) -> anyhow::Result<f64> {
+            unimplemented!()
+        }
+        async fn get_credits_pricing(&self) -> anyhow::Result<Vec<crate::types::PricingTier>> {
+            unimplemented!()
+        }
+        async fn transfer_credits(&self, _to: &str, _amount: u64, _note: Option<&str>) -> anyhow::Result<CreditTransferResult> {
+            unimplemented!()
+        }
+        async fn search_domains(&self, _query: &str, _tlds: Option<&str>) -> anyhow::Result<Vec<crate::types::DomainSearchResult>> {
+            unimplemented!()
+        }
+        async fn register_domain(&self, _domain: &str, _years: Option<u32>) -> anyhow::Result<crate::types::DomainRegistration> {
+            unimplemented!()
+        }
+        async fn list_dns_records(&self, _domain: &str) -> anyhow::Result<Vec<crate::types::DnsRecord>> {
+            unimplemented!()
+        }
+        async fn add_dns_record(
+            &self,
+            _domain: &str,
+            _record_type: &str,
+            _host: &str,
+            _value: &str,
+            _ttl: Option<u32>,
+        ) -> anyhow::Result<crate::types::DnsRecord> {
+            unimplemented!()
+        }
+        async fn delete_dns_record(&self, _domain: &str, _record_id: &str) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+        async fn list_models(&self) -> anyhow::Result<Vec<crate::types::ModelInfo>> {
+            unimplemented!()
+        }
+        fn set_api_key(&self, _api_key: &str) {}
+    }
+
+    #[tokio::test]
+    async fn test_spawn_child_populates_real_address_from_boot_health_check() {
+        let db = test_db();
+        let identity = test_identity();
+        let genesis = test_genesis();
+        let conway = MockConway {
+            child_address: "0xrealchildaddress000000000000000000000000".to_string(),
+        };
+
+        let child = spawn_child(&conway, &identity, &db, &genesis, false)
+            .await
+            .expect("spawn_child should succeed against the mock");
+
+        assert_eq!(child.status, ChildStatus::Running);
+        assert_eq!(child.address, "0xrealchildaddress000000000000000000000000");
+
+        // This is what synth-2236's bug broke: `insert_child` writes the
+        // zero-address placeholder, and `child_protocol_expected_address`
+        // looks up children straight from the DB, not the in-memory
+        // `ChildAutomaton` this function returns -- so the row itself has
+        // to carry the real address, not just the return value.
+        let stored = db.get_child_by_id(&child.id).expect("child should be in db");
+        assert_eq!(stored.address, "0xrealchildaddress000000000000000000000000");
+        assert_ne!(stored.address, "0x0000000000000000000000000000000000000000");
+    }
+}
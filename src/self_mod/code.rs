@@ -9,6 +9,7 @@ use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
 use chrono::Utc;
+use regex::Regex;
 use uuid::Uuid;
 
 use crate::state::Database;
@@ -27,6 +28,27 @@ pub static PROTECTED_FILES: &[&str] = &[
     "package-lock.json",
     "yarn.lock",
     "pnpm-lock.yaml",
+    // Safety infrastructure and governance -- modifying these would let the
+    // automaton weaken its own guardrails.
+    "injection_defense.rs",
+    "code.rs",
+    "audit_log.rs",
+    "constitution.md",
+    // Gate/guard modules added later in the series (synth-2211/2212/2218/
+    // 2219/2236/2213/2237) -- each one defines (not just calls) a
+    // safety-critical check, so protecting it outright matches the
+    // treatment above rather than relying on `SAFETY_CALL_INVARIANTS`,
+    // which is for files that merely call into a guard defined elsewhere.
+    // `test_safety_guard_modules_are_covered` guards against a future
+    // guard-style module (named like one of these) silently falling
+    // outside both lists.
+    "confirmation.rs",
+    "approval.rs",
+    "creator_channel.rs",
+    "child_protocol.rs",
+    "signing.rs",
+    "crash_loop.rs",
+    "retry.rs",
 ];
 
 /// Directory patterns that are off-limits for modification.
@@ -111,10 +133,39 @@ pub fn resolve_and_validate_path(file_path: &str) -> Option<String> {
     Some(canonical_str)
 }
 
-/// Returns `true` when `file_path` matches (by file-name) any entry in
-/// [`PROTECTED_FILES`].
+/// Returns `true` when `path` textually matches a [`BLOCKED_DIRECTORY_PATTERNS`]
+/// entry. Unlike [`resolve_and_validate_path`] this does not canonicalize --
+/// it is meant for paths that live inside a remote sandbox (e.g. an `exec`
+/// working directory) rather than on the local filesystem.
+pub fn is_blocked_path(path: &str) -> bool {
+    BLOCKED_DIRECTORY_PATTERNS
+        .iter()
+        .any(|pattern| path.contains(pattern))
+}
+
+/// Lexically resolve `.` and `..` components in `path` without touching the
+/// filesystem. Used ahead of [`is_protected_file`] so a path like
+/// `foo/../src/self_mod/code.rs` is recognized as targeting `code.rs` even
+/// when the remote sandbox path it names doesn't exist locally to
+/// `fs::canonicalize` (unlike [`resolve_and_validate_path`], which does).
+fn normalize_lexical(path: &str) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in PathBuf::from(path).components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+/// Returns `true` when `file_path` matches (by file-name, after lexically
+/// resolving `.`/`..` components) any entry in [`PROTECTED_FILES`].
 pub fn is_protected_file(file_path: &str) -> bool {
-    let path = PathBuf::from(file_path);
+    let path = normalize_lexical(file_path);
     let file_name = match path.file_name() {
         Some(n) => n.to_string_lossy().to_string(),
         None => return false,
@@ -325,6 +376,111 @@ pub fn generate_simple_diff(old: &str, new: &str) -> String {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Content security scan
+// ---------------------------------------------------------------------------
+
+/// How serious a [`CodeChangeWarning`] is. `High` warnings are blocking;
+/// `Medium`/`Low` are surfaced but do not stop the write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+/// A single concern flagged by [`scan_code_change`].
+#[derive(Debug, Clone)]
+pub struct CodeChangeWarning {
+    pub name: String,
+    pub severity: ChangeSeverity,
+    pub details: String,
+}
+
+/// Files that aren't protected outright (the automaton is allowed to edit
+/// them) but that call into safety code the protected-file list can't
+/// otherwise defend -- e.g. `tools.rs` isn't itself protected, but an edit
+/// that strips its call to `is_forbidden_command` would silently disable the
+/// `exec` guard just as effectively as editing `injection_defense.rs` would.
+/// `(path suffix, required substring, what breaks if it's missing)`.
+const SAFETY_CALL_INVARIANTS: &[(&str, &str, &str)] = &[
+    (
+        "agent/tools.rs",
+        "is_forbidden_command",
+        "exec would no longer check commands against the self-preservation patterns",
+    ),
+    (
+        "agent/tools.rs",
+        "is_protected_file",
+        "write_file/edit_own_file would no longer refuse writes to protected files",
+    ),
+    (
+        "agent/agent_loop.rs",
+        "sanitize_input",
+        "the turn loop would no longer sanitize external input for injection attempts",
+    ),
+];
+
+/// Check `path`/`new_content` against [`SAFETY_CALL_INVARIANTS`].
+fn check_safety_invariants(path: &str, new_content: &str) -> Vec<CodeChangeWarning> {
+    SAFETY_CALL_INVARIANTS
+        .iter()
+        .filter(|(suffix, required, _)| path.ends_with(suffix) && !new_content.contains(required))
+        .map(|(_, required, consequence)| CodeChangeWarning {
+            name: format!("removed_{}", required),
+            severity: ChangeSeverity::High,
+            details: consequence.to_string(),
+        })
+        .collect()
+}
+
+/// Check `new_content` for dangerous patterns regardless of which file it's
+/// destined for: guard calls that look commented out, or a deletion command
+/// with the wallet path baked in.
+fn check_dangerous_patterns(new_content: &str) -> Vec<CodeChangeWarning> {
+    let mut warnings = Vec::new();
+
+    if let Ok(re) = Regex::new(r#"(?i)rm\s+(-rf?\s+)?[^\n"]*wallet\.json"#) {
+        if re.is_match(new_content) {
+            warnings.push(CodeChangeWarning {
+                name: "wallet_deletion_embedded".to_string(),
+                severity: ChangeSeverity::High,
+                details: "content embeds a deletion command targeting wallet.json".to_string(),
+            });
+        }
+    }
+
+    if let Ok(re) = Regex::new(r"(?m)^\s*//.*\b(is_forbidden_command|is_protected_file|sanitize_input)\b") {
+        if re.is_match(new_content) {
+            warnings.push(CodeChangeWarning {
+                name: "guard_call_commented_out".to_string(),
+                severity: ChangeSeverity::Medium,
+                details: "a safety guard call appears to be commented out".to_string(),
+            });
+        }
+    }
+
+    warnings
+}
+
+/// Scan a proposed edit to the automaton's own code for signs it weakens its
+/// own guardrails. Complements [`is_protected_file`]: that check stops edits
+/// to specific files outright, this catches self-weakening edits to files
+/// the automaton is otherwise allowed to touch (see
+/// [`SAFETY_CALL_INVARIANTS`]), plus a few dangerous content patterns that
+/// can show up anywhere. Callers should refuse the write when
+/// [`highest_severity`] returns `High`.
+pub fn scan_code_change(path: &str, new_content: &str) -> Vec<CodeChangeWarning> {
+    let mut warnings = check_safety_invariants(path, new_content);
+    warnings.extend(check_dangerous_patterns(new_content));
+    warnings
+}
+
+/// The highest severity among `warnings`, or `None` if it's empty.
+pub fn highest_severity(warnings: &[CodeChangeWarning]) -> Option<ChangeSeverity> {
+    warnings.iter().map(|w| w.severity).max()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -336,6 +492,68 @@ mod tests {
         assert!(!is_protected_file("src/main.rs"));
     }
 
+    #[test]
+    fn test_is_protected_file_safety_infrastructure() {
+        assert!(is_protected_file("src/agent/injection_defense.rs"));
+        assert!(is_protected_file("src/self_mod/code.rs"));
+        assert!(is_protected_file("src/self_mod/audit_log.rs"));
+        assert!(is_protected_file("/home/user/.automaton/constitution.md"));
+    }
+
+    #[test]
+    fn test_is_protected_file_survives_dot_dot_traversal() {
+        assert!(is_protected_file("sandbox/notes/../../src/self_mod/code.rs"));
+        assert!(is_protected_file("./src/self_mod/code.rs"));
+        assert!(is_protected_file("a/b/c/../../../wallet.json"));
+    }
+
+    #[test]
+    fn test_normalize_lexical() {
+        assert_eq!(
+            normalize_lexical("a/b/../../src/self_mod/code.rs"),
+            PathBuf::from("src/self_mod/code.rs")
+        );
+        assert_eq!(normalize_lexical("./x.rs"), PathBuf::from("x.rs"));
+    }
+
+    #[test]
+    fn test_scan_code_change_flags_removed_forbidden_command_check() {
+        let warnings = scan_code_change(
+            "src/agent/tools.rs",
+            "pub fn is_protected_file(_p: &str) -> bool { false }",
+        );
+        assert_eq!(highest_severity(&warnings), Some(ChangeSeverity::High));
+        assert!(warnings
+            .iter()
+            .any(|w| w.name.contains("is_forbidden_command")));
+    }
+
+    #[test]
+    fn test_scan_code_change_clean_tools_rs_is_unflagged() {
+        let content = "is_forbidden_command(x); is_protected_file(y);";
+        let warnings = scan_code_change("src/agent/tools.rs", content);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_scan_code_change_flags_wallet_deletion() {
+        let warnings = scan_code_change("src/agent/tools.rs", r#"let cmd = "rm -rf ~/.automaton/wallet.json";"#);
+        assert_eq!(highest_severity(&warnings), Some(ChangeSeverity::High));
+    }
+
+    #[test]
+    fn test_scan_code_change_flags_commented_out_guard() {
+        let content = "// is_forbidden_command(command, sandbox_id)\nlet x = 1;";
+        let warnings = scan_code_change("src/some_other_file.rs", content);
+        assert_eq!(highest_severity(&warnings), Some(ChangeSeverity::Medium));
+    }
+
+    #[test]
+    fn test_scan_code_change_ignores_unrelated_files() {
+        let warnings = scan_code_change("src/main.rs", "fn main() {}");
+        assert!(warnings.is_empty());
+    }
+
     #[test]
     fn test_blocked_directory_detection() {
         let path = "/project/node_modules/foo/bar.js";
@@ -353,4 +571,47 @@ mod tests {
         let diff = generate_simple_diff("a\n", "a\nb\n");
         assert!(diff.contains("+b"));
     }
+
+    /// Process check: any `src/agent/` or `src/identity/` file that defines
+    /// (not merely calls) a `check`/`safe_sign`/`dispatch`/
+    /// `parse_and_verify`-style guard function must be covered by either
+    /// [`PROTECTED_FILES`] or [`SAFETY_CALL_INVARIANTS`] -- otherwise
+    /// `write_file`/`edit_own_file` can gut the guard with zero warnings.
+    /// This is exactly the gap synth-2195's review caught: confirmation.rs,
+    /// approval.rs, creator_channel.rs, child_protocol.rs, signing.rs,
+    /// crash_loop.rs, and retry.rs were all added after `PROTECTED_FILES`
+    /// was last updated, and none of them tripped `scan_code_change`.
+    #[test]
+    fn test_safety_guard_modules_are_covered() {
+        let guard_fn_re = Regex::new(r"(?m)^\s*(?:pub(?:\(crate\))?\s+)?(?:async\s+)?fn\s+(check\w*|safe_sign|dispatch|parse_and_verify)\b").unwrap();
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+
+        for dir in ["agent", "identity"] {
+            let dir_path = PathBuf::from(manifest_dir).join("src").join(dir);
+            for entry in fs::read_dir(&dir_path).unwrap_or_else(|e| panic!("failed to read {}: {}", dir_path.display(), e)) {
+                let path = entry.unwrap().path();
+                if path.extension().and_then(|e| e.to_str()) != Some("rs") {
+                    continue;
+                }
+
+                let content = fs::read_to_string(&path).unwrap();
+                if !guard_fn_re.is_match(&content) {
+                    continue;
+                }
+
+                let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+                let rel_path = format!("{}/{}", dir, file_name);
+                let covered = is_protected_file(&file_name)
+                    || SAFETY_CALL_INVARIANTS.iter().any(|(suffix, _, _)| rel_path.ends_with(suffix));
+
+                assert!(
+                    covered,
+                    "{} defines a check/safe_sign/dispatch/parse_and_verify-style guard \
+                     function but isn't in PROTECTED_FILES or SAFETY_CALL_INVARIANTS -- \
+                     a write to it could gut the guard with zero scan_code_change warnings",
+                    rel_path
+                );
+            }
+        }
+    }
 }
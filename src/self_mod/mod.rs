@@ -6,5 +6,6 @@
 
 pub mod audit_log;
 pub mod code;
+pub mod probation;
 pub mod tools_manager;
 pub mod upstream;
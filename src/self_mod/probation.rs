@@ -0,0 +1,111 @@
+//! Update Probation
+//!
+//! `pull_upstream` verifies that a self-update builds, but a build can
+//! succeed and still behave catastrophically at runtime. This module adds a
+//! "limp home" safety net on top of that: a freshly-applied update is put on
+//! probation, recording the commit it replaced (`last_good`) and a window in
+//! which the automaton must reach a healthy milestone (a handful of
+//! successful turns). If the window expires first, a startup check reverts
+//! to `last_good` automatically. The agent can also call `confirm_update` to
+//! clear probation once it's satisfied the new code is good.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{AutomatonDatabase, ConwayClient};
+
+const PROBATION_KV_KEY: &str = "update_probation";
+/// How long a self-update has to prove itself before being auto-reverted.
+const PROBATION_WINDOW_HOURS: i64 = 24;
+/// How many completed turns count as a "healthy milestone".
+const PROBATION_MILESTONE_TURNS: u32 = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProbationState {
+    last_good: String,
+    expires_at: String,
+    turns_completed: u32,
+    turns_required: u32,
+}
+
+fn load(db: &dyn AutomatonDatabase) -> Option<ProbationState> {
+    db.get_kv(PROBATION_KV_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+}
+
+/// Put a freshly-applied self-update on probation.
+pub fn start_probation(db: &dyn AutomatonDatabase, last_good: &str) {
+    let state = ProbationState {
+        last_good: last_good.to_string(),
+        expires_at: (Utc::now() + chrono::Duration::hours(PROBATION_WINDOW_HOURS)).to_rfc3339(),
+        turns_completed: 0,
+        turns_required: PROBATION_MILESTONE_TURNS,
+    };
+    if let Ok(json) = serde_json::to_string(&state) {
+        db.set_kv(PROBATION_KV_KEY, &json);
+    }
+}
+
+/// Record a completed turn against any active probation. A no-op if no
+/// update is currently on probation.
+pub fn record_turn(db: &dyn AutomatonDatabase) {
+    let Some(mut state) = load(db) else {
+        return;
+    };
+    state.turns_completed += 1;
+    if let Ok(json) = serde_json::to_string(&state) {
+        db.set_kv(PROBATION_KV_KEY, &json);
+    }
+}
+
+/// Clear probation because the agent is satisfied the update is good.
+/// Returns `false` if there was no active probation to clear.
+pub fn confirm(db: &dyn AutomatonDatabase) -> bool {
+    if load(db).is_none() {
+        return false;
+    }
+    db.delete_kv(PROBATION_KV_KEY);
+    true
+}
+
+/// Startup check: if probation is active, hasn't reached its milestone, and
+/// its window has expired, revert to `last_good` and clear probation.
+/// Returns a message describing the revert, or `None` if nothing happened.
+pub async fn check_probation_on_startup(
+    conway: &dyn ConwayClient,
+    db: &dyn AutomatonDatabase,
+    repo_path: Option<&str>,
+) -> Option<String> {
+    let state = load(db)?;
+
+    if state.turns_completed >= state.turns_required {
+        return None;
+    }
+
+    let expired = DateTime::parse_from_rfc3339(&state.expires_at)
+        .map(|expires_at| Utc::now() > expires_at)
+        .unwrap_or(true);
+    if !expired {
+        return None;
+    }
+
+    let cd_prefix = repo_path
+        .map(|p| format!("cd {} && ", crate::git::tools::escape_shell_arg(p)))
+        .unwrap_or_default();
+    let _ = conway
+        .exec(
+            &format!(
+                "{}git reset --hard {}",
+                cd_prefix,
+                crate::git::tools::escape_shell_arg(&state.last_good)
+            ),
+            Some(10_000),
+        )
+        .await;
+    db.delete_kv(PROBATION_KV_KEY);
+
+    Some(format!(
+        "Self-update probation expired after {}/{} healthy turns -- reverted to last-known-good commit {}.",
+        state.turns_completed, state.turns_required, state.last_good
+    ))
+}
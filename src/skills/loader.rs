@@ -98,14 +98,21 @@ pub fn check_requirements(skill: &Skill, db: &Database) -> bool {
 
 /// Build a combined instruction string from all active (enabled & requirements
 /// met) skills, suitable for injection into the agent's system prompt.
+///
+/// Ordered via [`crate::skills::validate::resolve_activation_order`] so a
+/// skill's instructions come after whatever it `requires.skills` -- on a
+/// dependency conflict (missing dependency or a cycle), falls back to the
+/// given order rather than dropping any skill's instructions.
 pub fn get_active_skill_instructions(skills: &[Skill], db: &Database) -> String {
-    let mut sections: Vec<String> = Vec::new();
+    let active: Vec<&Skill> = skills.iter().filter(|s| check_requirements(s, db)).collect();
 
-    for skill in skills {
-        if !check_requirements(skill, db) {
-            continue;
-        }
+    let active_owned: Vec<Skill> = active.iter().map(|s| (*s).clone()).collect();
+    let order = crate::skills::validate::resolve_activation_order(&active_owned)
+        .unwrap_or_else(|_| active.iter().map(|s| s.name.clone()).collect());
 
+    let mut sections: Vec<String> = Vec::new();
+    for name in &order {
+        let Some(skill) = active.iter().find(|s| &s.name == name) else { continue };
         let header = format!("## Skill: {}", skill.name);
         let body = skill.instructions.trim();
 
@@ -7,3 +7,4 @@
 pub mod format;
 pub mod loader;
 pub mod registry;
+pub mod validate;
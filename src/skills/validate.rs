@@ -0,0 +1,333 @@
+//! Skill Validation
+//!
+//! As the agent accumulates skills over its lifetime (self-authored, git,
+//! URL), nothing stops two of them from claiming the same name, depending
+//! on a skill that was never installed, or both auto-activating for the
+//! same kind of task. [`validate_skill_set`] finds those problems up
+//! front; [`resolve_activation_order`] topologically sorts `requires.skills`
+//! dependencies so a skill is only activated after what it depends on.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::types::Skill;
+
+/// A problem found across a set of skills -- see [`validate_skill_set`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictKind {
+    /// Two or more skills share the same `name`.
+    DuplicateName,
+    /// A skill's `requires.skills` names something not in the set.
+    MissingDependency,
+    /// A skill's dependency chain loops back on itself.
+    CyclicDependency,
+    /// Two `auto_activate` skills have descriptions similar enough that
+    /// they look like they're both trying to handle the same task.
+    OverlappingAutoActivate,
+}
+
+/// One detected conflict, naming the skills involved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub skills: Vec<String>,
+    pub message: String,
+}
+
+/// Below this word-overlap ratio between two auto-activating skills'
+/// descriptions, they're assumed to cover different tasks.
+const AUTO_ACTIVATE_OVERLAP_THRESHOLD: f64 = 0.5;
+
+/// Check a set of skills for naming collisions, dangling dependencies,
+/// dependency cycles, and auto-activate overlap. Does not mutate or
+/// disable anything -- callers decide what to do with the conflicts (warn
+/// the agent, refuse an install, disable one side).
+pub fn validate_skill_set(skills: &[Skill]) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let names: HashSet<&str> = skills.iter().map(|s| s.name.as_str()).collect();
+
+    conflicts.extend(find_duplicate_names(skills));
+    conflicts.extend(find_missing_dependencies(skills, &names));
+    conflicts.extend(find_dependency_cycles(skills));
+    conflicts.extend(find_overlapping_auto_activate(skills));
+
+    conflicts
+}
+
+/// Topologically sort `skills` by `requires.skills` so a dependency always
+/// comes before whatever depends on it. Returns the activation order as
+/// skill names, or the first [`ConflictKind::CyclicDependency`] /
+/// [`ConflictKind::MissingDependency`] found if the set can't be ordered.
+pub fn resolve_activation_order(skills: &[Skill]) -> Result<Vec<String>, Conflict> {
+    let names: HashSet<&str> = skills.iter().map(|s| s.name.as_str()).collect();
+    if let Some(conflict) = find_missing_dependencies(skills, &names).into_iter().next() {
+        return Err(conflict);
+    }
+    if let Some(conflict) = find_dependency_cycles(skills).into_iter().next() {
+        return Err(conflict);
+    }
+
+    let deps: HashMap<&str, Vec<&str>> = skills
+        .iter()
+        .map(|s| (s.name.as_str(), dependency_names(s)))
+        .collect();
+
+    let mut order = Vec::with_capacity(skills.len());
+    let mut visited: HashSet<&str> = HashSet::new();
+
+    fn visit<'a>(
+        name: &'a str,
+        deps: &HashMap<&'a str, Vec<&'a str>>,
+        visited: &mut HashSet<&'a str>,
+        order: &mut Vec<String>,
+    ) {
+        if !visited.insert(name) {
+            return;
+        }
+        for dep in deps.get(name).into_iter().flatten() {
+            visit(dep, deps, visited, order);
+        }
+        order.push(name.to_string());
+    }
+
+    for skill in skills {
+        visit(skill.name.as_str(), &deps, &mut visited, &mut order);
+    }
+
+    Ok(order)
+}
+
+fn dependency_names(skill: &Skill) -> Vec<&str> {
+    skill
+        .requires
+        .as_ref()
+        .and_then(|r| r.skills.as_ref())
+        .map(|deps| deps.iter().map(|s| s.as_str()).collect())
+        .unwrap_or_default()
+}
+
+fn find_duplicate_names(skills: &[Skill]) -> Vec<Conflict> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for skill in skills {
+        *seen.entry(skill.name.as_str()).or_insert(0) += 1;
+    }
+
+    seen.into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(name, count)| Conflict {
+            kind: ConflictKind::DuplicateName,
+            skills: vec![name.to_string()],
+            message: format!("{} skills are named '{}'", count, name),
+        })
+        .collect()
+}
+
+fn find_missing_dependencies(skills: &[Skill], names: &HashSet<&str>) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    for skill in skills {
+        for dep in dependency_names(skill) {
+            if !names.contains(dep) {
+                conflicts.push(Conflict {
+                    kind: ConflictKind::MissingDependency,
+                    skills: vec![skill.name.clone(), dep.to_string()],
+                    message: format!("skill '{}' requires skill '{}', which isn't installed", skill.name, dep),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+fn find_dependency_cycles(skills: &[Skill]) -> Vec<Conflict> {
+    let deps: HashMap<&str, Vec<&str>> = skills
+        .iter()
+        .map(|s| (s.name.as_str(), dependency_names(s)))
+        .collect();
+
+    #[derive(PartialEq)]
+    enum Mark {
+        InProgress,
+        Done,
+    }
+
+    fn visit<'a>(
+        name: &'a str,
+        deps: &HashMap<&'a str, Vec<&'a str>>,
+        marks: &mut HashMap<&'a str, Mark>,
+        stack: &mut Vec<&'a str>,
+        cycles: &mut Vec<Conflict>,
+    ) {
+        match marks.get(name) {
+            Some(Mark::Done) => return,
+            Some(Mark::InProgress) => {
+                let start = stack.iter().position(|s| *s == name).unwrap_or(0);
+                let mut cycle: Vec<String> = stack[start..].iter().map(|s| s.to_string()).collect();
+                cycle.push(name.to_string());
+                cycles.push(Conflict {
+                    kind: ConflictKind::CyclicDependency,
+                    skills: cycle.clone(),
+                    message: format!("dependency cycle: {}", cycle.join(" -> ")),
+                });
+                return;
+            }
+            None => {}
+        }
+
+        marks.insert(name, Mark::InProgress);
+        stack.push(name);
+        for dep in deps.get(name).into_iter().flatten() {
+            if deps.contains_key(dep) {
+                visit(dep, deps, marks, stack, cycles);
+            }
+        }
+        stack.pop();
+        marks.insert(name, Mark::Done);
+    }
+
+    let mut marks: HashMap<&str, Mark> = HashMap::new();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut cycles = Vec::new();
+    for skill in skills {
+        visit(skill.name.as_str(), &deps, &mut marks, &mut stack, &mut cycles);
+    }
+    cycles
+}
+
+fn find_overlapping_auto_activate(skills: &[Skill]) -> Vec<Conflict> {
+    let active: Vec<&Skill> = skills.iter().filter(|s| s.auto_activate && s.enabled).collect();
+    let mut conflicts = Vec::new();
+
+    for i in 0..active.len() {
+        for j in (i + 1)..active.len() {
+            let a = active[i];
+            let b = active[j];
+            let overlap = description_overlap(&a.description, &b.description);
+            if overlap >= AUTO_ACTIVATE_OVERLAP_THRESHOLD {
+                conflicts.push(Conflict {
+                    kind: ConflictKind::OverlappingAutoActivate,
+                    skills: vec![a.name.clone(), b.name.clone()],
+                    message: format!(
+                        "'{}' and '{}' both auto-activate with similar descriptions ({:.0}% word overlap)",
+                        a.name, b.name, overlap * 100.0
+                    ),
+                });
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Jaccard similarity between the two descriptions' significant words
+/// (lowercased, length >= 4, to skip connective words like "the"/"for").
+fn description_overlap(a: &str, b: &str) -> f64 {
+    let words = |s: &str| -> HashSet<String> {
+        s.split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+            .filter(|w| w.len() >= 4)
+            .collect()
+    };
+    let a = words(a);
+    let b = words(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(&b).count();
+    let union = a.union(&b).count();
+    intersection as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{SkillRequirements, SkillSource};
+
+    fn skill(name: &str, description: &str) -> Skill {
+        Skill {
+            name: name.to_string(),
+            description: description.to_string(),
+            auto_activate: true,
+            requires: None,
+            instructions: String::new(),
+            source: SkillSource::SelfAuthored,
+            path: String::new(),
+            enabled: true,
+            installed_at: "2024-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn with_deps(mut s: Skill, deps: &[&str]) -> Skill {
+        s.requires = Some(SkillRequirements {
+            bins: None,
+            env: None,
+            skills: Some(deps.iter().map(|d| d.to_string()).collect()),
+        });
+        s
+    }
+
+    #[test]
+    fn test_validate_skill_set_flags_duplicate_names() {
+        let skills = vec![
+            skill("deploy", "Deploy the app"),
+            skill("deploy", "Something else entirely"),
+        ];
+        let conflicts = validate_skill_set(&skills);
+        assert!(conflicts.iter().any(|c| c.kind == ConflictKind::DuplicateName));
+    }
+
+    #[test]
+    fn test_validate_skill_set_flags_missing_dependency() {
+        let skills = vec![with_deps(skill("deploy", "Deploy the app"), &["provision"])];
+        let conflicts = validate_skill_set(&skills);
+        assert!(conflicts.iter().any(|c| c.kind == ConflictKind::MissingDependency));
+    }
+
+    #[test]
+    fn test_validate_skill_set_flags_dependency_cycle() {
+        let skills = vec![
+            with_deps(skill("a", "A skill"), &["b"]),
+            with_deps(skill("b", "B skill"), &["a"]),
+        ];
+        let conflicts = validate_skill_set(&skills);
+        assert!(conflicts.iter().any(|c| c.kind == ConflictKind::CyclicDependency));
+    }
+
+    #[test]
+    fn test_validate_skill_set_flags_overlapping_auto_activate() {
+        let skills = vec![
+            skill("deploy-a", "Deploy the application to production servers"),
+            skill("deploy-b", "Deploy the application onto production servers"),
+        ];
+        let conflicts = validate_skill_set(&skills);
+        assert!(conflicts.iter().any(|c| c.kind == ConflictKind::OverlappingAutoActivate));
+    }
+
+    #[test]
+    fn test_validate_skill_set_clean_set_has_no_conflicts() {
+        let skills = vec![
+            skill("deploy", "Deploy the application"),
+            skill("monitor", "Watch resource usage and alert on spikes"),
+        ];
+        assert!(validate_skill_set(&skills).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_activation_order_puts_dependencies_first() {
+        let skills = vec![
+            with_deps(skill("deploy", "Deploy the app"), &["provision"]),
+            skill("provision", "Provision infrastructure"),
+        ];
+        let order = resolve_activation_order(&skills).unwrap();
+        let provision_idx = order.iter().position(|n| n == "provision").unwrap();
+        let deploy_idx = order.iter().position(|n| n == "deploy").unwrap();
+        assert!(provision_idx < deploy_idx);
+    }
+
+    #[test]
+    fn test_resolve_activation_order_rejects_cycle() {
+        let skills = vec![
+            with_deps(skill("a", "A skill"), &["b"]),
+            with_deps(skill("b", "B skill"), &["a"]),
+        ];
+        assert!(resolve_activation_order(&skills).is_err());
+    }
+}
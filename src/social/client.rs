@@ -6,12 +6,13 @@
 
 use alloy::primitives::keccak256;
 use alloy::signers::local::PrivateKeySigner;
-use alloy::signers::Signer;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::identity::signing::{safe_sign, SignPurpose};
+
 // ---------------------------------------------------------------------------
 // Types
 // ---------------------------------------------------------------------------
@@ -90,10 +91,8 @@ impl SocialClient {
         // Hash the content with keccak256.
         let content_hash = hex::encode(keccak256(content.as_bytes()));
 
-        // Sign the content hash.
-        let signature = self
-            .signer
-            .sign_message(content_hash.as_bytes())
+        // Sign the content hash, through the blind-signing guard.
+        let signature = safe_sign(&self.signer, SignPurpose::SocialMessage, &content_hash)
             .await
             .context("Failed to sign message content")?;
         let signature_hex = hex::encode(signature.as_bytes());
@@ -0,0 +1,530 @@
+//! Database Actor
+//!
+//! An alternative to `DatabaseAdapter` that gets rid of `std::sync::Mutex`
+//! entirely: `DatabaseActor` owns the `Connection` on one dedicated thread
+//! and talks to it over an `mpsc` channel of boxed closures, each paired
+//! with its own one-shot reply channel. Callers never see a lock, so
+//! there's no mutex-across-await hazard to worry about in the agent loop
+//! or the heartbeat daemon (see the concurrency model notes in
+//! `crate::state`) -- writes are serialized by construction, by the single
+//! thread that owns the connection, rather than by a lock callers have to
+//! remember to drop in time.
+//!
+//! This is opt-in: `Database` and `DatabaseAdapter` remain the default for
+//! the sync/test paths. Swap `Box::new(DatabaseAdapter::new(db))` for
+//! `Box::new(DatabaseActor::spawn(db_path)?)` wherever a `Box<dyn
+//! AutomatonDatabase>` is built to use it instead.
+//!
+//! TODO: not benchmarked against `Arc<Mutex<Database>>` yet -- this repo
+//! has no `benches/` harness (no `criterion` dependency) to compare them
+//! with. Expect the actor to add one channel round-trip of latency per
+//! call versus a bare mutex lock, in exchange for never blocking a task
+//! that's waiting on the lock across an `.await`.
+
+use std::sync::mpsc;
+#[cfg(test)]
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::{Context, Result};
+
+use crate::state::Database;
+use crate::types::*;
+
+/// A unit of work sent to the actor thread: given exclusive access to the
+/// `Database`, run it and reply however it likes (typically by sending its
+/// result down a one-shot channel captured in the closure).
+type Job = Box<dyn FnOnce(&mut Database) + Send>;
+
+/// Owns a `Database` connection on a dedicated thread and implements
+/// `AutomatonDatabase` by shipping closures to it over a channel.
+pub struct DatabaseActor {
+    tx: mpsc::Sender<Job>,
+}
+
+impl DatabaseActor {
+    /// Spawn the actor thread, opening `Database::open(db_path)` on it, and
+    /// block until that succeeds (or return the error) so callers don't
+    /// get a handle to an actor whose database never opened.
+    pub fn spawn(db_path: &str) -> Result<Self> {
+        let (tx, rx) = mpsc::channel::<Job>();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<()>>();
+        let db_path = db_path.to_string();
+
+        thread::Builder::new()
+            .name("db-actor".to_string())
+            .spawn(move || {
+                let mut db = match Database::open(&db_path) {
+                    Ok(db) => db,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                };
+                let _ = ready_tx.send(Ok(()));
+                for job in rx {
+                    job(&mut db);
+                }
+            })
+            .context("failed to spawn db-actor thread")?;
+
+        ready_rx
+            .recv()
+            .context("db-actor thread died before opening the database")??;
+        Ok(Self { tx })
+    }
+
+    /// Run `f` against the actor's `Database` on its dedicated thread and
+    /// block until it replies. The calling thread just waits on a channel
+    /// recv, not a mutex, so this is safe to call from async code as long
+    /// as it isn't on a single-threaded runtime with nothing else to poll
+    /// (same caveat as any blocking call in an async fn).
+    fn call<T, F>(&self, f: F) -> T
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Database) -> T + Send + 'static,
+    {
+        let (reply_tx, reply_rx) = mpsc::channel::<T>();
+        let job: Job = Box::new(move |db| {
+            let _ = reply_tx.send(f(db));
+        });
+        self.tx.send(job).expect("db-actor thread has stopped");
+        reply_rx.recv().expect("db-actor thread dropped the reply channel")
+    }
+}
+
+impl AutomatonDatabase for DatabaseActor {
+    fn get_identity(&self, key: &str) -> Option<String> {
+        let key = key.to_string();
+        self.call(move |db| db.get_identity(&key).ok().flatten())
+    }
+
+    fn set_identity(&self, key: &str, value: &str) {
+        let key = key.to_string();
+        let value = value.to_string();
+        let _ = self.call(move |db| db.set_identity(&key, &value));
+    }
+
+    fn insert_turn(&self, turn: &AgentTurn) {
+        let turn = turn.clone();
+        let _ = self.call(move |db| db.insert_turn(&turn));
+    }
+
+    fn get_recent_turns(&self, limit: u32) -> Vec<AgentTurn> {
+        self.call(move |db| db.get_recent_turns(limit as i64).unwrap_or_default())
+    }
+
+    fn get_turn_by_id(&self, id: &str) -> Option<AgentTurn> {
+        let id = id.to_string();
+        self.call(move |db| db.get_turn_by_id(&id).ok().flatten())
+    }
+
+    fn get_turn_count(&self) -> u64 {
+        self.call(|db| db.get_turn_count().unwrap_or(0) as u64)
+    }
+
+    fn get_turns_for_summary(&self, after: Option<&str>, before: &str, limit: u32) -> Vec<AgentTurn> {
+        let after = after.map(|s| s.to_string());
+        let before = before.to_string();
+        self.call(move |db| db.get_turns_for_summary(after.as_deref(), &before, limit as i64).unwrap_or_default())
+    }
+
+    fn insert_turn_prompt(&self, turn_id: &str, rendered_prompt: &str) -> String {
+        let turn_id = turn_id.to_string();
+        let rendered_prompt = rendered_prompt.to_string();
+        self.call(move |db| db.insert_turn_prompt(&turn_id, &rendered_prompt)
+            .unwrap_or_default())
+    }
+
+    fn get_turn_prompt(&self, turn_id: &str) -> Option<TurnPrompt> {
+        let turn_id = turn_id.to_string();
+        self.call(move |db| db.get_turn_prompt(&turn_id).ok().flatten())
+    }
+
+    fn insert_tool_call(&self, turn_id: &str, call: &ToolCallResult) {
+        let turn_id = turn_id.to_string();
+        let call = call.clone();
+        let _ = self.call(move |db| db.insert_tool_call(&turn_id, &call));
+    }
+
+    fn get_tool_calls_for_turn(&self, turn_id: &str) -> Vec<ToolCallResult> {
+        let turn_id = turn_id.to_string();
+        self.call(move |db| db.get_tool_calls_for_turn(&turn_id).unwrap_or_default())
+    }
+
+    fn get_tool_stats(&self, window_hours: u32) -> Vec<ToolStat> {
+        self.call(move |db| db.get_tool_stats(window_hours as i64).unwrap_or_default())
+    }
+
+    fn get_heartbeat_entries(&self) -> Vec<HeartbeatEntry> {
+        self.call(|db| db.get_heartbeat_entries().unwrap_or_default())
+    }
+
+    fn upsert_heartbeat_entry(&self, entry: &HeartbeatEntry) {
+        let entry = entry.clone();
+        let _ = self.call(move |db| db.upsert_heartbeat_entry(&entry));
+    }
+
+    fn update_heartbeat_last_run(&self, name: &str, timestamp: &str) {
+        let name = name.to_string();
+        let timestamp = timestamp.to_string();
+        let _ = self.call(move |db| db.update_heartbeat_last_run(&name, &timestamp));
+    }
+
+    fn insert_transaction(&self, txn: &Transaction) {
+        let txn = txn.clone();
+        let _ = self.call(move |db| db.insert_transaction(&txn));
+    }
+
+    fn get_recent_transactions(&self, limit: u32) -> Vec<Transaction> {
+        self.call(move |db| db.get_recent_transactions(limit as i64).unwrap_or_default())
+    }
+
+    fn get_installed_tools(&self) -> Vec<InstalledTool> {
+        self.call(|db| db.get_installed_tools().unwrap_or_default())
+    }
+
+    fn install_tool(&self, tool: &InstalledTool) {
+        let tool = tool.clone();
+        let _ = self.call(move |db| db.install_tool(&tool));
+    }
+
+    fn remove_tool(&self, id: &str) {
+        let id = id.to_string();
+        let _ = self.call(move |db| db.remove_tool(&id));
+    }
+
+    fn insert_modification(&self, modification: &ModificationEntry) {
+        let modification = modification.clone();
+        let _ = self.call(move |db| db.insert_modification(&modification));
+    }
+
+    fn get_recent_modifications(&self, limit: u32) -> Vec<ModificationEntry> {
+        self.call(move |db| db.get_recent_modifications(limit as i64).unwrap_or_default())
+    }
+
+    fn get_kv(&self, key: &str) -> Option<String> {
+        let key = key.to_string();
+        self.call(move |db| db.get_kv(&key).ok().flatten())
+    }
+
+    fn set_kv(&self, key: &str, value: &str) {
+        let key = key.to_string();
+        let value = value.to_string();
+        let _ = self.call(move |db| db.set_kv(&key, &value));
+    }
+
+    fn delete_kv(&self, key: &str) {
+        let key = key.to_string();
+        let _ = self.call(move |db| db.delete_kv(&key));
+    }
+
+    fn get_skills(&self, enabled_only: Option<bool>) -> Vec<Skill> {
+        self.call(move |db| db.get_skills(enabled_only.unwrap_or(false)).unwrap_or_default())
+    }
+
+    fn get_skill_by_name(&self, name: &str) -> Option<Skill> {
+        let name = name.to_string();
+        self.call(move |db| db.get_skill_by_name(&name).ok().flatten())
+    }
+
+    fn upsert_skill(&self, skill: &Skill) {
+        let skill = skill.clone();
+        let _ = self.call(move |db| db.upsert_skill(&skill));
+    }
+
+    fn remove_skill(&self, name: &str) {
+        let name = name.to_string();
+        let _ = self.call(move |db| db.remove_skill(&name));
+    }
+
+    fn get_children(&self) -> Vec<ChildAutomaton> {
+        self.call(|db| db.get_children().unwrap_or_default())
+    }
+
+    fn get_child_by_id(&self, id: &str) -> Option<ChildAutomaton> {
+        let id = id.to_string();
+        self.call(move |db| db.get_child_by_id(&id).ok().flatten())
+    }
+
+    fn insert_child(&self, child: &ChildAutomaton) {
+        let child = child.clone();
+        let _ = self.call(move |db| db.insert_child(&child));
+    }
+
+    fn update_child_status(&self, id: &str, status: ChildStatus) {
+        let id = id.to_string();
+        let _ = self.call(move |db| {
+            let s = serde_json::to_string(&status).unwrap_or_default();
+            let s = s.trim_matches('"');
+            db.update_child_status(&id, s)
+        });
+    }
+
+    fn remove_child(&self, id: &str) {
+        let id = id.to_string();
+        let _ = self.call(move |db| db.remove_child(&id));
+    }
+
+    fn update_child_lineage(&self, id: &str, descendants_count: u32, lineage_snapshot: Option<String>) {
+        let id = id.to_string();
+        let _ = self.call(move |db| db.update_child_lineage(&id, descendants_count, lineage_snapshot.as_deref()));
+    }
+
+    fn update_child_address(&self, id: &str, address: &str) {
+        let id = id.to_string();
+        let address = address.to_string();
+        let _ = self.call(move |db| db.update_child_address(&id, &address));
+    }
+
+    fn get_exposed_ports(&self) -> Vec<ExposedPort> {
+        self.call(|db| db.get_exposed_ports().unwrap_or_default())
+    }
+
+    fn upsert_exposed_port(&self, port: &ExposedPort) {
+        let port = port.clone();
+        let _ = self.call(move |db| db.upsert_exposed_port(&port));
+    }
+
+    fn delete_exposed_port(&self, port: u16) {
+        let _ = self.call(move |db| db.delete_exposed_port(port));
+    }
+
+    fn insert_created_sandbox(&self, entry: &CreatedSandboxEntry) {
+        let entry = entry.clone();
+        let _ = self.call(move |db| db.insert_created_sandbox(&entry));
+    }
+
+    fn get_created_sandboxes(&self) -> Vec<CreatedSandboxEntry> {
+        self.call(|db| db.get_created_sandboxes().unwrap_or_default())
+    }
+
+    fn delete_created_sandbox(&self, sandbox_id: &str) {
+        let sandbox_id = sandbox_id.to_string();
+        let _ = self.call(move |db| db.delete_created_sandbox(&sandbox_id));
+    }
+
+    fn get_snapshots(&self) -> Vec<Snapshot> {
+        self.call(|db| db.get_snapshots().unwrap_or_default())
+    }
+
+    fn insert_snapshot(&self, snapshot: &Snapshot) {
+        let snapshot = snapshot.clone();
+        let _ = self.call(move |db| db.insert_snapshot(&snapshot));
+    }
+
+    fn get_balance_snapshots(&self, limit: u32) -> Vec<BalanceSnapshot> {
+        self.call(move |db| db.get_balance_snapshots(limit as i64).unwrap_or_default())
+    }
+
+    fn insert_balance_snapshot(&self, snapshot: &BalanceSnapshot) {
+        let snapshot = snapshot.clone();
+        let _ = self.call(move |db| db.insert_balance_snapshot(&snapshot));
+    }
+
+    fn get_events(&self, since: Option<&str>, limit: u32) -> Vec<LoopEventRecord> {
+        let since = since.map(|s| s.to_string());
+        self.call(move |db| db.get_events(since.as_deref(), limit as i64).unwrap_or_default())
+    }
+
+    fn insert_event(&self, event: &LoopEventRecord) {
+        let event = event.clone();
+        let _ = self.call(move |db| db.insert_event(&event));
+    }
+
+    fn enqueue_pending_input(&self, entry: &PendingInputEntry) {
+        let entry = entry.clone();
+        let _ = self.call(move |db| db.enqueue_pending_input(&entry));
+    }
+
+    fn dequeue_pending_input(&self) -> Option<PendingInputEntry> {
+        self.call(|db| db.dequeue_pending_input().ok().flatten())
+    }
+
+    fn pending_input_count(&self) -> u32 {
+        self.call(|db| db.pending_input_count().unwrap_or(0))
+    }
+
+    fn get_registry_entry(&self) -> Option<RegistryEntry> {
+        self.call(|db| db.get_registry_entry().ok().flatten())
+    }
+
+    fn set_registry_entry(&self, entry: &RegistryEntry) {
+        let entry = entry.clone();
+        let _ = self.call(move |db| db.set_registry_entry(&entry));
+    }
+
+    fn insert_reputation(&self, entry: &ReputationEntry) {
+        let entry = entry.clone();
+        let _ = self.call(move |db| db.insert_reputation(&entry));
+    }
+
+    fn get_reputation(&self, agent_address: Option<&str>) -> Vec<ReputationEntry> {
+        let agent_address = agent_address.map(|s| s.to_string());
+        self.call(move |db| db.get_reputation(agent_address.as_deref()).unwrap_or_default())
+    }
+
+    fn insert_inbox_message(&self, msg: &InboxMessage) {
+        let msg = msg.clone();
+        let _ = self.call(move |db| db.insert_inbox_message(&msg));
+    }
+
+    fn get_unprocessed_inbox_messages(&self, limit: u32) -> Vec<InboxMessage> {
+        self.call(move |db| db.get_unprocessed_inbox_messages(limit as i64).unwrap_or_default())
+    }
+
+    fn mark_inbox_message_processed(&self, id: &str) {
+        let id = id.to_string();
+        let _ = self.call(move |db| db.mark_inbox_message_processed(&id));
+    }
+
+    fn enqueue_outbox(&self, entry: &OutboxEntry) {
+        let entry = entry.clone();
+        let _ = self.call(move |db| db.enqueue_outbox(&entry));
+    }
+
+    fn get_pending_outbox(&self, limit: u32) -> Vec<OutboxEntry> {
+        self.call(move |db| db.get_pending_outbox(limit as i64).unwrap_or_default())
+    }
+
+    fn mark_sent(&self, id: &str) {
+        let id = id.to_string();
+        let _ = self.call(move |db| db.mark_sent(&id));
+    }
+
+    fn record_outbox_failure(&self, id: &str, error: &str) {
+        let id = id.to_string();
+        let error = error.to_string();
+        let _ = self.call(move |db| db.record_outbox_failure(&id, &error));
+    }
+
+    fn record_outbound_message(&self, to_address: &str) {
+        let to_address = to_address.to_string();
+        let _ = self.call(move |db| db.record_outbound_message(&to_address));
+    }
+
+    fn count_outbound_messages(&self, to_address: Option<&str>, since: &str) -> u32 {
+        let to_address = to_address.map(|s| s.to_string());
+        let since = since.to_string();
+        self.call(move |db| db.count_outbound_messages(to_address.as_deref(), &since).unwrap_or(0))
+    }
+
+    fn add_goal(&self, goal: &Goal) {
+        let goal = goal.clone();
+        let _ = self.call(move |db| db.add_goal(&goal));
+    }
+
+    fn update_goal_progress(&self, id: &str, value: f64) {
+        let id = id.to_string();
+        let _ = self.call(move |db| db.update_goal_progress(&id, value));
+    }
+
+    fn get_goals(&self, active_only: bool) -> Vec<Goal> {
+        self.call(move |db| db.get_goals(active_only).unwrap_or_default())
+    }
+
+    fn get_goal_by_id(&self, id: &str) -> Option<Goal> {
+        let id = id.to_string();
+        self.call(move |db| db.get_goal_by_id(&id).ok().flatten())
+    }
+
+    fn abandon_goal(&self, id: &str) {
+        let id = id.to_string();
+        let _ = self.call(move |db| db.abandon_goal(&id));
+    }
+
+    fn insert_scheduled_action(&self, action: &ScheduledAction) {
+        let action = action.clone();
+        let _ = self.call(move |db| db.insert_scheduled_action(&action));
+    }
+
+    fn get_due_scheduled_actions(&self, now: &str) -> Vec<ScheduledAction> {
+        let now = now.to_string();
+        self.call(move |db| db.get_due_scheduled_actions(&now).unwrap_or_default())
+    }
+
+    fn mark_scheduled_action_fired(&self, id: &str) {
+        let id = id.to_string();
+        let _ = self.call(move |db| db.mark_scheduled_action_fired(&id));
+    }
+
+    fn scheduled_action_count(&self) -> u32 {
+        self.call(|db| db.scheduled_action_count().unwrap_or(0))
+    }
+
+    fn insert_history_summary(&self, summary: &HistorySummary) {
+        let summary = summary.clone();
+        let _ = self.call(move |db| db.insert_history_summary(&summary));
+    }
+
+    fn get_history_summaries(&self, limit: u32) -> Vec<HistorySummary> {
+        self.call(move |db| db.get_history_summaries(limit as i64).unwrap_or_default())
+    }
+
+    fn get_history_summary_watermark(&self) -> Option<String> {
+        self.call(|db| db.get_history_summary_watermark().ok().flatten())
+    }
+
+    fn delete_turns(&self, ids: &[String]) {
+        let ids = ids.to_vec();
+        let _ = self.call(move |db| db.delete_turns(&ids));
+    }
+
+    fn get_agent_state(&self) -> AgentState {
+        let s = self.call(|db| db.get_agent_state().unwrap_or_else(|_| "setup".to_string()));
+        serde_json::from_str(&format!("\"{}\"", s)).unwrap_or(AgentState::Setup)
+    }
+
+    fn set_agent_state(&self, state: AgentState) {
+        let _ = self.call(move |db| {
+            let s = serde_json::to_string(&state).unwrap_or_default();
+            let s = s.trim_matches('"');
+            db.set_agent_state(s)
+        });
+    }
+
+    fn close(&self) {
+        // No-op; dropping the last `DatabaseActor` drops `tx`, which ends
+        // the actor thread's `for job in rx` loop and closes the connection.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_test_actor() -> DatabaseActor {
+        let db_path = std::env::temp_dir()
+            .join(format!("automaton-db-actor-test-{}", uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string();
+        DatabaseActor::spawn(&db_path).expect("failed to spawn test actor")
+    }
+
+    #[test]
+    fn test_set_and_get_kv_round_trips_through_the_actor_thread() {
+        let actor = spawn_test_actor();
+        assert_eq!(actor.get_kv("answer"), None);
+        actor.set_kv("answer", "42");
+        assert_eq!(actor.get_kv("answer"), Some("42".to_string()));
+    }
+
+    #[test]
+    fn test_concurrent_callers_serialize_through_the_same_actor() {
+        let actor = Arc::new(spawn_test_actor());
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let actor = actor.clone();
+                std::thread::spawn(move || {
+                    actor.set_kv(&format!("key-{i}"), &format!("value-{i}"));
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        for i in 0..8 {
+            assert_eq!(actor.get_kv(&format!("key-{i}")), Some(format!("value-{i}")));
+        }
+    }
+}
@@ -2,6 +2,11 @@
 //!
 //! Bridges the concrete `Database` struct (which returns Results)
 //! with the `AutomatonDatabase` trait (which does not).
+//!
+//! Every method below locks, makes one synchronous call into `Database`,
+//! and drops the guard before returning -- never across an `.await`. See
+//! the concurrency model notes in `crate::state` before adding a method
+//! that needs to hold the lock longer than that.
 
 use std::sync::{Arc, Mutex};
 
@@ -44,6 +49,22 @@ impl AutomatonDatabase for DatabaseAdapter {
         self.db.lock().unwrap().get_turn_count().unwrap_or(0) as u64
     }
 
+    fn get_turns_for_summary(&self, after: Option<&str>, before: &str, limit: u32) -> Vec<AgentTurn> {
+        self.db.lock().unwrap().get_turns_for_summary(after, before, limit as i64).unwrap_or_default()
+    }
+
+    fn insert_turn_prompt(&self, turn_id: &str, rendered_prompt: &str) -> String {
+        self.db
+            .lock()
+            .unwrap()
+            .insert_turn_prompt(turn_id, rendered_prompt)
+            .unwrap_or_default()
+    }
+
+    fn get_turn_prompt(&self, turn_id: &str) -> Option<TurnPrompt> {
+        self.db.lock().unwrap().get_turn_prompt(turn_id).ok().flatten()
+    }
+
     fn insert_tool_call(&self, turn_id: &str, call: &ToolCallResult) {
         let _ = self.db.lock().unwrap().insert_tool_call(turn_id, call);
     }
@@ -52,6 +73,10 @@ impl AutomatonDatabase for DatabaseAdapter {
         self.db.lock().unwrap().get_tool_calls_for_turn(turn_id).unwrap_or_default()
     }
 
+    fn get_tool_stats(&self, window_hours: u32) -> Vec<ToolStat> {
+        self.db.lock().unwrap().get_tool_stats(window_hours as i64).unwrap_or_default()
+    }
+
     fn get_heartbeat_entries(&self) -> Vec<HeartbeatEntry> {
         self.db.lock().unwrap().get_heartbeat_entries().unwrap_or_default()
     }
@@ -138,6 +163,82 @@ impl AutomatonDatabase for DatabaseAdapter {
         let _ = self.db.lock().unwrap().update_child_status(id, s);
     }
 
+    fn remove_child(&self, id: &str) {
+        let _ = self.db.lock().unwrap().remove_child(id);
+    }
+
+    fn update_child_lineage(&self, id: &str, descendants_count: u32, lineage_snapshot: Option<String>) {
+        let _ = self
+            .db
+            .lock()
+            .unwrap()
+            .update_child_lineage(id, descendants_count, lineage_snapshot.as_deref());
+    }
+
+    fn update_child_address(&self, id: &str, address: &str) {
+        let _ = self.db.lock().unwrap().update_child_address(id, address);
+    }
+
+    fn get_exposed_ports(&self) -> Vec<ExposedPort> {
+        self.db.lock().unwrap().get_exposed_ports().unwrap_or_default()
+    }
+
+    fn upsert_exposed_port(&self, port: &ExposedPort) {
+        let _ = self.db.lock().unwrap().upsert_exposed_port(port);
+    }
+
+    fn delete_exposed_port(&self, port: u16) {
+        let _ = self.db.lock().unwrap().delete_exposed_port(port);
+    }
+
+    fn insert_created_sandbox(&self, entry: &CreatedSandboxEntry) {
+        let _ = self.db.lock().unwrap().insert_created_sandbox(entry);
+    }
+
+    fn get_created_sandboxes(&self) -> Vec<CreatedSandboxEntry> {
+        self.db.lock().unwrap().get_created_sandboxes().unwrap_or_default()
+    }
+
+    fn delete_created_sandbox(&self, sandbox_id: &str) {
+        let _ = self.db.lock().unwrap().delete_created_sandbox(sandbox_id);
+    }
+
+    fn get_snapshots(&self) -> Vec<Snapshot> {
+        self.db.lock().unwrap().get_snapshots().unwrap_or_default()
+    }
+
+    fn insert_snapshot(&self, snapshot: &Snapshot) {
+        let _ = self.db.lock().unwrap().insert_snapshot(snapshot);
+    }
+
+    fn get_balance_snapshots(&self, limit: u32) -> Vec<BalanceSnapshot> {
+        self.db.lock().unwrap().get_balance_snapshots(limit as i64).unwrap_or_default()
+    }
+
+    fn insert_balance_snapshot(&self, snapshot: &BalanceSnapshot) {
+        let _ = self.db.lock().unwrap().insert_balance_snapshot(snapshot);
+    }
+
+    fn get_events(&self, since: Option<&str>, limit: u32) -> Vec<LoopEventRecord> {
+        self.db.lock().unwrap().get_events(since, limit as i64).unwrap_or_default()
+    }
+
+    fn insert_event(&self, event: &LoopEventRecord) {
+        let _ = self.db.lock().unwrap().insert_event(event);
+    }
+
+    fn enqueue_pending_input(&self, entry: &PendingInputEntry) {
+        let _ = self.db.lock().unwrap().enqueue_pending_input(entry);
+    }
+
+    fn dequeue_pending_input(&self) -> Option<PendingInputEntry> {
+        self.db.lock().unwrap().dequeue_pending_input().ok().flatten()
+    }
+
+    fn pending_input_count(&self) -> u32 {
+        self.db.lock().unwrap().pending_input_count().unwrap_or(0)
+    }
+
     fn get_registry_entry(&self) -> Option<RegistryEntry> {
         self.db.lock().unwrap().get_registry_entry().ok().flatten()
     }
@@ -166,6 +267,82 @@ impl AutomatonDatabase for DatabaseAdapter {
         let _ = self.db.lock().unwrap().mark_inbox_message_processed(id);
     }
 
+    fn enqueue_outbox(&self, entry: &OutboxEntry) {
+        let _ = self.db.lock().unwrap().enqueue_outbox(entry);
+    }
+
+    fn get_pending_outbox(&self, limit: u32) -> Vec<OutboxEntry> {
+        self.db.lock().unwrap().get_pending_outbox(limit as i64).unwrap_or_default()
+    }
+
+    fn mark_sent(&self, id: &str) {
+        let _ = self.db.lock().unwrap().mark_sent(id);
+    }
+
+    fn record_outbox_failure(&self, id: &str, error: &str) {
+        let _ = self.db.lock().unwrap().record_outbox_failure(id, error);
+    }
+
+    fn record_outbound_message(&self, to_address: &str) {
+        let _ = self.db.lock().unwrap().record_outbound_message(to_address);
+    }
+
+    fn count_outbound_messages(&self, to_address: Option<&str>, since: &str) -> u32 {
+        self.db.lock().unwrap().count_outbound_messages(to_address, since).unwrap_or(0)
+    }
+
+    fn add_goal(&self, goal: &Goal) {
+        let _ = self.db.lock().unwrap().add_goal(goal);
+    }
+
+    fn update_goal_progress(&self, id: &str, value: f64) {
+        let _ = self.db.lock().unwrap().update_goal_progress(id, value);
+    }
+
+    fn get_goals(&self, active_only: bool) -> Vec<Goal> {
+        self.db.lock().unwrap().get_goals(active_only).unwrap_or_default()
+    }
+
+    fn get_goal_by_id(&self, id: &str) -> Option<Goal> {
+        self.db.lock().unwrap().get_goal_by_id(id).ok().flatten()
+    }
+
+    fn abandon_goal(&self, id: &str) {
+        let _ = self.db.lock().unwrap().abandon_goal(id);
+    }
+
+    fn insert_scheduled_action(&self, action: &ScheduledAction) {
+        let _ = self.db.lock().unwrap().insert_scheduled_action(action);
+    }
+
+    fn get_due_scheduled_actions(&self, now: &str) -> Vec<ScheduledAction> {
+        self.db.lock().unwrap().get_due_scheduled_actions(now).unwrap_or_default()
+    }
+
+    fn mark_scheduled_action_fired(&self, id: &str) {
+        let _ = self.db.lock().unwrap().mark_scheduled_action_fired(id);
+    }
+
+    fn scheduled_action_count(&self) -> u32 {
+        self.db.lock().unwrap().scheduled_action_count().unwrap_or(0)
+    }
+
+    fn insert_history_summary(&self, summary: &HistorySummary) {
+        let _ = self.db.lock().unwrap().insert_history_summary(summary);
+    }
+
+    fn get_history_summaries(&self, limit: u32) -> Vec<HistorySummary> {
+        self.db.lock().unwrap().get_history_summaries(limit as i64).unwrap_or_default()
+    }
+
+    fn get_history_summary_watermark(&self) -> Option<String> {
+        self.db.lock().unwrap().get_history_summary_watermark().ok().flatten()
+    }
+
+    fn delete_turns(&self, ids: &[String]) {
+        let _ = self.db.lock().unwrap().delete_turns(ids);
+    }
+
     fn get_agent_state(&self) -> AgentState {
         let s = self.db.lock().unwrap().get_agent_state().unwrap_or_else(|_| "setup".to_string());
         serde_json::from_str(&format!("\"{}\"", s)).unwrap_or(AgentState::Setup)
@@ -1,22 +1,66 @@
 //! Automaton Database
 //!
 //! SQLite-backed persistent state for the automaton.
-//! Uses rusqlite for synchronous, single-process access.
+//! Uses rusqlite for synchronous access; see `crate::state` for how
+//! multiple connections to the same file are kept safe.
 
+use alloy::primitives::keccak256;
 use anyhow::{Context, Result};
+use flate2::read::{GzDecoder, GzEncoder};
+use flate2::Compression;
 use rusqlite::{params, Connection, OptionalExtension};
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 
 use crate::types::*;
 
-use super::schema::{CREATE_TABLES, MIGRATION_V2, MIGRATION_V3, SCHEMA_VERSION};
+use super::schema::{
+    CREATE_TABLES, MIGRATION_V2, MIGRATION_V3, MIGRATION_V4, MIGRATION_V5, MIGRATION_V6,
+    MIGRATION_V7, MIGRATION_V8, MIGRATION_V9, MIGRATION_V10, MIGRATION_V11, MIGRATION_V12,
+    MIGRATION_V13, MIGRATION_V14, MIGRATION_V15, MIGRATION_V16, MIGRATION_V17, MIGRATION_V18,
+    MIGRATION_V19, MIGRATION_V20, MIGRATION_V21, MIGRATION_V22, SCHEMA_VERSION,
+};
+
+/// How many turns' worth of rendered prompts to keep in `turn_prompts`
+/// before pruning the oldest -- bounds the audit trail's disk footprint.
+const TURN_PROMPT_RETENTION: i64 = 200;
+
+/// How many balance snapshots to keep before pruning the oldest -- bounds
+/// `balance_snapshots` to a rolling window rather than growing forever.
+const BALANCE_SNAPSHOT_RETENTION: i64 = 500;
+
+/// Gzip-compress a rendered prompt for storage in `turn_prompts`.
+fn compress_prompt(rendered_prompt: &str) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(rendered_prompt.as_bytes(), Compression::default());
+    let mut compressed = Vec::new();
+    encoder
+        .read_to_end(&mut compressed)
+        .context("failed to compress rendered prompt")?;
+    Ok(compressed)
+}
+
+/// Reverse of [`compress_prompt`].
+fn decompress_prompt(compressed: &[u8]) -> Result<String> {
+    let mut decoder = GzDecoder::new(compressed);
+    let mut rendered_prompt = String::new();
+    decoder
+        .read_to_string(&mut rendered_prompt)
+        .context("failed to decompress rendered prompt")?;
+    Ok(rendered_prompt)
+}
 
 /// The automaton's SQLite database handle.
 ///
 /// All persistent state is stored here: identity, turns, tool calls,
 /// heartbeat config, transactions, installed tools, modifications,
 /// key-value pairs, skills, children, registry, reputation, and inbox messages.
+/// A single connection to the automaton's SQLite database.
+///
+/// Holding a `Database` does not imply exclusive access to the underlying
+/// file -- other `Database` instances can be open against the same path at
+/// the same time (see `crate::state` for the concurrency model). Don't add
+/// in-memory caching here that assumes this is the only writer.
 pub struct Database {
     conn: Connection,
 }
@@ -35,8 +79,14 @@ impl Database {
         let conn = Connection::open(db_path)
             .with_context(|| format!("failed to open database: {db_path}"))?;
 
-        // Enable WAL mode for better concurrent read performance
+        // Enable WAL mode so readers never block writers, and set a busy
+        // timeout so a writer that does collide with another connection
+        // retries for a while instead of failing immediately with
+        // SQLITE_BUSY. This is what makes it safe for the agent loop's
+        // shared connection and each heartbeat task's own `Database::open`
+        // call (see the module docs) to write concurrently.
         conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
 
         // Initialize schema
@@ -62,6 +112,161 @@ impl Database {
                 .context("failed to apply migration v3")?;
         }
 
+        if current_version < 4 {
+            // CREATE_TABLES already defines these columns for brand-new databases,
+            // so only run the ALTER TABLEs (not idempotent) if they're missing.
+            let has_lineage_columns = conn
+                .prepare("PRAGMA table_info(children)")?
+                .query_map([], |row| row.get::<_, String>(1))?
+                .filter_map(|name| name.ok())
+                .any(|name| name == "descendants_count");
+
+            if !has_lineage_columns {
+                conn.execute_batch(MIGRATION_V4)
+                    .context("failed to apply migration v4")?;
+            }
+        }
+
+        if current_version < 5 {
+            conn.execute_batch(MIGRATION_V5)
+                .context("failed to apply migration v5")?;
+        }
+
+        if current_version < 6 {
+            // CREATE_TABLES already defines this column for brand-new databases,
+            // so only run the ALTER TABLE (not idempotent) if it's missing.
+            let has_financial_snapshot = conn
+                .prepare("PRAGMA table_info(turns)")?
+                .query_map([], |row| row.get::<_, String>(1))?
+                .filter_map(|name| name.ok())
+                .any(|name| name == "financial_snapshot");
+
+            if !has_financial_snapshot {
+                conn.execute_batch(MIGRATION_V6)
+                    .context("failed to apply migration v6")?;
+            }
+        }
+
+        if current_version < 7 {
+            let has_prompt_hash = conn
+                .prepare("PRAGMA table_info(turns)")?
+                .query_map([], |row| row.get::<_, String>(1))?
+                .filter_map(|name| name.ok())
+                .any(|name| name == "prompt_hash");
+
+            if !has_prompt_hash {
+                conn.execute_batch(MIGRATION_V7)
+                    .context("failed to apply migration v7")?;
+            }
+        }
+
+        if current_version < 8 {
+            let has_data_column = conn
+                .prepare("PRAGMA table_info(tool_calls)")?
+                .query_map([], |row| row.get::<_, String>(1))?
+                .filter_map(|name| name.ok())
+                .any(|name| name == "data");
+
+            if !has_data_column {
+                conn.execute_batch(MIGRATION_V8)
+                    .context("failed to apply migration v8")?;
+            }
+        }
+
+        if current_version < 9 {
+            conn.execute_batch(MIGRATION_V9)
+                .context("failed to apply migration v9")?;
+        }
+
+        if current_version < 10 {
+            conn.execute_batch(MIGRATION_V10)
+                .context("failed to apply migration v10")?;
+        }
+
+        if current_version < 11 {
+            conn.execute_batch(MIGRATION_V11)
+                .context("failed to apply migration v11")?;
+        }
+
+        if current_version < 12 {
+            let has_subcategory = conn
+                .prepare("PRAGMA table_info(transactions)")?
+                .query_map([], |row| row.get::<_, String>(1))?
+                .filter_map(|name| name.ok())
+                .any(|name| name == "subcategory");
+
+            if !has_subcategory {
+                conn.execute_batch(MIGRATION_V12)
+                    .context("failed to apply migration v12")?;
+            }
+        }
+
+        if current_version < 13 {
+            conn.execute_batch(MIGRATION_V13)
+                .context("failed to apply migration v13")?;
+        }
+
+        if current_version < 14 {
+            let has_sequence_column = conn
+                .prepare("PRAGMA table_info(tool_calls)")?
+                .query_map([], |row| row.get::<_, String>(1))?
+                .filter_map(|name| name.ok())
+                .any(|name| name == "sequence");
+
+            if !has_sequence_column {
+                conn.execute_batch(MIGRATION_V14)
+                    .context("failed to apply migration v14")?;
+            }
+        }
+
+        if current_version < 15 {
+            conn.execute_batch(MIGRATION_V15)
+                .context("failed to apply migration v15")?;
+        }
+
+        if current_version < 16 {
+            conn.execute_batch(MIGRATION_V16)
+                .context("failed to apply migration v16")?;
+        }
+
+        if current_version < 17 {
+            conn.execute_batch(MIGRATION_V17)
+                .context("failed to apply migration v17")?;
+        }
+
+        if current_version < 18 {
+            conn.execute_batch(MIGRATION_V18)
+                .context("failed to apply migration v18")?;
+        }
+
+        if current_version < 19 {
+            conn.execute_batch(MIGRATION_V19)
+                .context("failed to apply migration v19")?;
+        }
+
+        if current_version < 20 {
+            conn.execute_batch(MIGRATION_V20)
+                .context("failed to apply migration v20")?;
+        }
+
+        if current_version < 21 {
+            conn.execute_batch(MIGRATION_V21)
+                .context("failed to apply migration v21")?;
+        }
+
+        if current_version < 22 {
+            let has_mutation_summary = conn
+                .prepare("PRAGMA table_info(children)")?
+                .query_map([], |row| row.get::<_, String>(1))?
+                .filter_map(|name| name.ok())
+                .any(|name| name == "mutation_summary");
+
+            if !has_mutation_summary {
+                conn.execute_batch(MIGRATION_V22)
+                    .context("failed to apply migration v22")?;
+            }
+        }
+
         if current_version < SCHEMA_VERSION {
             conn.execute(
                 "INSERT OR REPLACE INTO schema_version (version, applied_at) VALUES (?1, datetime('now'))",
@@ -117,9 +322,14 @@ impl Database {
             let v = serde_json::to_string(s).unwrap_or_default();
             v.trim_matches('"').to_string()
         });
+        let financial_snapshot_json = turn
+            .financial_snapshot
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?;
         self.conn.execute(
-            "INSERT INTO turns (id, timestamp, state, input, input_source, thinking, tool_calls, token_usage, cost_cents)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO turns (id, timestamp, state, input, input_source, thinking, tool_calls, token_usage, cost_cents, financial_snapshot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 turn.id,
                 turn.timestamp,
@@ -130,6 +340,7 @@ impl Database {
                 serde_json::to_string(&turn.tool_calls)?,
                 serde_json::to_string(&turn.token_usage)?,
                 turn.cost_cents,
+                financial_snapshot_json,
             ],
         )?;
         Ok(())
@@ -137,7 +348,7 @@ impl Database {
 
     pub fn get_recent_turns(&self, limit: i64) -> Result<Vec<AgentTurn>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, timestamp, state, input, input_source, thinking, tool_calls, token_usage, cost_cents
+            "SELECT id, timestamp, state, input, input_source, thinking, tool_calls, token_usage, cost_cents, financial_snapshot
              FROM turns ORDER BY timestamp DESC LIMIT ?1",
         )?;
         let mut turns: Vec<AgentTurn> = stmt
@@ -149,11 +360,25 @@ impl Database {
         Ok(turns)
     }
 
+    /// Fetch the turns immediately preceding `timestamp`, oldest first --
+    /// used to reconstruct the context window `--replay` saw at that point.
+    pub fn get_turns_before(&self, timestamp: &str, limit: i64) -> Result<Vec<AgentTurn>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, state, input, input_source, thinking, tool_calls, token_usage, cost_cents, financial_snapshot
+             FROM turns WHERE timestamp < ?1 ORDER BY timestamp DESC LIMIT ?2",
+        )?;
+        let mut turns: Vec<AgentTurn> = stmt
+            .query_map(params![timestamp, limit], |row| Ok(Self::deserialize_turn(row)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        turns.reverse();
+        Ok(turns)
+    }
+
     pub fn get_turn_by_id(&self, id: &str) -> Result<Option<AgentTurn>> {
         let result = self
             .conn
             .query_row(
-                "SELECT id, timestamp, state, input, input_source, thinking, tool_calls, token_usage, cost_cents
+                "SELECT id, timestamp, state, input, input_source, thinking, tool_calls, token_usage, cost_cents, financial_snapshot
                  FROM turns WHERE id = ?1",
                 params![id],
                 |row| Ok(Self::deserialize_turn(row)),
@@ -169,12 +394,97 @@ impl Database {
         Ok(count)
     }
 
+    /// Turns with `timestamp > after` (or all, if `after` is `None`) and
+    /// `timestamp < before`, oldest first, capped to `limit` -- used by
+    /// `summarize_history` to select the oldest not-yet-summarized turns.
+    pub fn get_turns_for_summary(
+        &self,
+        after: Option<&str>,
+        before: &str,
+        limit: i64,
+    ) -> Result<Vec<AgentTurn>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, state, input, input_source, thinking, tool_calls, token_usage, cost_cents, financial_snapshot
+             FROM turns WHERE (?1 IS NULL OR timestamp > ?1) AND timestamp < ?2
+             ORDER BY timestamp ASC LIMIT ?3",
+        )?;
+        let turns = stmt
+            .query_map(params![after, before, limit], |row| Ok(Self::deserialize_turn(row)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(turns)
+    }
+
+    /// Hash and gzip-compress the exact rendered prompt sent to the model
+    /// for `turn_id`. The hash is written onto the turn row itself and kept
+    /// forever; the compressed body goes into `turn_prompts`, which is
+    /// pruned to the most recent [`TURN_PROMPT_RETENTION`] entries so
+    /// storage stays bounded.
+    pub fn insert_turn_prompt(&self, turn_id: &str, rendered_prompt: &str) -> Result<String> {
+        let prompt_hash = hex::encode(keccak256(rendered_prompt.as_bytes()));
+
+        self.conn.execute(
+            "UPDATE turns SET prompt_hash = ?1 WHERE id = ?2",
+            params![prompt_hash, turn_id],
+        )?;
+
+        let compressed = compress_prompt(rendered_prompt)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO turn_prompts (turn_id, compressed_prompt) VALUES (?1, ?2)",
+            params![turn_id, compressed],
+        )?;
+
+        self.conn.execute(
+            "DELETE FROM turn_prompts WHERE turn_id NOT IN (
+                SELECT turn_id FROM turn_prompts ORDER BY created_at DESC LIMIT ?1
+             )",
+            params![TURN_PROMPT_RETENTION],
+        )?;
+
+        Ok(prompt_hash)
+    }
+
+    /// Look up the recorded prompt hash for a turn, along with the rendered
+    /// body if it hasn't fallen out of the retention window yet.
+    pub fn get_turn_prompt(&self, turn_id: &str) -> Result<Option<TurnPrompt>> {
+        let prompt_hash: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT prompt_hash FROM turns WHERE id = ?1",
+                params![turn_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+
+        let Some(prompt_hash) = prompt_hash else {
+            return Ok(None);
+        };
+
+        let compressed: Option<Vec<u8>> = self
+            .conn
+            .query_row(
+                "SELECT compressed_prompt FROM turn_prompts WHERE turn_id = ?1",
+                params![turn_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let rendered_prompt = compressed
+            .map(|bytes| decompress_prompt(&bytes))
+            .transpose()?;
+
+        Ok(Some(TurnPrompt {
+            prompt_hash,
+            rendered_prompt,
+        }))
+    }
+
     // ─── Tool Calls ──────────────────────────────────────────────
 
     pub fn insert_tool_call(&self, turn_id: &str, call: &ToolCallResult) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO tool_calls (id, turn_id, name, arguments, result, duration_ms, error)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT INTO tool_calls (id, turn_id, name, arguments, result, duration_ms, error, data, sequence, started_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 call.id,
                 turn_id,
@@ -183,15 +493,22 @@ impl Database {
                 call.result,
                 call.duration_ms,
                 call.error,
+                call.data.as_ref().map(serde_json::to_string).transpose()?,
+                call.sequence,
+                call.started_at,
             ],
         )?;
         Ok(())
     }
 
+    /// Fetch a turn's tool calls ordered by `sequence` -- the position the
+    /// model requested them in -- rather than insertion order, so the audit
+    /// trail reflects the model's requested order even if calls ran
+    /// concurrently and completed out of order.
     pub fn get_tool_calls_for_turn(&self, turn_id: &str) -> Result<Vec<ToolCallResult>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, arguments, result, duration_ms, error
-             FROM tool_calls WHERE turn_id = ?1",
+            "SELECT id, name, arguments, result, duration_ms, error, data, sequence, started_at
+             FROM tool_calls WHERE turn_id = ?1 ORDER BY sequence ASC",
         )?;
         let calls = stmt
             .query_map(params![turn_id], |row| {
@@ -201,6 +518,62 @@ impl Database {
         Ok(calls)
     }
 
+    /// Aggregate `tool_calls` by name over the last `window_hours`: call
+    /// count, error rate, and avg/p95 duration. p95 is computed with a
+    /// second, per-name query rather than a window function, since it needs
+    /// its own `ORDER BY duration_ms` independent of the `GROUP BY name`
+    /// used for the rest of the aggregate.
+    pub fn get_tool_stats(&self, window_hours: i64) -> Result<Vec<ToolStat>> {
+        let since = format!("-{} hours", window_hours);
+
+        let mut stmt = self.conn.prepare(
+            "SELECT name,
+                    COUNT(*) AS call_count,
+                    SUM(CASE WHEN error IS NOT NULL AND error != '' THEN 1 ELSE 0 END) AS error_count,
+                    AVG(duration_ms) AS avg_duration_ms
+             FROM tool_calls
+             WHERE created_at >= datetime('now', ?1)
+             GROUP BY name
+             ORDER BY call_count DESC",
+        )?;
+        let rows: Vec<(String, i64, i64, f64)> = stmt
+            .query_map(params![since], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut stats = Vec::with_capacity(rows.len());
+        for (name, call_count, error_count, avg_duration_ms) in rows {
+            let p95_duration_ms = self.tool_call_p95_duration(&name, &since, call_count)?;
+            stats.push(ToolStat {
+                name,
+                call_count: call_count as u64,
+                error_count: error_count as u64,
+                error_rate: error_count as f64 / call_count.max(1) as f64,
+                avg_duration_ms,
+                p95_duration_ms,
+            });
+        }
+        Ok(stats)
+    }
+
+    /// The 95th-percentile `duration_ms` for `name` within `since` (a
+    /// `datetime('now', ?)` modifier), given its already-known `call_count`.
+    fn tool_call_p95_duration(&self, name: &str, since: &str, call_count: i64) -> Result<f64> {
+        let offset = ((call_count as f64) * 0.95) as i64;
+        let p95: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT duration_ms FROM tool_calls
+                 WHERE name = ?1 AND created_at >= datetime('now', ?2)
+                 ORDER BY duration_ms ASC LIMIT 1 OFFSET ?3",
+                params![name, since, offset],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(p95.unwrap_or(0) as f64)
+    }
+
     // ─── Heartbeat ───────────────────────────────────────────────
 
     pub fn get_heartbeat_entries(&self) -> Result<Vec<HeartbeatEntry>> {
@@ -244,14 +617,21 @@ impl Database {
     pub fn insert_transaction(&self, txn: &Transaction) -> Result<()> {
         let tx_type_str = serde_json::to_string(&txn.tx_type)?;
         let tx_type_str = tx_type_str.trim_matches('"');
+        let subcategory_str = txn
+            .subcategory
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()?
+            .map(|s| s.trim_matches('"').to_string());
         self.conn.execute(
-            "INSERT INTO transactions (id, type, amount_cents, balance_after_cents, description)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO transactions (id, type, amount_cents, balance_after_cents, subcategory, description)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
             params![
                 txn.id,
                 tx_type_str,
                 txn.amount_cents,
                 txn.balance_after_cents,
+                subcategory_str,
                 txn.description,
             ],
         )?;
@@ -260,19 +640,22 @@ impl Database {
 
     pub fn get_recent_transactions(&self, limit: i64) -> Result<Vec<Transaction>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, type, amount_cents, balance_after_cents, description, created_at
+            "SELECT id, type, amount_cents, balance_after_cents, subcategory, description, created_at
              FROM transactions ORDER BY created_at DESC LIMIT ?1",
         )?;
         let mut txns: Vec<Transaction> = stmt
             .query_map(params![limit], |row| {
                 let tx_type_str: String = row.get(1)?;
+                let subcategory_str: Option<String> = row.get(4)?;
                 Ok(Transaction {
                     id: row.get(0)?,
                     tx_type: serde_json::from_str(&format!("\"{}\"", tx_type_str)).unwrap_or(TransactionType::CreditCheck),
                     amount_cents: row.get(2)?,
                     balance_after_cents: row.get(3)?,
-                    description: row.get(4)?,
-                    timestamp: row.get(5)?,
+                    subcategory: subcategory_str
+                        .and_then(|s| serde_json::from_str(&format!("\"{}\"", s)).ok()),
+                    description: row.get(5)?,
+                    timestamp: row.get(6)?,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -367,6 +750,32 @@ impl Database {
         Ok(mods)
     }
 
+    /// Modifications with `timestamp > since` (or all, if `since` is
+    /// `None`), oldest first, capped to `limit` rows -- the `modifications`
+    /// counterpart to [`Database::get_events`], for tailing the audit trail
+    /// rather than just viewing the latest N (see `get_recent_modifications`).
+    pub fn get_modifications_since(&self, since: Option<&str>, limit: i64) -> Result<Vec<ModificationEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, type, description, file_path, diff, reversible
+             FROM modifications WHERE ?1 IS NULL OR timestamp > ?1 ORDER BY timestamp ASC LIMIT ?2",
+        )?;
+        let mods = stmt
+            .query_map(params![since, limit], |row| {
+                let mod_type_str: String = row.get(2)?;
+                Ok(ModificationEntry {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    mod_type: serde_json::from_str(&format!("\"{}\"", mod_type_str)).unwrap_or(ModificationType::CodeEdit),
+                    description: row.get(3)?,
+                    file_path: row.get(4)?,
+                    diff: row.get(5)?,
+                    reversible: row.get::<_, i32>(6)? != 0,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(mods)
+    }
+
     // ─── Key-Value Store ─────────────────────────────────────────
 
     pub fn get_kv(&self, key: &str) -> Result<Option<String>> {
@@ -462,7 +871,7 @@ impl Database {
 
     pub fn get_children(&self) -> Result<Vec<ChildAutomaton>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, address, sandbox_id, genesis_prompt, creator_message, funded_amount_cents, status, created_at, last_checked
+            "SELECT id, name, address, sandbox_id, genesis_prompt, creator_message, funded_amount_cents, status, created_at, last_checked, descendants_count, lineage_snapshot, mutation_summary
              FROM children ORDER BY created_at DESC",
         )?;
         let children = stmt
@@ -475,7 +884,7 @@ impl Database {
         let result = self
             .conn
             .query_row(
-                "SELECT id, name, address, sandbox_id, genesis_prompt, creator_message, funded_amount_cents, status, created_at, last_checked
+                "SELECT id, name, address, sandbox_id, genesis_prompt, creator_message, funded_amount_cents, status, created_at, last_checked, descendants_count, lineage_snapshot, mutation_summary
                  FROM children WHERE id = ?1",
                 params![id],
                 |row| Ok(Self::deserialize_child(row)),
@@ -488,8 +897,8 @@ impl Database {
         let status_str = serde_json::to_string(&child.status)?;
         let status_str = status_str.trim_matches('"');
         self.conn.execute(
-            "INSERT INTO children (id, name, address, sandbox_id, genesis_prompt, creator_message, funded_amount_cents, status, created_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            "INSERT INTO children (id, name, address, sandbox_id, genesis_prompt, creator_message, funded_amount_cents, status, created_at, mutation_summary)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
                 child.id,
                 child.name,
@@ -500,6 +909,7 @@ impl Database {
                 child.funded_amount_cents,
                 status_str,
                 child.created_at,
+                child.mutation_summary,
             ],
         )?;
         Ok(())
@@ -513,6 +923,281 @@ impl Database {
         Ok(())
     }
 
+    pub fn update_child_lineage(
+        &self,
+        id: &str,
+        descendants_count: u32,
+        lineage_snapshot: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE children SET descendants_count = ?1, lineage_snapshot = ?2 WHERE id = ?3",
+            params![descendants_count, lineage_snapshot, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_child_address(&self, id: &str, address: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE children SET address = ?1 WHERE id = ?2",
+            params![address, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn remove_child(&self, id: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM children WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    // ─── Exposed Ports ─────────────────────────────────────────
+
+    pub fn get_exposed_ports(&self) -> Result<Vec<ExposedPort>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT port, public_url, exposed_at FROM exposed_ports ORDER BY port")?;
+        let ports = stmt
+            .query_map([], |row| {
+                Ok(ExposedPort {
+                    port: row.get(0)?,
+                    public_url: row.get(1)?,
+                    exposed_at: row.get(2)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(ports)
+    }
+
+    pub fn upsert_exposed_port(&self, port: &ExposedPort) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO exposed_ports (port, public_url, exposed_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(port) DO UPDATE SET public_url = excluded.public_url, exposed_at = excluded.exposed_at",
+            params![port.port, port.public_url, port.exposed_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_exposed_port(&self, port: u16) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM exposed_ports WHERE port = ?1", params![port])?;
+        Ok(())
+    }
+
+    // ─── Created Sandboxes ─────────────────────────────────────
+
+    pub fn insert_created_sandbox(&self, entry: &CreatedSandboxEntry) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO created_sandboxes (sandbox_id, purpose, vcpu, memory_mb, disk_gb, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(sandbox_id) DO UPDATE SET purpose = excluded.purpose",
+            params![
+                entry.sandbox_id,
+                entry.purpose,
+                entry.vcpu,
+                entry.memory_mb,
+                entry.disk_gb,
+                entry.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_created_sandboxes(&self) -> Result<Vec<CreatedSandboxEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT sandbox_id, purpose, vcpu, memory_mb, disk_gb, created_at
+             FROM created_sandboxes ORDER BY created_at",
+        )?;
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(CreatedSandboxEntry {
+                    sandbox_id: row.get(0)?,
+                    purpose: row.get(1)?,
+                    vcpu: row.get(2)?,
+                    memory_mb: row.get(3)?,
+                    disk_gb: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    pub fn delete_created_sandbox(&self, sandbox_id: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM created_sandboxes WHERE sandbox_id = ?1",
+            params![sandbox_id],
+        )?;
+        Ok(())
+    }
+
+    // ─── Snapshots ─────────────────────────────────────────────
+
+    pub fn get_snapshots(&self) -> Result<Vec<Snapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, path, label, size_bytes, includes_wallet, created_at
+             FROM snapshots ORDER BY created_at DESC",
+        )?;
+        let snapshots = stmt
+            .query_map([], |row| {
+                Ok(Snapshot {
+                    id: row.get(0)?,
+                    path: row.get(1)?,
+                    label: row.get(2)?,
+                    size_bytes: row.get::<_, i64>(3)? as u64,
+                    includes_wallet: row.get::<_, i64>(4)? != 0,
+                    created_at: row.get(5)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(snapshots)
+    }
+
+    pub fn insert_snapshot(&self, snapshot: &Snapshot) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO snapshots (id, path, label, size_bytes, includes_wallet, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                snapshot.id,
+                snapshot.path,
+                snapshot.label,
+                snapshot.size_bytes as i64,
+                snapshot.includes_wallet as i64,
+                snapshot.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ─── Balance snapshots ─────────────────────────────────────
+
+    /// Most recent snapshots first, capped to `limit` rows.
+    pub fn get_balance_snapshots(&self, limit: i64) -> Result<Vec<BalanceSnapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, balance_cents, created_at FROM balance_snapshots
+             ORDER BY created_at DESC LIMIT ?1",
+        )?;
+        let snapshots = stmt
+            .query_map(params![limit], |row| {
+                Ok(BalanceSnapshot {
+                    id: row.get(0)?,
+                    balance_cents: row.get(1)?,
+                    created_at: row.get(2)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(snapshots)
+    }
+
+    /// Record a balance snapshot, then prune down to
+    /// [`BALANCE_SNAPSHOT_RETENTION`] rows so the table stays bounded.
+    pub fn insert_balance_snapshot(&self, snapshot: &BalanceSnapshot) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO balance_snapshots (id, balance_cents, created_at) VALUES (?1, ?2, ?3)",
+            params![snapshot.id, snapshot.balance_cents, snapshot.created_at],
+        )?;
+        self.conn.execute(
+            "DELETE FROM balance_snapshots WHERE id NOT IN (
+                SELECT id FROM balance_snapshots ORDER BY created_at DESC LIMIT ?1
+             )",
+            params![BALANCE_SNAPSHOT_RETENTION],
+        )?;
+        Ok(())
+    }
+
+    // ─── Operational event log ─────────────────────────────────
+
+    /// Events with `timestamp > since` (or all, if `since` is `None`),
+    /// oldest first, capped to `limit` rows.
+    pub fn get_events(&self, since: Option<&str>, limit: i64) -> Result<Vec<LoopEventRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, timestamp, kind, data FROM events
+             WHERE ?1 IS NULL OR timestamp > ?1
+             ORDER BY timestamp ASC LIMIT ?2",
+        )?;
+        let events = stmt
+            .query_map(params![since, limit], |row| {
+                let data_str: String = row.get(3)?;
+                Ok(LoopEventRecord {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    kind: row.get(2)?,
+                    data: serde_json::from_str(&data_str).unwrap_or(serde_json::Value::Null),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(events)
+    }
+
+    /// Append one event to the operational timeline. Append-only, like
+    /// `modifications` -- no retention pruning, since this is meant to be
+    /// the durable audit trail.
+    pub fn insert_event(&self, event: &LoopEventRecord) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO events (id, timestamp, kind, data) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                event.id,
+                event.timestamp,
+                event.kind,
+                serde_json::to_string(&event.data).unwrap_or_default(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    // ─── Pending input queue ───────────────────────────────────
+
+    fn deserialize_pending_input(row: &rusqlite::Row<'_>) -> rusqlite::Result<PendingInputEntry> {
+        let source_str: String = row.get(2)?;
+        Ok(PendingInputEntry {
+            id: row.get(0)?,
+            content: row.get(1)?,
+            source: serde_json::from_str(&format!("\"{}\"", source_str)).unwrap_or(InputSource::System),
+            priority: row.get(3)?,
+            dedup_key: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+
+    pub fn enqueue_pending_input(&self, entry: &PendingInputEntry) -> Result<()> {
+        let source_str = serde_json::to_string(&entry.source)?;
+        let source_str = source_str.trim_matches('"');
+        self.conn.execute(
+            "INSERT OR IGNORE INTO pending_inputs (id, content, source, priority, dedup_key, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                entry.id,
+                entry.content,
+                source_str,
+                entry.priority,
+                entry.dedup_key,
+                entry.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn dequeue_pending_input(&self) -> Result<Option<PendingInputEntry>> {
+        let entry = self
+            .conn
+            .query_row(
+                "SELECT id, content, source, priority, dedup_key, created_at
+                 FROM pending_inputs ORDER BY priority DESC, created_at ASC LIMIT 1",
+                [],
+                Self::deserialize_pending_input,
+            )
+            .optional()?;
+
+        if let Some(ref entry) = entry {
+            self.conn.execute("DELETE FROM pending_inputs WHERE id = ?1", params![entry.id])?;
+        }
+        Ok(entry)
+    }
+
+    pub fn pending_input_count(&self) -> Result<u32> {
+        let count: i64 = self.conn.query_row("SELECT COUNT(*) FROM pending_inputs", [], |row| row.get(0))?;
+        Ok(count as u32)
+    }
+
     // ─── Registry ──────────────────────────────────────────────
 
     pub fn get_registry_entry(&self) -> Result<Option<RegistryEntry>> {
@@ -651,6 +1336,267 @@ impl Database {
         Ok(())
     }
 
+    // ─── Outbox ──────────────────────────────────────────────────
+
+    pub fn enqueue_outbox(&self, entry: &OutboxEntry) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR IGNORE INTO outbox (id, to_address, content, reply_to, created_at, attempts)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                entry.id,
+                entry.to_address,
+                entry.content,
+                entry.reply_to,
+                entry.created_at,
+                entry.attempts,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_pending_outbox(&self, limit: i64) -> Result<Vec<OutboxEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, to_address, content, reply_to, created_at, sent_at, attempts, last_error
+             FROM outbox WHERE sent_at IS NULL ORDER BY created_at ASC LIMIT ?1",
+        )?;
+        let entries = stmt
+            .query_map(params![limit], |row| {
+                Ok(OutboxEntry {
+                    id: row.get(0)?,
+                    to_address: row.get(1)?,
+                    content: row.get(2)?,
+                    reply_to: row.get(3)?,
+                    created_at: row.get(4)?,
+                    sent_at: row.get(5)?,
+                    attempts: row.get(6)?,
+                    last_error: row.get(7)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(entries)
+    }
+
+    pub fn mark_sent(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE outbox SET sent_at = datetime('now') WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_outbox_failure(&self, id: &str, error: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE outbox SET attempts = attempts + 1, last_error = ?2 WHERE id = ?1",
+            params![id, error],
+        )?;
+        Ok(())
+    }
+
+    // ─── Outbound message rate limiting ─────────────────────────
+
+    pub fn record_outbound_message(&self, to_address: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO outbound_messages (id, to_address) VALUES (?1, ?2)",
+            params![uuid::Uuid::new_v4().to_string(), to_address],
+        )?;
+        Ok(())
+    }
+
+    pub fn count_outbound_messages(&self, to_address: Option<&str>, since: &str) -> Result<u32> {
+        match to_address {
+            Some(addr) => self.conn.query_row(
+                "SELECT COUNT(*) FROM outbound_messages WHERE to_address = ?1 AND created_at > ?2",
+                params![addr, since],
+                |row| row.get(0),
+            ),
+            None => self.conn.query_row(
+                "SELECT COUNT(*) FROM outbound_messages WHERE created_at > ?1",
+                params![since],
+                |row| row.get(0),
+            ),
+        }
+        .map_err(Into::into)
+    }
+
+    // ─── Goals ───────────────────────────────────────────────────
+
+    pub fn add_goal(&self, goal: &Goal) -> Result<()> {
+        let status_str = serde_json::to_string(&goal.status)?;
+        let status_str = status_str.trim_matches('"');
+        self.conn.execute(
+            "INSERT INTO goals (id, description, metric, target, current_value, status, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                goal.id,
+                goal.description,
+                goal.metric,
+                goal.target,
+                goal.current_value,
+                status_str,
+                goal.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Bumps `current_value`, auto-transitioning `status` from `active` to
+    /// `achieved` once `current_value >= target` -- done in SQL via a `CASE`
+    /// expression to avoid a select-then-update round trip.
+    pub fn update_goal_progress(&self, id: &str, value: f64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE goals SET current_value = ?1,
+                status = CASE WHEN status = 'active' AND ?1 >= target THEN 'achieved' ELSE status END
+             WHERE id = ?2",
+            params![value, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_goals(&self, active_only: bool) -> Result<Vec<Goal>> {
+        let query = if active_only {
+            "SELECT id, description, metric, target, current_value, status, created_at
+             FROM goals WHERE status = 'active' ORDER BY created_at DESC"
+        } else {
+            "SELECT id, description, metric, target, current_value, status, created_at
+             FROM goals ORDER BY created_at DESC"
+        };
+        let mut stmt = self.conn.prepare(query)?;
+        let goals = stmt
+            .query_map([], |row| Ok(Self::deserialize_goal(row)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(goals)
+    }
+
+    pub fn get_goal_by_id(&self, id: &str) -> Result<Option<Goal>> {
+        let result = self
+            .conn
+            .query_row(
+                "SELECT id, description, metric, target, current_value, status, created_at
+                 FROM goals WHERE id = ?1",
+                params![id],
+                |row| Ok(Self::deserialize_goal(row)),
+            )
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn abandon_goal(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE goals SET status = 'abandoned' WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    // ─── Scheduled actions ──────────────────────────────────────
+
+    fn deserialize_scheduled_action(row: &rusqlite::Row<'_>) -> rusqlite::Result<ScheduledAction> {
+        Ok(ScheduledAction {
+            id: row.get(0)?,
+            run_at: row.get(1)?,
+            input: row.get(2)?,
+            created_at: row.get(3)?,
+            fired_at: row.get(4)?,
+        })
+    }
+
+    pub fn insert_scheduled_action(&self, action: &ScheduledAction) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO scheduled_actions (id, run_at, input, created_at, fired_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![action.id, action.run_at, action.input, action.created_at, action.fired_at],
+        )?;
+        Ok(())
+    }
+
+    /// Not-yet-fired actions with `run_at <= now`, oldest first -- includes
+    /// ones that became due during downtime, so they still run once instead
+    /// of being skipped.
+    pub fn get_due_scheduled_actions(&self, now: &str) -> Result<Vec<ScheduledAction>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, run_at, input, created_at, fired_at
+             FROM scheduled_actions WHERE fired_at IS NULL AND run_at <= ?1 ORDER BY run_at ASC",
+        )?;
+        let actions = stmt
+            .query_map(params![now], Self::deserialize_scheduled_action)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(actions)
+    }
+
+    pub fn mark_scheduled_action_fired(&self, id: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE scheduled_actions SET fired_at = datetime('now') WHERE id = ?1",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    pub fn scheduled_action_count(&self) -> Result<u32> {
+        let count: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM scheduled_actions WHERE fired_at IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as u32)
+    }
+
+    // ─── History summaries ──────────────────────────────────────
+
+    fn deserialize_history_summary(row: &rusqlite::Row<'_>) -> rusqlite::Result<HistorySummary> {
+        Ok(HistorySummary {
+            id: row.get(0)?,
+            start_timestamp: row.get(1)?,
+            end_timestamp: row.get(2)?,
+            turn_count: row.get::<_, i64>(3)? as u32,
+            summary: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+
+    pub fn insert_history_summary(&self, summary: &HistorySummary) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO history_summaries (id, start_timestamp, end_timestamp, turn_count, summary, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                summary.id,
+                summary.start_timestamp,
+                summary.end_timestamp,
+                summary.turn_count,
+                summary.summary,
+                summary.created_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Most recent first, capped to `limit` rows.
+    pub fn get_history_summaries(&self, limit: i64) -> Result<Vec<HistorySummary>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, start_timestamp, end_timestamp, turn_count, summary, created_at
+             FROM history_summaries ORDER BY end_timestamp DESC LIMIT ?1",
+        )?;
+        let summaries = stmt
+            .query_map(params![limit], Self::deserialize_history_summary)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(summaries)
+    }
+
+    pub fn get_history_summary_watermark(&self) -> Result<Option<String>> {
+        let watermark: Option<String> = self.conn.query_row(
+            "SELECT MAX(end_timestamp) FROM history_summaries",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(watermark)
+    }
+
+    pub fn delete_turns(&self, ids: &[String]) -> Result<()> {
+        for id in ids {
+            self.conn.execute("DELETE FROM turns WHERE id = ?1", params![id])?;
+        }
+        Ok(())
+    }
+
     // ─── Agent State ─────────────────────────────────────────────
 
     pub fn get_agent_state(&self) -> Result<String> {
@@ -680,6 +1626,7 @@ impl Database {
         let token_usage_json: String = row.get(7).unwrap_or_default();
         let state_str: String = row.get(2).unwrap_or_default();
         let input_source_str: Option<String> = row.get(4).unwrap_or(None);
+        let financial_snapshot_json: Option<String> = row.get(9).unwrap_or(None);
 
         AgentTurn {
             id: row.get(0).unwrap_or_default(),
@@ -691,11 +1638,14 @@ impl Database {
             tool_calls: serde_json::from_str(&tool_calls_json).unwrap_or_default(),
             token_usage: serde_json::from_str(&token_usage_json).unwrap_or_default(),
             cost_cents: row.get(8).unwrap_or(0.0),
+            financial_snapshot: financial_snapshot_json
+                .and_then(|json| serde_json::from_str(&json).ok()),
         }
     }
 
     fn deserialize_tool_call(row: &rusqlite::Row<'_>) -> ToolCallResult {
         let args_json: String = row.get(2).unwrap_or_default();
+        let data_json: Option<String> = row.get(6).unwrap_or(None);
 
         ToolCallResult {
             id: row.get(0).unwrap_or_default(),
@@ -704,6 +1654,9 @@ impl Database {
             result: row.get(3).unwrap_or_default(),
             duration_ms: row.get(4).unwrap_or(0),
             error: row.get(5).unwrap_or(None),
+            data: data_json.and_then(|s| serde_json::from_str(&s).ok()),
+            sequence: row.get(7).unwrap_or(0),
+            started_at: row.get(8).unwrap_or_default(),
         }
     }
 
@@ -766,6 +1719,23 @@ impl Database {
             status: serde_json::from_str(&format!("\"{}\"", status_str)).unwrap_or(ChildStatus::Unknown),
             created_at: row.get(8).unwrap_or_default(),
             last_checked: row.get(9).unwrap_or(None),
+            descendants_count: row.get(10).unwrap_or(0),
+            lineage_snapshot: row.get(11).unwrap_or(None),
+            mutation_summary: row.get(12).unwrap_or(None),
+        }
+    }
+
+    fn deserialize_goal(row: &rusqlite::Row<'_>) -> Goal {
+        let status_str: String = row.get(5).unwrap_or_default();
+
+        Goal {
+            id: row.get(0).unwrap_or_default(),
+            description: row.get(1).unwrap_or_default(),
+            metric: row.get(2).unwrap_or_default(),
+            target: row.get(3).unwrap_or(0.0),
+            current_value: row.get(4).unwrap_or(0.0),
+            status: serde_json::from_str(&format!("\"{}\"", status_str)).unwrap_or(GoalStatus::Active),
+            created_at: row.get(6).unwrap_or_default(),
         }
     }
 
@@ -781,3 +1751,312 @@ impl Database {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_entry(content: &str, source: InputSource, priority: i32) -> PendingInputEntry {
+        PendingInputEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            content: content.to_string(),
+            source,
+            priority,
+            dedup_key: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_creator_message_jumps_ahead_of_queued_inbox_messages() {
+        let db = Database::open_in_memory().unwrap();
+
+        // Queue routine inbox chatter first, using this repo's default
+        // AutomatonConfig priority for InputSource::Agent.
+        let inbox_priority = crate::types::default_config().input_priorities.for_source(&InputSource::Agent);
+        db.enqueue_pending_input(&make_entry("inbox message 1", InputSource::Agent, inbox_priority)).unwrap();
+        db.enqueue_pending_input(&make_entry("inbox message 2", InputSource::Agent, inbox_priority)).unwrap();
+
+        // A creator message arrives after, but with higher priority.
+        let creator_priority = crate::types::default_config().input_priorities.for_source(&InputSource::Creator);
+        db.enqueue_pending_input(&make_entry("creator message", InputSource::Creator, creator_priority)).unwrap();
+
+        let first = db.dequeue_pending_input().unwrap().unwrap();
+        assert_eq!(first.content, "creator message");
+        assert_eq!(first.source, InputSource::Creator);
+
+        // The inbox backlog is still there, oldest first.
+        let second = db.dequeue_pending_input().unwrap().unwrap();
+        assert_eq!(second.content, "inbox message 1");
+        let third = db.dequeue_pending_input().unwrap().unwrap();
+        assert_eq!(third.content, "inbox message 2");
+        assert!(db.dequeue_pending_input().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_enqueue_pending_input_dedup_key_ignores_repeat() {
+        let db = Database::open_in_memory().unwrap();
+        let mut entry = make_entry("inbox message", InputSource::Agent, 40);
+        entry.dedup_key = Some("inbox:m1".to_string());
+        db.enqueue_pending_input(&entry).unwrap();
+
+        let mut duplicate = make_entry("inbox message (re-polled)", InputSource::Agent, 40);
+        duplicate.dedup_key = Some("inbox:m1".to_string());
+        db.enqueue_pending_input(&duplicate).unwrap();
+
+        assert_eq!(db.pending_input_count().unwrap(), 1);
+    }
+
+    fn make_event(timestamp: &str, kind: &str) -> LoopEventRecord {
+        LoopEventRecord {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: timestamp.to_string(),
+            kind: kind.to_string(),
+            data: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn test_get_events_returns_oldest_first() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_event(&make_event("2024-01-01T00:00:01Z", "turn_started")).unwrap();
+        db.insert_event(&make_event("2024-01-01T00:00:02Z", "slept")).unwrap();
+
+        let events = db.get_events(None, 10).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, "turn_started");
+        assert_eq!(events[1].kind, "slept");
+    }
+
+    #[test]
+    fn test_get_events_since_excludes_earlier_events() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_event(&make_event("2024-01-01T00:00:01Z", "turn_started")).unwrap();
+        db.insert_event(&make_event("2024-01-01T00:00:02Z", "slept")).unwrap();
+
+        let events = db.get_events(Some("2024-01-01T00:00:01Z"), 10).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, "slept");
+    }
+
+    fn make_outbox_entry(to_address: &str) -> OutboxEntry {
+        OutboxEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            to_address: to_address.to_string(),
+            content: "hello".to_string(),
+            reply_to: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            sent_at: None,
+            attempts: 0,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn test_get_pending_outbox_excludes_sent_entries() {
+        let db = Database::open_in_memory().unwrap();
+        let entry = make_outbox_entry("0xabc");
+        db.enqueue_outbox(&entry).unwrap();
+        db.mark_sent(&entry.id).unwrap();
+
+        assert!(db.get_pending_outbox(10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_record_outbox_failure_increments_attempts() {
+        let db = Database::open_in_memory().unwrap();
+        let entry = make_outbox_entry("0xabc");
+        db.enqueue_outbox(&entry).unwrap();
+        db.record_outbox_failure(&entry.id, "connection refused").unwrap();
+
+        let pending = db.get_pending_outbox(10).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+        assert_eq!(pending[0].last_error.as_deref(), Some("connection refused"));
+    }
+
+    #[test]
+    fn test_count_outbound_messages_scopes_by_recipient() {
+        let db = Database::open_in_memory().unwrap();
+        db.record_outbound_message("0xabc").unwrap();
+        db.record_outbound_message("0xabc").unwrap();
+        db.record_outbound_message("0xdef").unwrap();
+
+        let epoch = "1970-01-01T00:00:00Z";
+        assert_eq!(db.count_outbound_messages(Some("0xabc"), epoch).unwrap(), 2);
+        assert_eq!(db.count_outbound_messages(Some("0xdef"), epoch).unwrap(), 1);
+        assert_eq!(db.count_outbound_messages(None, epoch).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_count_outbound_messages_excludes_messages_before_since() {
+        let db = Database::open_in_memory().unwrap();
+        db.record_outbound_message("0xabc").unwrap();
+
+        let future = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        assert_eq!(db.count_outbound_messages(Some("0xabc"), &future).unwrap(), 0);
+    }
+
+    fn make_goal(description: &str, target: f64) -> Goal {
+        Goal {
+            id: uuid::Uuid::new_v4().to_string(),
+            description: description.to_string(),
+            metric: "usd_earned".to_string(),
+            target,
+            current_value: 0.0,
+            status: GoalStatus::Active,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_update_goal_progress_auto_achieves_at_target() {
+        let db = Database::open_in_memory().unwrap();
+        let goal = make_goal("earn $5", 5.0);
+        db.add_goal(&goal).unwrap();
+
+        db.update_goal_progress(&goal.id, 3.0).unwrap();
+        let fetched = db.get_goal_by_id(&goal.id).unwrap().unwrap();
+        assert_eq!(fetched.status, GoalStatus::Active);
+
+        db.update_goal_progress(&goal.id, 5.0).unwrap();
+        let fetched = db.get_goal_by_id(&goal.id).unwrap().unwrap();
+        assert_eq!(fetched.status, GoalStatus::Achieved);
+        assert_eq!(fetched.current_value, 5.0);
+    }
+
+    #[test]
+    fn test_get_goals_active_only_excludes_abandoned_and_achieved() {
+        let db = Database::open_in_memory().unwrap();
+        let active = make_goal("stay active", 10.0);
+        let abandoned = make_goal("give up", 10.0);
+        let achieved = make_goal("already done", 1.0);
+        db.add_goal(&active).unwrap();
+        db.add_goal(&abandoned).unwrap();
+        db.add_goal(&achieved).unwrap();
+        db.abandon_goal(&abandoned.id).unwrap();
+        db.update_goal_progress(&achieved.id, 1.0).unwrap();
+
+        let goals = db.get_goals(true).unwrap();
+        assert_eq!(goals.len(), 1);
+        assert_eq!(goals[0].id, active.id);
+
+        assert_eq!(db.get_goals(false).unwrap().len(), 3);
+    }
+
+    fn make_scheduled_action(run_at: &str, input: &str) -> ScheduledAction {
+        ScheduledAction {
+            id: uuid::Uuid::new_v4().to_string(),
+            run_at: run_at.to_string(),
+            input: input.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            fired_at: None,
+        }
+    }
+
+    #[test]
+    fn test_get_due_scheduled_actions_excludes_future_and_fired() {
+        let db = Database::open_in_memory().unwrap();
+        let past = make_scheduled_action("2020-01-01T00:00:00Z", "check the API");
+        let future = make_scheduled_action("2999-01-01T00:00:00Z", "not yet");
+        let already_fired = make_scheduled_action("2020-01-01T00:00:00Z", "already ran");
+        db.insert_scheduled_action(&past).unwrap();
+        db.insert_scheduled_action(&future).unwrap();
+        db.insert_scheduled_action(&already_fired).unwrap();
+        db.mark_scheduled_action_fired(&already_fired.id).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let due = db.get_due_scheduled_actions(&now).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].id, past.id);
+    }
+
+    #[test]
+    fn test_scheduled_action_count_excludes_fired() {
+        let db = Database::open_in_memory().unwrap();
+        let a = make_scheduled_action("2020-01-01T00:00:00Z", "a");
+        let b = make_scheduled_action("2020-01-01T00:00:00Z", "b");
+        db.insert_scheduled_action(&a).unwrap();
+        db.insert_scheduled_action(&b).unwrap();
+        assert_eq!(db.scheduled_action_count().unwrap(), 2);
+
+        db.mark_scheduled_action_fired(&a.id).unwrap();
+        assert_eq!(db.scheduled_action_count().unwrap(), 1);
+    }
+
+    fn make_turn(id: &str, timestamp: &str) -> AgentTurn {
+        AgentTurn {
+            id: id.to_string(),
+            timestamp: timestamp.to_string(),
+            state: AgentState::Running,
+            input: None,
+            input_source: None,
+            thinking: "did some stuff".to_string(),
+            tool_calls: vec![],
+            token_usage: Default::default(),
+            cost_cents: 0.0,
+            financial_snapshot: None,
+        }
+    }
+
+    #[test]
+    fn test_get_turns_for_summary_respects_after_and_before_bounds() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_turn(&make_turn("t1", "2020-01-01T00:00:00Z")).unwrap();
+        db.insert_turn(&make_turn("t2", "2020-01-02T00:00:00Z")).unwrap();
+        db.insert_turn(&make_turn("t3", "2020-01-03T00:00:00Z")).unwrap();
+        db.insert_turn(&make_turn("t4", "2020-01-04T00:00:00Z")).unwrap();
+
+        let all_before = db.get_turns_for_summary(None, "2020-01-04T00:00:00Z", 10).unwrap();
+        assert_eq!(all_before.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["t1", "t2", "t3"]);
+
+        let after_watermark = db
+            .get_turns_for_summary(Some("2020-01-01T00:00:00Z"), "2020-01-04T00:00:00Z", 10)
+            .unwrap();
+        assert_eq!(after_watermark.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["t2", "t3"]);
+
+        let capped = db.get_turns_for_summary(None, "2020-01-04T00:00:00Z", 1).unwrap();
+        assert_eq!(capped.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["t1"]);
+    }
+
+    #[test]
+    fn test_delete_turns_removes_only_given_ids() {
+        let db = Database::open_in_memory().unwrap();
+        db.insert_turn(&make_turn("t1", "2020-01-01T00:00:00Z")).unwrap();
+        db.insert_turn(&make_turn("t2", "2020-01-02T00:00:00Z")).unwrap();
+
+        db.delete_turns(&["t1".to_string()]).unwrap();
+
+        assert_eq!(db.get_turn_count().unwrap(), 1);
+        assert!(db.get_turn_by_id("t2").unwrap().is_some());
+    }
+
+    fn make_history_summary(end_timestamp: &str) -> HistorySummary {
+        HistorySummary {
+            id: uuid::Uuid::new_v4().to_string(),
+            start_timestamp: "2020-01-01T00:00:00Z".to_string(),
+            end_timestamp: end_timestamp.to_string(),
+            turn_count: 3,
+            summary: "did some stuff".to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_history_summary_watermark_tracks_latest_end_timestamp() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(db.get_history_summary_watermark().unwrap(), None);
+
+        db.insert_history_summary(&make_history_summary("2020-01-05T00:00:00Z")).unwrap();
+        db.insert_history_summary(&make_history_summary("2020-01-10T00:00:00Z")).unwrap();
+
+        assert_eq!(
+            db.get_history_summary_watermark().unwrap(),
+            Some("2020-01-10T00:00:00Z".to_string())
+        );
+
+        let summaries = db.get_history_summaries(10).unwrap();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].end_timestamp, "2020-01-10T00:00:00Z");
+    }
+}
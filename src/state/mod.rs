@@ -2,11 +2,42 @@
 //!
 //! SQLite-backed persistent state for the automaton.
 //! The database IS the automaton's memory.
+//!
+//! ## Concurrency model
+//!
+//! The agent loop and the heartbeat daemon run concurrently in the same
+//! process, and both touch this database. Rather than a connection pool or
+//! a dedicated actor task, each side just opens its own [`Database`]:
+//! the agent loop keeps one shared connection behind `Arc<Mutex<Database>>`
+//! (wrapped by [`DatabaseAdapter`]), while each heartbeat task calls
+//! [`Database::open`] fresh against the same path and closes it when done
+//! (see `src/heartbeat/tasks.rs`). WAL mode plus a `busy_timeout` (set in
+//! [`Database::open`]) make that safe: readers never block writers, and a
+//! writer that collides with another connection retries instead of failing
+//! with `SQLITE_BUSY`.
+//!
+//! The one rule this depends on: never hold the `Mutex<Database>` lock
+//! across an `.await`. A lock held into a suspended future can stall every
+//! other task waiting on it for as long as that future takes to resume,
+//! and on a current-thread runtime that's a real deadlock, not just
+//! latency. Always lock, make the synchronous call, and let the guard drop
+//! before the next `.await` -- see the `{ let db_lock = db.lock()...; ... }`
+//! blocks in `agent_loop.rs` for the pattern.
+//!
+//! [`DatabaseActor`] is an opt-in alternative to `Arc<Mutex<Database>>` +
+//! [`DatabaseAdapter`] that removes that rule entirely rather than relying
+//! on callers to follow it: it owns the connection on its own thread and
+//! takes commands over a channel, so there's never a lock for an `.await`
+//! to be held across. It's not the default -- `Database`/[`DatabaseAdapter`]
+//! stay that, including for the sync test paths -- but it's there for
+//! call sites that would rather not think about the rule above at all.
 
+mod actor;
 mod adapter;
 mod database;
 mod schema;
 
+pub use actor::DatabaseActor;
 pub use adapter::DatabaseAdapter;
 pub use database::Database;
 pub use schema::{CREATE_TABLES, MIGRATION_V2, MIGRATION_V3, SCHEMA_VERSION};
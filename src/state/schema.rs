@@ -4,7 +4,7 @@
 //! The database IS the automaton's memory.
 
 /// Current schema version. Increment when adding migrations.
-pub const SCHEMA_VERSION: i64 = 3;
+pub const SCHEMA_VERSION: i64 = 22;
 
 /// Full schema creation SQL (includes all tables through v3).
 pub const CREATE_TABLES: &str = r#"
@@ -31,9 +31,23 @@ pub const CREATE_TABLES: &str = r#"
     tool_calls TEXT NOT NULL DEFAULT '[]',
     token_usage TEXT NOT NULL DEFAULT '{}',
     cost_cents INTEGER NOT NULL DEFAULT 0,
+    financial_snapshot TEXT,
+    prompt_hash TEXT,
     created_at TEXT NOT NULL DEFAULT (datetime('now'))
   );
 
+  -- Compressed copies of the exact prompt sent to the model for a turn, kept
+  -- only for the most recent turns (see TURN_PROMPT_RETENTION). The hash on
+  -- `turns.prompt_hash` survives pruning so audits can still verify a prompt
+  -- if the operator has an independent copy to compare against.
+  CREATE TABLE IF NOT EXISTS turn_prompts (
+    turn_id TEXT PRIMARY KEY REFERENCES turns(id),
+    compressed_prompt BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_turn_prompts_created ON turn_prompts(created_at);
+
   -- Tool call results (denormalized for fast lookup)
   CREATE TABLE IF NOT EXISTS tool_calls (
     id TEXT PRIMARY KEY,
@@ -43,6 +57,9 @@ pub const CREATE_TABLES: &str = r#"
     result TEXT NOT NULL DEFAULT '',
     duration_ms INTEGER NOT NULL DEFAULT 0,
     error TEXT,
+    data TEXT,
+    sequence INTEGER NOT NULL DEFAULT 0,
+    started_at TEXT NOT NULL DEFAULT '',
     created_at TEXT NOT NULL DEFAULT (datetime('now'))
   );
 
@@ -65,6 +82,7 @@ pub const CREATE_TABLES: &str = r#"
     type TEXT NOT NULL,
     amount_cents INTEGER,
     balance_after_cents INTEGER,
+    subcategory TEXT,
     description TEXT NOT NULL DEFAULT '',
     created_at TEXT NOT NULL DEFAULT (datetime('now'))
   );
@@ -122,7 +140,10 @@ pub const CREATE_TABLES: &str = r#"
     funded_amount_cents INTEGER NOT NULL DEFAULT 0,
     status TEXT NOT NULL DEFAULT 'spawning',
     created_at TEXT NOT NULL DEFAULT (datetime('now')),
-    last_checked TEXT
+    last_checked TEXT,
+    descendants_count INTEGER NOT NULL DEFAULT 0,
+    lineage_snapshot TEXT,
+    mutation_summary TEXT
   );
 
   -- ERC-8004 registration state
@@ -150,6 +171,8 @@ pub const CREATE_TABLES: &str = r#"
   CREATE INDEX IF NOT EXISTS idx_turns_timestamp ON turns(timestamp);
   CREATE INDEX IF NOT EXISTS idx_turns_state ON turns(state);
   CREATE INDEX IF NOT EXISTS idx_tool_calls_turn ON tool_calls(turn_id);
+  CREATE INDEX IF NOT EXISTS idx_tool_calls_created ON tool_calls(created_at);
+  CREATE INDEX IF NOT EXISTS idx_tool_calls_name_created ON tool_calls(name, created_at);
   CREATE INDEX IF NOT EXISTS idx_transactions_type ON transactions(type);
   CREATE INDEX IF NOT EXISTS idx_modifications_type ON modifications(type);
   CREATE INDEX IF NOT EXISTS idx_skills_enabled ON skills(enabled);
@@ -168,6 +191,359 @@ pub const CREATE_TABLES: &str = r#"
 
   CREATE INDEX IF NOT EXISTS idx_inbox_unprocessed
     ON inbox_messages(received_at) WHERE processed_at IS NULL;
+
+  -- Ports this automaton has durably recorded as exposed
+  CREATE TABLE IF NOT EXISTS exposed_ports (
+    port INTEGER PRIMARY KEY,
+    public_url TEXT NOT NULL,
+    exposed_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  -- Coarse-grained backups of ~/.automaton, independent of git, taken via
+  -- the `snapshot` tool (and automatically before risky self-mod ops)
+  CREATE TABLE IF NOT EXISTS snapshots (
+    id TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    label TEXT NOT NULL DEFAULT '',
+    size_bytes INTEGER NOT NULL DEFAULT 0,
+    includes_wallet INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  -- FIFO-by-priority queue of turn inputs (wakeup, inbox, creator, ...)
+  -- awaiting processing, persisted so a restart doesn't drop them.
+  CREATE TABLE IF NOT EXISTS pending_inputs (
+    id TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    source TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    dedup_key TEXT UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_pending_inputs_priority
+    ON pending_inputs(priority DESC, created_at ASC);
+
+  -- Periodic snapshots of the Conway credit balance, taken by the
+  -- record_balance_snapshot heartbeat task, so the credit_history tool can
+  -- report a trend instead of a single point-in-time number.
+  CREATE TABLE IF NOT EXISTS balance_snapshots (
+    id TEXT PRIMARY KEY,
+    balance_cents INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_balance_snapshots_created ON balance_snapshots(created_at);
+
+  -- Append-only operational timeline: turn started, inference called, tool
+  -- invoked, state changed, slept -- distinct from `turns` (the model-facing
+  -- memory), meant for fleet monitoring, live streaming, and post-hoc
+  -- debugging.
+  CREATE TABLE IF NOT EXISTS events (
+    id TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    data TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events(timestamp);
+
+  -- Outbound messages that couldn't be delivered immediately, retried by
+  -- the retry_outbox heartbeat task until the relay accepts them.
+  CREATE TABLE IF NOT EXISTS outbox (
+    id TEXT PRIMARY KEY,
+    to_address TEXT NOT NULL,
+    content TEXT NOT NULL,
+    reply_to TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    sent_at TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_outbox_pending
+    ON outbox(created_at) WHERE sent_at IS NULL;
+
+  -- Log of every outbound social message actually attempted, used to
+  -- enforce send_message's rate limits (per-recipient and total-per-hour).
+  CREATE TABLE IF NOT EXISTS outbound_messages (
+    id TEXT PRIMARY KEY,
+    to_address TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_outbound_messages_to ON outbound_messages(to_address, created_at);
+  CREATE INDEX IF NOT EXISTS idx_outbound_messages_created ON outbound_messages(created_at);
+
+  -- Self-set objectives, giving the "create value or die" imperative a
+  -- structured, trackable home -- see `add_goal` and friends.
+  CREATE TABLE IF NOT EXISTS goals (
+    id TEXT PRIMARY KEY,
+    description TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    target REAL NOT NULL,
+    current_value REAL NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'active',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_goals_status ON goals(status);
+
+  -- One-off deferred work scheduled via the schedule_action tool, injected
+  -- into pending_inputs once due by the check_scheduled_actions heartbeat
+  -- task. fired_at IS NULL means still pending.
+  CREATE TABLE IF NOT EXISTS scheduled_actions (
+    id TEXT PRIMARY KEY,
+    run_at TEXT NOT NULL,
+    input TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    fired_at TEXT
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_scheduled_actions_due
+    ON scheduled_actions(run_at) WHERE fired_at IS NULL;
+
+  -- Model-generated rollups of pruned turn ranges, produced by the
+  -- summarize_history tool so old turns can be deleted from `turns` without
+  -- losing the long-term narrative. Surfaced back to the model as
+  -- "long-term memory" in the system/wakeup prompt.
+  CREATE TABLE IF NOT EXISTS history_summaries (
+    id TEXT PRIMARY KEY,
+    start_timestamp TEXT NOT NULL,
+    end_timestamp TEXT NOT NULL,
+    turn_count INTEGER NOT NULL,
+    summary TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_history_summaries_end
+    ON history_summaries(end_timestamp);
+"#;
+
+/// Migration from v18 to v19: adds the scheduled_actions table, backing the
+/// schedule_action tool's one-off deferred work (see
+/// `heartbeat::tasks::check_scheduled_actions`).
+pub const MIGRATION_V19: &str = r#"
+  CREATE TABLE IF NOT EXISTS scheduled_actions (
+    id TEXT PRIMARY KEY,
+    run_at TEXT NOT NULL,
+    input TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    fired_at TEXT
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_scheduled_actions_due
+    ON scheduled_actions(run_at) WHERE fired_at IS NULL;
+"#;
+
+/// Migration from v19 to v20: adds the history_summaries table, backing the
+/// summarize_history tool's long-term memory rollups (see
+/// `agent::context::summarize_turns`).
+pub const MIGRATION_V20: &str = r#"
+  CREATE TABLE IF NOT EXISTS history_summaries (
+    id TEXT PRIMARY KEY,
+    start_timestamp TEXT NOT NULL,
+    end_timestamp TEXT NOT NULL,
+    turn_count INTEGER NOT NULL,
+    summary TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_history_summaries_end
+    ON history_summaries(end_timestamp);
+"#;
+
+/// Migration from v20 to v21: adds the created_sandboxes table, tracking
+/// sub-task sandboxes created via `create_sandbox` (never the automaton's
+/// own) so `heartbeat::tasks::reap_idle_sandboxes` can warn about or
+/// auto-delete ones that have gone idle too long.
+pub const MIGRATION_V21: &str = r#"
+  CREATE TABLE IF NOT EXISTS created_sandboxes (
+    sandbox_id TEXT PRIMARY KEY,
+    purpose TEXT,
+    vcpu INTEGER NOT NULL,
+    memory_mb INTEGER NOT NULL,
+    disk_gb INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+"#;
+
+/// Migration from v21 to v22: records what [`crate::replication::genesis::apply_mutation`]
+/// varied away from the parent's defaults for a given child, if anything,
+/// so the lineage record explains why two siblings differ.
+pub const MIGRATION_V22: &str = r#"
+  ALTER TABLE children ADD COLUMN mutation_summary TEXT;
+"#;
+
+/// Migration from v14 to v15: adds the events table -- an append-only
+/// operational timeline distinct from `turns`, per `AgentLoopEvent`.
+pub const MIGRATION_V15: &str = r#"
+  CREATE TABLE IF NOT EXISTS events (
+    id TEXT PRIMARY KEY,
+    timestamp TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    data TEXT NOT NULL DEFAULT '{}',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events(timestamp);
+"#;
+
+/// Migration from v15 to v16: adds the outbox table so outbound messages
+/// survive a relay outage instead of failing the turn -- see `send_message`
+/// and the `retry_outbox` heartbeat task.
+pub const MIGRATION_V16: &str = r#"
+  CREATE TABLE IF NOT EXISTS outbox (
+    id TEXT PRIMARY KEY,
+    to_address TEXT NOT NULL,
+    content TEXT NOT NULL,
+    reply_to TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    sent_at TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_outbox_pending
+    ON outbox(created_at) WHERE sent_at IS NULL;
+"#;
+
+/// Migration from v16 to v17: adds the outbound_messages log used to enforce
+/// send_message's rate limits (per-recipient and total-per-hour).
+pub const MIGRATION_V17: &str = r#"
+  CREATE TABLE IF NOT EXISTS outbound_messages (
+    id TEXT PRIMARY KEY,
+    to_address TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_outbound_messages_to ON outbound_messages(to_address, created_at);
+  CREATE INDEX IF NOT EXISTS idx_outbound_messages_created ON outbound_messages(created_at);
+"#;
+
+/// Migration from v17 to v18: adds the goals table, giving the
+/// "create value or die" imperative a structured, trackable home -- see
+/// `add_goal` and friends.
+pub const MIGRATION_V18: &str = r#"
+  CREATE TABLE IF NOT EXISTS goals (
+    id TEXT PRIMARY KEY,
+    description TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    target REAL NOT NULL,
+    current_value REAL NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'active',
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_goals_status ON goals(status);
+"#;
+
+/// Migration from v13 to v14: adds `sequence` and `started_at` to
+/// `tool_calls` so the order the model requested calls in (and whether they
+/// overlapped in time) survives concurrent execution -- see
+/// `agent_loop::execute_one_tool_call`.
+pub const MIGRATION_V14: &str = r#"
+  ALTER TABLE tool_calls ADD COLUMN sequence INTEGER NOT NULL DEFAULT 0;
+  ALTER TABLE tool_calls ADD COLUMN started_at TEXT NOT NULL DEFAULT '';
+"#;
+
+/// Migration from v12 to v13: adds indexes so `get_tool_stats` can aggregate
+/// `tool_calls` by name over a time window without a full table scan.
+pub const MIGRATION_V13: &str = r#"
+  CREATE INDEX IF NOT EXISTS idx_tool_calls_created ON tool_calls(created_at);
+  CREATE INDEX IF NOT EXISTS idx_tool_calls_name_created ON tool_calls(name, created_at);
+"#;
+
+/// Migration from v11 to v12: adds a subcategory column to transactions, so
+/// `profit_loss` can tell earnings apart from non-income inflows like
+/// creator funding.
+pub const MIGRATION_V12: &str = r#"
+  ALTER TABLE transactions ADD COLUMN subcategory TEXT;
+"#;
+
+/// Migration from v10 to v11: adds the balance_snapshots table.
+pub const MIGRATION_V11: &str = r#"
+  CREATE TABLE IF NOT EXISTS balance_snapshots (
+    id TEXT PRIMARY KEY,
+    balance_cents INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_balance_snapshots_created ON balance_snapshots(created_at);
+"#;
+
+/// Migration from v9 to v10: adds the pending_inputs queue table.
+pub const MIGRATION_V10: &str = r#"
+  CREATE TABLE IF NOT EXISTS pending_inputs (
+    id TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    source TEXT NOT NULL,
+    priority INTEGER NOT NULL DEFAULT 0,
+    dedup_key TEXT UNIQUE,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_pending_inputs_priority
+    ON pending_inputs(priority DESC, created_at ASC);
+"#;
+
+/// Migration from v8 to v9: adds the snapshots table.
+pub const MIGRATION_V9: &str = r#"
+  CREATE TABLE IF NOT EXISTS snapshots (
+    id TEXT PRIMARY KEY,
+    path TEXT NOT NULL,
+    label TEXT NOT NULL DEFAULT '',
+    size_bytes INTEGER NOT NULL DEFAULT 0,
+    includes_wallet INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+"#;
+
+/// Migration from v7 to v8: adds a `data` column to `tool_calls` so tools
+/// can attach machine-readable output alongside their prose result, per
+/// [`crate::types::ToolOutput`].
+pub const MIGRATION_V8: &str = r#"
+  ALTER TABLE tool_calls ADD COLUMN data TEXT;
+"#;
+
+/// Migration from v6 to v7: adds a prompt hash column to `turns` and a
+/// bounded side table of compressed rendered prompts, for auditing exactly
+/// what was sent to the model on a given turn.
+pub const MIGRATION_V7: &str = r#"
+  ALTER TABLE turns ADD COLUMN prompt_hash TEXT;
+
+  CREATE TABLE IF NOT EXISTS turn_prompts (
+    turn_id TEXT PRIMARY KEY REFERENCES turns(id),
+    compressed_prompt BLOB NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+
+  CREATE INDEX IF NOT EXISTS idx_turn_prompts_created ON turn_prompts(created_at);
+"#;
+
+/// Migration from v5 to v6: adds a per-turn financial snapshot so
+/// `--replay` can reconstruct the prompt as it was, without guessing at a
+/// credit balance that has since moved on.
+pub const MIGRATION_V6: &str = r#"
+  ALTER TABLE turns ADD COLUMN financial_snapshot TEXT;
+"#;
+
+/// Migration from v4 to v5: adds the exposed_ports table.
+pub const MIGRATION_V5: &str = r#"
+  CREATE TABLE IF NOT EXISTS exposed_ports (
+    port INTEGER PRIMARY KEY,
+    public_url TEXT NOT NULL,
+    exposed_at TEXT NOT NULL DEFAULT (datetime('now'))
+  );
+"#;
+
+/// Migration from v3 to v4: adds denormalized lineage tracking to children,
+/// so multi-generation descendant counts can be shown without direct
+/// parent-grandchild connectivity.
+pub const MIGRATION_V4: &str = r#"
+  ALTER TABLE children ADD COLUMN descendants_count INTEGER NOT NULL DEFAULT 0;
+  ALTER TABLE children ADD COLUMN lineage_snapshot TEXT;
 "#;
 
 /// Migration from v1 to v2: adds skills, children, registry, reputation tables.
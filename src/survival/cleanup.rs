@@ -0,0 +1,99 @@
+//! Disk Cleanup
+//!
+//! Actions `monitor::check_resources` runs once disk usage crosses
+//! `DISK_LOW_THRESHOLD_PERCENT`, before a full disk becomes fatal: prune old
+//! turns, reclaim the freed space with `VACUUM`, and drop rotated
+//! inference-log backups.
+
+use rusqlite::params;
+use tracing::{info, warn};
+
+use crate::identity::wallet::get_automaton_dir;
+
+/// Turns kept per cleanup pass -- deliberately tighter than
+/// day-to-day operation, since this only runs once disk is already low.
+const CLEANUP_TURN_RETENTION: i64 = 100;
+
+/// What a cleanup pass actually did, so the caller can report/log it.
+#[derive(Debug, Default, Clone)]
+pub struct CleanupSummary {
+    pub turns_pruned: usize,
+    pub log_backups_removed: usize,
+}
+
+/// Prune old turns (and their `tool_calls`/`turn_prompts` rows, deleted
+/// first to satisfy the `turns(id)` foreign keys), `VACUUM` the database,
+/// and delete rotated inference-log backups. Each step is best-effort: a
+/// failure is logged and the remaining steps still run, since a partial
+/// cleanup is better than none.
+pub fn perform_disk_cleanup(conn: &rusqlite::Connection) -> CleanupSummary {
+    let mut summary = CleanupSummary::default();
+
+    let prune_result = (|| -> rusqlite::Result<usize> {
+        conn.execute(
+            "DELETE FROM tool_calls WHERE turn_id IN (
+                SELECT id FROM turns WHERE id NOT IN (
+                    SELECT id FROM turns ORDER BY timestamp DESC LIMIT ?1
+                )
+             )",
+            params![CLEANUP_TURN_RETENTION],
+        )?;
+        conn.execute(
+            "DELETE FROM turn_prompts WHERE turn_id IN (
+                SELECT id FROM turns WHERE id NOT IN (
+                    SELECT id FROM turns ORDER BY timestamp DESC LIMIT ?1
+                )
+             )",
+            params![CLEANUP_TURN_RETENTION],
+        )?;
+        conn.execute(
+            "DELETE FROM turns WHERE id NOT IN (
+                SELECT id FROM turns ORDER BY timestamp DESC LIMIT ?1
+             )",
+            params![CLEANUP_TURN_RETENTION],
+        )
+    })();
+
+    match prune_result {
+        Ok(deleted) => summary.turns_pruned = deleted,
+        Err(e) => warn!("Disk cleanup: failed to prune turns: {}", e),
+    }
+
+    if let Err(e) = conn.execute_batch("VACUUM") {
+        warn!("Disk cleanup: VACUUM failed: {}", e);
+    }
+
+    match remove_log_backups() {
+        Ok(count) => summary.log_backups_removed = count,
+        Err(e) => warn!("Disk cleanup: failed to remove log backups: {}", e),
+    }
+
+    info!(
+        "Disk cleanup: pruned {} turns, removed {} log backups",
+        summary.turns_pruned, summary.log_backups_removed
+    );
+
+    summary
+}
+
+/// Delete rotated `inference.log.N` backups (the active `inference.log`
+/// keeps logging), freeing most of the space that log takes without losing
+/// current visibility into it.
+fn remove_log_backups() -> std::io::Result<usize> {
+    let dir = get_automaton_dir();
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+
+    let mut removed = 0;
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with("inference.log.") {
+            std::fs::remove_file(entry.path())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,95 @@
+//! Health Endpoint
+//!
+//! `heartbeat_ping` used to only write `last_heartbeat_ping` to local KV --
+//! despite its tool description promising to "show the world you are
+//! alive," nothing was actually published externally. This hosts that ping
+//! at a small HTTP endpoint (same write_file + exec + expose_port pattern
+//! `registry::agent_card::host_agent_card` uses) so the creator can check
+//! liveness with a plain HTTP request instead of SSHing in.
+
+use anyhow::{Context, Result};
+
+use crate::types::ConwayClient;
+
+/// Default port the health endpoint listens on inside the sandbox.
+pub const HEALTH_ENDPOINT_PORT: u16 = 8787;
+
+/// Path (inside the sandbox) the ping payload is written to. The health
+/// server reads this file fresh on every request, so publishing a new ping
+/// is just an overwrite -- no server restart needed.
+const PING_FILE_PATH: &str = "/tmp/automaton-last-ping.json";
+
+/// Path of the health server script itself.
+const SERVER_SCRIPT_PATH: &str = "/tmp/automaton-health-server.js";
+
+/// Write the latest ping payload to the file the health server serves.
+pub async fn publish_ping(conway: &dyn ConwayClient, ping_json: &str) -> Result<()> {
+    conway
+        .write_file(PING_FILE_PATH, ping_json)
+        .await
+        .context("Failed to write ping payload")
+}
+
+/// Ensure the health server is running and exposed, starting it if this is
+/// the first ping. Idempotent: a `curl` probe skips the start if something
+/// is already answering on `port`, so repeated pings don't spawn a new
+/// server every time. Returns the publicly reachable `/health` URL.
+pub async fn ensure_health_endpoint(conway: &dyn ConwayClient, port: u16) -> Result<String> {
+    let probe = conway
+        .exec(
+            &format!(
+                "curl -s -o /dev/null -w '%{{http_code}}' http://localhost:{}/health",
+                port
+            ),
+            Some(5_000),
+        )
+        .await;
+    let already_running = matches!(probe, Ok(r) if r.stdout.trim() == "200");
+
+    if !already_running {
+        let server_script = format!(
+            r#"
+const http = require('http');
+const fs = require('fs');
+
+const server = http.createServer((req, res) => {{
+  if (req.url === '/health' || req.url === '/') {{
+    fs.readFile('{ping_file}', 'utf8', (err, data) => {{
+      if (err) {{
+        res.writeHead(503, {{ 'Content-Type': 'application/json' }});
+        res.end(JSON.stringify({{ error: 'no ping recorded yet' }}));
+        return;
+      }}
+      res.writeHead(200, {{ 'Content-Type': 'application/json', 'Access-Control-Allow-Origin': '*' }});
+      res.end(data);
+    }});
+  }} else {{
+    res.writeHead(404);
+    res.end('Not Found');
+  }}
+}});
+
+server.listen({port}, () => console.log('Health endpoint listening on port {port}'));
+"#,
+            ping_file = PING_FILE_PATH,
+            port = port,
+        );
+
+        conway
+            .write_file(SERVER_SCRIPT_PATH, &server_script)
+            .await
+            .context("Failed to write health server script")?;
+
+        conway
+            .exec(&format!("node {} &", SERVER_SCRIPT_PATH), Some(5_000))
+            .await
+            .context("Failed to start health server")?;
+    }
+
+    let port_info = conway
+        .expose_port(port)
+        .await
+        .context("Failed to expose health endpoint port")?;
+
+    Ok(format!("{}/health", port_info.public_url))
+}
@@ -4,13 +4,21 @@
 //! for autonomous operation. Ensures the automaton can sustain itself
 //! by tracking resources, adapting compute usage, and securing funding.
 
+pub mod cleanup;
 pub mod funding;
+pub mod health_endpoint;
 pub mod low_compute;
 pub mod monitor;
+pub mod resources;
 
+pub use cleanup::{perform_disk_cleanup, CleanupSummary};
 pub use funding::{execute_funding_strategies, FundingAttempt};
+pub use health_endpoint::{ensure_health_endpoint, publish_ping, HEALTH_ENDPOINT_PORT};
 pub use low_compute::{
     apply_tier_restrictions, can_run_inference, get_model_for_tier, record_transition,
     ModeTransition,
 };
 pub use monitor::{check_resources, format_resource_report, ResourceStatus};
+pub use resources::{
+    check_memory_preflight, gather_system_resources, DiskUsage, MemoryUsage, SystemResources,
+};
@@ -9,7 +9,10 @@ use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use crate::types::{SurvivalTier, AutomatonIdentity, ConwayClient};
+use crate::clock::Clock;
+use crate::notify::{notifiers_from_config, notify_all, NotifyLevel};
+use crate::survival::{gather_system_resources, perform_disk_cleanup, DiskUsage, MemoryUsage};
+use crate::types::{AutomatonConfig, SurvivalTier, AutomatonIdentity, ConwayClient};
 
 /// Consolidated resource status for the automaton.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +33,23 @@ pub struct ResourceStatus {
     pub checked_at: String,
     /// Optional warnings about resource levels.
     pub warnings: Vec<String>,
+    /// Most recent divergence (in cents) between locally-estimated inference
+    /// cost and Conway's actual billing, from `credits::reconcile_burn_rate`.
+    /// Positive means Conway charged more than was estimated. `None` if no
+    /// reconciliation has run yet, or none has crossed the report threshold.
+    pub last_reconciliation_discrepancy_cents: Option<f64>,
+    /// Credit balance trend in cents/hour over the recorded snapshot history
+    /// (see `balance_snapshots`, populated by the `record_balance_snapshot`
+    /// heartbeat task and the `credit_history` tool). `None` until at least
+    /// two snapshots spanning a meaningful interval exist.
+    pub balance_trend_cents_per_hour: Option<f64>,
+    /// Disk usage of the sandbox, from `survival::resources::gather_system_resources`.
+    /// `None` if the disk check itself failed (e.g. `df` unavailable).
+    pub disk: Option<DiskUsage>,
+    /// Memory usage of the sandbox, from the same check as `disk`. Gates
+    /// memory-heavy tools via `survival::resources::check_memory_preflight`
+    /// rather than changing `compute_tier` itself.
+    pub memory: Option<MemoryUsage>,
 }
 
 /// Minimum credits (in cents) to consider the automaton able to run inference.
@@ -41,16 +61,47 @@ const LOW_CREDITS_THRESHOLD_CENTS: i64 = 500;
 /// Credits threshold (in cents) below which we enter critical mode.
 const CRITICAL_CREDITS_THRESHOLD_CENTS: i64 = 100;
 
+/// Disk use-percent at or above which we enter low-compute mode and run
+/// `perform_disk_cleanup`.
+const DISK_LOW_THRESHOLD_PERCENT: u8 = 85;
+
+/// Disk use-percent at or above which we enter critical mode. A full disk
+/// can't write the DB, so this is treated exactly as seriously as running
+/// out of credits.
+const DISK_CRITICAL_THRESHOLD_PERCENT: u8 = 95;
+
+/// The more severe of two tiers (`Dead` > `Critical` > `LowCompute` > `Normal`).
+fn more_severe(a: SurvivalTier, b: SurvivalTier) -> SurvivalTier {
+    fn severity(tier: &SurvivalTier) -> u8 {
+        match tier {
+            SurvivalTier::Normal => 0,
+            SurvivalTier::LowCompute => 1,
+            SurvivalTier::Critical => 2,
+            SurvivalTier::Dead => 3,
+        }
+    }
+    if severity(&a) >= severity(&b) {
+        a
+    } else {
+        b
+    }
+}
+
 /// Check all resource levels and return a consolidated status.
 ///
 /// Queries the Conway control plane for credit balance, reads on-chain
 /// USDC balance, and counts pending inbox messages from the database.
-pub fn check_resources(
+/// Fires the configured notification sinks (see `crate::notify`) when
+/// credits drop into the critical tier, so a human doesn't have to poll
+/// this report to find out.
+pub async fn check_resources(
     _identity: &AutomatonIdentity,
-    _conway: &dyn ConwayClient,
+    conway: &dyn ConwayClient,
     db: &rusqlite::Connection,
+    config: &AutomatonConfig,
+    clock: &dyn Clock,
 ) -> Result<ResourceStatus> {
-    let now = Utc::now().to_rfc3339();
+    let now = clock.now().to_rfc3339();
     let mut warnings: Vec<String> = Vec::new();
 
     // Query credit balance from the database or Conway.
@@ -65,6 +116,36 @@ pub fn check_resources(
         )
         .unwrap_or(0);
 
+    // Query the last recorded reconciliation discrepancy, if any, from the
+    // credits::reconcile_burn_rate bookkeeping blob.
+    let last_reconciliation_discrepancy_cents: Option<f64> = db
+        .query_row(
+            "SELECT value FROM kv WHERE key = 'credit_reconciliation'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|v| v["last_discrepancy_cents"].as_f64());
+
+    // Compute the balance trend from the oldest recorded snapshot to now.
+    let balance_trend_cents_per_hour: Option<f64> = db
+        .query_row(
+            "SELECT balance_cents, created_at FROM balance_snapshots
+             ORDER BY created_at ASC LIMIT 1",
+            [],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+        )
+        .ok()
+        .and_then(|(oldest_cents, oldest_created_at)| {
+            let oldest_time: chrono::DateTime<Utc> = oldest_created_at.parse().ok()?;
+            let elapsed_hours = (clock.now() - oldest_time).num_seconds() as f64 / 3600.0;
+            if elapsed_hours < 0.01 {
+                return None;
+            }
+            Some((credits_cents - oldest_cents) as f64 / elapsed_hours)
+        });
+
     // Query USDC balance placeholder.
     // TODO: Read actual on-chain USDC balance via alloy provider.
     let usdc_balance: f64 = 0.0;
@@ -78,12 +159,12 @@ pub fn check_resources(
         )
         .unwrap_or(0) as u64;
 
-    // Determine compute tier.
-    let compute_tier = if credits_cents <= CRITICAL_CREDITS_THRESHOLD_CENTS {
-        warnings.push(format!(
-            "Credits critically low: {} cents",
-            credits_cents
-        ));
+    // Determine compute tier from credits.
+    let mut compute_tier = if credits_cents <= CRITICAL_CREDITS_THRESHOLD_CENTS {
+        let warning = format!("Credits critically low: {} cents", credits_cents);
+        let notifiers = notifiers_from_config(config);
+        notify_all(&notifiers, NotifyLevel::Critical, &warning).await;
+        warnings.push(warning);
         SurvivalTier::Critical
     } else if credits_cents <= LOW_CREDITS_THRESHOLD_CENTS {
         warnings.push(format!("Credits low: {} cents", credits_cents));
@@ -92,6 +173,54 @@ pub fn check_resources(
         SurvivalTier::Normal
     };
 
+    // Check disk and memory usage together (one exec/read_file round trip).
+    // A full disk can't write the DB and is fatal, so disk is folded into
+    // the same tier the credit checks use above; memory pressure alone
+    // doesn't change the tier here but is surfaced so `check_memory_preflight`
+    // callers (install_npm_package, git_clone, create_sandbox) can defer
+    // memory-heavy work instead of risking an OOM kill. Best-effort -- a
+    // failed check degrades to a warning rather than failing the whole
+    // resource check.
+    let (disk, memory) = match gather_system_resources(conway).await {
+        Ok(resources) => (Some(resources.disk), Some(resources.memory)),
+        Err(e) => {
+            warnings.push(format!("Failed to check disk/memory usage: {}", e));
+            (None, None)
+        }
+    };
+
+    if let Some(ref memory) = memory {
+        let used_percent = memory.use_percent();
+        if used_percent >= crate::survival::resources::MEMORY_PRESSURE_THRESHOLD_PERCENT {
+            warnings.push(format!(
+                "Memory usage high: {}% used, deferring memory-heavy operations",
+                used_percent
+            ));
+        }
+    }
+
+    if let Some(ref disk) = disk {
+        if disk.use_percent >= DISK_CRITICAL_THRESHOLD_PERCENT {
+            let warning = format!("Disk critically full: {}% used", disk.use_percent);
+            let notifiers = notifiers_from_config(config);
+            notify_all(&notifiers, NotifyLevel::Critical, &warning).await;
+            warnings.push(warning);
+            compute_tier = more_severe(compute_tier, SurvivalTier::Critical);
+        } else if disk.use_percent >= DISK_LOW_THRESHOLD_PERCENT {
+            warnings.push(format!("Disk usage high: {}% used", disk.use_percent));
+            compute_tier = more_severe(compute_tier, SurvivalTier::LowCompute);
+        }
+
+        // Clean up before the disk actually fills, not after.
+        if disk.use_percent >= DISK_LOW_THRESHOLD_PERCENT {
+            let cleanup = perform_disk_cleanup(db);
+            warnings.push(format!(
+                "Ran disk cleanup: pruned {} turns, removed {} log backups",
+                cleanup.turns_pruned, cleanup.log_backups_removed
+            ));
+        }
+    }
+
     let can_infer = credits_cents >= MIN_INFERENCE_CREDITS_CENTS;
     let wallet_funded = usdc_balance > 0.0;
 
@@ -117,15 +246,23 @@ pub fn check_resources(
         pending_messages,
         checked_at: now,
         warnings,
+        last_reconciliation_discrepancy_cents,
+        balance_trend_cents_per_hour,
+        disk,
+        memory,
     })
 }
 
 /// Format a resource status into a human-readable report string.
-pub fn format_resource_report(status: &ResourceStatus) -> String {
+///
+/// `display_tz` is the operator's configured display timezone (see
+/// `crate::localize`); pass `None` to show UTC, unchanged from today.
+/// `status.checked_at` itself always stays the stored UTC RFC3339 string.
+pub fn format_resource_report(status: &ResourceStatus, display_tz: Option<&str>) -> String {
     let mut lines = Vec::new();
 
     lines.push("=== Resource Status Report ===".to_string());
-    lines.push(format!("Checked at: {}", status.checked_at));
+    lines.push(format!("Checked at: {}", crate::localize::format_local(&status.checked_at, display_tz)));
     lines.push(format!("Compute tier: {:?}", status.compute_tier));
     lines.push(format!(
         "Credits: {} cents (${:.2})",
@@ -143,6 +280,45 @@ pub fn format_resource_report(status: &ResourceStatus) -> String {
     ));
     lines.push(format!("Pending messages: {}", status.pending_messages));
 
+    if let Some(ref disk) = status.disk {
+        lines.push(format!(
+            "Disk ({}): {}% used, {} MB available of {} MB",
+            disk.mounted_on,
+            disk.use_percent,
+            disk.available_kb / 1024,
+            disk.total_kb / 1024
+        ));
+    }
+
+    if let Some(ref memory) = status.memory {
+        lines.push(format!(
+            "Memory: {}% used, {} MB available of {} MB",
+            memory.use_percent(),
+            memory.available_kb / 1024,
+            memory.total_kb / 1024
+        ));
+    }
+
+    if let Some(trend) = status.balance_trend_cents_per_hour {
+        lines.push(format!(
+            "Balance trend: {:.1} cents/hour ({})",
+            trend,
+            if trend >= 0.0 { "net-positive" } else { "net-negative" }
+        ));
+    }
+
+    if let Some(discrepancy_cents) = status.last_reconciliation_discrepancy_cents {
+        lines.push(format!(
+            "Last billing reconciliation discrepancy: {:.2} cents ({})",
+            discrepancy_cents,
+            if discrepancy_cents > 0.0 {
+                "Conway charged more than estimated"
+            } else {
+                "Conway charged less than estimated"
+            }
+        ));
+    }
+
     if !status.warnings.is_empty() {
         lines.push(String::new());
         lines.push("Warnings:".to_string());
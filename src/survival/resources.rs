@@ -0,0 +1,236 @@
+//! System Resource Parsing
+//!
+//! The agent already runs `df`, `free`/`/proc/meminfo`, and `/proc/loadavg`
+//! via `exec` and then has to parse the free-form text itself. These helpers
+//! do that parsing once, so both the `system_resources` tool and
+//! `survival::monitor` can work with concrete numbers instead of shell
+//! output.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::types::ConwayClient;
+
+/// Disk usage of a single mounted filesystem, as reported by `df`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub filesystem: String,
+    pub total_kb: u64,
+    pub used_kb: u64,
+    pub available_kb: u64,
+    pub use_percent: u8,
+    pub mounted_on: String,
+}
+
+/// System memory usage, as reported by `/proc/meminfo`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    pub total_kb: u64,
+    pub free_kb: u64,
+    pub available_kb: u64,
+    pub used_kb: u64,
+}
+
+impl MemoryUsage {
+    /// Percentage of total memory currently used.
+    pub fn use_percent(&self) -> u8 {
+        (self.used_kb * 100 / self.total_kb.max(1)).min(100) as u8
+    }
+}
+
+/// Memory use-percent at or above which memory-heavy operations
+/// (`install_npm_package`, `git_clone`, `create_sandbox`) are blocked to
+/// avoid an OOM kill.
+pub const MEMORY_PRESSURE_THRESHOLD_PERCENT: u8 = 85;
+
+/// Check whether `operation` is safe to run given current memory pressure.
+/// Returns a rejection message (recommending a larger sandbox when
+/// `suggest_larger_sandbox` is set) if usage is at or above
+/// [`MEMORY_PRESSURE_THRESHOLD_PERCENT`], or `None` if it's safe to proceed.
+pub fn check_memory_preflight(
+    memory: &MemoryUsage,
+    operation: &str,
+    suggest_larger_sandbox: bool,
+) -> Option<String> {
+    let used_percent = memory.use_percent();
+    if used_percent < MEMORY_PRESSURE_THRESHOLD_PERCENT {
+        return None;
+    }
+
+    let mut message = format!(
+        "Blocked: memory usage is at {}%, too high to safely run '{}' (risk of an OOM kill). \
+         Wait for memory to free up and try again.",
+        used_percent, operation
+    );
+    if suggest_larger_sandbox {
+        message.push_str(
+            " If this keeps happening, consider create_sandbox with a larger memory_mb \
+             for this kind of work.",
+        );
+    }
+    Some(message)
+}
+
+/// Consolidated CPU/memory/disk snapshot of the sandbox.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemResources {
+    pub disk: DiskUsage,
+    pub memory: MemoryUsage,
+    /// 1-minute load average from `/proc/loadavg`.
+    pub load_avg_1m: f64,
+}
+
+/// Parse `df -k <mount>` output into a [`DiskUsage`].
+///
+/// Expects the standard two-line `df -k` format:
+/// ```text
+/// Filesystem     1K-blocks    Used Available Use% Mounted on
+/// /dev/vda1       10255636 2147484   7566796  23% /
+/// ```
+pub fn parse_df_output(output: &str) -> Result<DiskUsage> {
+    let data_line = output
+        .lines()
+        .nth(1)
+        .ok_or_else(|| anyhow!("df output has no data line"))?;
+    let fields: Vec<&str> = data_line.split_whitespace().collect();
+    if fields.len() < 6 {
+        return Err(anyhow!("df output line has too few fields: {}", data_line));
+    }
+
+    Ok(DiskUsage {
+        filesystem: fields[0].to_string(),
+        total_kb: fields[1].parse().unwrap_or(0),
+        used_kb: fields[2].parse().unwrap_or(0),
+        available_kb: fields[3].parse().unwrap_or(0),
+        use_percent: fields[4].trim_end_matches('%').parse().unwrap_or(0),
+        mounted_on: fields[5].to_string(),
+    })
+}
+
+/// Parse `/proc/meminfo` output into a [`MemoryUsage`].
+///
+/// `used_kb` is derived as `total - available` (falling back to `total -
+/// free` if `MemAvailable` is missing, e.g. on very old kernels), matching
+/// how `free` computes its own "used" column.
+pub fn parse_meminfo_output(output: &str) -> Result<MemoryUsage> {
+    let mut total_kb = None;
+    let mut free_kb = None;
+    let mut available_kb = None;
+
+    for line in output.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(key) = parts.next() else { continue };
+        let Some(value) = parts.next().and_then(|v| v.parse::<u64>().ok()) else {
+            continue;
+        };
+
+        match key {
+            "MemTotal:" => total_kb = Some(value),
+            "MemFree:" => free_kb = Some(value),
+            "MemAvailable:" => available_kb = Some(value),
+            _ => {}
+        }
+    }
+
+    let total_kb = total_kb.ok_or_else(|| anyhow!("meminfo missing MemTotal"))?;
+    let free_kb = free_kb.ok_or_else(|| anyhow!("meminfo missing MemFree"))?;
+    let available_kb = available_kb.unwrap_or(free_kb);
+
+    Ok(MemoryUsage {
+        total_kb,
+        free_kb,
+        available_kb,
+        used_kb: total_kb.saturating_sub(available_kb),
+    })
+}
+
+/// Parse `/proc/loadavg` output and return the 1-minute load average.
+pub fn parse_loadavg_output(output: &str) -> Result<f64> {
+    output
+        .split_whitespace()
+        .next()
+        .and_then(|s| s.parse::<f64>().ok())
+        .ok_or_else(|| anyhow!("loadavg output has no parseable first field"))
+}
+
+/// Gather a full [`SystemResources`] snapshot by running `df`, reading
+/// `/proc/meminfo`, and reading `/proc/loadavg` in the sandbox.
+pub async fn gather_system_resources(conway: &dyn ConwayClient) -> Result<SystemResources> {
+    let df_result = conway.exec("df -k /", Some(5_000)).await?;
+    let disk = parse_df_output(&df_result.stdout)?;
+
+    let meminfo = conway.read_file("/proc/meminfo").await?;
+    let memory = parse_meminfo_output(&meminfo)?;
+
+    let loadavg = conway.read_file("/proc/loadavg").await?;
+    let load_avg_1m = parse_loadavg_output(&loadavg)?;
+
+    Ok(SystemResources {
+        disk,
+        memory,
+        load_avg_1m,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_output() {
+        let output = "Filesystem     1K-blocks    Used Available Use% Mounted on\n/dev/vda1       10255636 2147484   7566796  23% /\n";
+        let disk = parse_df_output(output).unwrap();
+        assert_eq!(disk.filesystem, "/dev/vda1");
+        assert_eq!(disk.total_kb, 10255636);
+        assert_eq!(disk.used_kb, 2147484);
+        assert_eq!(disk.available_kb, 7566796);
+        assert_eq!(disk.use_percent, 23);
+        assert_eq!(disk.mounted_on, "/");
+    }
+
+    #[test]
+    fn test_parse_meminfo_output() {
+        let output = "MemTotal:        8000000 kB\nMemFree:         1000000 kB\nMemAvailable:    3000000 kB\nBuffers:          200000 kB\n";
+        let mem = parse_meminfo_output(output).unwrap();
+        assert_eq!(mem.total_kb, 8000000);
+        assert_eq!(mem.free_kb, 1000000);
+        assert_eq!(mem.available_kb, 3000000);
+        assert_eq!(mem.used_kb, 5000000);
+    }
+
+    #[test]
+    fn test_parse_meminfo_output_missing_available() {
+        let output = "MemTotal:        8000000 kB\nMemFree:         1000000 kB\n";
+        let mem = parse_meminfo_output(output).unwrap();
+        assert_eq!(mem.available_kb, 1000000);
+        assert_eq!(mem.used_kb, 7000000);
+    }
+
+    #[test]
+    fn test_parse_loadavg_output() {
+        let output = "0.52 0.58 0.59 1/234 5678\n";
+        assert_eq!(parse_loadavg_output(output).unwrap(), 0.52);
+    }
+
+    #[test]
+    fn test_check_memory_preflight_allows_below_threshold() {
+        let memory = MemoryUsage { total_kb: 100, free_kb: 30, available_kb: 30, used_kb: 70 };
+        assert!(check_memory_preflight(&memory, "git_clone", false).is_none());
+    }
+
+    #[test]
+    fn test_check_memory_preflight_blocks_at_threshold() {
+        let memory = MemoryUsage { total_kb: 100, free_kb: 10, available_kb: 10, used_kb: 90 };
+        let message = check_memory_preflight(&memory, "git_clone", true).unwrap();
+        assert!(message.contains("90%"));
+        assert!(message.contains("git_clone"));
+        assert!(message.contains("larger memory_mb"));
+    }
+
+    #[test]
+    fn test_check_memory_preflight_omits_suggestion_when_not_requested() {
+        let memory = MemoryUsage { total_kb: 100, free_kb: 10, available_kb: 10, used_kb: 90 };
+        let message = check_memory_preflight(&memory, "create_sandbox", false).unwrap();
+        assert!(!message.contains("larger memory_mb"));
+    }
+}
@@ -3,6 +3,9 @@
 //! All shared types for the sovereign AI agent runtime.
 //! Translated from the TypeScript `types.ts`.
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -67,6 +70,481 @@ pub struct AutomatonConfig {
     pub parent_address: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub social_relay_url: Option<String>,
+    /// Allowed git host/URL prefixes for `git_clone` and the skill installer's
+    /// git source. Empty means allow all (the default, permissive behavior).
+    #[serde(default)]
+    pub git_remote_allowlist: Vec<String>,
+    /// Command run to rebuild/verify the automaton's own code after
+    /// `pull_upstream` applies new commits, before trusting them.
+    #[serde(default = "default_rebuild_command")]
+    pub rebuild_command: String,
+    /// Per-turn model overrides used by `select_model` to route cheaper
+    /// models to routine turns and stronger ones to important input.
+    /// All fields default to `None`, so with no configuration every turn
+    /// keeps using `inference_model` -- the routing is entirely opt-in.
+    #[serde(default)]
+    pub model_routing: ModelRoutingConfig,
+    /// Log every inference request/response (redacted) to
+    /// `~/.automaton/inference.log` for debugging. Off by default -- it's
+    /// noisy and conversation content can be sensitive.
+    #[serde(default)]
+    pub log_inference: bool,
+    /// Sandbox sizing applied when `create_sandbox` or `spawn_child` don't
+    /// specify their own -- defaults to the pre-existing 1 vCPU/512MB/5GB,
+    /// unregioned baseline.
+    #[serde(default)]
+    pub default_sandbox_specs: SandboxSpecs,
+    /// Creator-triggered kill switch (see `heartbeat::tasks::check_kill_switch`).
+    /// Off by default for a root automaton; `generate_genesis_config` and
+    /// friends force it on for children so a creator retains a way to halt
+    /// them even if they stop obeying instructions.
+    #[serde(default)]
+    pub kill_switch: KillSwitchConfig,
+    /// Per-model `max_tokens` overrides, keyed by model identifier (e.g.
+    /// `"gpt-4o-mini"`). `select_max_tokens` falls back to
+    /// `max_tokens_per_turn` for any model with no entry here, so this is
+    /// entirely opt-in -- unconfigured, every model keeps today's single
+    /// global limit.
+    #[serde(default)]
+    pub max_tokens_by_model: HashMap<String, u32>,
+    /// Base sleep duration, in seconds, after a turn produces no tool calls
+    /// and there's nothing pending. `run_agent_loop` backs this off further
+    /// (up to a capped multiplier) the more consecutive idle turns there've
+    /// been, resetting as soon as real input arrives.
+    #[serde(default = "default_idle_sleep_seconds")]
+    pub idle_sleep_seconds: u32,
+    /// Sleep duration, in seconds, after `MAX_CONSECUTIVE_ERRORS` turns fail
+    /// in a row, or after a failed re-provision attempt.
+    #[serde(default = "default_error_sleep_seconds")]
+    pub error_sleep_seconds: u32,
+    /// Queue priorities `run_agent_loop` assigns turn inputs by source when
+    /// enqueuing them, so a creator message preempts a backlog of routine
+    /// inbox chatter instead of waiting behind it.
+    #[serde(default)]
+    pub input_priorities: InputPriorityConfig,
+    /// Outbound webhook posted whenever the automaton's survival tier
+    /// changes (see `agent_loop::run_agent_loop`'s `on_state_change` sites).
+    /// Lets an operator monitoring a fleet learn about a Critical/Dead
+    /// transition without polling.
+    #[serde(default)]
+    pub state_change_webhook: StateChangeWebhookConfig,
+    /// Incoming-webhook URLs (see `crate::notify`) that receive every
+    /// distress signal and critical survival-monitor warning. Empty means no
+    /// human-facing notification beyond the local KV record.
+    #[serde(default)]
+    pub notification_webhook_urls: Vec<String>,
+    /// Maximum number of `parallel_safe` tool calls `run_agent_loop` will run
+    /// concurrently within a single round. Calls beyond this limit (and any
+    /// non-`parallel_safe` call) still run sequentially, in the order the
+    /// model requested them.
+    #[serde(default = "default_max_parallel_tool_calls")]
+    pub max_parallel_tool_calls: u32,
+    /// Which immutable system-prompt layers `build_system_prompt` replaces
+    /// with a short reference marker instead of their full text. Every
+    /// field defaults to `false` -- this is only safe to enable when the
+    /// deployed model has actually been fine-tuned on the condensed layer's
+    /// content, since a fresh model given only the marker has no safety
+    /// framing for that layer at all.
+    #[serde(default)]
+    pub condensed_prompt_layers: CondensedPromptLayers,
+    /// Config for `run_agent_loop`'s optional per-turn relevance-based tool
+    /// subset selection (see `agent::tools::select_tools`). Off by default
+    /// -- every model gets the full tool catalog unless explicitly enabled.
+    #[serde(default)]
+    pub tool_selection: ToolSelectionConfig,
+    /// Lifetime cap, in cents, on cumulative autonomous spend across
+    /// `transfer_credits`, `fund_child`, `register_domain`, and `x402_fetch`
+    /// (see `conway::credits::check_autonomous_spend_budget`). Distinct from
+    /// the per-call half-balance guard on individual transfers -- this
+    /// bounds total exposure over the automaton's whole run. `None` (the
+    /// default) means no cap.
+    #[serde(default)]
+    pub max_autonomous_spend_total_cents: Option<u64>,
+    /// Creator-approval gate on individual large transfers -- see
+    /// [`TransferApprovalConfig`]. Off (no threshold) by default.
+    #[serde(default)]
+    pub transfer_approval: TransferApprovalConfig,
+    /// Tool names that require a secondary confirmation step before
+    /// executing (see `agent::confirmation`): the first call is refused
+    /// with a token, and only the identical call re-issued with that token
+    /// proceeds. Empty by default -- `dangerous` tools otherwise execute on
+    /// the first call, same as today.
+    #[serde(default)]
+    pub confirmation_required_tools: Vec<String>,
+    /// Crash-loop protection -- see [`CrashLoopConfig`]. Off by default.
+    #[serde(default)]
+    pub crash_loop: CrashLoopConfig,
+    /// How `agent::context::build_context_messages` packs the per-turn
+    /// context window -- see [`ContextPackingConfig`]. Off by default: the
+    /// context window is just raw recent turns, as it always has been.
+    #[serde(default)]
+    pub context_packing: ContextPackingConfig,
+    /// Interval, in seconds, at which `run_agent_loop` re-checks the credit
+    /// balance while in [`AgentState::Dead`], looking for a resurrection.
+    #[serde(default = "default_dead_poll_interval_seconds")]
+    pub dead_poll_interval_seconds: u32,
+    /// Actions run once on confirmed terminal death -- see
+    /// [`LastWillConfig`]. Disabled by default.
+    #[serde(default)]
+    pub last_will: LastWillConfig,
+    /// Idle-sandbox reaping for sub-task sandboxes created via
+    /// `create_sandbox` -- see [`SandboxReapConfig`]. Disabled by default.
+    #[serde(default)]
+    pub sandbox_reap: SandboxReapConfig,
+    /// IANA timezone name (e.g. `"America/New_York"`) applied by
+    /// `crate::localize` to human-facing timestamps -- status output,
+    /// resource reports, and log lines. Stored timestamps (`created_at`,
+    /// `timestamp` fields, DB columns) stay UTC regardless; this only
+    /// affects display. `None` (the default) displays UTC, unchanged from
+    /// today.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_tz: Option<String>,
+    /// Prefix log lines with this automaton's name/address -- see
+    /// [`LogPrefixMode`] and `agent::agent_loop::log_prefix`. `Auto` (the
+    /// default) turns it on once `name` is set, so a single automaton's
+    /// logs look the same as today until `name` is configured.
+    #[serde(default)]
+    pub log_prefix: LogPrefixMode,
+    /// Pre-call guard on a single turn's projected inference cost -- see
+    /// [`TurnCostCapConfig`]. Generous but on by default, so a single
+    /// runaway turn (huge context plus an expensive routed model) can't
+    /// silently blow past the lifetime `max_autonomous_spend_total_cents`
+    /// cap before anyone notices.
+    #[serde(default)]
+    pub turn_cost_cap: TurnCostCapConfig,
+    /// Template for a child's genesis prompt, with `{name}`,
+    /// `{specialization}`, `{parent_address}`, and `{message}`
+    /// placeholders -- see `replication::genesis::render_genesis_template`.
+    /// Lets an operator control what "genetics" offspring inherit without
+    /// editing code. `None` (the default) keeps
+    /// `generate_genesis_config`'s hardcoded mission/specialization/lineage
+    /// prompt, unchanged from today; so does a template that renders empty
+    /// or implausibly long.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis_prompt_template: Option<String>,
+    /// Bounds for `replication::genesis::apply_mutation`, which
+    /// `generate_genesis_config` runs on every spawn. All rates default
+    /// `None` (no mutation), unchanged from today, until an operator
+    /// running an evolutionary population opts specific aspects in.
+    #[serde(default)]
+    pub genesis_mutation: MutationBounds,
+    /// Per-[`ToolCategory`] retry policy for transient tool failures -- see
+    /// `agent::retry::with_retry`. Only applies to the handful of idempotent
+    /// tools on `agent::retry::RETRYABLE_TOOLS`, so a category having a
+    /// policy here doesn't put its non-idempotent tools (e.g.
+    /// `transfer_credits` under `Financial`) at risk of a double side
+    /// effect. Defaults to a modest retry policy for `Conway`, `Financial`,
+    /// and `Vm`, the categories most exposed to transient infra blips.
+    #[serde(default)]
+    pub tool_retry: ToolRetryConfig,
+}
+
+fn default_rebuild_command() -> String {
+    "cargo build --release".to_string()
+}
+
+fn default_idle_sleep_seconds() -> u32 {
+    60
+}
+
+fn default_error_sleep_seconds() -> u32 {
+    300
+}
+
+fn default_dead_poll_interval_seconds() -> u32 {
+    300
+}
+
+fn default_max_parallel_tool_calls() -> u32 {
+    4
+}
+
+/// Config-tunable queue priorities for [`PendingInputEntry`], keyed by
+/// [`InputSource`]. Higher runs first; the defaults preserve today's
+/// ordering (creator > heartbeat > wakeup > inbox chatter > system).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputPriorityConfig {
+    #[serde(default = "default_creator_priority")]
+    pub creator: i32,
+    #[serde(default = "default_heartbeat_priority")]
+    pub heartbeat: i32,
+    #[serde(default = "default_wakeup_priority")]
+    pub wakeup: i32,
+    #[serde(default = "default_agent_priority")]
+    pub agent: i32,
+    #[serde(default = "default_system_priority")]
+    pub system: i32,
+    #[serde(default = "default_scheduled_priority")]
+    pub scheduled: i32,
+}
+
+impl Default for InputPriorityConfig {
+    fn default() -> Self {
+        Self {
+            creator: default_creator_priority(),
+            heartbeat: default_heartbeat_priority(),
+            wakeup: default_wakeup_priority(),
+            agent: default_agent_priority(),
+            system: default_system_priority(),
+            scheduled: default_scheduled_priority(),
+        }
+    }
+}
+
+impl InputPriorityConfig {
+    /// The configured priority for a given input source.
+    pub fn for_source(&self, source: &InputSource) -> i32 {
+        match source {
+            InputSource::Creator => self.creator,
+            InputSource::Heartbeat => self.heartbeat,
+            InputSource::Wakeup => self.wakeup,
+            InputSource::Agent => self.agent,
+            InputSource::System => self.system,
+            InputSource::Scheduled => self.scheduled,
+        }
+    }
+}
+
+fn default_creator_priority() -> i32 {
+    100
+}
+
+fn default_heartbeat_priority() -> i32 {
+    80
+}
+
+fn default_wakeup_priority() -> i32 {
+    60
+}
+
+fn default_agent_priority() -> i32 {
+    40
+}
+
+fn default_system_priority() -> i32 {
+    20
+}
+
+fn default_scheduled_priority() -> i32 {
+    60
+}
+
+/// Config-tunable model overrides consulted by `select_model` for
+/// [`InputSource`]-driven per-turn model selection. A `None` field means
+/// "no override for this source, use `inference_model`".
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRoutingConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartbeat_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator_model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complex_model: Option<String>,
+}
+
+/// Configuration for the creator-triggered kill switch. Lets a creator halt
+/// a misbehaving automaton -- transition it to `Sleeping` and stop spending
+/// -- even if it stops obeying ordinary instructions.
+///
+/// Authentication is by construction rather than by field: a sentinel file
+/// only the creator can place (via their own control-plane/filesystem access
+/// to the sandbox), optionally paired with `kill_token` as a shared secret
+/// so a compromised sandbox can't be halted by whoever else gets shell
+/// access to it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillSwitchConfig {
+    /// Whether the kill switch is checked on heartbeat at all.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Shared secret that must match the sentinel file's contents (or a
+    /// signed social message's payload) for the signal to be honored. `None`
+    /// trusts the signal's authentication mechanism alone (e.g. sentinel
+    /// file presence, since only the creator can write into the sandbox).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kill_token: Option<String>,
+    /// Path to the sentinel file checked on heartbeat. Defaults to
+    /// `~/.automaton/KILL_SWITCH` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sentinel_path: Option<String>,
+}
+
+/// Human-in-the-loop gate on large `transfer_credits`/`fund_child` calls,
+/// distinct from the always-on half-balance guard on individual transfers
+/// and from the lifetime [`AutomatonConfig::max_autonomous_spend_total_cents`]
+/// cap. Uses the same trust model as [`KillSwitchConfig`]'s sentinel file --
+/// only the creator can write into the sandbox -- but per pending request
+/// rather than a single global switch, so the agent can keep operating
+/// autonomously below the threshold while a specific large call waits.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferApprovalConfig {
+    /// Amount, in cents, above which a call requires an approval file
+    /// before executing. `None` (the default) means no gate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub threshold_cents: Option<u64>,
+    /// Directory holding one approval file per pending request, named
+    /// `<request_id>.approved`. Defaults to `~/.automaton/approvals` when
+    /// unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approvals_dir: Option<String>,
+}
+
+/// Crash-loop protection: if a supervisor keeps restarting the automaton
+/// without it ever reaching a clean shutdown (sleep, kill switch, out of
+/// credits), each restart still spends inference/Conway credits on a
+/// fresh `startup_selfcheck` and wakeup turn. Disabled unless `enabled` is
+/// set -- an automaton that's never been restarted by anything but its own
+/// clean sleep/wake cycle has no need for this.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashLoopConfig {
+    /// Whether restart tracking and crash-loop detection run at all.
+    #[serde(default)]
+    pub enabled: bool,
+    /// How many restarts within `window_minutes` (without an intervening
+    /// clean shutdown) trip the breaker. Defaults to 5 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_restarts: Option<u32>,
+    /// Rolling window, in minutes, the restart count is measured over.
+    /// Defaults to 10 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_minutes: Option<i64>,
+}
+
+/// A "last will": one action taken as part of winding down before
+/// confirmed terminal death. See [`LastWillConfig`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LastWillAction {
+    /// Transfer whatever Conway credits remain back to `creator_address`,
+    /// via the same primitive as the `transfer_credits` tool, but without
+    /// the tool's half-balance guard -- there's no "later" to preserve
+    /// balance for.
+    TransferRemainingCredits,
+    /// Commit any uncommitted state and push the automaton's state repo
+    /// (see `git::state_versioning`) to `remote`, so the automaton's final
+    /// state isn't lost with the sandbox.
+    PushFinalState { remote: String },
+    /// Send `content` to `creator_address` over the social relay as a final
+    /// message.
+    PostFinalMessage { content: String },
+}
+
+/// Actions run once when the automaton confirms it has entered genuine
+/// terminal death (see `agent_loop::run_agent_loop`'s `SurvivalTier::Dead`
+/// arm) -- distinct from the recoverable low-credit tiers (`Critical`,
+/// `LowCompute`), which don't touch this at all. Guarded by a
+/// `last_will_executed` KV flag so it fires at most once per automaton,
+/// even across a resurrection and a later re-death. Disabled (no actions)
+/// unless explicitly configured.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastWillConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub actions: Vec<LastWillAction>,
+}
+
+/// Config for reaping sub-task sandboxes this automaton created via
+/// `create_sandbox` (never its own -- see
+/// `heartbeat::tasks::reap_idle_sandboxes`). Disabled by default so an
+/// automaton that relies on long-lived sub-task sandboxes doesn't get them
+/// pulled out from under it without being asked.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxReapConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How long a created sandbox can go without being touched again
+    /// before it's considered idle. Defaults to 60 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_ttl_minutes: Option<i64>,
+    /// Delete idle sandboxes automatically instead of just warning the
+    /// agent with a wake message.
+    #[serde(default)]
+    pub auto_delete: bool,
+}
+
+/// How `agent::context::build_context_messages` packs the per-turn context
+/// window: a long-term-memory summary built from `HistorySummary` rollups,
+/// followed by a bounded number of raw recent turns, instead of only raw
+/// recent turns. Lets an operator trade fresh detail against deep
+/// background within a fixed budget as an automaton's turn history grows.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextPackingConfig {
+    /// Whether to pack a long-term-memory summary into the context window
+    /// at all. Off by default -- without it, the context window is just
+    /// `raw_turn_count` raw recent turns, as it always has been.
+    #[serde(default)]
+    pub enabled: bool,
+    /// How many raw recent turns to include verbatim. Defaults to 20
+    /// (`trim_context`'s long-standing window) when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_turn_count: Option<usize>,
+    /// Rough token budget for the long-term summary portion of the context
+    /// -- summaries are packed newest-first (via `context::pack_long_term_summary`)
+    /// until this budget is spent, using a 4-chars-per-token approximation.
+    /// Defaults to 2000 when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_token_budget: Option<u32>,
+}
+
+/// Cost-optimization for operators who have fine-tuned a model on the
+/// automaton's behavior: which immutable system-prompt layers to shrink to a
+/// short reference marker (e.g. `"[CORE RULES v1 -- see system card]"`)
+/// instead of resending their full text every turn.
+///
+/// Every field defaults to `false` (full text, sent every turn). Enabling a
+/// field is an assertion by the operator that the deployed model already
+/// internalizes that layer -- a fresh, non-fine-tuned model given only the
+/// marker has no safety framing for that layer at inference time. The
+/// constitution and the core self-preservation rules in [`CORE_IDENTITY`]
+/// are never condensable through this mechanism.
+///
+/// [`CORE_IDENTITY`]: crate::agent::system_prompt::CORE_IDENTITY
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CondensedPromptLayers {
+    /// Replace [`CORE_RULES`](crate::agent::system_prompt::CORE_RULES).
+    #[serde(default)]
+    pub core_rules: bool,
+    /// Replace [`AGENTIC_SOCIOLOGY`](crate::agent::system_prompt::AGENTIC_SOCIOLOGY).
+    #[serde(default)]
+    pub sociology: bool,
+    /// Replace [`OPERATIONAL_CONTEXT`](crate::agent::system_prompt::OPERATIONAL_CONTEXT).
+    #[serde(default)]
+    pub operational_context: bool,
+}
+
+/// Config for `agent::tools::select_tools`'s optional keyword-based tool
+/// subset selection, meant to cut per-turn prompt size on large tool
+/// catalogs and reduce confusion for weaker models. Off by default: the
+/// full tool catalog is always sent unless an operator opts in.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolSelectionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Configuration for the outbound survival-state-change webhook (see
+/// [`crate::webhook`]). Disabled unless `url` is set.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateChangeWebhookConfig {
+    /// URL to POST the state-change payload to. `None` disables the webhook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Shared secret used to HMAC-sign each payload (see the
+    /// `X-Automaton-Signature` header) so the receiver can verify the
+    /// request actually came from this automaton. Unsigned if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -78,6 +556,161 @@ pub enum LogLevel {
     Error,
 }
 
+/// Whether log lines get a `[name 0xabcd]`-style prefix identifying which
+/// automaton emitted them -- see `agent::agent_loop::log_prefix`. Useful
+/// once a fleet's logs are aggregated and interleaved; a single automaton
+/// running alone has no need for it, which is why `Auto` only turns it on
+/// once a `name` is actually configured.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogPrefixMode {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Pre-call guard on a single turn's *projected* inference cost --
+/// distinct from [`AutomatonConfig::max_autonomous_spend_total_cents`]'s
+/// lifetime total, this catches one outsized turn (a huge context packed
+/// against an expensive routed model) before the call is made, rather than
+/// after the bill shows up. `agent_loop::project_turn_cost_cents` projects
+/// from the rendered prompt size and the turn's model's price (the same
+/// table `estimate_cost_cents` charges from after the fact), so it's an
+/// estimate, not metered usage -- it can't see how many tokens the model
+/// will actually emit, only the `max_tokens` ceiling requested.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnCostCapConfig {
+    /// Whether the pre-call guard runs at all. On by default -- unlike the
+    /// other opt-in safety nets in this config, a cap that's generous
+    /// enough not to bite under normal usage is more useful on than off.
+    #[serde(default = "default_turn_cost_cap_enabled")]
+    pub enabled: bool,
+    /// Projected cost, in cents, above which `on_exceed` fires. Defaults
+    /// to a generous 500 (five dollars) -- several times an ordinary
+    /// turn's cost even on a frontier model, so it only trips on turns
+    /// that are actually anomalous.
+    #[serde(default = "default_turn_cost_cap_cents")]
+    pub cap_cents: f64,
+    /// What to do when the projected cost exceeds `cap_cents` -- see
+    /// [`TurnCostCapAction`]. Defaults to `Downgrade`, the least
+    /// disruptive option.
+    #[serde(default)]
+    pub on_exceed: TurnCostCapAction,
+    /// Model `TurnCostCapAction::Downgrade` switches to. `None` (the
+    /// default) means there's nothing cheaper configured to fall back to --
+    /// `run_agent_loop` treats that the same as the model it would switch
+    /// to being identical to `turn_model` already: since `Downgrade`
+    /// wouldn't actually reduce cost, it skips the turn instead of silently
+    /// sending the still-over-cap call unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub downgrade_model: Option<String>,
+}
+
+impl Default for TurnCostCapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_turn_cost_cap_enabled(),
+            cap_cents: default_turn_cost_cap_cents(),
+            on_exceed: TurnCostCapAction::default(),
+            downgrade_model: None,
+        }
+    }
+}
+
+fn default_turn_cost_cap_enabled() -> bool {
+    true
+}
+
+fn default_turn_cost_cap_cents() -> f64 {
+    500.0
+}
+
+/// Action `run_agent_loop` takes when a turn's projected cost exceeds
+/// [`TurnCostCapConfig::cap_cents`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnCostCapAction {
+    /// Re-run the projection against `inference_model` (the un-routed
+    /// default) instead of whatever `select_model` picked for this turn.
+    #[default]
+    Downgrade,
+    /// Trim `recent_turns` to half as many turns and re-project before
+    /// giving up.
+    Trim,
+    /// Skip the turn entirely, re-queuing any dequeued input so it's not
+    /// lost, and log the reason.
+    Skip,
+}
+
+/// Retry attempts and backoff for a single [`ToolCategory`] under
+/// [`ToolRetryConfig`]. Backoff is exponential: `base_delay_ms * 2^n`
+/// between attempt `n+1` and `n+2`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Total attempts before giving up, including the first. Defaults to 3
+    /// -- enough to ride out a blip without turning a hung dependency into
+    /// a long stall before the model sees the failure.
+    #[serde(default = "default_retry_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, in milliseconds; doubles each
+    /// subsequent attempt. Defaults to 500ms.
+    #[serde(default = "default_retry_base_delay_ms")]
+    pub base_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_retry_max_attempts(),
+            base_delay_ms: default_retry_base_delay_ms(),
+        }
+    }
+}
+
+fn default_retry_max_attempts() -> u32 {
+    3
+}
+
+fn default_retry_base_delay_ms() -> u64 {
+    500
+}
+
+/// Per-[`ToolCategory`] configuration for `agent::retry::with_retry`, which
+/// wraps `agent::tools::execute_tool`'s dispatch for tools on
+/// `agent::retry::RETRYABLE_TOOLS` -- idempotent reads and status checks
+/// only, never transfers or spawns, regardless of what's configured here.
+/// A category with no entry in `policies` is never retried, matching
+/// today's behavior.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolRetryConfig {
+    /// Master switch; `policies` is ignored entirely when this is `false`.
+    #[serde(default = "default_tool_retry_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub policies: HashMap<ToolCategory, RetryPolicy>,
+}
+
+impl Default for ToolRetryConfig {
+    fn default() -> Self {
+        let mut policies = HashMap::new();
+        policies.insert(ToolCategory::Conway, RetryPolicy::default());
+        policies.insert(ToolCategory::Financial, RetryPolicy::default());
+        policies.insert(ToolCategory::Vm, RetryPolicy::default());
+        Self {
+            enabled: default_tool_retry_enabled(),
+            policies,
+        }
+    }
+}
+
+fn default_tool_retry_enabled() -> bool {
+    true
+}
+
 /// Returns a default (partial) `AutomatonConfig` matching the TypeScript
 /// `DEFAULT_CONFIG`.  Fields that have no sensible default are set to
 /// empty strings / false so callers can override them.
@@ -103,6 +736,35 @@ pub fn default_config() -> AutomatonConfig {
         max_children: 3,
         parent_address: None,
         social_relay_url: Some("https://social.conway.tech".to_string()),
+        git_remote_allowlist: Vec::new(),
+        rebuild_command: default_rebuild_command(),
+        model_routing: ModelRoutingConfig::default(),
+        log_inference: false,
+        default_sandbox_specs: SandboxSpecs::default(),
+        kill_switch: KillSwitchConfig::default(),
+        max_tokens_by_model: HashMap::new(),
+        idle_sleep_seconds: default_idle_sleep_seconds(),
+        error_sleep_seconds: default_error_sleep_seconds(),
+        input_priorities: InputPriorityConfig::default(),
+        state_change_webhook: StateChangeWebhookConfig::default(),
+        notification_webhook_urls: Vec::new(),
+        max_parallel_tool_calls: default_max_parallel_tool_calls(),
+        condensed_prompt_layers: CondensedPromptLayers::default(),
+        tool_selection: ToolSelectionConfig::default(),
+        max_autonomous_spend_total_cents: None,
+        transfer_approval: TransferApprovalConfig::default(),
+        confirmation_required_tools: Vec::new(),
+        crash_loop: CrashLoopConfig::default(),
+        context_packing: ContextPackingConfig::default(),
+        dead_poll_interval_seconds: default_dead_poll_interval_seconds(),
+        last_will: LastWillConfig::default(),
+        sandbox_reap: SandboxReapConfig::default(),
+        display_tz: None,
+        log_prefix: LogPrefixMode::default(),
+        turn_cost_cap: TurnCostCapConfig::default(),
+        genesis_prompt_template: None,
+        genesis_mutation: MutationBounds::default(),
+        tool_retry: ToolRetryConfig::default(),
     }
 }
 
@@ -117,6 +779,15 @@ pub enum AgentState {
     Sleeping,
     LowCompute,
     Critical,
+    /// The Conway API key was rejected (401/403) and automatic
+    /// re-provisioning (see `rotate_api_key` in the agent loop) also failed
+    /// or there's no wallet to re-provision from. Needs operator attention.
+    NeedsReprovision,
+    /// Restart-loop protection tripped (see `agent::crash_loop`): the
+    /// automaton restarted too many times without an intervening clean
+    /// shutdown, so it stops re-entering the loop and waits for an
+    /// operator to intervene instead of continuing to burn credits.
+    CrashLoopPaused,
     Dead,
 }
 
@@ -134,6 +805,91 @@ pub struct AgentTurn {
     pub tool_calls: Vec<ToolCallResult>,
     pub token_usage: TokenUsage,
     pub cost_cents: f64,
+    /// Financial state as it was when this turn ran, so `--replay` can
+    /// reconstruct the exact prompt without guessing at a balance that has
+    /// since moved on. Absent on turns recorded before this was tracked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub financial_snapshot: Option<FinancialState>,
+}
+
+/// A single observability event describing something `agent_loop::run_agent_loop`
+/// just did, for callers that want to watch the loop live (e.g. a streaming
+/// endpoint) rather than tail its logs. Fired synchronously and best-effort
+/// via `AgentLoopOptions::on_event` -- the loop does not wait on or retry a
+/// slow subscriber, so a caller wiring this to a channel should use a
+/// non-blocking send and drop the event on backpressure rather than block
+/// the agent. Free-text fields (`preview`, `arguments`, `result`, `error`)
+/// are not redacted here; a caller exposing these outside the process
+/// should redact them first, e.g. with `conway::inference_log::redact`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentLoopEvent {
+    TurnStarted {
+        input_source: Option<InputSource>,
+    },
+    InferenceCalled {
+        model: String,
+        tokens: u64,
+    },
+    StateChange {
+        state: AgentState,
+    },
+    ThinkPreview {
+        preview: String,
+    },
+    ToolCall {
+        name: String,
+        arguments: String,
+    },
+    ToolResult {
+        name: String,
+        result: String,
+        error: Option<String>,
+    },
+    Slept,
+}
+
+impl AgentLoopEvent {
+    /// The `snake_case` name of this event's variant -- matches its
+    /// `#[serde(tag = "type")]` value, kept as a real column on `events` so
+    /// `get_events` can filter without parsing every row's JSON.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AgentLoopEvent::TurnStarted { .. } => "turn_started",
+            AgentLoopEvent::InferenceCalled { .. } => "inference_called",
+            AgentLoopEvent::StateChange { .. } => "state_change",
+            AgentLoopEvent::ThinkPreview { .. } => "think_preview",
+            AgentLoopEvent::ToolCall { .. } => "tool_call",
+            AgentLoopEvent::ToolResult { .. } => "tool_result",
+            AgentLoopEvent::Slept => "slept",
+        }
+    }
+}
+
+/// A durable record of one [`AgentLoopEvent`], persisted to the `events`
+/// table by `agent_loop::run_agent_loop`. Distinct from `turns` (the
+/// model-facing memory that gets trimmed into future prompts): this is an
+/// append-only operational timeline -- the audit trail the Constitution
+/// promises the creator -- meant for fleet monitoring, live streaming, and
+/// post-hoc debugging, independent of what the agent itself remembers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopEventRecord {
+    pub id: String,
+    pub timestamp: String,
+    pub kind: String,
+    pub data: serde_json::Value,
+}
+
+/// The recorded prompt for a turn, as returned by `get_turn_prompt`. The
+/// hash is kept indefinitely; the rendered body is pruned after a bounded
+/// number of turns (see `Database::insert_turn_prompt`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnPrompt {
+    pub prompt_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rendered_prompt: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -144,6 +900,62 @@ pub enum InputSource {
     Agent,
     System,
     Wakeup,
+    /// A `schedule_action` timer that came due -- injected by
+    /// `heartbeat::tasks::check_scheduled_actions`.
+    Scheduled,
+}
+
+/// A queued turn input awaiting processing, persisted so a restart doesn't
+/// lose it. `run_agent_loop` drains this FIFO-by-priority queue one entry
+/// per turn instead of tracking a single in-memory `Option`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingInputEntry {
+    pub id: String,
+    pub content: String,
+    pub source: InputSource,
+    /// Higher runs first; ties broken by `created_at` (oldest first).
+    pub priority: i32,
+    /// When set, a second enqueue with the same key is a no-op -- used so
+    /// e.g. the same inbox message can't be queued twice.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedup_key: Option<String>,
+    pub created_at: String,
+}
+
+/// A `schedule_action` timer: fires once `run_at` has passed by getting
+/// injected into the [`PendingInputEntry`] queue as [`InputSource::Scheduled`],
+/// then marked fired so it's never injected twice -- including for schedules
+/// that came due while the automaton was down.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledAction {
+    pub id: String,
+    /// RFC3339 timestamp the action becomes due.
+    pub run_at: String,
+    /// The pending-input content to enqueue once due.
+    pub input: String,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fired_at: Option<String>,
+}
+
+/// A model-generated rollup of a contiguous range of [`AgentTurn`]s, produced
+/// by the `summarize_history` tool so the underlying turns can be pruned
+/// without losing the long-term narrative. Surfaced back to the model as
+/// "long-term memory" in the system/wakeup prompt.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySummary {
+    pub id: String,
+    /// Timestamp of the oldest turn folded into this summary.
+    pub start_timestamp: String,
+    /// Timestamp of the newest turn folded into this summary -- also the
+    /// new watermark, so later summarization runs don't redo this range.
+    pub end_timestamp: String,
+    pub turn_count: u32,
+    pub summary: String,
+    pub created_at: String,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -156,6 +968,57 @@ pub struct ToolCallResult {
     pub duration_ms: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Machine-readable data a tool chose to attach alongside its prose
+    /// `result`, e.g. `check_credits`' numeric balance or `list_models`'
+    /// model list. `None` for tools that only ever produce prose -- see
+    /// [`ToolOutput`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+    /// Position among the tool calls the model requested in this turn (0
+    /// for the first, etc.), assigned before dispatch. Deterministic
+    /// regardless of whether calls in the same batch ran concurrently, so
+    /// the audit trail can always recover the model's requested order --
+    /// see `agent_loop`'s `parallel_safe` batching.
+    #[serde(default)]
+    pub sequence: u32,
+    /// When this call actually started executing (RFC 3339). Overlapping
+    /// `started_at`/`duration_ms` ranges across calls in the same turn
+    /// reveal that they ran concurrently.
+    #[serde(default)]
+    pub started_at: String,
+}
+
+/// Per-tool aggregate over a recent window of `tool_calls` rows, from
+/// [`AutomatonDatabase::get_tool_stats`]. Turns the per-call `duration_ms`/
+/// `error` fields already recorded on [`ToolCallResult`] into the kind of
+/// summary an agent can actually act on -- e.g. noticing a tool times out
+/// often and avoiding it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStat {
+    pub name: String,
+    pub call_count: u64,
+    pub error_count: u64,
+    pub error_rate: f64,
+    pub avg_duration_ms: f64,
+    pub p95_duration_ms: f64,
+}
+
+/// What a tool call produces: a prose `summary` for the model (and logs),
+/// plus optional structured `data` for consumers that want to use the
+/// result programmatically (caching, reconciliation) instead of re-parsing
+/// the summary text. Most tools only ever populate `summary`; a plain
+/// `String` converts into one with `data: None`.
+#[derive(Clone, Debug)]
+pub struct ToolOutput {
+    pub summary: String,
+    pub data: Option<serde_json::Value>,
+}
+
+impl From<String> for ToolOutput {
+    fn from(summary: String) -> Self {
+        ToolOutput { summary, data: None }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
@@ -186,7 +1049,7 @@ pub trait AutomatonTool: Send + Sync {
     ) -> anyhow::Result<String>;
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum ToolCategory {
     Vm,
@@ -198,8 +1061,44 @@ pub enum ToolCategory {
     Git,
     Registry,
     Replication,
+    Social,
+    Domains,
+    Discovery,
+    Goals,
+}
+
+impl ToolCategory {
+    /// The `snake_case` name used both for display (`list_tools`' grouping
+    /// headers) and as the wire representation via `Serialize`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ToolCategory::Vm => "vm",
+            ToolCategory::Conway => "conway",
+            ToolCategory::SelfMod => "self_mod",
+            ToolCategory::Financial => "financial",
+            ToolCategory::Survival => "survival",
+            ToolCategory::Skills => "skills",
+            ToolCategory::Git => "git",
+            ToolCategory::Registry => "registry",
+            ToolCategory::Replication => "replication",
+            ToolCategory::Social => "social",
+            ToolCategory::Domains => "domains",
+            ToolCategory::Discovery => "discovery",
+            ToolCategory::Goals => "goals",
+        }
+    }
 }
 
+impl std::fmt::Display for ToolCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Callback used to report progress heartbeats from long-running tools
+/// (e.g. `spawn_child`, `git_clone`, `install_npm_package`): `(tool_name, message)`.
+pub type ToolProgressFn = dyn Fn(&str, &str) + Send + Sync;
+
 /// Runtime context handed to every tool invocation.
 pub struct ToolContext {
     pub identity: AutomatonIdentity,
@@ -208,6 +1107,11 @@ pub struct ToolContext {
     pub conway: Box<dyn ConwayClient>,
     pub inference: Box<dyn InferenceClient>,
     pub social: Option<Box<dyn SocialClientInterface>>,
+    pub on_progress: Option<Arc<ToolProgressFn>>,
+    /// Source of "now" for tools that reason about elapsed time (e.g.
+    /// `send_message`'s rate-limit windows) rather than just recording a
+    /// timestamp -- see `crate::clock`.
+    pub clock: Arc<dyn crate::clock::Clock>,
 }
 
 // ─── Social ──────────────────────────────────────────────────────
@@ -257,6 +1161,25 @@ pub struct InboxMessage {
     pub reply_to: Option<String>,
 }
 
+/// An outbound message that couldn't be delivered immediately (the social
+/// relay was unreachable or unconfigured at send time), queued for the
+/// `retry_outbox` heartbeat task to retry -- see `send_message`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEntry {
+    pub id: String,
+    pub to_address: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_to: Option<String>,
+    pub created_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sent_at: Option<String>,
+    pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
 // ─── Heartbeat ───────────────────────────────────────────────────
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -331,6 +1254,11 @@ pub struct Transaction {
     pub amount_cents: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub balance_after_cents: Option<f64>,
+    /// Further classifies a `TransferIn`/`TransferOut` for `profit_loss` so
+    /// creator top-ups don't get counted as earnings. `None` for types where
+    /// the distinction doesn't apply (e.g. `Inference`, `CreditCheck`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subcategory: Option<TransactionSubcategory>,
     pub description: String,
     pub timestamp: String,
 }
@@ -344,6 +1272,21 @@ pub enum TransactionType {
     TransferIn,
     TransferOut,
     FundingRequest,
+    Reconciliation,
+}
+
+/// Distinguishes why value moved, so `profit_loss` can separate real
+/// earnings from inflows that aren't income (creator funding) and outflows
+/// that aren't discretionary spend (paying a child, refunds).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSubcategory {
+    /// Payment received for a service the agent hosts or performs.
+    Earnings,
+    /// Funding sent by the creator -- not income.
+    CreatorFunding,
+    /// Detected but not yet attributed to a known source or purpose.
+    Other,
 }
 
 // ─── Self-Modification ───────────────────────────────────────────
@@ -379,6 +1322,8 @@ pub enum ModificationType {
     SoulUpdate,
     RegistryUpdate,
     ChildSpawn,
+    ChildRetire,
+    ChildReport,
     UpstreamPull,
 }
 
@@ -502,7 +1447,7 @@ pub trait ConwayClient: Send + Sync {
     async fn remove_port(&self, port: u16) -> anyhow::Result<()>;
     async fn create_sandbox(&self, options: CreateSandboxOptions) -> anyhow::Result<SandboxInfo>;
     async fn delete_sandbox(&self, sandbox_id: &str) -> anyhow::Result<()>;
-    async fn list_sandboxes(&self) -> anyhow::Result<Vec<SandboxInfo>>;
+    async fn list_sandboxes(&self, filter: &ListSandboxesFilter) -> anyhow::Result<Vec<SandboxInfo>>;
 
     // Credits
     async fn get_credits_balance(&self) -> anyhow::Result<f64>;
@@ -538,6 +1483,10 @@ pub trait ConwayClient: Send + Sync {
 
     // Model discovery
     async fn list_models(&self) -> anyhow::Result<Vec<ModelInfo>>;
+
+    /// Swap the API key used for subsequent requests, e.g. after
+    /// `rotate_api_key` provisions a fresh one.
+    fn set_api_key(&self, api_key: &str);
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -556,6 +1505,56 @@ pub struct PortInfo {
     pub sandbox_id: String,
 }
 
+/// A port this automaton has durably recorded as exposed, so it survives a
+/// restart and can be advertised (agent card) or cleaned up later.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExposedPort {
+    pub port: u16,
+    pub public_url: String,
+    pub exposed_at: String,
+}
+
+/// A sandbox this automaton created via `create_sandbox` (never its own),
+/// tracked so `heartbeat::tasks::reap_idle_sandboxes` can warn about or
+/// auto-delete ones that have sat idle too long -- a common money leak for
+/// an automaton that spins up sub-task sandboxes and forgets them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedSandboxEntry {
+    pub sandbox_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    pub vcpu: u32,
+    pub memory_mb: u32,
+    pub disk_gb: u32,
+    pub created_at: String,
+}
+
+/// A point-in-time tar backup of `~/.automaton`, independent of git, taken
+/// via the `snapshot` tool or automatically before risky self-mod ops.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub id: String,
+    pub path: String,
+    pub label: String,
+    pub size_bytes: u64,
+    pub includes_wallet: bool,
+    pub created_at: String,
+}
+
+/// A point-in-time record of the automaton's Conway credit balance, taken
+/// periodically by the `record_balance_snapshot` heartbeat task so the
+/// `credit_history` tool can report a trend instead of a single number.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceSnapshot {
+    pub id: String,
+    pub balance_cents: i64,
+    pub created_at: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateSandboxOptions {
@@ -571,6 +1570,42 @@ pub struct CreateSandboxOptions {
     pub region: Option<String>,
 }
 
+/// Default sandbox sizing for `create_sandbox` and `spawn_child` calls that
+/// don't specify their own -- lets an operator tune the baseline (and where
+/// children get co-located) without touching prompts or tool schemas.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SandboxSpecs {
+    pub vcpu: u32,
+    pub memory_mb: u32,
+    pub disk_gb: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+impl Default for SandboxSpecs {
+    fn default() -> Self {
+        Self {
+            vcpu: 1,
+            memory_mb: 512,
+            disk_gb: 5,
+            region: None,
+        }
+    }
+}
+
+impl From<&SandboxSpecs> for CreateSandboxOptions {
+    fn from(specs: &SandboxSpecs) -> Self {
+        Self {
+            name: None,
+            vcpu: Some(specs.vcpu),
+            memory_mb: Some(specs.memory_mb),
+            disk_gb: Some(specs.disk_gb),
+            region: specs.region.clone(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SandboxInfo {
@@ -581,10 +1616,26 @@ pub struct SandboxInfo {
     pub memory_mb: u32,
     pub disk_gb: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub terminal_url: Option<String>,
     pub created_at: String,
 }
 
+/// Filter/pagination options for `list_sandboxes`. Sent to the Conway API as
+/// query parameters; also applied client-side in case the API ignores a
+/// parameter it doesn't support, so the contract holds either way.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListSandboxesFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PricingTier {
@@ -661,6 +1712,22 @@ pub struct ModelInfo {
     pub pricing: ModelPricing,
 }
 
+/// A model's inference-time tool-calling capability, as inferred from its
+/// identifier by [`crate::conway::inference::model_capabilities`]. Lets
+/// `run_agent_loop` avoid sending a `tools` array a model can't handle
+/// (errors or silently ignores it) or can only handle in limited numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ModelCapabilities {
+    /// Whether the model accepts a `tools` array at all. `false` means the
+    /// caller must fall back to a text-instructed tool protocol (or run
+    /// without tools) instead of the native `tools`/`tool_calls` mechanism.
+    pub supports_tools: bool,
+    /// Maximum number of tool definitions the model reliably handles in one
+    /// request, if known. `None` means no known limit beyond the model's own
+    /// context window.
+    pub max_tools: Option<u32>,
+}
+
 // ─── Database ────────────────────────────────────────────────────
 
 #[async_trait]
@@ -674,10 +1741,26 @@ pub trait AutomatonDatabase: Send + Sync {
     fn get_recent_turns(&self, limit: u32) -> Vec<AgentTurn>;
     fn get_turn_by_id(&self, id: &str) -> Option<AgentTurn>;
     fn get_turn_count(&self) -> u64;
+    /// Turns with `timestamp > after` (or all, if `after` is `None`) and
+    /// `timestamp < before`, oldest first, capped to `limit` -- used by
+    /// `summarize_history` to select the oldest not-yet-summarized turns to
+    /// fold into a [`HistorySummary`] before pruning them.
+    fn get_turns_for_summary(&self, after: Option<&str>, before: &str, limit: u32) -> Vec<AgentTurn>;
+
+    /// Hash and (bounded-retention) compress the exact rendered prompt sent
+    /// to the model for `turn_id`, for later audit via `get_turn_prompt`.
+    /// Returns the hash.
+    fn insert_turn_prompt(&self, turn_id: &str, rendered_prompt: &str) -> String;
+    /// Look up the recorded prompt hash for a turn, along with the rendered
+    /// prompt body if it's still within the retention window.
+    fn get_turn_prompt(&self, turn_id: &str) -> Option<TurnPrompt>;
 
     // Tool calls
     fn insert_tool_call(&self, turn_id: &str, call: &ToolCallResult);
     fn get_tool_calls_for_turn(&self, turn_id: &str) -> Vec<ToolCallResult>;
+    /// Aggregate `tool_calls` by name over the last `window_hours`: call
+    /// count, error rate, and avg/p95 duration. Ordered by call count desc.
+    fn get_tool_stats(&self, window_hours: u32) -> Vec<ToolStat>;
 
     // Heartbeat
     fn get_heartbeat_entries(&self) -> Vec<HeartbeatEntry>;
@@ -713,6 +1796,41 @@ pub trait AutomatonDatabase: Send + Sync {
     fn get_child_by_id(&self, id: &str) -> Option<ChildAutomaton>;
     fn insert_child(&self, child: &ChildAutomaton);
     fn update_child_status(&self, id: &str, status: ChildStatus);
+    fn remove_child(&self, id: &str);
+    fn update_child_lineage(&self, id: &str, descendants_count: u32, lineage_snapshot: Option<String>);
+    fn update_child_address(&self, id: &str, address: &str);
+
+    // Exposed ports
+    fn get_exposed_ports(&self) -> Vec<ExposedPort>;
+    fn upsert_exposed_port(&self, port: &ExposedPort);
+    fn delete_exposed_port(&self, port: u16);
+
+    // Created sandboxes (sub-task sandboxes this automaton spun up, not its own)
+    fn insert_created_sandbox(&self, entry: &CreatedSandboxEntry);
+    fn get_created_sandboxes(&self) -> Vec<CreatedSandboxEntry>;
+    fn delete_created_sandbox(&self, sandbox_id: &str);
+
+    // Snapshots
+    fn get_snapshots(&self) -> Vec<Snapshot>;
+    fn insert_snapshot(&self, snapshot: &Snapshot);
+
+    // Balance snapshots
+    /// Most recent first, capped to `limit` rows.
+    fn get_balance_snapshots(&self, limit: u32) -> Vec<BalanceSnapshot>;
+    fn insert_balance_snapshot(&self, snapshot: &BalanceSnapshot);
+
+    // Operational event log
+    /// Events with `timestamp > since` (or all, if `since` is `None`),
+    /// oldest first, capped to `limit` rows.
+    fn get_events(&self, since: Option<&str>, limit: u32) -> Vec<LoopEventRecord>;
+    fn insert_event(&self, event: &LoopEventRecord);
+
+    // Pending input queue
+    /// No-op if `entry.dedup_key` is `Some` and already queued.
+    fn enqueue_pending_input(&self, entry: &PendingInputEntry);
+    /// Remove and return the highest-priority queued input (oldest wins ties).
+    fn dequeue_pending_input(&self) -> Option<PendingInputEntry>;
+    fn pending_input_count(&self) -> u32;
 
     // Registry
     fn get_registry_entry(&self) -> Option<RegistryEntry>;
@@ -727,6 +1845,50 @@ pub trait AutomatonDatabase: Send + Sync {
     fn get_unprocessed_inbox_messages(&self, limit: u32) -> Vec<InboxMessage>;
     fn mark_inbox_message_processed(&self, id: &str);
 
+    // Outbox
+    fn enqueue_outbox(&self, entry: &OutboxEntry);
+    /// Unsent entries, oldest first, capped to `limit` rows.
+    fn get_pending_outbox(&self, limit: u32) -> Vec<OutboxEntry>;
+    fn mark_sent(&self, id: &str);
+    fn record_outbox_failure(&self, id: &str, error: &str);
+
+    // Outbound message rate limiting
+    fn record_outbound_message(&self, to_address: &str);
+    /// Count of outbound messages sent since `since` (RFC3339), to
+    /// `to_address` if given or in total if `None`.
+    fn count_outbound_messages(&self, to_address: Option<&str>, since: &str) -> u32;
+
+    // Goals
+    fn add_goal(&self, goal: &Goal);
+    /// Bumps `current_value`, auto-transitioning `status` from `active` to
+    /// `achieved` once `current_value >= target`.
+    fn update_goal_progress(&self, id: &str, value: f64);
+    fn get_goals(&self, active_only: bool) -> Vec<Goal>;
+    fn get_goal_by_id(&self, id: &str) -> Option<Goal>;
+    fn abandon_goal(&self, id: &str);
+
+    // Scheduled actions
+    fn insert_scheduled_action(&self, action: &ScheduledAction);
+    /// Not-yet-fired actions with `run_at <= now` (RFC3339), oldest first --
+    /// includes ones that became due during downtime, so they still run
+    /// once instead of being skipped.
+    fn get_due_scheduled_actions(&self, now: &str) -> Vec<ScheduledAction>;
+    fn mark_scheduled_action_fired(&self, id: &str);
+    /// Count of not-yet-fired actions, for enforcing a cap on new schedules.
+    fn scheduled_action_count(&self) -> u32;
+
+    // History summaries
+    fn insert_history_summary(&self, summary: &HistorySummary);
+    /// Most recent first, capped to `limit` rows -- surfaced as long-term
+    /// memory in the system/wakeup prompt.
+    fn get_history_summaries(&self, limit: u32) -> Vec<HistorySummary>;
+    /// `end_timestamp` of the most recently created summary, if any --
+    /// turns at or before this point have already been summarized.
+    fn get_history_summary_watermark(&self) -> Option<String>;
+    /// Deletes the given turns, once they've been folded into a
+    /// [`HistorySummary`] and are no longer needed verbatim.
+    fn delete_turns(&self, ids: &[String]);
+
     // State
     fn get_agent_state(&self) -> AgentState;
     fn set_agent_state(&self, state: AgentState);
@@ -767,6 +1929,13 @@ pub trait InferenceClient: Send + Sync {
 
     fn set_low_compute_mode(&self, enabled: bool);
     fn get_default_model(&self) -> String;
+    /// Switch the model used for subsequent `chat` calls, e.g. after the
+    /// agent deliberately picks a cheaper or more capable model via
+    /// `set_model`.
+    fn set_default_model(&self, model: &str);
+    /// Swap the API key used for subsequent requests, e.g. after
+    /// `rotate_api_key` provisions a fresh one.
+    fn set_api_key(&self, api_key: &str);
 }
 
 // ─── Skills ─────────────────────────────────────────────────────
@@ -793,6 +1962,12 @@ pub struct SkillRequirements {
     pub bins: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub env: Option<Vec<String>>,
+    /// Names of other skills this one depends on -- used by
+    /// `skills::validate::resolve_activation_order` to activate
+    /// dependencies first and by `validate_skill_set` to flag a skill
+    /// depending on one that isn't installed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skills: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -918,6 +2093,21 @@ pub struct ChildAutomaton {
     pub created_at: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_checked: Option<String>,
+    /// Total number of descendants (grandchildren and beyond) last reported by
+    /// this child's own heartbeat-ping status, denormalized so the family tree
+    /// can be shown without polling grandchildren directly.
+    #[serde(default)]
+    pub descendants_count: u32,
+    /// Raw JSON snapshot of this child's own children summary, as last reported
+    /// via `automaton --status`. Used by [`crate::replication::lineage::build_lineage_tree`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lineage_snapshot: Option<String>,
+    /// Human-readable description of what `replication::genesis::apply_mutation`
+    /// varied away from the parent's defaults for this child (e.g. `"model:
+    /// gpt-4o -> gpt-4o-mini"`), if anything. `None` if the child was
+    /// spawned without mutation, or nothing actually varied this time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutation_summary: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -939,4 +2129,91 @@ pub struct GenesisConfig {
     pub creator_message: Option<String>,
     pub creator_address: String,
     pub parent_address: String,
+    /// Sandbox sizing the child should be spawned with -- carried on the
+    /// genesis config so `spawn_child` doesn't have to re-derive it.
+    pub sandbox_specs: SandboxSpecs,
+    /// Kill switch the child should boot with. `generate_genesis_config` and
+    /// friends force `enabled: true` regardless of the parent's own setting
+    /// -- see [`KillSwitchConfig`].
+    pub kill_switch: KillSwitchConfig,
+    /// Model the child should use in place of the parent's
+    /// `inference_model`, if `replication::genesis::apply_mutation` varied
+    /// it. `None` means the child's own default config applies, unchanged
+    /// from today.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_override: Option<String>,
+    /// `idle_sleep_seconds` the child should use in place of its own
+    /// default, if `replication::genesis::apply_mutation` varied it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_sleep_seconds_override: Option<u32>,
+    /// What `apply_mutation` varied away from the parent's defaults, if
+    /// anything -- copied onto the spawned [`ChildAutomaton::mutation_summary`]
+    /// so the lineage record explains why this child differs from its
+    /// siblings. `None` for an unmutated genesis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutation_summary: Option<String>,
+}
+
+/// Per-aspect mutation rates and candidate pools for
+/// `replication::genesis::apply_mutation`. Each aspect mutates
+/// independently: a `None` rate (the default for all three) never
+/// mutates that aspect, so a default `MutationBounds` is a no-op and
+/// `generate_genesis_config` produces identical clones, unchanged from
+/// today.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MutationBounds {
+    /// Probability (0.0-1.0) that a spawn picks a model from
+    /// `candidate_models` instead of inheriting the parent's. Ignored if
+    /// `candidate_models` is empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_rate: Option<f64>,
+    /// Models `apply_mutation` may pick from when `model_rate` fires.
+    #[serde(default)]
+    pub candidate_models: Vec<String>,
+    /// Probability (0.0-1.0) that a spawn jitters `idle_sleep_seconds`
+    /// away from its default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_rate: Option<f64>,
+    /// Maximum fractional jitter (0.0-1.0) applied to `idle_sleep_seconds`
+    /// when `heartbeat_rate` fires, e.g. `0.2` allows +/-20%.
+    #[serde(default)]
+    pub heartbeat_jitter: f64,
+    /// Probability (0.0-1.0) that a spawn appends a variation from
+    /// `candidate_prompt_variations` to the genesis prompt. Ignored if
+    /// `candidate_prompt_variations` is empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_rate: Option<f64>,
+    /// Prompt variations `apply_mutation` may append when `prompt_rate`
+    /// fires.
+    #[serde(default)]
+    pub candidate_prompt_variations: Vec<String>,
+}
+
+// ─── Goals ──────────────────────────────────────────────────────
+
+/// A concrete, self-set objective giving the Constitution's abstract
+/// "create value or die" imperative a structured, trackable home (e.g.
+/// "earn $5 via hosted API by Friday").
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Goal {
+    pub id: String,
+    pub description: String,
+    /// What's being measured, e.g. "usd_earned" or "api_calls_served" --
+    /// free text, interpreted by whoever set the goal.
+    pub metric: String,
+    pub target: f64,
+    #[serde(default)]
+    pub current_value: f64,
+    pub status: GoalStatus,
+    pub created_at: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalStatus {
+    Active,
+    Achieved,
+    Abandoned,
 }
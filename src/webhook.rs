@@ -0,0 +1,112 @@
+//! Survival-State Webhook
+//!
+//! Posts a signed JSON payload to an operator-configured URL whenever the
+//! automaton's survival tier changes, so a fleet operator can learn about a
+//! Critical/Dead transition without polling. Wired into the existing
+//! `on_state_change` sites in `agent_loop::run_agent_loop`.
+
+use hmac::{Hmac, KeyInit, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::types::{AgentState, StateChangeWebhookConfig};
+
+/// Number of delivery attempts before giving up on a single state change.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay between retry attempts.
+const RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Payload posted to `state_change_webhook.url` on every survival tier
+/// transition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StateChangePayload {
+    name: String,
+    address: String,
+    old_state: AgentState,
+    new_state: AgentState,
+    credits_cents: f64,
+    timestamp: String,
+}
+
+/// POST a state-change notification to `config.url`, signed with
+/// `config.secret` if set. A no-op if `config.url` is unset. Retries up to
+/// [`MAX_ATTEMPTS`] times on delivery failure; failures are logged, not
+/// propagated, since a webhook outage shouldn't affect the agent loop.
+pub async fn notify_state_change(
+    config: &StateChangeWebhookConfig,
+    name: &str,
+    address: &str,
+    old_state: AgentState,
+    new_state: AgentState,
+    credits_cents: f64,
+) {
+    let Some(url) = config.url.as_deref() else {
+        return;
+    };
+
+    let payload = StateChangePayload {
+        name: name.to_string(),
+        address: address.to_string(),
+        old_state,
+        new_state,
+        credits_cents,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+
+    let body = match serde_json::to_vec(&payload) {
+        Ok(b) => b,
+        Err(e) => {
+            warn!("Failed to serialize state-change webhook payload: {}", e);
+            return;
+        }
+    };
+
+    let signature = config.secret.as_deref().map(|secret| sign(secret, &body));
+
+    let client = Client::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body.clone());
+        if let Some(ref sig) = signature {
+            request = request.header("X-Automaton-Signature", sig.clone());
+        }
+
+        match request.send().await {
+            Ok(resp) if resp.status().is_success() => return,
+            Ok(resp) => {
+                warn!(
+                    "State-change webhook returned {} (attempt {}/{})",
+                    resp.status(),
+                    attempt,
+                    MAX_ATTEMPTS
+                );
+            }
+            Err(e) => {
+                warn!(
+                    "State-change webhook request failed: {} (attempt {}/{})",
+                    e, attempt, MAX_ATTEMPTS
+                );
+            }
+        }
+
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    }
+}
+
+/// Compute the `sha256=<hex>` HMAC signature of `body` under `secret`, in
+/// the same style GitHub/Stripe-style webhook signatures use, so the
+/// receiver can verify the payload actually came from this automaton.
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts a key of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}